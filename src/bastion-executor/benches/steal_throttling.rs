@@ -0,0 +1,72 @@
+#![feature(test)]
+
+extern crate test;
+
+use bastion_executor::load_balancer;
+use bastion_executor::pool::spawn;
+use bastion_executor::run::run;
+use futures::future::join_all;
+use lightproc::proc_stack::ProcStack;
+use std::thread;
+use std::time::Duration;
+use test::Bencher;
+
+// The CPU time used by the whole process so far, idle workers spinning included.
+#[cfg(unix)]
+fn cpu_time() -> Option<Duration> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+
+    let time = |tv: libc::timeval| Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000);
+    Some(time(usage.ru_utime) + time(usage.ru_stime))
+}
+
+// Without `getrusage` only the iterations are timed, by the bencher.
+#[cfg(not(unix))]
+fn cpu_time() -> Option<Duration> {
+    None
+}
+
+// A trickle of processes spawned from a worker, which queues them locally for the other
+// workers to steal.
+fn light_load(b: &mut Bencher, threshold: usize) {
+    load_balancer::set_steal_threshold(threshold);
+
+    let start = cpu_time();
+    let mut iterations = 0;
+    b.iter(|| {
+        iterations += 1;
+        let handle = spawn(
+            async {
+                let handles = (0..4)
+                    .map(|_| spawn(async {}, ProcStack::default()))
+                    .collect::<Vec<_>>();
+                join_all(handles).await;
+            },
+            ProcStack::default(),
+        );
+        run(handle, ProcStack::default());
+        thread::sleep(Duration::from_micros(100));
+    });
+
+    if let (Some(start), Some(end)) = (start, cpu_time()) {
+        eprintln!(
+            "steal threshold {}: {:?} of CPU time per iteration",
+            threshold,
+            (end - start) / iterations
+        );
+    }
+    load_balancer::set_steal_threshold(load_balancer::DEFAULT_STEAL_THRESHOLD);
+}
+
+#[bench]
+fn light_load_stealing(b: &mut Bencher) {
+    light_load(b, load_balancer::DEFAULT_STEAL_THRESHOLD);
+}
+
+#[bench]
+fn light_load_throttled(b: &mut Bencher) {
+    light_load(b, 1);
+}
@@ -56,7 +56,7 @@ use std::future::Future;
 use std::io::ErrorKind;
 use std::iter::Iterator;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{env, thread};
 
@@ -69,7 +69,7 @@ use lightproc::proc_stack::ProcStack;
 use lightproc::recoverable_handle::RecoverableHandle;
 
 use crate::placement::CoreId;
-use crate::{load_balancer, placement};
+use crate::{deadlines, load_balancer, placement};
 
 /// If low watermark isn't configured this is the default scaler value.
 /// This value is used for the heuristics of the scaler
@@ -94,6 +94,9 @@ static FREQUENCY: AtomicU64 = AtomicU64::new(0);
 /// Possible max threads (without OS contract).
 static MAX_THREADS: AtomicU64 = AtomicU64::new(10_000);
 
+/// Identifier of the next blocking thread, used to name it.
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(0);
+
 /// Pool interface between the scheduler and thread pool
 struct Pool {
     sender: Sender<LightProc>,
@@ -103,22 +106,26 @@ struct Pool {
 lazy_static! {
     /// Blocking pool with static starting thread count.
     static ref POOL: Pool = {
+        // The threads which couldn't be started are replaced by
+        // dynamic ones once the pool manager sees the tasks pile up.
         for _ in 0..*low_watermark() {
-            thread::Builder::new()
-                .name("bastion-blocking-driver".to_string())
+            let res = thread::Builder::new()
+                .name(blocking_thread_name())
                 .spawn(|| {
                     self::affinity_pinner();
 
                     for task in &POOL.receiver {
                         task.run();
                     }
-                })
-                .expect("cannot start a thread driving blocking tasks");
+                });
+            if let Err(err) = res {
+                eprintln!("cannot start a thread driving blocking tasks: {}", err);
+            }
         }
 
         // Pool manager to check frequency of task rates
         // and take action by scaling the pool accordingly.
-        thread::Builder::new()
+        let res = thread::Builder::new()
             .name("bastion-pool-manager".to_string())
             .spawn(|| {
                 let poll_interval = Duration::from_millis(MANAGER_POLL_INTERVAL);
@@ -126,8 +133,11 @@ lazy_static! {
                     scale_pool();
                     thread::sleep(poll_interval);
                 }
-            })
-            .expect("thread pool manager cannot be started");
+            });
+        if let Err(err) = res {
+            // The pool keeps running with its static threads only.
+            eprintln!("cannot start the blocking pool manager: {}", err);
+        }
 
         // We want to use an unbuffered channel here to help
         // us drive our dynamic control. In effect, the
@@ -242,6 +252,13 @@ fn scale_pool() {
     }
 }
 
+/// Returns a distinct name for a new blocking thread, kept within the
+/// 15 bytes OS thread names are truncated to on Linux.
+fn blocking_thread_name() -> String {
+    let id = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+    format!("bastion-blk-{}", id)
+}
+
 /// Creates blocking thread to receive tasks
 /// Dynamic threads will terminate themselves if they don't
 /// receive any work after between one and ten seconds.
@@ -267,7 +284,7 @@ fn create_blocking_thread() {
         .expect("shouldn't overflow");
 
     let _ = thread::Builder::new()
-        .name("bastion-blocking-driver-dynamic".to_string())
+        .name(blocking_thread_name())
         .spawn(move || {
             self::affinity_pinner();
 
@@ -306,10 +323,21 @@ fn create_blocking_thread() {
 /// nonblocking way and spinning up needed amount of threads
 /// based on the previous statistics without relying on
 /// if there is not a thread ready to accept the work or not.
-fn schedule(t: LightProc) {
+pub fn schedule_blocking(t: LightProc) {
     // Add up for every incoming scheduled task
     FREQUENCY.fetch_add(1, Ordering::Acquire);
 
+    // The threads of the pool are shared by all the tasks, which is
+    // why the ones requesting their own stack size get a dedicated
+    // thread instead.
+    let t = match t.stack().stack_size() {
+        Some(stack_size) => match run_with_stack_size(t, stack_size) {
+            Ok(()) => return,
+            Err(t) => t,
+        },
+        None => t,
+    };
+
     if let Err(err) = POOL.sender.try_send(t) {
         // We were not able to send to the channel without
         // blocking.
@@ -317,15 +345,58 @@ fn schedule(t: LightProc) {
     }
 }
 
+/// Runs the task on a new thread with the given stack size, giving
+/// the task back if the size is invalid or the thread couldn't be
+/// spawned with it.
+fn run_with_stack_size(t: LightProc, stack_size: usize) -> Result<(), LightProc> {
+    if stack_size == 0 {
+        return Err(t);
+    }
+
+    // The task is lost if the closure owning it gets dropped because
+    // the thread couldn't be spawned, so it is shared with the thread.
+    let slot = Arc::new(Mutex::new(Some(t)));
+    let task = slot.clone();
+    let res = thread::Builder::new()
+        .name(blocking_thread_name())
+        .stack_size(stack_size)
+        .spawn(move || {
+            self::affinity_pinner();
+
+            let task = task.lock().unwrap().take();
+            if let Some(task) = task {
+                task.run();
+            }
+        });
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            eprintln!(
+                "cannot start a thread with a stack of {} bytes, falling back to the pool: {}",
+                stack_size, err
+            );
+            match slot.lock().unwrap().take() {
+                Some(t) => Err(t),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
 /// Spawns a blocking task.
 ///
 /// The task will be spawned onto a thread pool specifically dedicated to blocking tasks.
+/// If its [ProcStack] requests a stack size (see [ProcStack::with_stack_size]), it's run
+/// on a dedicated thread with this stack size instead, or on the pool if the size is
+/// invalid.
 pub fn spawn_blocking<F, R>(future: F, stack: ProcStack) -> RecoverableHandle<R>
 where
     F: Future<Output = R> + Send + 'static,
     R: Send + 'static,
 {
-    let (task, handle) = LightProc::recoverable(future, schedule, stack);
+    let (task, handle) = LightProc::recoverable(future, schedule_blocking, stack);
+    deadlines::watch(&handle);
     task.schedule();
     handle
 }
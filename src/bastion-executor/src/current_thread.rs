@@ -0,0 +1,246 @@
+//!
+//! Executor running all of its processes on a single thread.
+//!
+//! The [CurrentThread] executor doesn't spawn any thread: the processes scheduled on
+//! it are queued and only run when the queue is driven with [CurrentThread::block_on]
+//! or [CurrentThread::run_until_stalled], one at a time and in the order they were
+//! scheduled in. As long as the processes don't depend on other threads (e.g. timers),
+//! they always interleave the same way, which makes their tests reproducible.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::current_thread::CurrentThread;
+//! use bastion_executor::prelude::*;
+//! use lightproc::prelude::*;
+//! use std::sync::{Arc, Mutex};
+//!
+//! let executor = CurrentThread::new();
+//! let order = Arc::new(Mutex::new(Vec::new()));
+//!
+//! for id in 0..2 {
+//!     let order = order.clone();
+//!     executor.spawn(
+//!         async move {
+//!             order.lock().unwrap().push(id);
+//!             yield_now().await;
+//!             order.lock().unwrap().push(id);
+//!         },
+//!         ProcStack::default(),
+//!     );
+//! }
+//!
+//! executor.run_until_stalled();
+//! assert_eq!(*order.lock().unwrap(), vec![0, 1, 0, 1]);
+//! ```
+use crate::run;
+use crossbeam_utils::sync::{Parker, Unparker};
+use lightproc::prelude::*;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+///
+/// Executor running its processes on the thread driving it, in a deterministic order.
+///
+/// Cloning the executor returns a new handle to the same queue of processes.
+#[derive(Clone, Default)]
+pub struct CurrentThread {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    // The processes waiting to run, in the order they were scheduled in.
+    queue: Mutex<VecDeque<LightProc>>,
+    // Wakes up the thread driving the executor, if any.
+    driver: Mutex<Option<Unparker>>,
+}
+
+impl CurrentThread {
+    ///
+    /// Creates a new executor without any process to run.
+    pub fn new() -> Self {
+        CurrentThread::default()
+    }
+
+    ///
+    /// Schedules an already built process to run after the ones already scheduled.
+    pub fn schedule(&self, proc: LightProc) {
+        self.inner.queue.lock().unwrap().push_back(proc);
+
+        if let Some(driver) = &*self.inner.driver.lock().unwrap() {
+            driver.unpark();
+        }
+    }
+
+    ///
+    /// Spawns a process (which contains future + process stack) onto the executor.
+    ///
+    /// The process only starts running once the executor is driven.
+    pub fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let executor = self.clone();
+        let schedule = move |proc| executor.schedule(proc);
+        let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+        proc.schedule();
+        handle
+    }
+
+    ///
+    /// Runs the scheduled processes until none of them is left to run, and returns
+    /// how many times processes were run.
+    ///
+    /// The processes which are waiting on something else than another process of
+    /// the executor (e.g. on a timer) stay pending.
+    pub fn run_until_stalled(&self) -> usize {
+        let mut runs = 0;
+        while let Some(proc) = self.pop() {
+            proc.run();
+            runs += 1;
+        }
+
+        runs
+    }
+
+    ///
+    /// Blocks the current thread until the passed future is resolved, running the
+    /// processes of the executor on it in the meantime.
+    ///
+    /// The future is polled again after each run of a process, and the thread is
+    /// parked while there is no process to run.
+    pub fn block_on<F, T>(&self, future: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        pin_utils::pin_mut!(future);
+
+        let parker = Parker::new();
+        let waker = run::waker(parker.unparker().clone());
+        let cx = &mut Context::from_waker(&waker);
+
+        let previous = self
+            .inner
+            .driver
+            .lock()
+            .unwrap()
+            .replace(parker.unparker().clone());
+
+        let output = loop {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                break output;
+            }
+
+            match self.pop() {
+                Some(proc) => proc.run(),
+                None => parker.park(),
+            }
+        };
+
+        *self.inner.driver.lock().unwrap() = previous;
+        output
+    }
+
+    ///
+    /// Returns the number of processes waiting to run.
+    pub fn len(&self) -> usize {
+        self.inner.queue.lock().unwrap().len()
+    }
+
+    ///
+    /// Returns whether no process is waiting to run.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn pop(&self) -> Option<LightProc> {
+        // The lock is released before running the process, which might
+        // schedule other ones.
+        self.inner.queue.lock().unwrap().pop_front()
+    }
+}
+
+impl Debug for CurrentThread {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("CurrentThread")
+            .field("queued", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CurrentThread;
+    use crate::pool::yield_now;
+    use futures::channel::oneshot;
+    use lightproc::proc_stack::ProcStack;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn interleaves_procs_in_a_fixed_order() {
+        let executor = CurrentThread::new();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for id in 0..3 {
+            let order = order.clone();
+            executor.spawn(
+                async move {
+                    for step in 0..2 {
+                        order.lock().unwrap().push((id, step));
+                        yield_now().await;
+                    }
+                },
+                ProcStack::default(),
+            );
+        }
+
+        assert_eq!(executor.len(), 3);
+        assert_eq!(executor.run_until_stalled(), 9);
+        assert!(executor.is_empty());
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn block_on_drives_the_spawned_procs() {
+        let executor = CurrentThread::new();
+        let driver = thread::current().id();
+
+        let handle = executor.spawn(
+            async move {
+                yield_now().await;
+                thread::current().id()
+            },
+            ProcStack::default(),
+        );
+
+        assert_eq!(executor.block_on(handle), Some(driver));
+    }
+
+    #[test]
+    fn block_on_wakes_up_when_a_proc_is_scheduled() {
+        let executor = CurrentThread::new();
+        let (sender, recver) = oneshot::channel();
+
+        // The proc is scheduled while the driving thread is parked.
+        let spawner = executor.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            spawner.spawn(
+                async move { sender.send(42).unwrap() },
+                ProcStack::default(),
+            );
+        });
+
+        assert_eq!(executor.block_on(recver), Ok(42));
+    }
+}
@@ -0,0 +1,47 @@
+//!
+//! Deadlines of the processes spawned with one (see [ProcStack::with_deadline]), which
+//! the load balancer thread cancels once they are exceeded.
+//!
+//! The deadlines are checked about four times a second, so a process can run for a
+//! little longer than its deadline before being cancelled.
+use crate::load_balancer;
+use lazy_static::lazy_static;
+use lightproc::prelude::*;
+use std::sync::Mutex;
+use std::time::Instant;
+
+lazy_static! {
+    static ref DEADLINES: Mutex<Vec<(Instant, AbortHandle)>> = Mutex::new(Vec::new());
+}
+
+/// Cancels the process of the given handle once it exceeds the deadline of its stack,
+/// if there is one.
+pub(crate) fn watch<T>(handle: &RecoverableHandle<T>) {
+    if let Some(deadline) = handle.stack().deadline() {
+        let deadline = Instant::now() + deadline;
+        DEADLINES
+            .lock()
+            .unwrap()
+            .push((deadline, handle.abort_handle()));
+
+        // The process can't be cancelled without the load balancer thread, but it
+        // still runs like the processes without a deadline.
+        let _ = load_balancer::start_sampling();
+    }
+}
+
+/// Cancels the processes whose deadline was exceeded, forgetting the ones which
+/// finished.
+pub(crate) fn expire() {
+    let now = Instant::now();
+    DEADLINES.lock().unwrap().retain(|(deadline, abort)| {
+        if abort.is_finished() {
+            false
+        } else if *deadline <= now {
+            abort.abort();
+            false
+        } else {
+            true
+        }
+    });
+}
@@ -7,8 +7,12 @@ use crate::placement::{self, CoreId};
 use crate::run_queue::{Stealer, Worker};
 use crate::worker;
 use lightproc::prelude::*;
+use std::io;
 use std::thread;
 
+/// The closure run by a worker thread.
+type WorkerMain = Box<dyn FnOnce() + Send>;
+
 pub(crate) struct Distributor {
     pub(crate) cores: Vec<CoreId>,
 }
@@ -16,31 +20,111 @@ pub(crate) struct Distributor {
 impl Distributor {
     pub(crate) fn new() -> Self {
         Distributor {
-            cores: placement::get_core_ids().expect("Core mapping couldn't be fetched"),
+            cores: placement::worker_core_ids().to_vec(),
         }
     }
 
-    pub(crate) fn assign(self) -> Vec<Stealer<LightProc>> {
-        let mut stealers = Vec::<Stealer<LightProc>>::new();
+    /// Starts a worker on each core, returning the stealers of the ones which started along
+    /// with their core.
+    ///
+    /// The cores whose worker thread couldn't be spawned are left out, and an error is
+    /// only returned if no worker could be started at all.
+    pub(crate) fn assign(self) -> io::Result<Vec<(usize, Stealer<LightProc>)>> {
+        self.assign_with(|builder, main| builder.spawn(main).map(drop))
+    }
+
+    fn assign_with<S>(self, spawn: S) -> io::Result<Vec<(usize, Stealer<LightProc>)>>
+    where
+        S: Fn(thread::Builder, WorkerMain) -> io::Result<()>,
+    {
+        let mut stealers = Vec::new();
+        let mut last_err = None;
 
         for core in self.cores {
             let wrk = Worker::new_fifo();
-            stealers.push(wrk.stealer());
-
-            thread::Builder::new()
-                .name("bastion-async-thread".to_string())
-                .spawn(move || {
-                    // affinity assignment
-                    placement::set_for_current(core);
-
-                    // run initial stats generation for cores
-                    worker::stats_generator(core.id, &wrk);
-                    // actual execution
-                    worker::main_loop(core.id, wrk);
-                })
-                .expect("cannot start the thread for running proc");
+            let stealer = wrk.stealer();
+
+            let builder = thread::Builder::new().name(worker_thread_name(core.id));
+            let main = Box::new(move || {
+                // affinity assignment
+                placement::set_for_current(core);
+                // priority assignment
+                placement::set_worker_priority_for_current();
+
+                // run initial stats generation for cores
+                worker::stats_generator(core.id, &wrk);
+                // actual execution
+                worker::main_loop(core.id, wrk, || true);
+            });
+
+            match spawn(builder, main) {
+                Ok(()) => stealers.push((core.id, stealer)),
+                Err(err) => {
+                    eprintln!(
+                        "cannot start the thread for running proc on core {}: {}",
+                        core.id, err
+                    );
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        match last_err {
+            Some(err) if stealers.is_empty() => Err(err),
+            _ => Ok(stealers),
         }
+    }
+}
+
+/// Returns the name of the thread of the worker pinned to the given core.
+///
+/// OS thread names are truncated to 15 bytes on Linux, which is why the name
+/// is kept short enough to still tell the workers apart in `top -H`.
+pub(crate) fn worker_thread_name(core_id: usize) -> String {
+    format!("bastion-wrk-{}", core_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Distributor;
+    use crate::placement::CoreId;
+    use std::cell::Cell;
+    use std::io;
+
+    #[test]
+    fn assign_with_unknown_topology() {
+        // The fallback used when the core mapping couldn't be fetched.
+        let distributor = Distributor {
+            cores: vec![CoreId { id: 0 }],
+        };
+
+        let stealers = distributor.assign().unwrap();
+        assert_eq!(stealers.len(), 1);
+    }
+
+    #[test]
+    fn assign_survives_spawn_failures() {
+        let cores = |ids: &[usize]| Distributor {
+            cores: ids.iter().map(|&id| CoreId { id }).collect(),
+        };
+        let out_of_threads = || io::Error::new(io::ErrorKind::WouldBlock, "out of threads");
+
+        // The workers which couldn't start are left out...
+        let spawned = Cell::new(0);
+        let stealers = cores(&[0, 1])
+            .assign_with(|_, _| {
+                spawned.set(spawned.get() + 1);
+                if spawned.get() == 1 {
+                    Err(out_of_threads())
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap();
+        assert_eq!(stealers.len(), 1);
 
-        stealers
+        // ...unless none of them could.
+        let res = cores(&[0]).assign_with(|_, _| Err(out_of_threads()));
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::WouldBlock);
     }
 }
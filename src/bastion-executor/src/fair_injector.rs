@@ -0,0 +1,282 @@
+//!
+//! Bounded global run queue sharing its capacity fairly between the producers.
+//!
+//! A single [Injector] is drained in the order the processes were pushed in, so a
+//! thread spawning lots of processes makes the processes spawned by the other threads
+//! wait behind all of its own. The [FairInjector] gives each producing thread one of
+//! its shards instead, and the consumers visit the shards in turn, which gives the
+//! producers a roughly equal share of the consumers.
+//!
+//! The queue is also bounded, so that spawning onto it can wait for room instead of
+//! growing it without limit.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::fair_injector::FairInjector;
+//! use bastion_executor::run_queue::Worker;
+//!
+//! let injector = FairInjector::new(2);
+//! injector.try_push(1).unwrap();
+//! injector.try_push(2).unwrap();
+//!
+//! // The queue is full.
+//! assert_eq!(injector.try_push(3), Err(3));
+//!
+//! let local = Worker::new_fifo();
+//! assert_eq!(injector.steal_batch_and_pop(&local).success(), Some(1));
+//! assert_eq!(injector.len(), 1);
+//! ```
+use crate::run_queue::{Injector, Steal, Worker};
+use std::cell::Cell;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The amount of shards of a [FairInjector].
+pub const SHARDS: usize = 8;
+
+/// The capacity of the global run queue of the pool, if it isn't changed.
+pub const DEFAULT_CAPACITY: usize = 1 << 16;
+
+/// Hands out the shards to the producing threads in turn.
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Returns the shard the current thread pushes its processes to.
+fn shard() -> usize {
+    SHARD.with(|shard| match shard.get() {
+        Some(shard) => shard,
+        None => {
+            let next = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS;
+            shard.set(Some(next));
+            next
+        }
+    })
+}
+
+///
+/// Global run queue made of one [Injector] per group of producing threads, whose
+/// consumers take from each of them in turn.
+pub struct FairInjector<T> {
+    shards: [Injector<T>; SHARDS],
+    // The shard the next consumer starts looking at.
+    cursor: AtomicUsize,
+    len: AtomicUsize,
+    capacity: AtomicUsize,
+}
+
+impl<T> FairInjector<T> {
+    ///
+    /// Creates a new queue holding at most `capacity` items pushed with [FairInjector::try_push].
+    pub fn new(capacity: usize) -> Self {
+        FairInjector {
+            shards: Default::default(),
+            cursor: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            capacity: AtomicUsize::new(capacity),
+        }
+    }
+
+    ///
+    /// Pushes an item to the shard of the current thread, even if the queue is full.
+    ///
+    /// This is meant for the items which can't wait, like processes being woken up.
+    pub fn push(&self, item: T) {
+        self.len.fetch_add(1, Ordering::AcqRel);
+        self.shards[shard()].push(item);
+    }
+
+    ///
+    /// Pushes an item to the shard of the current thread if the queue isn't full, or
+    /// gives it back otherwise.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let capacity = self.capacity();
+        let reserved = self
+            .len
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |len| {
+                if len < capacity {
+                    Some(len + 1)
+                } else {
+                    None
+                }
+            });
+
+        match reserved {
+            Ok(_) => {
+                self.shards[shard()].push(item);
+                Ok(())
+            }
+            Err(_) => Err(item),
+        }
+    }
+
+    ///
+    /// Steals a batch of items from the next non-empty shard, moves them to `dest` and
+    /// pops one of them.
+    pub fn steal_batch_and_pop(&self, dest: &Worker<T>) -> Steal<T> {
+        let start = self.cursor.load(Ordering::Relaxed);
+        // The batch is stolen into a queue nobody else can steal from, so that the
+        // items taken from the shard can be counted before being moved to `dest`.
+        let batch = Worker::new_fifo();
+
+        let mut retry = false;
+        for i in 0..SHARDS {
+            let shard = (start + i) % SHARDS;
+            match self.shards[shard].steal_batch_and_pop(&batch) {
+                Steal::Success(item) => {
+                    // The next consumer starts with the next shard, whichever
+                    // shards were empty.
+                    self.cursor.store(shard + 1, Ordering::Relaxed);
+                    let mut taken = 1;
+                    while let Some(item) = batch.pop() {
+                        dest.push(item);
+                        taken += 1;
+                    }
+                    self.len.fetch_sub(taken, Ordering::AcqRel);
+                    return Steal::Success(item);
+                }
+                Steal::Retry => retry = true,
+                Steal::Empty => (),
+            }
+        }
+
+        if retry {
+            Steal::Retry
+        } else {
+            Steal::Empty
+        }
+    }
+
+    ///
+    /// Returns the amount of items in the queue, counting the ones being pushed.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    ///
+    /// Returns whether the queue is empty, which is the case when [FairInjector::len]
+    /// is `0`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Returns whether [FairInjector::try_push] would give its item back.
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+
+    ///
+    /// Returns the maximum amount of items pushed with [FairInjector::try_push].
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Sets the maximum amount of items pushed with [FairInjector::try_push].
+    ///
+    /// The items already in the queue are kept if there are more of them.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+    }
+}
+
+impl<T> Debug for FairInjector<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("FairInjector")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FairInjector, SHARDS};
+    use crate::run_queue::Worker;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn shares_the_consumers_between_producers() {
+        let injector = FairInjector::new(1024);
+
+        // A hot producer pushes a lot of items before another one pushes a few.
+        thread::scope(|scope| {
+            scope.spawn(|| (0..100).for_each(|_| injector.push("hot")));
+        });
+        thread::scope(|scope| {
+            scope.spawn(|| (0..5).for_each(|_| injector.push("cold")));
+        });
+        assert_eq!(injector.len(), 105);
+
+        // The items of the cold producer don't wait behind all the hot ones.
+        let mut taken = Vec::new();
+        while taken.iter().filter(|item| **item == "cold").count() < 5 {
+            let local = Worker::new_fifo();
+            taken.push(injector.steal_batch_and_pop(&local).success().unwrap());
+            while let Some(item) = local.pop() {
+                taken.push(item);
+            }
+        }
+        assert!(taken.len() < 100);
+        assert_eq!(injector.len(), 105 - taken.len());
+    }
+
+    #[test]
+    fn bounds_the_pushes_which_can_wait() {
+        let injector = FairInjector::new(SHARDS);
+        for i in 0..SHARDS {
+            injector.try_push(i).unwrap();
+        }
+        assert!(injector.is_full());
+        assert_eq!(injector.try_push(SHARDS), Err(SHARDS));
+
+        // Pushes which can't wait go through anyway.
+        injector.push(SHARDS);
+        assert_eq!(injector.len(), SHARDS + 1);
+
+        injector.set_capacity(SHARDS * 2);
+        assert!(injector.try_push(SHARDS + 1).is_ok());
+    }
+
+    #[test]
+    fn counts_the_items_stolen_meanwhile() {
+        let injector = FairInjector::new(1024);
+        (0..1000).for_each(|i| injector.push(i));
+
+        // Other threads steal from the consumer's queue while it takes
+        // batches from the injector.
+        let local = Worker::new_fifo();
+        let stealer = local.stealer();
+        let done = AtomicBool::new(false);
+        let stolen = AtomicUsize::new(0);
+        let mut taken = 0;
+        thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    while !done.load(Ordering::Acquire) {
+                        if stealer.steal().is_success() {
+                            stolen.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+
+            while injector.steal_batch_and_pop(&local).success().is_some() {
+                taken += 1;
+                while local.pop().is_some() {
+                    taken += 1;
+                }
+            }
+            done.store(true, Ordering::Release);
+        });
+
+        assert_eq!(taken + stolen.load(Ordering::Relaxed), 1000);
+        assert_eq!(injector.len(), 0);
+        assert!(injector.is_empty());
+    }
+}
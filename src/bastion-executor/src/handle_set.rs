@@ -0,0 +1,180 @@
+//!
+//! Set of process handles which resolves them in their completion order.
+//!
+//! It is useful to wait for a group of processes without caring about
+//! the order they were spawned in, e.g. while shutting down workers.
+use crate::run;
+use crossbeam_utils::sync::Parker;
+use lightproc::recoverable_handle::RecoverableHandle;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+///
+/// Set of [RecoverableHandle]s which yields their outputs as soon as they complete.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let mut set = ProcHandleSet::new();
+/// set.push(spawn(async { 1 }, ProcStack::default()));
+/// set.push(spawn(async { 2 }, ProcStack::default()));
+///
+/// let sum = run(
+///     async move {
+///         let mut sum = 0;
+///         while let Some(res) = set.join_next().await {
+///             sum += res.unwrap();
+///         }
+///         sum
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// assert_eq!(sum, 3);
+/// ```
+pub struct ProcHandleSet<T> {
+    handles: Vec<RecoverableHandle<T>>,
+}
+
+impl<T> ProcHandleSet<T> {
+    ///
+    /// Creates an empty set of handles.
+    pub fn new() -> Self {
+        ProcHandleSet {
+            handles: Vec::new(),
+        }
+    }
+
+    ///
+    /// Adds the handle of a process to the set.
+    pub fn push(&mut self, handle: RecoverableHandle<T>) {
+        self.handles.push(handle);
+    }
+
+    ///
+    /// Amount of processes of the set which didn't complete yet.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    ///
+    /// Returns `true` if all the processes of the set completed.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    ///
+    /// Returns a future resolving to the output of the next process of the set
+    /// to complete, or to `None` if the set is empty.
+    ///
+    /// The output of a process is `None` if it panicked or got cancelled.
+    pub fn join_next(&mut self) -> JoinNext<'_, T> {
+        JoinNext { set: self }
+    }
+
+    ///
+    /// Blocks the current thread until all the processes of the set completed
+    /// or until the given duration elapsed.
+    ///
+    /// Returns the outputs of the processes which completed, in their completion
+    /// order, and the handles of the processes which are still running. The
+    /// latter aren't cancelled, it's up to the caller to decide what to do with them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::prelude::*;
+    /// use lightproc::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut set = ProcHandleSet::new();
+    /// set.push(spawn(async { 1 }, ProcStack::default()));
+    /// set.push(spawn(
+    ///     async {
+    ///         loop {
+    ///             yield_now().await;
+    ///         }
+    ///     },
+    ///     ProcStack::default(),
+    /// ));
+    ///
+    /// let (done, running) = set.join_all_timeout(Duration::from_millis(100));
+    /// assert_eq!(done, vec![Some(1)]);
+    /// assert_eq!(running.len(), 1);
+    ///
+    /// running.iter().for_each(|handle| handle.cancel());
+    /// ```
+    pub fn join_all_timeout(
+        mut self,
+        dur: Duration,
+    ) -> (Vec<Option<T>>, Vec<RecoverableHandle<T>>) {
+        let deadline = Instant::now() + dur;
+        let mut done = Vec::with_capacity(self.handles.len());
+
+        let parker = Parker::new();
+        let waker = run::waker(parker.unparker().clone());
+        let cx = &mut Context::from_waker(&waker);
+
+        loop {
+            while let Poll::Ready(Some(res)) = self.poll_next(cx) {
+                done.push(res);
+            }
+
+            let now = Instant::now();
+            if self.handles.is_empty() || now >= deadline {
+                return (done, self.handles);
+            }
+
+            parker.park_timeout(deadline - now);
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Option<T>>> {
+        if self.handles.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        for i in 0..self.handles.len() {
+            if let Poll::Ready(res) = Pin::new(&mut self.handles[i]).poll(cx) {
+                self.handles.swap_remove(i);
+                return Poll::Ready(Some(res));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T> Default for ProcHandleSet<T> {
+    fn default() -> Self {
+        ProcHandleSet::new()
+    }
+}
+
+impl<T> Debug for ProcHandleSet<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("ProcHandleSet")
+            .field("handles", &self.handles)
+            .finish()
+    }
+}
+
+///
+/// Future returned by [ProcHandleSet::join_next].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinNext<'a, T> {
+    set: &'a mut ProcHandleSet<T>,
+}
+
+impl<T> Future for JoinNext<'_, T> {
+    type Output = Option<Option<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().set.poll_next(cx)
+    }
+}
@@ -34,15 +34,26 @@
 )]
 #[macro_use]
 mod macros;
+mod deadlines;
 
 pub mod allocator;
 pub mod blocking;
+pub mod current_thread;
 pub mod distributor;
+pub mod fair_injector;
+pub mod handle_set;
+pub mod lifecycle;
 pub mod load_balancer;
+pub mod local;
+#[cfg(feature = "metrics-server")]
+pub mod metrics_server;
 pub mod placement;
 pub mod pool;
+pub mod proc_stream;
+pub mod profiler;
 pub mod run;
 pub mod run_queue;
+pub mod scheduling;
 pub mod sleepers;
 pub mod worker;
 
@@ -50,6 +61,9 @@ pub mod worker;
 /// Prelude of Bastion Executor
 pub mod prelude {
     pub use crate::blocking::*;
+    pub use crate::handle_set::*;
+    pub use crate::local::*;
     pub use crate::pool::*;
+    pub use crate::proc_stream::*;
     pub use crate::run::*;
 }
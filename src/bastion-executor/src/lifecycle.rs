@@ -0,0 +1,82 @@
+//!
+//! Lifecycle events of the workers, for observing when they start, go idle and stop.
+//!
+//! A single callback can be [subscribe]d, which the workers call on their own thread every time
+//! one of their [WorkerEvent]s happens, so it should return quickly. While no callback is
+//! subscribed, the workers only check whether one is before emitting their events.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::lifecycle::{self, WorkerEvent};
+//! use bastion_executor::prelude::*;
+//! use lightproc::prelude::*;
+//!
+//! lifecycle::subscribe(|event| {
+//!     if let WorkerEvent::Parked(core_id) = event {
+//!         println!("the worker of core {} is idle", core_id);
+//!     }
+//! });
+//!
+//! let handle = spawn(async { 1 + 2 }, ProcStack::default());
+//! assert_eq!(run(handle, ProcStack::default()), Some(3));
+//!
+//! lifecycle::unsubscribe();
+//! ```
+use crossbeam_utils::sync::ShardedLock;
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+type Subscriber = Box<dyn Fn(WorkerEvent) + Send + Sync>;
+
+static SUBSCRIBED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref SUBSCRIBER: ShardedLock<Option<Subscriber>> = ShardedLock::new(None);
+}
+
+///
+/// Event emitted by the worker of a core (see [subscribe]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkerEvent {
+    /// The worker started running processes.
+    Started(usize),
+    /// The worker has nothing to run and goes to sleep.
+    Parked(usize),
+    /// The worker woke up.
+    Unparked(usize),
+    /// The worker stopped running processes, which only happens for the external workers (see
+    /// [pool::run_worker]).
+    ///
+    /// [pool::run_worker]: ../pool/fn.run_worker.html
+    Stopped(usize),
+}
+
+///
+/// Subscribes the given callback to the events of the workers, replacing the previous one.
+pub fn subscribe<F>(callback: F)
+where
+    F: Fn(WorkerEvent) + Send + Sync + 'static,
+{
+    *SUBSCRIBER.write().unwrap() = Some(Box::new(callback));
+    SUBSCRIBED.store(true, Ordering::Release);
+}
+
+///
+/// Unsubscribes the callback subscribed to the events of the workers, if any.
+pub fn unsubscribe() {
+    SUBSCRIBED.store(false, Ordering::Release);
+    SUBSCRIBER.write().unwrap().take();
+}
+
+///
+/// Passes the event to the subscribed callback, if any.
+pub(crate) fn emit(event: WorkerEvent) {
+    if !SUBSCRIBED.load(Ordering::Acquire) {
+        return;
+    }
+
+    if let Some(callback) = &*SUBSCRIBER.read().unwrap() {
+        callback(event);
+    }
+}
@@ -4,13 +4,25 @@
 //! Load balancer calculates sampled mean to provide average process execution amount
 //! to all runtime.
 //!
+//! The statistics are kept in atomics rather than behind a lock, so the workers and the
+//! sampling thread update them without waiting on each other, and no update is ever skipped
+//! when they are contended.
+//!
+use crate::deadlines;
 use crate::load_balancer;
 use crate::placement;
+use crate::profiler;
+#[cfg(feature = "poll-stats")]
+use fxhash::FxHashMap;
 use lazy_static::*;
+use std::io;
 use std::mem::MaybeUninit;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
 use std::thread;
 use std::time::Duration;
+#[cfg(feature = "poll-stats")]
+use std::time::Instant;
 use std::{fmt, usize};
 
 /// Stats of all the smp queues.
@@ -32,12 +44,29 @@ pub struct LoadBalancer;
 impl LoadBalancer {
     ///
     /// AMQL sampling thread for run queue load balancing.
-    pub fn amql_generation() {
+    ///
+    /// Returns an error if the thread couldn't be spawned, in which case the mean
+    /// level of the run queues is only updated by the workers themselves.
+    pub fn amql_generation() -> io::Result<()> {
         thread::Builder::new()
             .name("bastion-load-balancer-thread".to_string())
             .spawn(move || {
+                #[cfg(feature = "poll-stats")]
+                let mut sampled_at = Instant::now();
                 loop {
                     load_balancer::stats().update_mean();
+                    #[cfg(feature = "poll-stats")]
+                    {
+                        let now = Instant::now();
+                        load_balancer::stats().update_utilization(now - sampled_at);
+                        sampled_at = now;
+                    }
+                    profiler::sample();
+                    deadlines::expire();
+                    let hooks = sample_hooks().lock().unwrap().clone();
+                    for hook in hooks {
+                        hook();
+                    }
                     // We don't have β-reduction here… Life is unfair. Life is cruel.
                     //
                     // Try sleeping for a while to wait
@@ -47,61 +76,655 @@ impl LoadBalancer {
                     thread::yield_now();
                 }
             })
-            .expect("load-balancer couldn't start");
+            .map(drop)
     }
 }
 
 /// Maximum number of core supported by modern computers.
 const MAX_CORE: usize = 256;
 
+/// Smoothing factor of the mean level of the run queues if it isn't changed, which
+/// makes the mean the instantaneous average of the queues.
+pub const DEFAULT_MEAN_SMOOTHING: f64 = 1.0;
+
+/// Mean level of the run queues under which the workers stop stealing from each other,
+/// if it isn't changed, which never throttles the steals.
+pub const DEFAULT_STEAL_THRESHOLD: usize = 0;
+
+/// Maximum amount of live processes spawned on the pool, if it isn't changed, which
+/// never rejects a spawn.
+pub const DEFAULT_MAX_TASKS: usize = usize::MAX;
+
+///
+/// What spawning a process does once the maximum amount of live processes was reached
+/// (see [Stats::set_max_tasks]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverLimitPolicy {
+    /// The spawn is rejected right away.
+    #[default]
+    Reject,
+    /// The spawning thread blocks until a live process completes or gets cancelled.
+    ///
+    /// The spawns from a process are rejected right away, since blocking the thread
+    /// running it could deadlock the pool.
+    Block,
+    /// The spawning thread blocks until a live process completes or gets cancelled, or
+    /// the spawn is rejected once the given amount of time elapsed.
+    ///
+    /// The spawns from a process are rejected right away, like for `Block`.
+    BlockWithTimeout(Duration),
+}
+
+impl OverLimitPolicy {
+    // The policy is stored in a single atomic, as `0` for `Reject`,
+    // `u64::MAX` for `Block` and the timeout in nanoseconds plus one
+    // for `BlockWithTimeout`.
+    fn to_bits(self) -> u64 {
+        match self {
+            OverLimitPolicy::Reject => 0,
+            OverLimitPolicy::Block => u64::MAX,
+            OverLimitPolicy::BlockWithTimeout(timeout) => {
+                (timeout.as_nanos().min(u128::from(u64::MAX - 2)) as u64) + 1
+            }
+        }
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        match bits {
+            0 => OverLimitPolicy::Reject,
+            u64::MAX => OverLimitPolicy::Block,
+            nanos => OverLimitPolicy::BlockWithTimeout(Duration::from_nanos(nanos - 1)),
+        }
+    }
+}
+
 ///
 /// Holding all statistics related to the run queue
 ///
 /// Contains:
 /// * Mean level of processes in the run queues
 /// * SMP queue distributions
+/// * Amount of steal attempts, successful steals and pushes to the global queue
+/// * Amount of processes waiting in the global queue
+/// * Amount of live processes spawned on the pool and of rejected spawns
+/// * Time spent polling and amount of polls per core (with the `poll-stats` feature)
+/// * Utilization of each core in the last sampling window (with the `poll-stats` feature)
 pub struct Stats {
     smp_load: [AtomicUsize; MAX_CORE],
     mean_level: AtomicUsize,
+    // The unrounded mean level (as the bits of a `f64`), blended
+    // with the next samples, and the weight of those samples.
+    mean_ewma: AtomicU64,
+    mean_smoothing: AtomicU64,
+    steal_threshold: AtomicUsize,
+    steals_attempted: AtomicUsize,
+    steals_succeeded: AtomicUsize,
+    steals_throttled: AtomicUsize,
+    global_pushes: AtomicUsize,
+    global_run_queue: AtomicUsize,
+    max_tasks: AtomicUsize,
+    over_limit_policy: AtomicU64,
+    workers: AtomicUsize,
+    live_tasks: AtomicUsize,
+    tasks_rejected: AtomicUsize,
+    #[cfg(feature = "poll-stats")]
+    poll_time: [AtomicU64; MAX_CORE],
+    #[cfg(feature = "poll-stats")]
+    poll_count: [AtomicUsize; MAX_CORE],
+    // The time spent polling on each core when the utilization
+    // was last sampled, the time spent polling during the last
+    // sampling window and the length of that window, in nanoseconds.
+    #[cfg(feature = "poll-stats")]
+    sampled_poll_time: [AtomicU64; MAX_CORE],
+    #[cfg(feature = "poll-stats")]
+    window_poll_time: [AtomicU64; MAX_CORE],
+    #[cfg(feature = "poll-stats")]
+    window: AtomicU64,
 }
 
 impl fmt::Debug for Stats {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.debug_struct("Stats")
+        let mut stats = fmt.debug_struct("Stats");
+        stats
             .field("smp_load", &&self.smp_load[..])
             .field("mean_level", &self.mean_level)
-            .finish()
+            .field("mean_smoothing", &self.mean_smoothing())
+            .field("steal_threshold", &self.steal_threshold)
+            .field("steals_attempted", &self.steals_attempted)
+            .field("steals_succeeded", &self.steals_succeeded)
+            .field("steals_throttled", &self.steals_throttled)
+            .field("global_pushes", &self.global_pushes)
+            .field("global_run_queue", &self.global_run_queue)
+            .field("max_tasks", &self.max_tasks)
+            .field("over_limit_policy", &self.over_limit_policy())
+            .field("workers", &self.workers)
+            .field("live_tasks", &self.live_tasks)
+            .field("tasks_rejected", &self.tasks_rejected);
+        #[cfg(feature = "poll-stats")]
+        stats
+            .field("poll_time", &&self.poll_time[..])
+            .field("poll_count", &&self.poll_count[..])
+            .field("per_core_utilization", &self.per_core_utilization());
+        stats.finish()
     }
 }
 
 impl Stats {
     /// new returns LockLessStats
     pub fn new(num_cores: usize) -> Stats {
-        let smp_load: [AtomicUsize; MAX_CORE] = {
-            let mut data: [MaybeUninit<AtomicUsize>; MAX_CORE] =
-                unsafe { MaybeUninit::uninit().assume_init() };
-            let mut i = 0;
-            while i < MAX_CORE {
-                if i < num_cores {
-                    unsafe {
-                        std::ptr::write(data[i].as_mut_ptr(), AtomicUsize::new(0));
-                    }
-                    i += 1;
-                    continue;
-                }
-                // MAX is for unused slot.
-                unsafe {
-                    std::ptr::write(data[i].as_mut_ptr(), AtomicUsize::new(usize::MAX));
-                }
-                i += 1;
-            }
-            unsafe { std::mem::transmute::<_, [AtomicUsize; MAX_CORE]>(data) }
-        };
+        // MAX is for unused slot.
+        let smp_load = atomic_array(|i| if i < num_cores { 0 } else { usize::MAX });
         Stats {
             smp_load,
             mean_level: AtomicUsize::new(0),
+            mean_ewma: AtomicU64::new(0f64.to_bits()),
+            mean_smoothing: AtomicU64::new(DEFAULT_MEAN_SMOOTHING.to_bits()),
+            steal_threshold: AtomicUsize::new(DEFAULT_STEAL_THRESHOLD),
+            steals_attempted: AtomicUsize::new(0),
+            steals_succeeded: AtomicUsize::new(0),
+            steals_throttled: AtomicUsize::new(0),
+            global_pushes: AtomicUsize::new(0),
+            global_run_queue: AtomicUsize::new(0),
+            max_tasks: AtomicUsize::new(DEFAULT_MAX_TASKS),
+            over_limit_policy: AtomicU64::new(OverLimitPolicy::default().to_bits()),
+            workers: AtomicUsize::new(num_cores),
+            live_tasks: AtomicUsize::new(0),
+            tasks_rejected: AtomicUsize::new(0),
+            #[cfg(feature = "poll-stats")]
+            poll_time: atomic_u64_array(),
+            #[cfg(feature = "poll-stats")]
+            poll_count: atomic_array(|_| 0),
+            #[cfg(feature = "poll-stats")]
+            sampled_poll_time: atomic_u64_array(),
+            #[cfg(feature = "poll-stats")]
+            window_poll_time: atomic_u64_array(),
+            #[cfg(feature = "poll-stats")]
+            window: AtomicU64::new(0),
+        }
+    }
+
+    ///
+    /// Total amount of processes waiting in all smp queues and in the global queue.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::{SmpStats, Stats};
+    ///
+    /// let stats = Stats::new(2);
+    /// stats.store_load(0, 3);
+    /// stats.store_load(1, 4);
+    /// stats.store_global_run_queue(2);
+    ///
+    /// assert_eq!(stats.total_queued(), 9);
+    /// ```
+    pub fn total_queued(&self) -> usize {
+        self.smp_queued().saturating_add(self.global_run_queue())
+    }
+
+    /// Amount of processes waiting in all smp queues.
+    fn smp_queued(&self) -> usize {
+        self.smp_load
+            .iter()
+            .map(|item| item.load(Ordering::SeqCst))
+            // load till maximum core.
+            .take_while(|load| *load != usize::MAX)
+            .fold(0, usize::saturating_add)
+    }
+
+    ///
+    /// Returns `true` if the amount of queued processes reached the given threshold.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::{SmpStats, Stats};
+    ///
+    /// let stats = Stats::new(2);
+    /// stats.store_load(0, 3);
+    ///
+    /// assert!(stats.is_saturated(3));
+    /// assert!(!stats.is_saturated(4));
+    /// ```
+    pub fn is_saturated(&self, threshold: usize) -> bool {
+        self.total_queued() >= threshold
+    }
+
+    ///
+    /// Sets the smoothing factor of the mean level of the run queues.
+    ///
+    /// Each time the mean is updated, the new average of the run queues is weighted
+    /// by `smoothing` and blended with the previous mean, weighted by `1 - smoothing`
+    /// (an exponentially weighted moving average). Lower factors make the mean follow
+    /// bursts of processes more slowly, which keeps the workers from oscillating
+    /// between stealing and not stealing. A factor of `1.0` (the default) makes the
+    /// mean the instantaneous average.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `smoothing` isn't in `(0.0, 1.0]`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::{core_retrieval, SmpStats, Stats};
+    ///
+    /// let cores = *core_retrieval();
+    /// let stats = Stats::new(cores);
+    /// stats.set_mean_smoothing(0.5);
+    ///
+    /// // A burst of 8 processes per core.
+    /// stats.store_load(0, 8 * cores);
+    /// stats.update_mean();
+    /// assert_eq!(stats.mean(), 4);
+    /// stats.update_mean();
+    /// assert_eq!(stats.mean(), 6);
+    /// ```
+    pub fn set_mean_smoothing(&self, smoothing: f64) {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "the smoothing factor must be in (0.0, 1.0], got {}",
+            smoothing
+        );
+        self.mean_smoothing
+            .store(smoothing.to_bits(), Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns the smoothing factor of the mean level of the run queues.
+    pub fn mean_smoothing(&self) -> f64 {
+        f64::from_bits(self.mean_smoothing.load(Ordering::Relaxed))
+    }
+
+    ///
+    /// Sets the mean level of the run queues under which the workers stop stealing
+    /// processes from each other's queues.
+    ///
+    /// Under a light load, stealing the few processes queued by the other workers moves
+    /// them (and their data) across cores for little benefit. Throttled workers still
+    /// take processes from the global queues, and park instead of spinning over the
+    /// other workers' queues. A threshold of `0` (the default) never throttles the steals.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::{core_retrieval, SmpStats, Stats};
+    ///
+    /// let cores = *core_retrieval();
+    /// let stats = Stats::new(cores);
+    /// stats.set_steal_threshold(2);
+    ///
+    /// stats.store_load(0, cores);
+    /// stats.update_mean();
+    /// assert!(stats.is_steal_throttled());
+    ///
+    /// stats.store_load(0, 2 * cores);
+    /// stats.update_mean();
+    /// assert!(!stats.is_steal_throttled());
+    /// ```
+    pub fn set_steal_threshold(&self, threshold: usize) {
+        self.steal_threshold.store(threshold, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns the mean level of the run queues under which the workers stop stealing
+    /// processes from each other's queues.
+    pub fn steal_threshold(&self) -> usize {
+        self.steal_threshold.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Returns `true` if the mean level of the run queues is under the steal threshold
+    /// (see [Stats::set_steal_threshold]).
+    pub fn is_steal_throttled(&self) -> bool {
+        self.mean() < self.steal_threshold()
+    }
+
+    ///
+    /// Records a steal from the other workers' queues which was skipped because the
+    /// steals were throttled.
+    pub fn record_throttled_steal(&self) {
+        self.steals_throttled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Amount of steals from the other workers' queues skipped since the start because
+    /// the steals were throttled (see [Stats::set_steal_threshold]).
+    pub fn steals_throttled(&self) -> usize {
+        self.steals_throttled.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Sets the maximum amount of live processes spawned on the pool, over which the
+    /// spawns are rejected.
+    ///
+    /// A process is live from the moment it is spawned until it completes or gets
+    /// cancelled. Lowering the maximum doesn't affect the processes already live.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    ///
+    /// let stats = Stats::new(1);
+    /// stats.set_max_tasks(1);
+    ///
+    /// assert!(stats.acquire_task());
+    /// assert!(!stats.acquire_task());
+    /// assert_eq!(stats.tasks_rejected(), 1);
+    ///
+    /// stats.release_task();
+    /// assert!(stats.acquire_task());
+    /// ```
+    pub fn set_max_tasks(&self, max: usize) {
+        self.max_tasks.store(max, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns the maximum amount of live processes spawned on the pool.
+    pub fn max_tasks(&self) -> usize {
+        self.max_tasks.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Counts a new live process, unless the maximum amount of live processes was
+    /// reached, in which case the spawn is counted as rejected and `false` is returned.
+    pub fn acquire_task(&self) -> bool {
+        if self.reserve_task() {
+            return true;
+        }
+
+        self.record_rejected_task();
+        false
+    }
+
+    /// Counts a new live process unless the maximum amount of live processes was
+    /// reached, without counting the spawn as rejected otherwise.
+    pub(crate) fn reserve_task(&self) -> bool {
+        let live = self.live_tasks.fetch_add(1, Ordering::AcqRel);
+        if live < self.max_tasks() {
+            return true;
+        }
+
+        self.live_tasks.fetch_sub(1, Ordering::AcqRel);
+        false
+    }
+
+    pub(crate) fn record_rejected_task(&self) {
+        self.tasks_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Sets what spawning a process does once the maximum amount of live processes was
+    /// reached, which is rejecting the spawn by default.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::{OverLimitPolicy, Stats};
+    /// use std::time::Duration;
+    ///
+    /// let stats = Stats::new(1);
+    /// assert_eq!(stats.over_limit_policy(), OverLimitPolicy::Reject);
+    ///
+    /// let policy = OverLimitPolicy::BlockWithTimeout(Duration::from_millis(100));
+    /// stats.set_over_limit_policy(policy);
+    /// assert_eq!(stats.over_limit_policy(), policy);
+    /// ```
+    pub fn set_over_limit_policy(&self, policy: OverLimitPolicy) {
+        self.over_limit_policy
+            .store(policy.to_bits(), Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns what spawning a process does once the maximum amount of live processes
+    /// was reached.
+    pub fn over_limit_policy(&self) -> OverLimitPolicy {
+        OverLimitPolicy::from_bits(self.over_limit_policy.load(Ordering::Relaxed))
+    }
+
+    ///
+    /// Stops counting a live process, once it completed or got cancelled.
+    pub fn release_task(&self) {
+        self.live_tasks.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    ///
+    /// Amount of processes spawned on the pool which didn't complete yet.
+    pub fn live_tasks(&self) -> usize {
+        self.live_tasks.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Amount of workers running processes, which the mean level of the run queues is
+    /// computed over.
+    ///
+    /// Every core has a worker, unless the pool runs on external workers (see
+    /// [pool::use_external_workers]), in which case only the workers currently running are
+    /// counted.
+    ///
+    /// [pool::use_external_workers]: ../pool/fn.use_external_workers.html
+    pub fn workers(&self) -> usize {
+        self.workers.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_workers(&self, workers: usize) {
+        self.workers.store(workers, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_worker(&self) {
+        self.workers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn remove_worker(&self) {
+        self.workers.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Amount of spawns rejected since the start because the maximum amount of live
+    /// processes was reached (see [Stats::set_max_tasks]).
+    pub fn tasks_rejected(&self) -> usize {
+        self.tasks_rejected.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Records a steal attempt from the global queue or from other workers' queues.
+    pub fn record_steal(&self, succeeded: bool) {
+        self.steals_attempted.fetch_add(1, Ordering::Relaxed);
+        if succeeded {
+            self.steals_succeeded.fetch_add(1, Ordering::Relaxed);
         }
     }
+
+    ///
+    /// Records a process which was pushed to the global queue (e.g. because it was
+    /// spawned from outside of the workers) instead of a worker's local queue.
+    pub fn record_global_push(&self) {
+        self.global_pushes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Amount of times workers tried to steal processes since the start.
+    ///
+    /// A high amount of attempts compared to the successful steals
+    /// means that the workers are thrashing over empty queues.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    ///
+    /// let stats = Stats::new(1);
+    /// stats.record_steal(false);
+    /// stats.record_steal(true);
+    ///
+    /// assert_eq!(stats.steals_attempted(), 2);
+    /// assert_eq!(stats.steals_succeeded(), 1);
+    /// ```
+    pub fn steals_attempted(&self) -> usize {
+        self.steals_attempted.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Amount of steals which brought back a process since the start.
+    pub fn steals_succeeded(&self) -> usize {
+        self.steals_succeeded.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Amount of processes which were pushed to the global queue
+    /// instead of a worker's local queue since the start.
+    ///
+    /// The local queues are unbounded, so the processes only go through the
+    /// global queue when they are scheduled from outside of the workers.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    ///
+    /// let stats = Stats::new(1);
+    /// stats.record_global_push();
+    ///
+    /// assert_eq!(stats.global_pushes(), 1);
+    /// ```
+    pub fn global_pushes(&self) -> usize {
+        self.global_pushes.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Stores the amount of processes waiting in the global queue.
+    pub fn store_global_run_queue(&self, len: usize) {
+        self.global_run_queue.store(len, Ordering::Relaxed);
+    }
+
+    ///
+    /// Amount of processes waiting in the global queue, as of the last time
+    /// it was pushed to or stolen from.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    ///
+    /// let stats = Stats::new(1);
+    /// stats.store_global_run_queue(3);
+    ///
+    /// assert_eq!(stats.global_run_queue(), 3);
+    /// ```
+    pub fn global_run_queue(&self) -> usize {
+        self.global_run_queue.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "poll-stats")]
+    ///
+    /// Records a poll of a process which took the given amount of time on the given core.
+    pub fn record_poll(&self, affinity: usize, elapsed: Duration) {
+        self.poll_time[affinity].fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.poll_count[affinity].fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "poll-stats")]
+    ///
+    /// Total time spent polling processes on the given core.
+    ///
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    /// use std::time::Duration;
+    ///
+    /// let stats = Stats::new(1);
+    /// stats.record_poll(0, Duration::from_millis(2));
+    /// stats.record_poll(0, Duration::from_millis(3));
+    ///
+    /// assert_eq!(stats.poll_time(0), Duration::from_millis(5));
+    /// assert_eq!(stats.poll_count(0), 2);
+    /// ```
+    pub fn poll_time(&self, affinity: usize) -> Duration {
+        Duration::from_nanos(self.poll_time[affinity].load(Ordering::Relaxed))
+    }
+
+    #[cfg(feature = "poll-stats")]
+    ///
+    /// Amount of polls done on the given core.
+    pub fn poll_count(&self, affinity: usize) -> usize {
+        self.poll_count[affinity].load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "poll-stats")]
+    ///
+    /// Ends the current sampling window of the utilization of the cores, which lasted
+    /// the given amount of time, by accounting the time spent polling on each core since
+    /// the previous one.
+    ///
+    /// The load balancer thread calls this each time it samples the runtime.
+    pub fn update_utilization(&self, window: Duration) {
+        for (core, sampled) in self.sampled_poll_time.iter().enumerate() {
+            let poll_time = self.poll_time[core].load(Ordering::Relaxed);
+            let previous = sampled.swap(poll_time, Ordering::Relaxed);
+            self.window_poll_time[core].store(poll_time.wrapping_sub(previous), Ordering::Relaxed);
+        }
+
+        self.window
+            .store(window.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "poll-stats")]
+    ///
+    /// Fraction of the last sampling window that each core spent polling processes,
+    /// between `0.0` (idle) and `1.0` (busy the whole time), keyed by the core.
+    ///
+    /// Unlike the load of the run queues, this tells how busy the workers actually
+    /// are. The cores are all idle until a sampling window ended.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::load_balancer::Stats;
+    /// use std::time::Duration;
+    ///
+    /// let stats = Stats::new(2);
+    /// stats.record_poll(0, Duration::from_millis(50));
+    /// stats.update_utilization(Duration::from_millis(100));
+    ///
+    /// let utilization = stats.per_core_utilization();
+    /// assert_eq!(utilization[&0], 0.5);
+    /// assert_eq!(utilization[&1], 0.0);
+    /// ```
+    pub fn per_core_utilization(&self) -> FxHashMap<usize, f64> {
+        let window = self.window.load(Ordering::Relaxed);
+        // The unused slots aren't counted.
+        let cores = self
+            .smp_load
+            .iter()
+            .take_while(|load| load.load(Ordering::Relaxed) != usize::MAX)
+            .count();
+
+        (0..cores)
+            .map(|core| {
+                let busy = self.window_poll_time[core].load(Ordering::Relaxed);
+                let utilization = if window == 0 {
+                    0.0
+                } else {
+                    // The polls overlapping the end of the window can
+                    // slightly overshoot it.
+                    (busy as f64 / window as f64).min(1.0)
+                };
+
+                (core, utilization)
+            })
+            .collect()
+    }
+}
+
+/// Creates an array of atomics initialized with the given function.
+fn atomic_array<F: Fn(usize) -> usize>(init: F) -> [AtomicUsize; MAX_CORE] {
+    let mut data: [MaybeUninit<AtomicUsize>; MAX_CORE] =
+        unsafe { MaybeUninit::uninit().assume_init() };
+    for (i, slot) in data.iter_mut().enumerate() {
+        unsafe {
+            std::ptr::write(slot.as_mut_ptr(), AtomicUsize::new(init(i)));
+        }
+    }
+    unsafe { std::mem::transmute::<_, [AtomicUsize; MAX_CORE]>(data) }
+}
+
+#[cfg(feature = "poll-stats")]
+/// Creates an array of 64-bit atomics initialized to zero, which don't wrap around
+/// after a few seconds worth of nanoseconds on 32-bit targets, unlike `AtomicUsize`.
+fn atomic_u64_array() -> [AtomicU64; MAX_CORE] {
+    [(); MAX_CORE].map(|_| AtomicU64::new(0))
 }
 
 unsafe impl Sync for Stats {}
@@ -132,20 +755,19 @@ impl SmpStats for Stats {
     }
 
     fn update_mean(&self) {
-        let mut sum: usize = 0;
+        // The unused slots aren't counted, even when all the queues
+        // are empty. The global queue can't be stolen from.
+        let sum = self.smp_queued();
+        let sample = sum.wrapping_div(self.workers().max(1)) as f64;
 
-        for item in self.smp_load.iter() {
-            let load = item.load(Ordering::SeqCst);
-            if let Some(tmp) = sum.checked_add(load) {
-                sum = tmp;
-                continue;
-            }
-            break;
-        }
-        self.mean_level.store(
-            sum.wrapping_div(placement::get_core_ids().unwrap().len()),
-            Ordering::SeqCst,
-        );
+        // Only the sampler thread updates the mean.
+        let smoothing = self.mean_smoothing();
+        let previous = f64::from_bits(self.mean_ewma.load(Ordering::Relaxed));
+        let mean = smoothing * sample + (1.0 - smoothing) * previous;
+
+        self.mean_ewma.store(mean.to_bits(), Ordering::Relaxed);
+        self.mean_level
+            .store(mean.round() as usize, Ordering::SeqCst);
     }
 }
 
@@ -159,12 +781,73 @@ pub fn stats() -> &'static Stats {
     &*LOCKLESS_STATS
 }
 
+///
+/// Sets the smoothing factor of the mean level of the run queues of the runtime
+/// (see [Stats::set_mean_smoothing]).
+pub fn set_mean_smoothing(smoothing: f64) {
+    stats().set_mean_smoothing(smoothing)
+}
+
+///
+/// Sets the mean level of the run queues of the runtime under which the workers stop
+/// stealing from each other (see [Stats::set_steal_threshold]).
+pub fn set_steal_threshold(threshold: usize) {
+    stats().set_steal_threshold(threshold)
+}
+
+///
+/// Sets the maximum amount of live processes spawned on the pool, over which the
+/// spawns are rejected (see [Stats::set_max_tasks]).
+pub fn set_max_tasks(max: usize) {
+    stats().set_max_tasks(max)
+}
+
+///
+/// Sets what spawning a process on the pool does once the maximum amount of live
+/// processes was reached (see [Stats::set_over_limit_policy]).
+pub fn set_over_limit_policy(policy: OverLimitPolicy) {
+    stats().set_over_limit_policy(policy)
+}
+
+///
+/// Starts the load balancer thread unless it already was, which the features sampling
+/// the runtime periodically rely on.
+///
+/// Only the first call returns the error of the thread failing to spawn.
+pub(crate) fn start_sampling() -> io::Result<()> {
+    static SAMPLER: Once = Once::new();
+
+    let mut started = Ok(());
+    SAMPLER.call_once(|| started = LoadBalancer::amql_generation());
+    started
+}
+
+///
+/// Makes the load balancer thread call `hook` each time it samples the runtime, about
+/// four times a second, which allows checking something periodically without spawning
+/// another thread. The load balancer thread is started unless it already was.
+///
+/// Returns an error if this call started the thread and it couldn't be spawned, in which
+/// case `hook` isn't called.
+pub fn on_sample(hook: fn()) -> io::Result<()> {
+    sample_hooks().lock().unwrap().push(hook);
+    start_sampling()
+}
+
+fn sample_hooks() -> &'static Mutex<Vec<fn()>> {
+    lazy_static! {
+        static ref SAMPLE_HOOKS: Mutex<Vec<fn()>> = Mutex::new(Vec::new());
+    }
+
+    &SAMPLE_HOOKS
+}
+
 ///
 /// Retrieve core count for the runtime scheduling purposes
 #[inline]
 pub fn core_retrieval() -> &'static usize {
     lazy_static! {
-        static ref CORE_COUNT: usize = placement::get_core_ids().unwrap().len();
+        static ref CORE_COUNT: usize = placement::worker_core_ids().len();
     }
 
     &*CORE_COUNT
@@ -0,0 +1,208 @@
+//!
+//! Local executor running the processes whose futures aren't `Send`.
+//!
+//! Futures holding e.g. an `Rc` can't be moved to the worker threads of the pool. They can
+//! instead be spawned with [spawn_local] onto the local executor of the current thread, which
+//! has its own run queue that is never stolen from: its processes only ever run on the thread
+//! driving it with [run_local], which they were spawned from.
+//!
+//! [spawn_local] must be called from within the local executor's thread while it is being driven
+//! (i.e. from the future passed to [run_local], or from a process it runs) and panics otherwise.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::prelude::*;
+//! use lightproc::prelude::*;
+//! use std::rc::Rc;
+//!
+//! let res = run_local(async {
+//!     let shared = Rc::new(21);
+//!     let handle = spawn_local(
+//!         async move { *shared * 2 },
+//!         ProcStack::default(),
+//!     );
+//!     handle.await
+//! });
+//!
+//! assert_eq!(res, Some(42));
+//! ```
+use crate::current_thread::CurrentThread;
+use lightproc::prelude::*;
+use std::cell::RefCell;
+use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread::{self, ThreadId};
+
+thread_local! {
+    // The local executor driven by the current thread, if any.
+    static LOCAL: RefCell<Option<CurrentThread>> = const { RefCell::new(None) };
+}
+
+///
+/// Spawns a process whose future isn't `Send` onto the local executor of the current thread.
+///
+/// The process only runs on the current thread, while the local executor is driven by
+/// [run_local]. The returned handle can be awaited from any thread.
+///
+/// # Panics
+///
+/// Panics if it isn't called from within a thread driving its local executor with [run_local].
+pub fn spawn_local<F, T>(future: F, stack: ProcStack) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + 'static,
+    T: Send + 'static,
+{
+    let executor = LOCAL.with(|local| local.borrow().clone()).expect(
+        "`spawn_local` must be called from within a thread driving its local executor \
+         with `run_local`",
+    );
+
+    let future = Local::new(future);
+    let schedule = move |proc| executor.schedule(proc);
+    let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+    proc.schedule();
+    handle
+}
+
+///
+/// Blocks the current thread until the passed future is resolved, running the processes spawned
+/// on its local executor with [spawn_local] in the meantime.
+///
+/// The processes which are still pending once the future resolved stay queued, and run the next
+/// time the local executor of the thread is driven.
+pub fn run_local<F, T>(future: F) -> T
+where
+    F: Future<Output = T>,
+{
+    struct Restore(Option<CurrentThread>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            LOCAL.with(|local| *local.borrow_mut() = previous);
+        }
+    }
+
+    // Nested calls drive the same executor.
+    let executor = LOCAL.with(|local| local.borrow().clone().unwrap_or_default());
+    let previous = LOCAL.with(|local| local.borrow_mut().replace(executor.clone()));
+    let _restore = Restore(previous);
+
+    executor.block_on(future)
+}
+
+// A future which isn't `Send`, which can be moved across threads as long as it is only polled
+// and dropped on the thread it was created on.
+struct Local<F> {
+    future: ManuallyDrop<F>,
+    thread: ThreadId,
+}
+
+// The future is only accessed from the thread it was created on (which is checked).
+unsafe impl<F> Send for Local<F> {}
+
+impl<F> Local<F> {
+    fn new(future: F) -> Self {
+        Local {
+            future: ManuallyDrop::new(future),
+            thread: thread::current().id(),
+        }
+    }
+}
+
+impl<F: Future> Future for Local<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        assert_eq!(
+            self.thread,
+            thread::current().id(),
+            "a local process was polled outside of its thread"
+        );
+
+        // The future is never moved out of its pinned wrapper.
+        unsafe { self.map_unchecked_mut(|local| &mut *local.future).poll(cx) }
+    }
+}
+
+impl<F> Drop for Local<F> {
+    fn drop(&mut self) {
+        // If the process is dropped by another thread (e.g. because it was woken up after its
+        // thread exited), its future is leaked instead.
+        if self.thread == thread::current().id() {
+            unsafe { ManuallyDrop::drop(&mut self.future) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_local, spawn_local};
+    use crate::pool::yield_now;
+    use futures::channel::oneshot;
+    use lightproc::proc_stack::ProcStack;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn runs_procs_on_the_spawning_thread() {
+        let driver = thread::current().id();
+        let order = Rc::new(RefCell::new(Vec::new()));
+
+        let handles = run_local(async {
+            let handles = (0..2)
+                .map(|id| {
+                    let order = order.clone();
+                    spawn_local(
+                        async move {
+                            order.borrow_mut().push(id);
+                            yield_now().await;
+                            order.borrow_mut().push(id);
+                            thread::current().id()
+                        },
+                        ProcStack::default(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let mut threads = vec![];
+            for handle in handles {
+                threads.push(handle.await);
+            }
+            threads
+        });
+
+        assert_eq!(handles, vec![Some(driver), Some(driver)]);
+        assert_eq!(*order.borrow(), vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn procs_are_woken_up_from_other_threads() {
+        let (sender, recver) = oneshot::channel();
+        thread::spawn(move || sender.send(42).unwrap());
+
+        let res = run_local(async {
+            let local = Rc::new(());
+            let handle = spawn_local(
+                async move {
+                    let _local = local;
+                    recver.await.unwrap()
+                },
+                ProcStack::default(),
+            );
+            handle.await
+        });
+
+        assert_eq!(res, Some(42));
+    }
+
+    #[test]
+    #[should_panic(expected = "within a thread driving its local executor")]
+    fn spawning_outside_of_the_local_executor_panics() {
+        spawn_local(async {}, ProcStack::default());
+    }
+}
@@ -0,0 +1,355 @@
+//!
+//! A tiny HTTP endpoint exposing the statistics of the runtime for the monitoring systems
+//! to scrape, available with the `metrics-server` feature.
+//!
+//! Once started with [serve], it answers these `GET` requests from the blocking pool:
+//! * `/metrics` - The statistics in the Prometheus text format (see [render_prometheus]).
+//! * `/stats` - The statistics as JSON (see [render_json]).
+//! * `/health` - A liveness probe answering `OK` as long as the server runs.
+//!
+//! It only understands what it needs to answer those requests, so that enabling it doesn't
+//! pull in an HTTP stack.
+//!
+//! # Example
+//! ```rust
+//! use bastion_executor::metrics_server;
+//! use std::io::{Read, Write};
+//! use std::net::TcpStream;
+//!
+//! let server = metrics_server::serve("127.0.0.1:0").unwrap();
+//!
+//! let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+//! stream.write_all(b"GET /health HTTP/1.1\r\n\r\n").unwrap();
+//! let mut response = String::new();
+//! stream.read_to_string(&mut response).unwrap();
+//! assert!(response.starts_with("HTTP/1.1 200 OK"));
+//!
+//! server.shutdown();
+//! ```
+use crate::blocking::spawn_blocking;
+use crate::load_balancer::{stats, SmpStats, Stats};
+use crate::run::run;
+use lightproc::prelude::*;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long the server waits between two checks for new connections.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long the server waits for a request before dropping its connection.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Prefix of the name of the metrics in the Prometheus text format.
+const PREFIX: &str = "bastion_executor";
+
+///
+/// A running metrics server, stopped with [MetricsServer::shutdown].
+#[derive(Debug)]
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: RecoverableHandle<()>,
+}
+
+struct Metric {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    // The values of the metric, labelled with their core for the per core metrics.
+    samples: Vec<(Option<usize>, f64)>,
+}
+
+impl MetricsServer {
+    ///
+    /// Returns the address the server listens on, which tells the port it was given when
+    /// bound to port `0`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    ///
+    /// Stops accepting connections and waits for the server to stop.
+    pub fn shutdown(self) {
+        self.running.store(false, Ordering::Release);
+        run(self.handle, ProcStack::default());
+    }
+}
+
+///
+/// Starts serving the statistics of the runtime over HTTP on the given address, from the
+/// blocking pool.
+///
+/// Returns an error if the address can't be bound.
+pub fn serve<A: ToSocketAddrs>(addr: A) -> io::Result<MetricsServer> {
+    let listener = TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    let local_addr = listener.local_addr()?;
+
+    let running = Arc::new(AtomicBool::new(true));
+    let accepting = running.clone();
+    let handle = spawn_blocking(
+        async move {
+            while accepting.load(Ordering::Acquire) {
+                match listener.accept() {
+                    // A client failing to send its request only affects its connection.
+                    Ok((stream, _)) => drop(respond(stream)),
+                    Err(_) => thread::sleep(ACCEPT_INTERVAL),
+                }
+            }
+        },
+        ProcStack::default(),
+    );
+
+    Ok(MetricsServer {
+        local_addr,
+        running,
+        handle,
+    })
+}
+
+fn respond(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // The headers are read so that closing the connection doesn't reset it.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => (
+            "200 OK",
+            "text/plain; version=0.0.4",
+            render_prometheus(stats()),
+        ),
+        (Some("GET"), Some("/stats")) => ("200 OK", "application/json", render_json(stats())),
+        (Some("GET"), Some("/health")) => ("200 OK", "text/plain", "OK\n".to_string()),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+///
+/// Renders the given statistics in the Prometheus text format, the per core metrics being
+/// labelled with their core.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::load_balancer::{SmpStats, Stats};
+/// use bastion_executor::metrics_server::render_prometheus;
+///
+/// let stats = Stats::new(2);
+/// stats.store_load(1, 4);
+///
+/// let text = render_prometheus(&stats);
+/// assert!(text.contains("bastion_executor_queued{core=\"1\"} 4\n"));
+/// ```
+pub fn render_prometheus(stats: &Stats) -> String {
+    let mut text = String::new();
+    for metric in metrics(stats) {
+        let _ = writeln!(text, "# HELP {}_{} {}", PREFIX, metric.name, metric.help);
+        let _ = writeln!(text, "# TYPE {}_{} {}", PREFIX, metric.name, metric.kind);
+        for (core, value) in metric.samples {
+            let _ = match core {
+                Some(core) => writeln!(
+                    text,
+                    "{}_{}{{core=\"{}\"}} {}",
+                    PREFIX, metric.name, core, value
+                ),
+                None => writeln!(text, "{}_{} {}", PREFIX, metric.name, value),
+            };
+        }
+    }
+
+    text
+}
+
+///
+/// Renders the given statistics as a JSON object, the per core metrics being objects
+/// keyed by their core.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::load_balancer::Stats;
+/// use bastion_executor::metrics_server::render_json;
+///
+/// let stats = Stats::new(1);
+///
+/// let json = render_json(&stats);
+/// assert!(json.starts_with("{\"workers\":1,"));
+/// ```
+pub fn render_json(stats: &Stats) -> String {
+    let fields = metrics(stats)
+        .into_iter()
+        .map(|metric| {
+            let value = match metric.samples.as_slice() {
+                [(None, value)] => value.to_string(),
+                samples => {
+                    let per_core = samples
+                        .iter()
+                        .map(|(core, value)| format!("\"{}\":{}", core.unwrap_or(0), value))
+                        .collect::<Vec<_>>();
+                    format!("{{{}}}", per_core.join(","))
+                }
+            };
+            format!("\"{}\":{}", metric.name, value)
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn metrics(stats: &Stats) -> Vec<Metric> {
+    let single = |name, help, kind, value: usize| Metric {
+        name,
+        help,
+        kind,
+        samples: vec![(None, value as f64)],
+    };
+
+    let mut queued = stats
+        .get_sorted_load()
+        .into_iter()
+        .map(|(core, load)| (Some(core), load as f64))
+        .collect::<Vec<_>>();
+    queued.sort_by_key(|(core, _)| *core);
+
+    let metrics = vec![
+        single("workers", "Amount of workers.", "gauge", stats.workers()),
+        single(
+            "live_tasks",
+            "Amount of live processes spawned on the pool.",
+            "gauge",
+            stats.live_tasks(),
+        ),
+        single(
+            "max_tasks",
+            "Maximum amount of live processes spawned on the pool.",
+            "gauge",
+            stats.max_tasks(),
+        ),
+        single(
+            "tasks_rejected",
+            "Amount of spawns rejected because of the maximum amount of live processes.",
+            "counter",
+            stats.tasks_rejected(),
+        ),
+        single(
+            "total_queued",
+            "Amount of processes waiting in all the run queues.",
+            "gauge",
+            stats.total_queued(),
+        ),
+        Metric {
+            name: "queued",
+            help: "Amount of processes waiting in the run queue of each core.",
+            kind: "gauge",
+            samples: queued,
+        },
+        single(
+            "mean_queued",
+            "Sampled mean of the processes waiting in the run queues.",
+            "gauge",
+            stats.mean(),
+        ),
+        single(
+            "global_run_queue",
+            "Amount of processes waiting in the global queue.",
+            "gauge",
+            stats.global_run_queue(),
+        ),
+        single(
+            "steals_attempted",
+            "Amount of attempts to steal processes from other run queues.",
+            "counter",
+            stats.steals_attempted(),
+        ),
+        single(
+            "steals_succeeded",
+            "Amount of successful steals of processes from other run queues.",
+            "counter",
+            stats.steals_succeeded(),
+        ),
+        single(
+            "steals_throttled",
+            "Amount of steals skipped because of the steal threshold.",
+            "counter",
+            stats.steals_throttled(),
+        ),
+        single(
+            "global_pushes",
+            "Amount of processes pushed to the global queue instead of a local one.",
+            "counter",
+            stats.global_pushes(),
+        ),
+    ];
+
+    metrics.into_iter().chain(poll_metrics(stats)).collect()
+}
+
+#[cfg(feature = "poll-stats")]
+fn poll_metrics(stats: &Stats) -> Vec<Metric> {
+    let cores = 0..stats.get_sorted_load().len();
+    vec![
+        Metric {
+            name: "poll_seconds",
+            help: "Time spent polling processes on each core.",
+            kind: "counter",
+            samples: cores
+                .clone()
+                .map(|core| (Some(core), stats.poll_time(core).as_secs_f64()))
+                .collect(),
+        },
+        Metric {
+            name: "polls",
+            help: "Amount of polls done on each core.",
+            kind: "counter",
+            samples: cores
+                .map(|core| (Some(core), stats.poll_count(core) as f64))
+                .collect(),
+        },
+        Metric {
+            name: "core_utilization",
+            help: "Fraction of the last sampling window each core spent polling processes.",
+            kind: "gauge",
+            samples: {
+                let mut samples: Vec<_> = stats
+                    .per_core_utilization()
+                    .into_iter()
+                    .map(|(core, utilization)| (Some(core), utilization))
+                    .collect();
+                samples.sort_by_key(|(core, _)| *core);
+                samples
+            },
+        },
+    ]
+}
+
+#[cfg(not(feature = "poll-stats"))]
+fn poll_metrics(_: &Stats) -> Vec<Metric> {
+    vec![]
+}
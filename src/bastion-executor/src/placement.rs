@@ -3,6 +3,11 @@
 //!
 //! Placement module enables thread placement onto the cores.
 //! CPU level affinity assignment is done here.
+use lazy_static::*;
+use std::env;
+use std::io;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
 
 /// This function tries to retrieve information
 /// on all the "cores" active on this system.
@@ -10,12 +15,180 @@ pub fn get_core_ids() -> Option<Vec<CoreId>> {
     get_core_ids_helper()
 }
 
+///
+/// Retrieves the cores that the runtime should use.
+///
+/// If the topology of the system can't be retrieved (e.g. on unsupported
+/// platforms or inside restricted sandboxes) it falls back to a single
+/// logical core, so the runtime can still boot.
+pub fn core_ids() -> Vec<CoreId> {
+    core_ids_or_fallback(get_core_ids())
+}
+
+fn core_ids_or_fallback(core_ids: Option<Vec<CoreId>>) -> Vec<CoreId> {
+    match core_ids {
+        Some(core_ids) if !core_ids.is_empty() => core_ids,
+        _ => vec![CoreId { id: 0 }],
+    }
+}
+
+///
+/// Retrieves the logical cores sharing the same physical core as the given one
+/// (e.g. hyperthread siblings), including the given core itself.
+///
+/// If the topology of the system can't be retrieved, the core is considered
+/// to be the only logical core of its physical core.
+pub fn sibling_core_ids(core_id: CoreId) -> Vec<CoreId> {
+    match get_sibling_core_ids_helper(core_id) {
+        Some(siblings) if !siblings.is_empty() => siblings,
+        _ => vec![core_id],
+    }
+}
+
+///
+/// Retrieves one logical core per physical core among the cores that the
+/// runtime could use (see [core_ids]).
+///
+/// Logical cores sharing a physical core share its execution units, so
+/// compute-heavy workloads can benefit from only using one of them.
+pub fn physical_core_ids() -> Vec<CoreId> {
+    let core_ids = core_ids();
+    let mut physical: Vec<CoreId> = Vec::with_capacity(core_ids.len());
+
+    for core_id in core_ids {
+        let siblings = sibling_core_ids(core_id);
+        if !physical
+            .iter()
+            .any(|used| siblings.iter().any(|sibling| sibling.id == used.id))
+        {
+            physical.push(core_id);
+        }
+    }
+
+    physical
+}
+
+///
+/// Retrieves the cores that the executor's workers are going to be pinned to.
+///
+/// These are all the cores returned by [core_ids], or only one logical core per
+/// physical core (see [physical_core_ids]) if the `BASTION_PHYSICAL_CORES_ONLY`
+/// env var is set at runtime.
+pub fn worker_core_ids() -> &'static [CoreId] {
+    lazy_static! {
+        static ref WORKER_CORE_IDS: Vec<CoreId> = {
+            if env::var_os("BASTION_PHYSICAL_CORES_ONLY").is_some() {
+                physical_core_ids()
+            } else {
+                core_ids()
+            }
+        };
+    }
+
+    &WORKER_CORE_IDS
+}
+
 ///
 /// Sets the current threads affinity
 pub fn set_for_current(core_id: CoreId) {
     set_for_current_helper(core_id);
 }
 
+///
+/// The OS scheduling priority of a thread.
+///
+/// The priorities stay within the default scheduling policy of the platform, so
+/// elevated threads still share the cores with the other threads of the system
+/// instead of starving them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// The priority threads are given by default.
+    #[default]
+    Normal,
+    /// A priority above the default one (a nice value of `-5` on Linux).
+    AboveNormal,
+    /// The highest priority of the default scheduling policy (a nice value of `-20`
+    /// on Linux).
+    Highest,
+}
+
+impl ThreadPriority {
+    fn to_bits(self) -> u8 {
+        match self {
+            ThreadPriority::Normal => 0,
+            ThreadPriority::AboveNormal => 1,
+            ThreadPriority::Highest => 2,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits {
+            1 => ThreadPriority::AboveNormal,
+            2 => ThreadPriority::Highest,
+            _ => ThreadPriority::Normal,
+        }
+    }
+}
+
+static WORKER_PRIORITY: AtomicU8 = AtomicU8::new(0);
+
+///
+/// Sets the priority that the executor's workers are going to run at, which is
+/// [ThreadPriority::Normal] by default.
+///
+/// The priority is set when the worker threads start, right after they were pinned
+/// to their core (see [worker_core_ids]), so this has to be called before the first
+/// process is spawned. Elevating the priority usually requires privileges (e.g.
+/// `CAP_SYS_NICE` on Linux): if the process lacks them, a warning is printed and the
+/// workers keep the normal priority.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::placement::{self, ThreadPriority};
+///
+/// placement::set_worker_priority(ThreadPriority::AboveNormal);
+/// assert_eq!(placement::worker_priority(), ThreadPriority::AboveNormal);
+/// ```
+pub fn set_worker_priority(priority: ThreadPriority) {
+    WORKER_PRIORITY.store(priority.to_bits(), Ordering::Relaxed);
+}
+
+///
+/// Returns the priority that the executor's workers run at (see [set_worker_priority]).
+pub fn worker_priority() -> ThreadPriority {
+    ThreadPriority::from_bits(WORKER_PRIORITY.load(Ordering::Relaxed))
+}
+
+///
+/// Sets the current thread's priority, returning the error of the platform if it
+/// couldn't be set (e.g. because of missing privileges).
+pub fn set_priority_for_current(priority: ThreadPriority) -> io::Result<()> {
+    set_priority_for_current_helper(priority)
+}
+
+///
+/// Sets the priority of the current worker thread to the one of [worker_priority],
+/// unless it's the normal one.
+///
+/// Only the first failure is reported, to not print a warning per worker.
+pub(crate) fn set_worker_priority_for_current() {
+    static WARNED: Once = Once::new();
+
+    let priority = worker_priority();
+    if priority == ThreadPriority::Normal {
+        return;
+    }
+
+    if let Err(err) = set_priority_for_current(priority) {
+        WARNED.call_once(|| {
+            eprintln!(
+                "cannot set the priority of the worker threads to {:?}, they keep the normal one: {}",
+                priority, err
+            );
+        });
+    }
+}
+
 ///
 /// CoreID implementation to identify system cores.
 #[derive(Copy, Clone, Debug)]
@@ -38,13 +211,28 @@ fn set_for_current_helper(core_id: CoreId) {
     linux::set_for_current(core_id);
 }
 
+#[cfg(target_os = "linux")]
+#[inline]
+fn get_sibling_core_ids_helper(core_id: CoreId) -> Option<Vec<CoreId>> {
+    linux::get_sibling_core_ids(core_id)
+}
+
+#[cfg(target_os = "linux")]
+#[inline]
+fn set_priority_for_current_helper(priority: ThreadPriority) -> io::Result<()> {
+    linux::set_priority_for_current(priority)
+}
+
 #[cfg(target_os = "linux")]
 mod linux {
+    use std::fs;
+    use std::io;
     use std::mem;
 
     use libc::{cpu_set_t, sched_getaffinity, sched_setaffinity, CPU_ISSET, CPU_SET, CPU_SETSIZE};
+    use libc::{id_t, setpriority, syscall, SYS_gettid, PRIO_PROCESS};
 
-    use super::CoreId;
+    use super::{CoreId, ThreadPriority};
 
     pub fn get_core_ids() -> Option<Vec<CoreId>> {
         if let Some(full_set) = get_affinity_mask() {
@@ -79,6 +267,55 @@ mod linux {
         }
     }
 
+    pub fn set_priority_for_current(priority: ThreadPriority) -> io::Result<()> {
+        let nice = match priority {
+            ThreadPriority::Normal => 0,
+            ThreadPriority::AboveNormal => -5,
+            ThreadPriority::Highest => -20,
+        };
+
+        // The nice value applies to the thread whose id is given, unlike POSIX which
+        // says it applies to the whole process.
+        let res = unsafe {
+            let tid = syscall(SYS_gettid) as id_t;
+            setpriority(PRIO_PROCESS as _, tid, nice)
+        };
+
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub fn get_sibling_core_ids(core_id: CoreId) -> Option<Vec<CoreId>> {
+        let path = format!(
+            "/sys/devices/system/cpu/cpu{}/topology/thread_siblings_list",
+            core_id.id
+        );
+        let list = fs::read_to_string(path).ok()?;
+
+        parse_cpu_list(list.trim())
+    }
+
+    /// Parses a list of cpus as formatted by the kernel (e.g. `0-3,8,10-11`).
+    fn parse_cpu_list(list: &str) -> Option<Vec<CoreId>> {
+        let mut core_ids = Vec::new();
+
+        for range in list.split(',').filter(|range| !range.is_empty()) {
+            let mut bounds = range.splitn(2, '-');
+            let start = bounds.next()?.parse::<usize>().ok()?;
+            let end = match bounds.next() {
+                Some(end) => end.parse::<usize>().ok()?,
+                None => start,
+            };
+
+            core_ids.extend((start..=end).map(|id| CoreId { id }));
+        }
+
+        Some(core_ids)
+    }
+
     fn get_affinity_mask() -> Option<cpu_set_t> {
         let mut set = new_cpu_set();
 
@@ -129,6 +366,24 @@ mod linux {
             }
         }
 
+        #[test]
+        fn test_linux_parse_cpu_list() {
+            let ids = parse_cpu_list("0-2,8,10-11").unwrap();
+            let ids = ids.iter().map(|core| core.id).collect::<Vec<_>>();
+            assert_eq!(ids, vec![0, 1, 2, 8, 10, 11]);
+
+            assert!(parse_cpu_list("0-x").is_none());
+        }
+
+        #[test]
+        fn test_linux_get_sibling_core_ids() {
+            let ids = get_core_ids().unwrap();
+
+            if let Some(siblings) = get_sibling_core_ids(ids[0]) {
+                assert!(siblings.iter().any(|sibling| sibling.id == ids[0].id));
+            }
+        }
+
         #[test]
         fn test_linux_set_for_current() {
             let ids = get_core_ids().unwrap();
@@ -174,17 +429,34 @@ fn set_for_current_helper(core_id: CoreId) {
     windows::set_for_current(core_id);
 }
 
+#[cfg(target_os = "windows")]
+#[inline]
+fn get_sibling_core_ids_helper(_core_id: CoreId) -> Option<Vec<CoreId>> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+#[inline]
+fn set_priority_for_current_helper(priority: ThreadPriority) -> io::Result<()> {
+    windows::set_priority_for_current(priority)
+}
+
 #[cfg(target_os = "windows")]
 extern crate winapi;
 
 #[cfg(target_os = "windows")]
 mod windows {
+    use std::io;
     #[allow(unused_imports)]
     use winapi::shared::basetsd::{DWORD_PTR, PDWORD_PTR};
-    use winapi::um::processthreadsapi::{GetCurrentProcess, GetCurrentThread};
+
+    use winapi::um::processthreadsapi::{GetCurrentProcess, GetCurrentThread, SetThreadPriority};
     use winapi::um::winbase::{GetProcessAffinityMask, SetThreadAffinityMask};
+    use winapi::um::winbase::{
+        THREAD_PRIORITY_ABOVE_NORMAL, THREAD_PRIORITY_HIGHEST, THREAD_PRIORITY_NORMAL,
+    };
 
-    use super::CoreId;
+    use super::{CoreId, ThreadPriority};
 
     pub fn get_core_ids() -> Option<Vec<CoreId>> {
         if let Some(mask) = get_affinity_mask() {
@@ -215,6 +487,20 @@ mod windows {
         }
     }
 
+    pub fn set_priority_for_current(priority: ThreadPriority) -> io::Result<()> {
+        let priority = match priority {
+            ThreadPriority::Normal => THREAD_PRIORITY_NORMAL,
+            ThreadPriority::AboveNormal => THREAD_PRIORITY_ABOVE_NORMAL,
+            ThreadPriority::Highest => THREAD_PRIORITY_HIGHEST,
+        };
+
+        if unsafe { SetThreadPriority(GetCurrentThread(), priority as _) } != 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
     fn get_affinity_mask() -> Option<usize> {
         let mut process_mask: usize = 0;
         let mut system_mask: usize = 0;
@@ -280,13 +566,28 @@ fn set_for_current_helper(core_id: CoreId) {
     macos::set_for_current(core_id);
 }
 
+#[cfg(target_os = "macos")]
+#[inline]
+fn get_sibling_core_ids_helper(_core_id: CoreId) -> Option<Vec<CoreId>> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+#[inline]
+fn set_priority_for_current_helper(priority: ThreadPriority) -> io::Result<()> {
+    macos::set_priority_for_current(priority)
+}
+
 #[cfg(target_os = "macos")]
 mod macos {
+    use std::io;
     use std::mem;
 
     use libc::{c_int, c_uint, pthread_self};
+    use libc::{pthread_getschedparam, pthread_setschedparam, sched_param};
+    use libc::{sched_get_priority_max, sched_get_priority_min};
 
-    use super::CoreId;
+    use super::{CoreId, ThreadPriority};
 
     type KernReturnT = c_int;
     type IntegerT = c_int;
@@ -341,6 +642,32 @@ mod macos {
         }
     }
 
+    pub fn set_priority_for_current(priority: ThreadPriority) -> io::Result<()> {
+        unsafe {
+            let mut policy: c_int = 0;
+            let mut param: sched_param = mem::zeroed();
+            let res = pthread_getschedparam(pthread_self(), &mut policy, &mut param);
+            if res != 0 {
+                return Err(io::Error::from_raw_os_error(res));
+            }
+
+            // The default priority is the middle of the range of the thread's policy.
+            let min = sched_get_priority_min(policy);
+            let max = sched_get_priority_max(policy);
+            let normal = (min + max) / 2;
+            param.sched_priority = match priority {
+                ThreadPriority::Normal => normal,
+                ThreadPriority::AboveNormal => (normal + max) / 2,
+                ThreadPriority::Highest => max,
+            };
+
+            match pthread_setschedparam(pthread_self(), policy, &param) {
+                0 => Ok(()),
+                res => Err(io::Error::from_raw_os_error(res)),
+            }
+        }
+    }
+
     #[cfg(test)]
     mod tests {
 
@@ -381,6 +708,21 @@ fn get_core_ids_helper() -> Option<Vec<CoreId>> {
 #[inline]
 fn set_for_current_helper(core_id: CoreId) {}
 
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+#[inline]
+fn get_sibling_core_ids_helper(_core_id: CoreId) -> Option<Vec<CoreId>> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+#[inline]
+fn set_priority_for_current_helper(_priority: ThreadPriority) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "thread priorities aren't supported on this platform",
+    ))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -406,4 +748,44 @@ mod tests {
 
         set_for_current(ids[0]);
     }
+
+    #[test]
+    fn test_core_ids_fallback() {
+        let core_ids = core_ids_or_fallback(None);
+        assert_eq!(core_ids.len(), 1);
+        assert_eq!(core_ids[0].id, 0);
+
+        let core_ids = core_ids_or_fallback(Some(vec![]));
+        assert_eq!(core_ids.len(), 1);
+    }
+    #[test]
+    fn test_physical_core_ids() {
+        let core_ids = core_ids();
+        let physical = physical_core_ids();
+
+        assert!(!physical.is_empty());
+        assert!(physical.len() <= core_ids.len());
+
+        // No two physical cores are siblings.
+        for (i, core) in physical.iter().enumerate() {
+            let siblings = sibling_core_ids(*core);
+            assert!(physical[i + 1..]
+                .iter()
+                .all(|other| siblings.iter().all(|sibling| sibling.id != other.id)));
+        }
+    }
+
+    #[test]
+    fn test_thread_priority_bits() {
+        for priority in [
+            ThreadPriority::Normal,
+            ThreadPriority::AboveNormal,
+            ThreadPriority::Highest,
+        ]
+        .iter()
+        {
+            assert_eq!(ThreadPriority::from_bits(priority.to_bits()), *priority);
+        }
+        assert_eq!(ThreadPriority::default(), ThreadPriority::Normal);
+    }
 }
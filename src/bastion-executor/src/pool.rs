@@ -4,13 +4,33 @@
 //! Pool management and tracking belongs here.
 //! We spawn futures onto the pool with [spawn] method of global run queue or
 //! with corresponding [Worker]'s spawn method.
+use crate::deadlines;
 use crate::distributor::Distributor;
-use crate::run_queue::{Injector, Stealer};
+use crate::fair_injector::{self, FairInjector};
+use crate::load_balancer::{self, OverLimitPolicy, SmpStats};
+use crate::run_queue::{Injector, Stealer, Worker};
 use crate::sleepers::Sleepers;
 use crate::worker;
+use crossbeam_utils::sync::ShardedLock;
 use lazy_static::lazy_static;
 use lightproc::prelude::*;
 use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static EXTERNAL_WORKERS: AtomicBool = AtomicBool::new(false);
+// The amount of spawns waiting for a live process to complete
+// (see `OverLimitPolicy::Block`).
+static OVER_LIMIT_WAITERS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref TASK_RELEASED: (Mutex<()>, Condvar) = (Mutex::new(()), Condvar::new());
+}
 
 ///
 /// Spawn a process (which contains future + process stack) onto the executor from the global level.
@@ -37,6 +57,13 @@ use std::future::Future;
 ///     stack.clone(),
 /// );
 /// ```
+///
+/// If the maximum amount of live processes was reached (see
+/// [load_balancer::set_max_tasks]), the future is dropped without being run and the
+/// returned handle resolves to `None`, like for a cancelled process. Use [try_spawn]
+/// to get an error instead, or [load_balancer::set_over_limit_policy] to wait for a
+/// live process to complete. The spawns from a process are still rejected right away
+/// instead, since blocking the worker running it could deadlock the pool.
 pub fn spawn<F, T>(future: F, stack: ProcStack) -> RecoverableHandle<T>
 where
     F: Future<Output = T> + Send + 'static,
@@ -45,25 +72,283 @@ where
     self::get().spawn(future, stack)
 }
 
+///
+/// Spawn a process onto the executor from the global level, or return why the executor
+/// couldn't be started instead of panicking.
+///
+/// The executor is started on its first use, which fails if none of its worker threads
+/// could be spawned (e.g. because the process reached its thread limit). It isn't
+/// started again afterwards, so every later spawn fails the same way.
+///
+/// The spawn also fails if the maximum amount of live processes was reached (see
+/// [load_balancer::set_max_tasks]), after waiting for a live process to complete if
+/// asked to (see [load_balancer::set_over_limit_policy]).
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// match try_spawn(async { 1 + 2 }, ProcStack::default()) {
+///     Ok(handle) => assert_eq!(run(handle, ProcStack::default()), Some(3)),
+///     Err(err) => eprintln!("couldn't start the executor: {}", err),
+/// }
+/// ```
+pub fn try_spawn<F, T>(future: F, stack: ProcStack) -> io::Result<RecoverableHandle<T>>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    self::try_get()?.try_spawn(future, stack)
+}
+
+///
+/// Schedule an already built process onto the executor.
+///
+/// This is the schedule function used by [spawn] and can be used to run
+/// processes built with [LightProc::recoverable] or [LightProc::build].
+pub fn schedule(proc: LightProc) {
+    worker::schedule(proc)
+}
+
+///
+/// Spawn a process onto the executor and return its handle together with a [CancelGuard].
+///
+/// Dropping the returned handle detaches the process, while dropping the guard cancels it.
+/// This allows tying the lifetime of a process to a scope.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let (handle, guard) = spawn_scoped(
+///     async {
+///         loop {
+///             yield_now().await;
+///         }
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// drop(guard);
+///
+/// let res: Option<()> = run(handle, ProcStack::default());
+/// assert!(res.is_none());
+/// ```
+pub fn spawn_scoped<F, T>(future: F, stack: ProcStack) -> (RecoverableHandle<T>, CancelGuard)
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let handle = self::spawn(future, stack);
+    let guard = handle.cancel_guard();
+    (handle, guard)
+}
+
+///
+/// Spawn a process onto the executor with the given scheduling [Priority].
+///
+/// This builds the process stack with the priority set, so that there is no need
+/// to build a [ProcStack] manually. Processes with a [Priority::High] priority are
+/// picked up by the workers before any other process.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let handle = spawn_with_priority(Priority::High, async { 1 + 2 });
+///
+/// let res = run(handle, ProcStack::default());
+/// assert_eq!(res, Some(3));
+/// ```
+pub fn spawn_with_priority<F, T>(priority: Priority, future: F) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    self::spawn(future, ProcStack::default().with_priority(priority))
+}
+
+///
+/// Yields the execution of the current process back to the pool.
+///
+/// The process is rescheduled to the back of the run queue, giving the other
+/// processes waiting on the same worker a chance to make progress. This is useful
+/// for long-running CPU-bound processes which would starve their core otherwise.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let handle = spawn(
+///     async {
+///         let mut sum = 0;
+///         for i in 0..1_000 {
+///             sum += i;
+///             if i % 100 == 0 {
+///                 yield_now().await;
+///             }
+///         }
+///         sum
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// let sum = run(handle, ProcStack::default());
+/// assert_eq!(sum, Some(499_500));
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+///
+/// Future returned by [yield_now].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+
+        // Waking while the proc is running only marks it as scheduled,
+        // the proc is pushed back to the run queue once this poll returns.
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+///
+/// Extension of [RecoverableHandle] composing processes on the executor.
+pub trait ProcHandleExt<R> {
+    ///
+    /// Spawn a process which awaits this handle and feeds the output of its
+    /// process to `f`, returning the handle of the continuation.
+    ///
+    /// If the first process panicked or got cancelled, `f` isn't called and the
+    /// continuation resolves to `None`. Cancelling the continuation cancels it
+    /// if it's still pending, but doesn't cancel the first process.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bastion_executor::prelude::*;
+    /// use lightproc::prelude::*;
+    ///
+    /// let handle = spawn(async { 21 }, ProcStack::default())
+    ///     .and_then(|res| async move { res * 2 })
+    ///     .and_then(|res| async move { res.to_string() });
+    ///
+    /// let res = run(handle, ProcStack::default());
+    /// assert_eq!(res, Some("42".to_string()));
+    /// ```
+    fn and_then<F, Fut, T>(self, f: F) -> RecoverableHandle<T>
+    where
+        F: FnOnce(R) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static;
+}
+
+impl<R: Send + 'static> ProcHandleExt<R> for RecoverableHandle<R> {
+    fn and_then<F, Fut, T>(self, f: F) -> RecoverableHandle<T>
+    where
+        F: FnOnce(R) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (task, handle) = LightProc::and_then(self, f, worker::schedule, ProcStack::default());
+        task.schedule();
+        handle
+    }
+}
+
 ///
 /// Pool that global run queue, stealers of the workers, and parked threads.
 #[derive(Debug)]
 pub struct Pool {
     ///
-    /// Global run queue implementation
-    pub(crate) injector: Injector<LightProc>,
+    /// Global run queue implementation, bounded for the processes spawned from outside
+    /// of the workers
+    pub(crate) injector: FairInjector<LightProc>,
+    ///
+    /// Global run queue of the high priority processes
+    pub(crate) priority_injector: Injector<LightProc>,
+    ///
+    /// Global run queue of the low priority processes
+    pub(crate) low_injector: Injector<LightProc>,
     ///
-    /// Stealers of the workers
-    pub(crate) stealers: Vec<Stealer<LightProc>>,
+    /// Stealers of the workers, indexed by their core (`None` for the cores without a
+    /// running worker)
+    pub(crate) stealers: ShardedLock<Vec<Option<Stealer<LightProc>>>>,
     ///
     /// Container of parked threads
     pub(crate) sleepers: Sleepers,
+    ///
+    /// Whether the external workers were asked to return
+    pub(crate) shut_down: AtomicBool,
 }
 
 impl Pool {
     ///
     /// Spawn a process (which contains future + process stack) onto the executor via [Pool] interface.
+    ///
+    /// If the maximum amount of live processes was reached, the future is dropped without
+    /// being run and the returned handle resolves to `None`.
     pub fn spawn<F, T>(&self, future: F, stack: ProcStack) -> RecoverableHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let live = LiveTask::acquire();
+        let rejected = live.is_none();
+        let (task, handle) = self.build(future, stack, live);
+        if rejected {
+            // Dropping the process without running it closes its handle.
+            drop(task);
+        } else {
+            worker::schedule_spawned(task);
+        }
+        handle
+    }
+
+    ///
+    /// Spawn a process onto the executor via [Pool] interface, or return an error if the
+    /// maximum amount of live processes was reached.
+    pub fn try_spawn<F, T>(&self, future: F, stack: ProcStack) -> io::Result<RecoverableHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let live = match LiveTask::acquire() {
+            Some(live) => live,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "the maximum amount of live processes was reached",
+                ))
+            }
+        };
+
+        let (task, handle) = self.build(future, stack, Some(live));
+        worker::schedule_spawned(task);
+        Ok(handle)
+    }
+
+    fn build<F, T>(
+        &self,
+        future: F,
+        stack: ProcStack,
+        live: Option<LiveTask>,
+    ) -> (LightProc, RecoverableHandle<T>)
     where
         F: Future<Output = T> + Send + 'static,
         T: Send + 'static,
@@ -72,27 +357,277 @@ impl Pool {
         let _child_id = stack.get_pid() as u64;
         let _parent_id = worker::get_proc_stack(|t| t.get_pid() as u64).unwrap_or(0);
 
+        // The process stops being live once its future is dropped, as it completed or
+        // got cancelled.
+        let future = async move {
+            let _live = live;
+            future.await
+        };
+
         let (task, handle) = LightProc::recoverable(future, worker::schedule, stack);
-        task.schedule();
-        handle
+        deadlines::watch(&handle);
+        (task, handle)
+    }
+}
+
+/// A live process, counted until it is dropped.
+struct LiveTask;
+
+impl LiveTask {
+    fn acquire() -> Option<LiveTask> {
+        let stats = load_balancer::stats();
+        if stats.reserve_task() {
+            return Some(LiveTask);
+        }
+
+        // Blocking a thread running a process (e.g. a worker) could wait for the
+        // processes only it can complete.
+        let in_proc = worker::get_proc_stack(|_| ()).is_some();
+        let deadline = match stats.over_limit_policy() {
+            _ if in_proc => {
+                stats.record_rejected_task();
+                return None;
+            }
+            OverLimitPolicy::Reject => {
+                stats.record_rejected_task();
+                return None;
+            }
+            OverLimitPolicy::Block => None,
+            OverLimitPolicy::BlockWithTimeout(timeout) => Some(Instant::now() + timeout),
+        };
+
+        let (lock, released) = &*TASK_RELEASED;
+        let mut guard = lock.lock().unwrap();
+        // The live processes notify the waiters once registered, so
+        // none of the releases is missed.
+        OVER_LIMIT_WAITERS.fetch_add(1, Ordering::SeqCst);
+        let acquired = loop {
+            if stats.reserve_task() {
+                break true;
+            }
+
+            match deadline {
+                None => guard = released.wait(guard).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break false;
+                    }
+
+                    guard = released.wait_timeout(guard, deadline - now).unwrap().0;
+                }
+            }
+        };
+        OVER_LIMIT_WAITERS.fetch_sub(1, Ordering::SeqCst);
+
+        if acquired {
+            Some(LiveTask)
+        } else {
+            stats.record_rejected_task();
+            None
+        }
+    }
+}
+
+impl Drop for LiveTask {
+    fn drop(&mut self) {
+        load_balancer::stats().release_task();
+        if OVER_LIMIT_WAITERS.load(Ordering::SeqCst) > 0 {
+            let _guard = TASK_RELEASED.0.lock().unwrap();
+            TASK_RELEASED.1.notify_all();
+        }
+    }
+}
+
+///
+/// Sets how many processes spawned from outside of the workers can wait in the global
+/// run queue.
+///
+/// Once the global run queue is full, spawning a process from outside of the workers
+/// waits until the workers took some processes from it. The processes being woken up
+/// are never held back. The default capacity is 65536 processes.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// set_global_queue_capacity(1024);
+///
+/// let handle = spawn(async { 1 + 2 }, ProcStack::default());
+/// assert_eq!(run(handle, ProcStack::default()), Some(3));
+/// ```
+pub fn set_global_queue_capacity(capacity: usize) {
+    self::get().injector.set_capacity(capacity)
+}
+
+///
+/// Makes the pool run its processes on threads provided by the application (see [run_worker])
+/// instead of spawning its own worker threads.
+///
+/// This must be called before the pool is first used (e.g. by spawning a process), and returns
+/// an error otherwise. The blocking processes (see [spawn_blocking]) still run on the threads
+/// of the blocking pool.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+/// use std::thread;
+///
+/// use_external_workers().unwrap();
+///
+/// let worker = thread::spawn(|| run_worker(0));
+///
+/// let handle = spawn(async { 1 + 2 }, ProcStack::default());
+/// assert_eq!(run(handle, ProcStack::default()), Some(3));
+///
+/// shutdown_workers();
+/// worker.join().unwrap().unwrap();
+/// ```
+///
+/// [spawn_blocking]: ../blocking/fn.spawn_blocking.html
+pub fn use_external_workers() -> io::Result<()> {
+    if STARTED.load(Ordering::SeqCst) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the pool already started its own worker threads",
+        ));
+    }
+
+    EXTERNAL_WORKERS.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+///
+/// Blocks the current thread, running the processes of the pool as the worker of the given
+/// core until [shutdown_workers] is called.
+///
+/// The pool must have been set up to run on external workers with [use_external_workers]. The
+/// thread isn't pinned to the core, whose id (lower than [load_balancer::core_retrieval]) only
+/// tells the workers apart: each core can be run by a single worker at a time. The workers can
+/// join and leave at any time, the processes left in the run queue of a worker being handed to
+/// the other ones when it returns.
+///
+/// Returns an error if the pool doesn't run on external workers, if the core doesn't exist or
+/// if it already has a running worker.
+pub fn run_worker(core_id: usize) -> io::Result<()> {
+    if !EXTERNAL_WORKERS.load(Ordering::SeqCst) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the pool runs its own worker threads (see `use_external_workers`)",
+        ));
+    }
+
+    if core_id >= *load_balancer::core_retrieval() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("there is no core {}", core_id),
+        ));
+    }
+
+    let pool = self::try_get()?;
+    let wrk = Worker::new_fifo();
+    {
+        let mut stealers = pool.stealers.write().unwrap();
+        if stealers.len() <= core_id {
+            stealers.resize_with(core_id + 1, || None);
+        }
+
+        if stealers[core_id].is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("core {} already has a running worker", core_id),
+            ));
+        }
+        stealers[core_id] = Some(wrk.stealer());
+    }
+
+    load_balancer::stats().add_worker();
+    worker::stats_generator(core_id, &wrk);
+    let wrk = worker::main_loop(core_id, wrk, || !pool.shut_down.load(Ordering::SeqCst));
+
+    // Hand the processes left behind to the other workers.
+    pool.stealers.write().unwrap()[core_id] = None;
+    while let Some(proc) = wrk.pop() {
+        pool.injector.push(proc);
+    }
+    load_balancer::stats().store_load(core_id, 0);
+    load_balancer::stats().remove_worker();
+    pool.sleepers.notify_one();
+
+    Ok(())
+}
+
+///
+/// Makes the workers run with [run_worker] return once they are done running their current
+/// process, including the ones which are parked.
+///
+/// The shutdown is final: the workers started afterwards return right away.
+pub fn shutdown_workers() {
+    if let Ok(pool) = self::try_get() {
+        pool.shut_down.store(true, Ordering::SeqCst);
+        pool.sleepers.notify_all();
     }
 }
 
 ///
 /// Acquire the static Pool reference
+///
+/// # Panics
+///
+/// Panics if none of the worker threads of the pool could be started (see [try_get]).
 #[inline]
 pub fn get() -> &'static Pool {
+    match self::try_get() {
+        Ok(pool) => pool,
+        Err(err) => panic!("cannot start the executor: {}", err),
+    }
+}
+
+///
+/// Acquire the static Pool reference, or the error which prevented it from starting.
+///
+/// The pool starts as long as at least one of its worker threads could be spawned, or right
+/// away if it runs on external workers (see [use_external_workers]).
+pub fn try_get() -> io::Result<&'static Pool> {
     lazy_static! {
-        static ref POOL: Pool = {
-            let distributor = Distributor::new();
-            let stealers = distributor.assign();
+        static ref POOL: io::Result<Pool> = {
+            STARTED.store(true, Ordering::SeqCst);
 
-            Pool {
-                injector: Injector::new(),
-                stealers,
-                sleepers: Sleepers::new(),
+            let mut stealers = Vec::new();
+            if EXTERNAL_WORKERS.load(Ordering::SeqCst) {
+                // The workers register themselves once they are run.
+                load_balancer::stats().set_workers(0);
+            } else {
+                let distributor = Distributor::new();
+                for (core, stealer) in distributor.assign()? {
+                    if stealers.len() <= core {
+                        stealers.resize_with(core + 1, || None);
+                    }
+                    stealers[core] = Some(stealer);
+                }
             }
+
+            // The utilization of the cores is computed when the
+            // runtime is sampled.
+            #[cfg(feature = "poll-stats")]
+            let _ = load_balancer::start_sampling();
+
+            Ok(Pool {
+                injector: FairInjector::new(fair_injector::DEFAULT_CAPACITY),
+                priority_injector: Injector::new(),
+                low_injector: Injector::new(),
+                stealers: ShardedLock::new(stealers),
+                sleepers: Sleepers::new(),
+                shut_down: AtomicBool::new(false),
+            })
         };
     }
-    &*POOL
+
+    match &*POOL {
+        Ok(pool) => Ok(pool),
+        // The error can't be cloned.
+        Err(err) => Err(io::Error::new(err.kind(), err.to_string())),
+    }
 }
@@ -0,0 +1,244 @@
+//!
+//! Processes producing a stream of values instead of a single output.
+//!
+//! The values are accumulated into a bounded buffer which the consumer
+//! drains in chunks, rather than awaiting one handle per value. This suits
+//! generator-style processes with a high throughput.
+use crate::pool;
+use lightproc::proc_stack::ProcStack;
+use lightproc::recoverable_handle::RecoverableHandle;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    buffer: VecDeque<T>,
+    capacity: usize,
+    // Set once the sink got dropped, i.e. the producer completed.
+    finished: bool,
+    // Set once the stream got dropped.
+    closed: bool,
+    producer: Option<Waker>,
+    consumer: Option<Waker>,
+}
+
+///
+/// Spawn a process producing values through a [StreamSink] and return the
+/// [ProcStream] used to consume them.
+///
+/// At most `capacity` values are buffered: once the buffer is full, the
+/// producer waits for the consumer to drain it before being able to send
+/// more values.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let mut stream = spawn_stream(
+///     16,
+///     |sink| async move {
+///         for i in 0..100 {
+///             if sink.send(i).await.is_err() {
+///                 return;
+///             }
+///         }
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// let sum = run(
+///     async move {
+///         let mut sum = 0;
+///         while let Some(chunk) = stream.next_chunk().await {
+///             assert!(chunk.len() <= 16);
+///             sum += chunk.iter().sum::<u32>();
+///         }
+///         sum
+///     },
+///     ProcStack::default(),
+/// );
+///
+/// assert_eq!(sum, 4950);
+/// ```
+pub fn spawn_stream<F, Fut, T>(capacity: usize, producer: F, stack: ProcStack) -> ProcStream<T>
+where
+    F: FnOnce(StreamSink<T>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+    T: Send + 'static,
+{
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Mutex::new(Shared {
+        buffer: VecDeque::with_capacity(capacity),
+        capacity,
+        finished: false,
+        closed: false,
+        producer: None,
+        consumer: None,
+    }));
+
+    let sink = StreamSink {
+        shared: shared.clone(),
+    };
+    let handle = pool::spawn(producer(sink), stack);
+
+    ProcStream { shared, handle }
+}
+
+///
+/// Consumer side of a process spawned with [spawn_stream].
+///
+/// Dropping it detaches the process, whose next sends fail.
+pub struct ProcStream<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    handle: RecoverableHandle<()>,
+}
+
+impl<T> ProcStream<T> {
+    ///
+    /// Returns a future resolving to all the values currently buffered once
+    /// there is at least one, or to `None` once the producer completed and
+    /// all its values were consumed.
+    pub fn next_chunk(&mut self) -> NextChunk<'_, T> {
+        NextChunk { stream: self }
+    }
+
+    ///
+    /// Returns the handle of the producing process, e.g. to cancel it.
+    pub fn handle(&self) -> &RecoverableHandle<()> {
+        &self.handle
+    }
+}
+
+impl<T> Drop for ProcStream<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        shared.buffer.clear();
+        if let Some(waker) = shared.producer.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Debug for ProcStream<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("ProcStream")
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+///
+/// Future returned by [ProcStream::next_chunk].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextChunk<'a, T> {
+    stream: &'a mut ProcStream<T>,
+}
+
+impl<T> Future for NextChunk<'_, T> {
+    type Output = Option<Vec<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.stream.shared.lock().unwrap();
+
+        if !shared.buffer.is_empty() {
+            let chunk = shared.buffer.drain(..).collect();
+            // There is room in the buffer again.
+            if let Some(waker) = shared.producer.take() {
+                waker.wake();
+            }
+            return Poll::Ready(Some(chunk));
+        }
+
+        // The producer might also have panicked or been cancelled, in which
+        // case the sink got dropped too.
+        if shared.finished {
+            return Poll::Ready(None);
+        }
+
+        shared.consumer = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+///
+/// Producer side of a process spawned with [spawn_stream].
+///
+/// Dropping it ends the stream once its buffered values were consumed.
+pub struct StreamSink<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> StreamSink<T> {
+    ///
+    /// Returns a future which buffers the given value as soon as there is room
+    /// for it, resolving to `Err(value)` if the [ProcStream] got dropped.
+    pub fn send(&self, value: T) -> StreamSend<'_, T> {
+        StreamSend {
+            sink: self,
+            value: Some(value),
+        }
+    }
+}
+
+impl<T> Drop for StreamSink<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.finished = true;
+        if let Some(waker) = shared.consumer.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Debug for StreamSink<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("StreamSink").finish()
+    }
+}
+
+///
+/// Future returned by [StreamSink::send].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct StreamSend<'a, T> {
+    sink: &'a StreamSink<T>,
+    value: Option<T>,
+}
+
+impl<T> Unpin for StreamSend<'_, T> {}
+
+impl<T> Future for StreamSend<'_, T> {
+    type Output = Result<(), T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut shared = this.sink.shared.lock().unwrap();
+        let value = this
+            .value
+            .take()
+            .expect("`StreamSend` polled after completion");
+
+        if shared.closed {
+            return Poll::Ready(Err(value));
+        }
+
+        if shared.buffer.len() >= shared.capacity {
+            this.value = Some(value);
+            shared.producer = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        shared.buffer.push_back(value);
+        if let Some(waker) = shared.consumer.take() {
+            waker.wake();
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
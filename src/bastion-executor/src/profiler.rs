@@ -0,0 +1,195 @@
+//!
+//! Sampling profiler estimating the share of the workers' time spent running each process.
+//!
+//! Once [enable]d, the workers publish the name of the process they are running and the
+//! load balancer thread, which wakes up periodically, samples them (enabling the profiler
+//! starts that thread if it isn't running yet). Every sample
+//! counts towards the process running on the sampled core, if any, which makes the share of
+//! the samples of a process an estimate of the share of CPU time it used.
+//!
+//! Processes are named by their `name` metadata (see [ProcStack::with_metadata]), or by their
+//! pid otherwise. The hottest ones are returned by [profile_snapshot].
+//!
+//! The profiler is disabled by default, in which case the workers only check whether it is
+//! enabled before running processes. Once enabled, they copy the names of the processes into
+//! buffers which are reused, so the profiler doesn't allocate besides recording new names.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::profiler;
+//! use bastion_executor::prelude::*;
+//! use lightproc::prelude::*;
+//!
+//! profiler::enable().unwrap();
+//! let handle = spawn(
+//!     async { (0..1_000).sum::<usize>() },
+//!     ProcStack::default().with_metadata("name", "sum"),
+//! );
+//! run(handle, ProcStack::default());
+//!
+//! for task in profiler::profile_snapshot() {
+//!     println!("{}: {:.1}%", task.name, task.share * 100.0);
+//! }
+//! profiler::disable();
+//! ```
+//!
+//! [ProcStack::with_metadata]: ../../lightproc/proc_stack/struct.ProcStack.html#method.with_metadata
+use crate::load_balancer;
+use lazy_static::lazy_static;
+use lightproc::proc_stack::ProcStack;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Metadata key of the name of the processes.
+pub const NAME_KEY: &str = "name";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    // The name of the process running on each core, empty while idle.
+    static ref RUNNING: Vec<Mutex<String>> = (0..*load_balancer::core_retrieval())
+        .map(|_| Mutex::new(String::new()))
+        .collect();
+    static ref SAMPLES: Mutex<Samples> = Mutex::new(Samples::default());
+}
+
+#[derive(Default)]
+struct Samples {
+    // The amount of samples of every core, including the idle ones...
+    total: usize,
+    // ...and of each process.
+    tasks: HashMap<String, usize>,
+}
+
+///
+/// Estimated share of the workers' time spent running a process, as returned by
+/// [profile_snapshot].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskProfile {
+    /// Name of the process.
+    pub name: String,
+    /// Amount of samples during which the process was running.
+    pub samples: usize,
+    /// Share of the samples (between `0` and `1`) during which the process was running.
+    pub share: f64,
+}
+
+///
+/// Enables the profiler, which starts sampling the processes run from now on.
+///
+/// Returns an error if the load balancer thread, which takes the samples, couldn't be
+/// spawned.
+pub fn enable() -> io::Result<()> {
+    load_balancer::start_sampling()?;
+
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+///
+/// Disables the profiler, keeping the samples taken so far.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+    for running in RUNNING.iter() {
+        running.lock().unwrap().clear();
+    }
+}
+
+///
+/// Returns whether the profiler is enabled.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+///
+/// Discards the samples taken so far.
+pub fn reset() {
+    let mut samples = SAMPLES.lock().unwrap();
+    samples.total = 0;
+    samples.tasks.clear();
+}
+
+///
+/// Returns the processes which were sampled so far, from the hottest one to the coldest one.
+pub fn profile_snapshot() -> Vec<TaskProfile> {
+    let samples = SAMPLES.lock().unwrap();
+    let mut tasks = samples
+        .tasks
+        .iter()
+        .map(|(name, count)| TaskProfile {
+            name: name.clone(),
+            samples: *count,
+            share: *count as f64 / samples.total as f64,
+        })
+        .collect::<Vec<_>>();
+
+    tasks.sort_by(|a, b| b.samples.cmp(&a.samples).then_with(|| a.name.cmp(&b.name)));
+    tasks
+}
+
+///
+/// Publishes the name of the process with the given stack as running on the given core if
+/// the profiler is enabled, until the returned guard is dropped.
+///
+/// The name is copied as the stack might be freed while the process runs.
+pub(crate) fn running(affinity: usize, stack: &ProcStack) -> Running {
+    if !is_enabled() {
+        return Running(None);
+    }
+
+    let slot = match RUNNING.get(affinity) {
+        Some(slot) => slot,
+        None => return Running(None),
+    };
+
+    let mut running = slot.lock().unwrap();
+    running.clear();
+    match stack.get_metadata(NAME_KEY) {
+        Some(name) => running.push_str(name),
+        None => {
+            write!(running, "pid {}", stack.get_pid()).ok();
+        }
+    }
+
+    Running(Some(slot))
+}
+
+/// The guard returned by [running], marking the core as idle once dropped.
+pub(crate) struct Running(Option<&'static Mutex<String>>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if let Some(slot) = self.0 {
+            slot.lock().unwrap().clear();
+        }
+    }
+}
+
+///
+/// Samples the processes running on every core, if the profiler is enabled.
+pub(crate) fn sample() {
+    if !is_enabled() {
+        return;
+    }
+
+    let mut samples = SAMPLES.lock().unwrap();
+    for running in RUNNING.iter() {
+        samples.total += 1;
+
+        let running = running.lock().unwrap();
+        if running.is_empty() {
+            continue;
+        }
+
+        match samples.tasks.get_mut(running.as_str()) {
+            Some(count) => *count += 1,
+            None => {
+                samples.tasks.insert(running.clone(), 1);
+            }
+        }
+    }
+}
@@ -3,11 +3,10 @@
 //!
 //!
 use crate::worker;
-use crossbeam_utils::sync::Parker;
+use crossbeam_utils::sync::{Parker, Unparker};
 use lightproc::proc_stack::ProcStack;
 use std::cell::{Cell, UnsafeCell};
 use std::future::Future;
-use std::mem::ManuallyDrop;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
@@ -70,63 +69,118 @@ where
     }
 }
 
+///
+/// Drives the passed future to completion on the current thread and returns its output,
+/// while the processes it spawns run on the worker pool.
+///
+/// This is the entry point to use Bastion Executor as the main runtime (e.g. from `main`).
+/// Unlike [run], it can't be nested: calling it from within the future passed to
+/// another `block_on` panics, as the outer call would be blocked until the inner one returns.
+///
+/// # Example
+/// ```rust
+/// use bastion_executor::prelude::*;
+/// use lightproc::prelude::*;
+///
+/// let res = block_on(async {
+///     let handle = spawn(async { 1 + 2 }, ProcStack::default());
+///     handle.await
+/// });
+///
+/// assert_eq!(res, Some(3));
+/// ```
+pub fn block_on<F, T>(future: F) -> T
+where
+    F: Future<Output = T>,
+{
+    thread_local! {
+        static BLOCKING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    struct ResetBlocking<'a>(&'a Cell<bool>);
+
+    impl Drop for ResetBlocking<'_> {
+        fn drop(&mut self) {
+            self.0.set(false);
+        }
+    }
+
+    BLOCKING.with(|blocking| {
+        if blocking.replace(true) {
+            panic!(
+                "`block_on` cannot be called from within a future which is already \
+                 driven by `block_on` on the same thread"
+            );
+        }
+        let _guard = ResetBlocking(blocking);
+
+        run(future, ProcStack::default())
+    })
+}
+
 fn block<F, T>(f: F) -> T
 where
     F: Future<Output = T>,
 {
     thread_local! {
-        // May hold a pre-allocated parker that can be reused for efficiency.
+        // May hold a pre-allocated parker and its waker that can be reused for efficiency.
         //
         // Note that each invocation of `block` needs its own parker. In particular, if `block`
         // recursively calls itself, we must make sure that each recursive call uses a distinct
         // parker instance.
-        static CACHE: Cell<Option<Arc<Parker>>> = Cell::new(None);
+        static CACHE: Cell<Option<(Parker, Waker)>> = const { Cell::new(None) };
     }
 
     pin_utils::pin_mut!(f);
 
     CACHE.with(|cache| {
         // Reuse a cached parker or create a new one for this invocation of `block`.
-        let arc_parker: Arc<Parker> = cache.take().unwrap_or_else(|| Arc::new(Parker::new()));
-
-        let ptr = (&*arc_parker as *const Parker) as *const ();
-        let vt = vtable();
-
-        let waker = unsafe { ManuallyDrop::new(Waker::from_raw(RawWaker::new(ptr, vt))) };
+        let (parker, waker) = cache.take().unwrap_or_else(|| {
+            let parker = Parker::new();
+            let waker = waker(parker.unparker().clone());
+            (parker, waker)
+        });
         let cx = &mut Context::from_waker(&waker);
 
         loop {
             if let Poll::Ready(t) = f.as_mut().poll(cx) {
                 // Save the parker for the next invocation of `block`.
-                cache.set(Some(arc_parker));
+                cache.set(Some((parker, waker)));
                 return t;
             }
-            arc_parker.park();
+            parker.park();
         }
     })
 }
 
-fn vtable() -> &'static RawWakerVTable {
+///
+/// Creates a waker unparking the thread of the passed unparker's parker.
+///
+/// Only the unparker is shared with the waker, as it can be woken from any thread
+/// while the parker stays on the thread parking on it.
+pub(crate) fn waker(unparker: Unparker) -> Waker {
     unsafe fn clone_raw(ptr: *const ()) -> RawWaker {
-        #![allow(clippy::redundant_clone)]
-        let arc = ManuallyDrop::new(Arc::from_raw(ptr as *const Parker));
-        mem::forget(arc.clone());
+        Arc::increment_strong_count(ptr as *const Unparker);
         RawWaker::new(ptr, vtable())
     }
 
     unsafe fn wake_raw(ptr: *const ()) {
-        let arc = Arc::from_raw(ptr as *const Parker);
-        arc.unparker().unpark();
+        let arc = Arc::from_raw(ptr as *const Unparker);
+        arc.unpark();
     }
 
     unsafe fn wake_by_ref_raw(ptr: *const ()) {
-        let arc = ManuallyDrop::new(Arc::from_raw(ptr as *const Parker));
-        arc.unparker().unpark();
+        (*(ptr as *const Unparker)).unpark();
     }
 
     unsafe fn drop_raw(ptr: *const ()) {
-        drop(Arc::from_raw(ptr as *const Parker))
+        drop(Arc::from_raw(ptr as *const Unparker))
+    }
+
+    fn vtable() -> &'static RawWakerVTable {
+        &RawWakerVTable::new(clone_raw, wake_raw, wake_by_ref_raw, drop_raw)
     }
 
-    &RawWakerVTable::new(clone_raw, wake_raw, wake_by_ref_raw, drop_raw)
+    let ptr = Arc::into_raw(Arc::new(unparker)) as *const ();
+    unsafe { Waker::from_raw(RawWaker::new(ptr, vtable())) }
 }
@@ -0,0 +1,191 @@
+//!
+//! Selection of the priority level the workers take their next process from.
+//!
+//! By default, the workers always run the processes with the highest [Priority]
+//! first, which can starve the processes with a lower priority forever under a
+//! constant load of higher priority ones. The [SchedulingMode::WeightedFair] mode
+//! gives each level a share of the polls instead, using a deficit round-robin over
+//! the queues of each level.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bastion_executor::scheduling::{self, SchedulingMode, Shares};
+//!
+//! // 70% of the polls go to the high priority processes, 25% to the normal ones
+//! // and 5% to the low priority ones, as long as each level has processes to run.
+//! scheduling::set_mode(SchedulingMode::WeightedFair(Shares::new(70, 25, 5)));
+//! # scheduling::set_mode(SchedulingMode::Strict);
+//! ```
+use lightproc::proc_stack::Priority;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// The levels, in the order they are visited.
+const LEVELS: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+static WEIGHTED_FAIR: AtomicBool = AtomicBool::new(false);
+static SHARES: [AtomicU32; 3] = [AtomicU32::new(70), AtomicU32::new(25), AtomicU32::new(5)];
+
+/// How the workers pick the priority level of the next process they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedulingMode {
+    /// The processes with a higher priority always run first.
+    #[default]
+    Strict,
+    /// Each priority level gets a share of the polls, so that the processes with a
+    /// lower priority keep making progress.
+    WeightedFair(Shares),
+}
+
+/// The share of the polls given to each priority level in the
+/// [SchedulingMode::WeightedFair] mode.
+///
+/// The shares are relative to each other, and a level without processes to run
+/// leaves its share to the others. The default shares are 70/25/5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shares {
+    high: u32,
+    normal: u32,
+    low: u32,
+}
+
+impl Shares {
+    /// Creates new shares for the high, normal and low priority levels.
+    ///
+    /// Every level gets a share of at least one poll per round, so that none of
+    /// them can be starved.
+    pub fn new(high: u32, normal: u32, low: u32) -> Self {
+        Shares {
+            high: high.max(1),
+            normal: normal.max(1),
+            low: low.max(1),
+        }
+    }
+
+    /// Returns the share of the given priority level.
+    pub fn get(&self, priority: Priority) -> u32 {
+        match priority {
+            Priority::High => self.high,
+            Priority::Normal => self.normal,
+            Priority::Low => self.low,
+        }
+    }
+}
+
+impl Default for Shares {
+    fn default() -> Self {
+        Shares::new(70, 25, 5)
+    }
+}
+
+///
+/// Sets how the workers pick the priority level of the next process they run.
+pub fn set_mode(mode: SchedulingMode) {
+    match mode {
+        SchedulingMode::Strict => WEIGHTED_FAIR.store(false, Ordering::Release),
+        SchedulingMode::WeightedFair(shares) => {
+            for (share, level) in SHARES.iter().zip(LEVELS.iter()) {
+                share.store(shares.get(*level), Ordering::Relaxed);
+            }
+            WEIGHTED_FAIR.store(true, Ordering::Release);
+        }
+    }
+}
+
+///
+/// Returns how the workers pick the priority level of the next process they run.
+pub fn mode() -> SchedulingMode {
+    if !WEIGHTED_FAIR.load(Ordering::Acquire) {
+        return SchedulingMode::Strict;
+    }
+
+    SchedulingMode::WeightedFair(Shares::new(
+        SHARES[0].load(Ordering::Relaxed),
+        SHARES[1].load(Ordering::Relaxed),
+        SHARES[2].load(Ordering::Relaxed),
+    ))
+}
+
+/// The state of the deficit round-robin of a worker.
+#[derive(Debug, Default)]
+pub(crate) struct DeficitRoundRobin {
+    // The number of polls each level can still get during its turn.
+    deficits: [u32; 3],
+    // The index of the level whose turn it is.
+    current: usize,
+}
+
+impl DeficitRoundRobin {
+    /// Takes the next item from the queues of the levels using `fetch`, giving
+    /// each level a number of items per round proportional to its share.
+    pub(crate) fn next<T, F>(&mut self, shares: Shares, mut fetch: F) -> Option<T>
+    where
+        F: FnMut(Priority) -> Option<T>,
+    {
+        // The level whose turn it is might be finishing it, so every level is
+        // visited once more to make sure that all of them were tried.
+        for _ in 0..=LEVELS.len() {
+            let level = self.current;
+            if self.deficits[level] > 0 {
+                if let Some(item) = fetch(LEVELS[level]) {
+                    self.deficits[level] -= 1;
+                    return Some(item);
+                }
+
+                // A level doesn't accumulate polls while it has nothing to run.
+                self.deficits[level] = 0;
+            }
+
+            self.current = (level + 1) % LEVELS.len();
+            self.deficits[self.current] += shares.get(LEVELS[self.current]);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeficitRoundRobin, Shares};
+    use lightproc::proc_stack::Priority;
+
+    #[test]
+    fn splits_polls_by_shares() {
+        let mut drr = DeficitRoundRobin::default();
+        let shares = Shares::new(70, 25, 5);
+
+        let mut polls = [0; 3];
+        for _ in 0..1000 {
+            let level = drr.next(shares, Some).unwrap();
+            polls[level as usize] += 1;
+        }
+
+        assert_eq!(polls, [700, 250, 50]);
+    }
+
+    #[test]
+    fn skips_empty_levels() {
+        let mut drr = DeficitRoundRobin::default();
+        let shares = Shares::new(70, 25, 5);
+
+        // Only the low priority level has items to run.
+        for _ in 0..100 {
+            let level = drr.next(shares, |priority| match priority {
+                Priority::Low => Some(priority),
+                _ => None,
+            });
+            assert_eq!(level, Some(Priority::Low));
+        }
+
+        assert_eq!(drr.next(shares, |_| None::<Priority>), None);
+
+        // The low priority level didn't keep the polls it got while the others
+        // were empty.
+        let mut polls = [0; 3];
+        for _ in 0..100 {
+            let level = drr.next(shares, Some).unwrap();
+            polls[level as usize] += 1;
+        }
+        assert!(polls[Priority::Low as usize] <= 10);
+    }
+}
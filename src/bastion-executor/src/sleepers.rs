@@ -43,8 +43,22 @@ impl Sleepers {
 
     /// Puts the current thread to sleep.
     pub fn wait(&self) {
+        self.wait_unless(|| false)
+    }
+
+    /// Puts the current thread to sleep, unless `done` returns `true`.
+    ///
+    /// `done` is checked while holding the lock taken by [notify_all], so a thread can't go
+    /// to sleep after missing a notification sent once the condition became true.
+    ///
+    /// [notify_all]: struct.Sleepers.html#method.notify_all
+    pub fn wait_unless<F: Fn() -> bool>(&self, done: F) {
         let mut sleep = self.sleep.lock().unwrap();
 
+        if done() {
+            return;
+        }
+
         if !self.notified.swap(false, Ordering::SeqCst) {
             *sleep += 1;
             std::mem::drop(self.wake.wait(sleep).unwrap());
@@ -64,4 +78,11 @@ impl Sleepers {
             }
         }
     }
+
+    /// Notifies every sleeping thread.
+    pub fn notify_all(&self) {
+        let mut sleep = self.sleep.lock().unwrap();
+        *sleep = 0;
+        self.wake.notify_all();
+    }
 }
@@ -3,12 +3,16 @@
 //!
 //! This worker implementation relies on worker run queue statistics which are hold in the pinned global memory
 //! where workload distribution calculated and amended to their own local queues.
+use crate::lifecycle::{self, WorkerEvent};
 use crate::load_balancer;
 use crate::pool::{self, Pool};
-use crate::run_queue::{Steal, Worker};
+use crate::profiler;
+use crate::run_queue::{Injector, Steal, Worker};
+use crate::scheduling::{self, DeficitRoundRobin, SchedulingMode};
+use crossbeam_utils::Backoff;
 use lightproc::prelude::*;
 use load_balancer::SmpStats;
-use std::cell::{Cell, UnsafeCell};
+use std::cell::{Cell, RefCell, UnsafeCell};
 use std::{iter, ptr};
 ///
 /// Get the current process's stack
@@ -57,14 +61,33 @@ where
 
 thread_local! {
     static QUEUE: UnsafeCell<Option<Worker<LightProc>>> = UnsafeCell::new(None);
+    static DRR: RefCell<DeficitRoundRobin> = RefCell::new(DeficitRoundRobin::default());
 }
 
 pub(crate) fn schedule(proc: LightProc) {
+    match proc.stack().priority() {
+        Priority::High => {
+            pool::get().priority_injector.push(proc);
+            pool::get().sleepers.notify_one();
+            return;
+        }
+        Priority::Low => {
+            pool::get().low_injector.push(proc);
+            pool::get().sleepers.notify_one();
+            return;
+        }
+        Priority::Normal => (),
+    }
+
     QUEUE.with(|queue| {
         let local = unsafe { (*queue.get()).as_ref() };
 
         match local {
-            None => pool::get().injector.push(proc),
+            None => {
+                load_balancer::stats().record_global_push();
+                pool::get().injector.push(proc);
+                load_balancer::stats().store_global_run_queue(pool::get().injector.len());
+            }
             Some(q) => q.push(proc),
         }
     });
@@ -72,6 +95,30 @@ pub(crate) fn schedule(proc: LightProc) {
     pool::get().sleepers.notify_one();
 }
 
+///
+/// Schedules a newly spawned process, waiting for room in the global run queue if it
+/// is spawned from outside of the workers and the queue is full.
+pub(crate) fn schedule_spawned(proc: LightProc) {
+    let is_worker = QUEUE.with(|queue| unsafe { (*queue.get()).is_some() });
+    if is_worker || proc.stack().priority() != Priority::Normal {
+        return schedule(proc);
+    }
+
+    let pool = pool::get();
+    let backoff = Backoff::new();
+    let mut proc = proc;
+    while let Err(full) = pool.injector.try_push(proc) {
+        proc = full;
+        // Make sure that the workers are draining the queue.
+        pool.sleepers.notify_one();
+        backoff.snooze();
+    }
+
+    load_balancer::stats().record_global_push();
+    load_balancer::stats().store_global_run_queue(pool.injector.len());
+    pool.sleepers.notify_one();
+}
+
 ///
 /// Fetch the process from the run queue.
 /// Does the work of work-stealing if process doesn't exist in the local run queue.
@@ -80,39 +127,67 @@ pub fn fetch_proc(affinity: usize) -> Option<LightProc> {
 
     QUEUE.with(|queue| {
         let local = unsafe { (*queue.get()).as_ref().unwrap() };
-        local.pop().or_else(|| affine_steal(pool, local, affinity))
+
+        match scheduling::mode() {
+            SchedulingMode::Strict => injector_steal(&pool.priority_injector)
+                .or_else(|| local.pop())
+                .or_else(|| affine_steal(pool, local, affinity))
+                .or_else(|| injector_steal(&pool.low_injector)),
+            SchedulingMode::WeightedFair(shares) => DRR.with(|drr| {
+                drr.borrow_mut().next(shares, |priority| match priority {
+                    Priority::High => injector_steal(&pool.priority_injector),
+                    Priority::Normal => affine_steal(pool, local, affinity),
+                    Priority::Low => injector_steal(&pool.low_injector),
+                })
+            }),
+        }
     })
 }
 
+fn injector_steal(injector: &Injector<LightProc>) -> Option<LightProc> {
+    iter::repeat_with(|| injector.steal())
+        .find(|s| !s.is_retry())
+        .and_then(|s| s.success())
+}
+
 fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Option<LightProc> {
     let load_mean = load_balancer::stats().mean();
     // Pop a task from the local queue, if not empty.
     local.pop().or_else(|| {
         // Otherwise, we need to look for a task elsewhere.
-        iter::repeat_with(|| {
+        let stolen = iter::repeat_with(|| {
             let core_vec = load_balancer::stats().get_sorted_load();
 
             // First try to get procs from global queue
-            pool.injector.steal_batch_and_pop(&local).or_else(|| {
+            let global = pool.injector.steal_batch_and_pop(local);
+            load_balancer::stats().store_global_run_queue(pool.injector.len());
+            global.or_else(|| {
                 match core_vec.get(0) {
                     Some((core, _)) => {
-                        // If affinity is the one with the highest let other's do the stealing
+                        // If affinity is the one with the highest let other's do the stealing.
+                        // Retrying would spin here forever instead of looking at the queues
+                        // of the other priorities.
                         if *core == affinity {
-                            Steal::Retry
+                            Steal::Empty
+                        } else if load_balancer::stats().is_steal_throttled() {
+                            // The load is too light for stealing to be worth
+                            // moving the processes across cores.
+                            load_balancer::stats().record_throttled_steal();
+                            Steal::Empty
                         } else {
+                            let stealers = pool.stealers.read().unwrap();
                             // Try iterating through biggest to smallest
                             core_vec
                                 .iter()
-                                .map(|s| {
+                                // The cores without a running worker are skipped.
+                                .filter_map(|s| stealers.get(s.0).and_then(Option::as_ref))
+                                .map(|stealer| {
                                     // Steal the mean amount to balance all queues considering incoming workloads
                                     // Otherwise do an ignorant steal (which is going to be useless)
                                     if load_mean > 0 {
-                                        pool.stealers
-                                            .get(s.0)
-                                            .unwrap()
-                                            .steal_batch_and_pop_with_amount(&local, load_mean)
+                                        stealer.steal_batch_and_pop_with_amount(local, load_mean)
                                     } else {
-                                        pool.stealers.get(s.0).unwrap().steal_batch_and_pop(&local)
+                                        stealer.steal_batch_and_pop(local)
                                         // TODO: Set evacuation flag in thread_local
                                     }
                                 })
@@ -126,7 +201,10 @@ fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Opti
         // Loop while no task was stolen and any steal operation needs to be retried.
         .find(|s| !s.is_retry())
         // Extract the stolen task, if there is one.
-        .and_then(|s| s.success())
+        .and_then(|s| s.success());
+
+        load_balancer::stats().record_steal(stolen.is_some());
+        stolen
     })
 }
 
@@ -134,18 +212,80 @@ pub(crate) fn stats_generator(affinity: usize, local: &Worker<LightProc>) {
     load_balancer::stats().store_load(affinity, local.worker_run_queue_size());
 }
 
-pub(crate) fn main_loop(affinity: usize, local: Worker<LightProc>) {
+///
+/// Checks whether any process is waiting in the global queue or in the smp queues.
+///
+/// The smp queues are left out while the steals are throttled, since they can't be stolen
+/// from, which lets the idle workers park.
+fn has_queued_procs(pool: &Pool) -> bool {
+    let stats = load_balancer::stats();
+    !pool.priority_injector.is_empty()
+        || !pool.low_injector.is_empty()
+        || !pool.injector.is_empty()
+        || (!stats.is_steal_throttled() && stats.total_queued() > 0)
+}
+
+///
+/// Called when the worker has nothing to run.
+/// Keeps spinning briefly while there is workload to steal, parks the worker otherwise.
+/// Parked workers are woken up by [schedule] when a new process arrives.
+fn idle<R: Fn() -> bool>(affinity: usize, running: &R) -> Option<LightProc> {
+    let pool = pool::get();
+    let backoff = Backoff::new();
+
+    while !backoff.is_completed() {
+        if has_queued_procs(pool) {
+            if let Some(proc) = fetch_proc(affinity) {
+                return Some(proc);
+            }
+        }
+
+        backoff.snooze();
+    }
+
+    // Don't let others spin over the stale load of an idle worker.
+    QUEUE.with(|queue| {
+        let local = unsafe { (*queue.get()).as_ref().unwrap() };
+        stats_generator(affinity, local);
+    });
+
+    lifecycle::emit(WorkerEvent::Parked(affinity));
+    pool.sleepers.wait_unless(|| !running());
+    lifecycle::emit(WorkerEvent::Unparked(affinity));
+    None
+}
+
+///
+/// Runs the processes as the worker of the given core for as long as `running` returns `true`,
+/// returning its run queue afterwards.
+pub(crate) fn main_loop<R: Fn() -> bool>(
+    affinity: usize,
+    local: Worker<LightProc>,
+    running: R,
+) -> Worker<LightProc> {
     QUEUE.with(|queue| unsafe { *queue.get() = Some(local) });
+    lifecycle::emit(WorkerEvent::Started(affinity));
 
-    loop {
+    while running() {
         QUEUE.with(|queue| {
             let local = unsafe { (*queue.get()).as_ref().unwrap() };
             stats_generator(affinity, local);
         });
 
-        match fetch_proc(affinity) {
-            Some(proc) => set_stack(proc.stack(), || proc.run()),
-            None => pool::get().sleepers.wait(),
+        if let Some(proc) = fetch_proc(affinity).or_else(|| idle(affinity, &running)) {
+            #[cfg(feature = "poll-stats")]
+            let start = std::time::Instant::now();
+
+            let _running = profiler::running(affinity, proc.stack());
+            set_stack(proc.stack(), || proc.run());
+
+            #[cfg(feature = "poll-stats")]
+            load_balancer::stats().record_poll(affinity, start.elapsed());
         }
     }
+
+    lifecycle::emit(WorkerEvent::Stopped(affinity));
+    QUEUE
+        .with(|queue| unsafe { (*queue.get()).take() })
+        .expect("the run queue of the worker is gone")
 }
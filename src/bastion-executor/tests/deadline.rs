@@ -0,0 +1,21 @@
+use bastion_executor::prelude::*;
+use futures::future;
+use lightproc::proc_stack::ProcStack;
+use std::time::{Duration, Instant};
+
+#[test]
+fn cancels_the_procs_past_their_deadline() {
+    let stack = ProcStack::default().with_deadline(Duration::from_millis(100));
+
+    let start = Instant::now();
+    let handle = spawn(future::pending::<()>(), stack.clone());
+    assert_eq!(run(handle, ProcStack::default()), None);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let handle = spawn_blocking(future::pending::<()>(), stack.clone());
+    assert_eq!(run(handle, ProcStack::default()), None);
+
+    // The procs finishing in time aren't affected.
+    let handle = spawn(async { 1 + 2 }, stack);
+    assert_eq!(run(handle, ProcStack::default()), Some(3));
+}
@@ -0,0 +1,51 @@
+use bastion_executor::load_balancer::{self, core_retrieval};
+use bastion_executor::pool::{run_worker, shutdown_workers, spawn, use_external_workers};
+use bastion_executor::run::run;
+use futures::future::join_all;
+use lightproc::proc_stack::ProcStack;
+use std::collections::HashSet;
+use std::io;
+use std::thread;
+
+#[test]
+fn runs_on_the_application_threads() {
+    use_external_workers().unwrap();
+
+    let cores = (*core_retrieval()).min(2);
+    let workers = (0..cores)
+        .map(|core| {
+            thread::Builder::new()
+                .name(format!("app-{}", core))
+                .spawn(move || run_worker(core))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let handles = (0..100)
+        .map(|_| {
+            spawn(
+                async { thread::current().name().map(String::from) },
+                ProcStack::default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Every process ran on one of the threads of the application.
+    let names = run(join_all(handles), ProcStack::default())
+        .into_iter()
+        .map(|name| name.unwrap().unwrap())
+        .collect::<HashSet<_>>();
+    assert!(names.iter().all(|name| name.starts_with("app-")));
+
+    // A core can only be run by one worker at a time...
+    let busy = thread::spawn(|| run_worker(0)).join().unwrap();
+    assert_eq!(busy.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+    // ...and the pool can't start its own threads anymore.
+    assert!(use_external_workers().is_err());
+
+    shutdown_workers();
+    for worker in workers {
+        worker.join().unwrap().unwrap();
+    }
+    assert_eq!(load_balancer::stats().workers(), 0);
+}
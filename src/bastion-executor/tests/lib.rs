@@ -1,6 +1,13 @@
 #[cfg(test)]
 mod tests {
-    use bastion_executor::{placement, pool};
+    use bastion_executor::prelude::*;
+    use bastion_executor::scheduling::{self, SchedulingMode, Shares};
+    use bastion_executor::{fair_injector, load_balancer, placement, pool};
+    use lightproc::prelude::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     fn affinity_replacement() {
@@ -12,4 +19,270 @@ mod tests {
     fn pool_check() {
         pool::get();
     }
+
+    #[test]
+    fn block_on_drives_to_completion() {
+        let res = block_on(async {
+            let handle = spawn(async { 21 * 2 }, ProcStack::default());
+            handle.await
+        });
+
+        assert_eq!(res, Some(42));
+    }
+
+    #[test]
+    #[should_panic(expected = "`block_on` cannot be called from within")]
+    fn nested_block_on_panics() {
+        block_on(async {
+            block_on(async {});
+        });
+    }
+
+    #[test]
+    fn yield_now_lets_others_progress() {
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let setter = {
+            let flag = flag.clone();
+            spawn(
+                async move { flag.store(true, Ordering::SeqCst) },
+                ProcStack::default(),
+            )
+        };
+
+        let waiter = spawn(
+            async move {
+                while !flag.load(Ordering::SeqCst) {
+                    yield_now().await;
+                }
+            },
+            ProcStack::default(),
+        );
+
+        run(
+            async {
+                setter.await;
+                waiter.await;
+            },
+            ProcStack::default(),
+        );
+    }
+
+    #[test]
+    fn proc_stream_applies_backpressure() {
+        let sent = Arc::new(AtomicUsize::new(0));
+
+        let mut stream = {
+            let sent = sent.clone();
+            spawn_stream(
+                2,
+                move |sink| async move {
+                    for i in 0..10 {
+                        sink.send(i).await.unwrap();
+                        sent.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                ProcStack::default(),
+            )
+        };
+
+        // The producer waits for the buffer to be drained.
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+
+        let values = run(
+            async move {
+                let mut values = vec![];
+                while let Some(chunk) = stream.next_chunk().await {
+                    assert!(chunk.len() <= 2);
+                    values.extend(chunk);
+                }
+                values
+            },
+            ProcStack::default(),
+        );
+
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn proc_stream_dropped_fails_sends() {
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let stream = spawn_stream(
+            1,
+            move |sink| async move {
+                let mut i = 0;
+                while sink.send(i).await.is_ok() {
+                    i += 1;
+                }
+                tx.send(i).unwrap();
+            },
+            ProcStack::default(),
+        );
+        drop(stream);
+
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn and_then_skips_failed_processes() {
+        let called = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let called = called.clone();
+            spawn(async { panic!("test") }, ProcStack::default())
+                .and_then(move |()| async move { called.store(true, Ordering::SeqCst) })
+        };
+
+        assert_eq!(run(handle, ProcStack::default()), None);
+        assert!(!called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn and_then_cancelled_continuation() {
+        let called = Arc::new(AtomicBool::new(false));
+
+        let (first, guard) = spawn_scoped(
+            async {
+                loop {
+                    yield_now().await;
+                }
+            },
+            ProcStack::default(),
+        );
+        let handle = {
+            let called = called.clone();
+            first.and_then(move |()| async move { called.store(true, Ordering::SeqCst) })
+        };
+
+        handle.cancel();
+        assert_eq!(run(handle, ProcStack::default()), None);
+        drop(guard);
+        assert!(!called.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn weighted_fair_runs_low_priority_procs() {
+        scheduling::set_mode(SchedulingMode::WeightedFair(Shares::default()));
+        let done = Arc::new(AtomicBool::new(false));
+
+        // Keeps rescheduling itself with a high priority until the low
+        // priority process ran, which would never happen with strict priorities.
+        let hog = {
+            let done = done.clone();
+            spawn_with_priority(Priority::High, async move {
+                while !done.load(Ordering::SeqCst) {
+                    yield_now().await;
+                }
+            })
+        };
+
+        let low = {
+            let done = done.clone();
+            spawn_with_priority(Priority::Low, async move {
+                done.store(true, Ordering::SeqCst);
+            })
+        };
+
+        run(
+            async {
+                low.await;
+                hog.await;
+            },
+            ProcStack::default(),
+        );
+        scheduling::set_mode(SchedulingMode::Strict);
+    }
+
+    #[test]
+    fn spawning_waits_for_room_in_the_global_queue() {
+        pool::set_global_queue_capacity(4);
+
+        // Spawned from outside of the workers, so they go through the global queue.
+        let handles = (0..100)
+            .map(|i| spawn(async move { i }, ProcStack::default()))
+            .collect::<Vec<_>>();
+
+        let sum = run(
+            async {
+                let mut sum = 0;
+                for handle in handles {
+                    sum += handle.await.unwrap();
+                }
+                sum
+            },
+            ProcStack::default(),
+        );
+        assert_eq!(sum, 4950);
+        assert!(load_balancer::stats().global_run_queue() <= 4);
+
+        pool::set_global_queue_capacity(fair_injector::DEFAULT_CAPACITY);
+    }
+
+    #[test]
+    fn threads_are_named() {
+        let handle = spawn(
+            async { thread::current().name().map(str::to_string) },
+            ProcStack::default(),
+        );
+        let name = run(handle, ProcStack::default()).unwrap().unwrap();
+        assert!(name.starts_with("bastion-wrk-"), "{}", name);
+
+        let handle = spawn_blocking(
+            async { thread::current().name().map(str::to_string) },
+            ProcStack::default(),
+        );
+        let name = run(handle, ProcStack::default()).unwrap().unwrap();
+        assert!(name.starts_with("bastion-blk-"), "{}", name);
+    }
+
+    fn recurse(depth: usize) -> usize {
+        // Make sure that every frame uses a good chunk of the stack.
+        let frame = std::hint::black_box([depth as u8; 1024]);
+        if depth == 0 {
+            return frame[0] as usize;
+        }
+        recurse(depth - 1) + frame[1023] as usize
+    }
+
+    #[test]
+    fn blocking_with_stack_size() {
+        // ~10MiB of stack, more than the default one of the blocking threads.
+        let stack = ProcStack::default().with_stack_size(64 * 1024 * 1024);
+        let handle = spawn_blocking(async { recurse(10_000) }, stack);
+        assert!(run(handle, ProcStack::default()).is_some());
+
+        // Invalid sizes fall back to the threads of the pool.
+        for stack_size in &[0, usize::MAX] {
+            let stack = ProcStack::default().with_stack_size(*stack_size);
+            let handle = spawn_blocking(async { recurse(10) }, stack);
+            assert!(run(handle, ProcStack::default()).is_some());
+        }
+    }
+
+    #[test]
+    fn smoothed_mean_converges_to_the_load() {
+        use bastion_executor::load_balancer::{SmpStats, Stats};
+
+        let cores = *load_balancer::core_retrieval();
+        let stats = Stats::new(cores);
+        stats.store_load(0, 10 * cores);
+
+        // The instantaneous mean follows the load right away...
+        stats.update_mean();
+        assert_eq!(stats.mean(), 10);
+
+        // ...while a smoothed one only gets closer to it.
+        stats.set_mean_smoothing(0.25);
+        stats.store_load(0, 0);
+        let mut means = Vec::new();
+        for _ in 0..20 {
+            stats.update_mean();
+            means.push(stats.mean());
+        }
+        assert_eq!(means[0], 8);
+        assert!(means.windows(2).all(|means| means[0] >= means[1]));
+        assert_eq!(means.last(), Some(&0));
+    }
 }
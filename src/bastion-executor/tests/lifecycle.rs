@@ -0,0 +1,32 @@
+use bastion_executor::lifecycle::{self, WorkerEvent};
+use bastion_executor::pool::{run_worker, shutdown_workers, spawn, use_external_workers};
+use bastion_executor::run::run;
+use lightproc::proc_stack::ProcStack;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn emits_the_events_of_the_workers() {
+    use_external_workers().unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    lifecycle::subscribe(move |event| recorded.lock().unwrap().push(event));
+
+    let worker = thread::spawn(|| run_worker(0));
+    let handle = spawn(async { 1 + 2 }, ProcStack::default());
+    assert_eq!(run(handle, ProcStack::default()), Some(3));
+
+    // Lets the worker go idle.
+    thread::sleep(Duration::from_millis(100));
+    shutdown_workers();
+    worker.join().unwrap().unwrap();
+    lifecycle::unsubscribe();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.first(), Some(&WorkerEvent::Started(0)));
+    assert_eq!(events.last(), Some(&WorkerEvent::Stopped(0)));
+    assert!(events.contains(&WorkerEvent::Parked(0)));
+    assert!(events.contains(&WorkerEvent::Unparked(0)));
+}
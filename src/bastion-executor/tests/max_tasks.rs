@@ -0,0 +1,41 @@
+use bastion_executor::load_balancer;
+use bastion_executor::pool::{spawn, try_spawn};
+use bastion_executor::run::run;
+use futures::channel::oneshot;
+use lightproc::proc_stack::ProcStack;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn rejects_spawns_over_the_limit() {
+    let live = load_balancer::stats().live_tasks();
+    load_balancer::set_max_tasks(live + 2);
+
+    let (sender, recver) = oneshot::channel::<()>();
+    let first = spawn(
+        async {
+            recver.await.ok();
+        },
+        ProcStack::default(),
+    );
+    let second = try_spawn(async { 42 }, ProcStack::default()).unwrap();
+
+    // The first process waits, keeping the limit reached once the
+    // second one is spawned...
+    assert!(try_spawn(async {}, ProcStack::default()).is_err());
+    let rejected = spawn(async { 42 }, ProcStack::default());
+    assert_eq!(run(rejected, ProcStack::default()), None);
+    assert_eq!(load_balancer::stats().tasks_rejected(), 2);
+
+    // ...until it completes.
+    assert_eq!(run(second, ProcStack::default()), Some(42));
+    sender.send(()).unwrap();
+    assert_eq!(run(first, ProcStack::default()), Some(()));
+
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(load_balancer::stats().live_tasks(), live);
+    let handle = try_spawn(async { 42 }, ProcStack::default()).unwrap();
+    assert_eq!(run(handle, ProcStack::default()), Some(42));
+
+    load_balancer::set_max_tasks(load_balancer::DEFAULT_MAX_TASKS);
+}
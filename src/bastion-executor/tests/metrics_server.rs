@@ -0,0 +1,38 @@
+#![cfg(feature = "metrics-server")]
+use bastion_executor::metrics_server;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn serves_the_stats() {
+    let server = metrics_server::serve("127.0.0.1:0").unwrap();
+    let addr = server.local_addr();
+
+    let health = get(addr, "/health");
+    assert!(health.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(health.ends_with("\r\n\r\nOK\n"));
+
+    let metrics = get(addr, "/metrics");
+    assert!(metrics.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(metrics.contains("# TYPE bastion_executor_workers gauge\n"));
+    assert!(metrics.contains("bastion_executor_queued{core=\"0\"} "));
+
+    let stats = get(addr, "/stats");
+    assert!(stats.contains("Content-Type: application/json\r\n"));
+    assert!(stats.contains("\r\n\r\n{\"workers\":"));
+    assert!(stats.ends_with('}'));
+
+    assert!(get(addr, "/unknown").starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    server.shutdown();
+    assert!(TcpStream::connect(addr).is_err());
+}
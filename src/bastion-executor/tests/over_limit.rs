@@ -0,0 +1,79 @@
+use bastion_executor::load_balancer::{self, OverLimitPolicy};
+use bastion_executor::pool::{spawn, try_spawn};
+use bastion_executor::run::run;
+use futures::channel::oneshot;
+use lightproc::proc_stack::ProcStack;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn waits_for_room_over_the_limit() {
+    let live = load_balancer::stats().live_tasks();
+    load_balancer::set_max_tasks(live + 1);
+
+    // The spawn blocks until the live process completes...
+    load_balancer::set_over_limit_policy(OverLimitPolicy::Block);
+    let (sender, recver) = oneshot::channel::<()>();
+    let first = spawn(
+        async {
+            recver.await.ok();
+        },
+        ProcStack::default(),
+    );
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        sender.send(()).unwrap();
+    });
+
+    let start = Instant::now();
+    let second = spawn(async { 42 }, ProcStack::default());
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert_eq!(run(first, ProcStack::default()), Some(()));
+    assert_eq!(run(second, ProcStack::default()), Some(42));
+    releaser.join().unwrap();
+
+    // ...or until it times out.
+    let timeout = Duration::from_millis(50);
+    load_balancer::set_over_limit_policy(OverLimitPolicy::BlockWithTimeout(timeout));
+    let (sender, recver) = oneshot::channel::<()>();
+    let first = spawn(
+        async {
+            recver.await.ok();
+        },
+        ProcStack::default(),
+    );
+
+    let start = Instant::now();
+    assert!(try_spawn(async {}, ProcStack::default()).is_err());
+    assert!(start.elapsed() >= timeout);
+    let rejected = spawn(async { 42 }, ProcStack::default());
+    assert_eq!(run(rejected, ProcStack::default()), None);
+    assert_eq!(load_balancer::stats().tasks_rejected(), 2);
+
+    sender.send(()).unwrap();
+    assert_eq!(run(first, ProcStack::default()), Some(()));
+
+    // The spawns from a process are rejected instead of blocking the worker
+    // running it.
+    load_balancer::set_max_tasks(live + 2);
+    load_balancer::set_over_limit_policy(OverLimitPolicy::Block);
+    let (sender, recver) = oneshot::channel::<()>();
+    let first = spawn(
+        async {
+            recver.await.ok();
+        },
+        ProcStack::default(),
+    );
+    let spawner = spawn(
+        async { spawn(async { 42 }, ProcStack::default()).await },
+        ProcStack::default(),
+    );
+    assert_eq!(run(spawner, ProcStack::default()), Some(None));
+    assert_eq!(load_balancer::stats().tasks_rejected(), 3);
+
+    sender.send(()).unwrap();
+    assert_eq!(run(first, ProcStack::default()), Some(()));
+
+    load_balancer::set_over_limit_policy(OverLimitPolicy::Reject);
+    load_balancer::set_max_tasks(load_balancer::DEFAULT_MAX_TASKS);
+}
@@ -0,0 +1,34 @@
+use bastion_executor::prelude::*;
+use bastion_executor::profiler;
+use lightproc::proc_stack::ProcStack;
+use std::time::{Duration, Instant};
+
+fn busy(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {}
+}
+
+#[test]
+fn samples_the_hottest_procs() {
+    profiler::enable().unwrap();
+
+    let hot = spawn(
+        async { busy(Duration::from_millis(1_200)) },
+        ProcStack::default().with_metadata(profiler::NAME_KEY, "hot"),
+    );
+    let cold = spawn(async {}, ProcStack::default().with_metadata("name", "cold"));
+    run(hot, ProcStack::default());
+    run(cold, ProcStack::default());
+
+    profiler::disable();
+    let snapshot = profiler::profile_snapshot();
+
+    // The load balancer thread samples the cores about 4 times per second.
+    assert_eq!(snapshot[0].name, "hot");
+    assert!(snapshot[0].samples >= 2);
+    assert!(snapshot[0].share > 0.0 && snapshot[0].share <= 1.0);
+    assert!(snapshot.iter().all(|task| task.name != "cold"));
+
+    profiler::reset();
+    assert!(profiler::profile_snapshot().is_empty());
+}
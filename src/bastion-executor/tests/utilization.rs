@@ -0,0 +1,53 @@
+#![cfg(feature = "poll-stats")]
+use bastion_executor::load_balancer::{self, SmpStats};
+use bastion_executor::prelude::*;
+use lightproc::proc_stack::ProcStack;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[test]
+fn samples_the_utilization_of_the_cores() {
+    let busy = spawn(
+        async {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(1_500) {
+                let chunk = Instant::now();
+                while chunk.elapsed() < Duration::from_millis(5) {}
+                YieldNow(false).await;
+            }
+        },
+        ProcStack::default(),
+    );
+
+    // The load balancer thread samples the cores about 4 times per second.
+    thread::sleep(Duration::from_millis(1_000));
+    let utilization = load_balancer::stats().per_core_utilization();
+    run(busy, ProcStack::default());
+
+    assert_eq!(
+        utilization.len(),
+        load_balancer::stats().get_sorted_load().len()
+    );
+    assert!(utilization.values().all(|u| (0.0..=1.0).contains(u)));
+    // One of the cores was kept busy by the process.
+    assert!(utilization.values().sum::<f64>() > 0.5);
+}
@@ -0,0 +1,37 @@
+#![cfg(target_os = "linux")]
+
+use bastion_executor::placement::{self, ThreadPriority};
+use bastion_executor::pool::spawn;
+use bastion_executor::run::run;
+use lightproc::proc_stack::ProcStack;
+use std::thread;
+
+fn current_nice() -> i32 {
+    unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        libc::getpriority(libc::PRIO_PROCESS as _, tid)
+    }
+}
+
+#[test]
+fn workers_run_at_the_configured_priority() {
+    // Whether the process is allowed to elevate the priority of its threads.
+    let privileged =
+        thread::spawn(|| placement::set_priority_for_current(ThreadPriority::AboveNormal).is_ok())
+            .join()
+            .unwrap();
+    let normal = current_nice();
+
+    // Set before the workers start.
+    placement::set_worker_priority(ThreadPriority::AboveNormal);
+
+    let handle = spawn(async { current_nice() }, ProcStack::default());
+    let nice = run(handle, ProcStack::default()).unwrap();
+
+    if privileged {
+        assert_eq!(nice, -5);
+    } else {
+        // The workers keep the normal priority.
+        assert_eq!(nice, normal);
+    }
+}
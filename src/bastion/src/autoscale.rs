@@ -0,0 +1,123 @@
+//!
+//! Automatic scaling of the number of elements of children groups
+//! depending on their load.
+use futures_timer::Delay;
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A sample of the load of a children group, as given to its
+/// [`AutoscalePolicy`].
+///
+/// [`AutoscalePolicy`]: trait.AutoscalePolicy.html
+pub struct LoadSample {
+    /// The number of elements the group currently has.
+    pub workers: usize,
+    /// The number of messages waiting in the mailboxes of all the
+    /// elements of the group.
+    pub mailbox_depth: usize,
+    /// The mean number of processes waiting in the run queues of
+    /// the executor.
+    pub run_queue_load: usize,
+}
+
+/// Decides how many elements a children group should have given
+/// samples of its load (see [`Children::with_autoscale`]).
+///
+/// This trait is implemented for all the closures taking a
+/// [`LoadSample`] and returning the number of elements the group
+/// should have, which are sampled every second.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// // Aims for at most ten waiting messages per element, looking
+/// // at the load of the group every 100 milliseconds.
+/// struct QueueDepth;
+///
+/// impl AutoscalePolicy for QueueDepth {
+///     fn target(&mut self, sample: &LoadSample) -> usize {
+///         (sample.mailbox_depth + 9) / 10
+///     }
+///
+///     fn interval(&self) -> Duration {
+///         Duration::from_millis(100)
+///     }
+/// }
+/// ```
+///
+/// [`Children::with_autoscale`]: ../children/struct.Children.html#method.with_autoscale
+/// [`LoadSample`]: struct.LoadSample.html
+pub trait AutoscalePolicy: Send + 'static {
+    /// Returns how many elements the group should have given the
+    /// latest sample of its load.
+    ///
+    /// The returned number is capped by the bounds given to
+    /// [`Children::with_autoscale`].
+    ///
+    /// [`Children::with_autoscale`]: ../children/struct.Children.html#method.with_autoscale
+    fn target(&mut self, sample: &LoadSample) -> usize;
+
+    /// Returns how long to wait between two samples of the load
+    /// of the group (one second by default).
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+impl<F> AutoscalePolicy for F
+where
+    F: FnMut(&LoadSample) -> usize + Send + 'static,
+{
+    fn target(&mut self, sample: &LoadSample) -> usize {
+        self(sample)
+    }
+}
+
+pub(crate) struct Autoscaler {
+    min: usize,
+    max: usize,
+    policy: Box<dyn AutoscalePolicy>,
+    // Ends when the load of the group should be sampled again.
+    timer: Delay,
+}
+
+impl Autoscaler {
+    pub(crate) fn new<P: AutoscalePolicy>(min: usize, max: usize, policy: P) -> Self {
+        // The pending messages of the retired elements are handed
+        // over to the remaining ones, so there needs to be one.
+        let min = min.max(1);
+        let max = max.max(min);
+        let timer = Delay::new(policy.interval());
+
+        Autoscaler {
+            min,
+            max,
+            policy: Box::new(policy),
+            timer,
+        }
+    }
+
+    pub(crate) fn timer(&mut self) -> &mut Delay {
+        &mut self.timer
+    }
+
+    /// Returns how many elements the group should have given
+    /// `sample`, and restarts the timer until the next one.
+    pub(crate) fn target(&mut self, sample: &LoadSample) -> usize {
+        self.timer = Delay::new(self.policy.interval());
+        self.policy.target(sample).clamp(self.min, self.max)
+    }
+}
+
+impl Debug for Autoscaler {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Autoscaler")
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .finish()
+    }
+}
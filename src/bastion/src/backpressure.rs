@@ -0,0 +1,405 @@
+//!
+//! A cooperative load shedding of the children groups whose
+//! elements can't keep up with the messages sent to them.
+use crate::context::BastionId;
+use futures_timer::Delay;
+use fxhash::FxHashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+// How many samples in a row an element's mailbox needs to be full
+// or empty for its capacity to be resized.
+const RESIZE_AFTER: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The watermarks of the mailboxes of a children group's elements
+/// (see [`Children::with_backpressure`]).
+///
+/// Once `high_watermark` messages are waiting in the mailbox of an
+/// element, it tells its group that it is at capacity and the
+/// group stops dispatching messages to it: they are sent to the
+/// other elements instead, or routed to the dead letters if all
+/// of them are at capacity. Once the element caught up and only
+/// `low_watermark` messages or fewer are waiting in its mailbox,
+/// it tells its group to resume dispatching messages to it.
+///
+/// The messages sent to an element directly (e.g. using
+/// [`ChildRef::tell_anonymously`]) are still delivered.
+///
+/// The notices of the elements which faulted don't wait behind the
+/// messages in the mailbox of their group and are never shed, so
+/// that a group whose elements are all at capacity can still be
+/// restarted. The messages telling them to stop are received once
+/// the messages sent to them earlier were.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| {
+///     children
+///         .with_redundancy(4)
+///         .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+///         .with_backpressure(Backpressure::new(100, 10))
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Children::with_backpressure`]: ../children/struct.Children.html#method.with_backpressure
+/// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+pub struct Backpressure {
+    high_watermark: usize,
+    low_watermark: usize,
+}
+
+impl Backpressure {
+    /// Creates a new backpressure configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `high_watermark` - How many messages waiting in the
+    ///     mailbox of an element make it at capacity (at least
+    ///     one).
+    /// * `low_watermark` - How few messages waiting in the mailbox
+    ///     of an element at capacity make it available again
+    ///     (lower than `high_watermark`).
+    pub fn new(high_watermark: usize, low_watermark: usize) -> Self {
+        let high_watermark = high_watermark.max(1);
+        Backpressure {
+            high_watermark,
+            low_watermark: low_watermark.min(high_watermark - 1),
+        }
+    }
+
+    /// Returns how many messages waiting in the mailbox of an
+    /// element make it at capacity.
+    pub fn high_watermark(&self) -> usize {
+        self.high_watermark
+    }
+
+    /// Returns how few messages waiting in the mailbox of an
+    /// element at capacity make it available again.
+    pub fn low_watermark(&self) -> usize {
+        self.low_watermark
+    }
+
+    // Returns the watermarks with the given high watermark, the
+    // low watermark being scaled alike.
+    fn scaled(self, high_watermark: usize) -> Self {
+        let low_watermark = self.low_watermark * high_watermark / self.high_watermark;
+        Backpressure::new(high_watermark, low_watermark)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The signal an element sends to its group when its mailbox
+/// crosses one of the group's watermarks.
+pub(crate) enum BackpressureSignal {
+    /// The element's mailbox reached the high watermark.
+    AtCapacity,
+    /// The element's mailbox went back to the low watermark.
+    Resume,
+}
+
+#[derive(Debug, Default)]
+/// Whether the mailbox of an element is at capacity, shared by the
+/// element and its references, which can wait for it to have room
+/// again.
+pub(crate) struct Capacity {
+    full: AtomicBool,
+    // The tasks waiting for the mailbox to have room again.
+    waiting: Mutex<Vec<Waker>>,
+}
+
+impl Capacity {
+    pub(crate) fn is_full(&self) -> bool {
+        self.full.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_full(&self, full: bool) {
+        self.full.store(full, Ordering::Release);
+        if !full {
+            for waker in self.waiting.lock().unwrap().drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Resolves once the mailbox isn't at capacity, waking up the
+    /// task as soon as it has room again.
+    pub(crate) fn poll_room(&self, cx: &mut Context) -> Poll<()> {
+        if !self.is_full() {
+            return Poll::Ready(());
+        }
+
+        let mut waiting = self.waiting.lock().unwrap();
+        if !waiting.iter().any(|waker| waker.will_wake(cx.waker())) {
+            waiting.push(cx.waker().clone());
+        }
+        drop(waiting);
+
+        // The mailbox might have had room again meanwhile.
+        if self.is_full() {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The bounds within which a children group resizes the mailbox
+/// capacity of each of its elements (see [`Children::with_resizer`]).
+///
+/// The capacity of a mailbox is the high watermark of its
+/// [`Backpressure`]. The depth of the mailboxes is sampled every
+/// second by default: the capacity of a mailbox which was full
+/// for three samples in a row is doubled, and the capacity of a
+/// mailbox which was empty for three samples in a row is halved,
+/// its low watermark being scaled alike. Resizing a mailbox never
+/// drops the messages waiting in it.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| {
+///     children
+///         .with_backpressure(Backpressure::new(100, 10))
+///         .with_resizer(Resizer::new(10, 1_000).with_interval(Duration::from_millis(100)))
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Children::with_resizer`]: ../children/struct.Children.html#method.with_resizer
+/// [`Backpressure`]: struct.Backpressure.html
+pub struct Resizer {
+    min_capacity: usize,
+    max_capacity: usize,
+    interval: Duration,
+}
+
+impl Resizer {
+    /// Creates a new resizer keeping the capacities between the
+    /// given bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_capacity` - The capacity under which the mailboxes
+    ///     are never shrunk (at least one).
+    /// * `max_capacity` - The capacity over which the mailboxes
+    ///     are never grown (at least `min_capacity`).
+    pub fn new(min_capacity: usize, max_capacity: usize) -> Self {
+        let min_capacity = min_capacity.max(1);
+        Resizer {
+            min_capacity,
+            max_capacity: max_capacity.max(min_capacity),
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets how long to wait between two samples of the depth of
+    /// the mailboxes (one second by default).
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the capacity under which the mailboxes are never
+    /// shrunk.
+    pub fn min_capacity(&self) -> usize {
+        self.min_capacity
+    }
+
+    /// Returns the capacity over which the mailboxes are never
+    /// grown.
+    pub fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
+    /// Returns how long to wait between two samples of the depth
+    /// of the mailboxes.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+// How many samples in a row a mailbox was full or empty.
+struct History {
+    full: usize,
+    empty: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct MailboxResizer {
+    resizer: Resizer,
+    history: FxHashMap<BastionId, History>,
+    // Ends when the mailboxes should be sampled again.
+    timer: Delay,
+}
+
+impl MailboxResizer {
+    pub(crate) fn new(resizer: Resizer) -> Self {
+        MailboxResizer {
+            resizer,
+            history: FxHashMap::default(),
+            timer: Delay::new(resizer.interval),
+        }
+    }
+
+    pub(crate) fn timer(&mut self) -> &mut Delay {
+        &mut self.timer
+    }
+
+    /// Returns the watermarks the elements start with, given the
+    /// ones of their group, if any.
+    pub(crate) fn initial(&self, backpressure: Option<Backpressure>) -> Backpressure {
+        let high = backpressure
+            .map(|backpressure| backpressure.high_watermark)
+            .unwrap_or(self.resizer.min_capacity)
+            .clamp(self.resizer.min_capacity, self.resizer.max_capacity);
+        match backpressure {
+            Some(backpressure) => backpressure.scaled(high),
+            None => Backpressure::new(high, 0),
+        }
+    }
+
+    /// Restarts the timer until the next samples, forgetting the
+    /// history of the elements which aren't in `ids` anymore.
+    pub(crate) fn restart(&mut self, ids: &[BastionId]) {
+        self.timer = Delay::new(self.resizer.interval);
+        self.history.retain(|id, _| ids.contains(id));
+    }
+
+    /// Records that the mailbox of the element with the given
+    /// identifier holds `len` messages, returning its new
+    /// watermarks if they should be resized.
+    pub(crate) fn sample(
+        &mut self,
+        id: &BastionId,
+        len: usize,
+        backpressure: Backpressure,
+    ) -> Option<Backpressure> {
+        let history = self.history.entry(id.clone()).or_default();
+        let high = backpressure.high_watermark;
+        if len >= high {
+            *history = History {
+                full: history.full + 1,
+                empty: 0,
+            };
+        } else if len == 0 {
+            *history = History {
+                full: 0,
+                empty: history.empty + 1,
+            };
+        } else {
+            *history = History::default();
+        }
+
+        let resized = if history.full >= RESIZE_AFTER {
+            high.saturating_mul(2).min(self.resizer.max_capacity)
+        } else if history.empty >= RESIZE_AFTER {
+            (high / 2).max(self.resizer.min_capacity)
+        } else {
+            return None;
+        };
+
+        *history = History::default();
+        if resized == high {
+            None
+        } else {
+            Some(backpressure.scaled(resized))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::{waker, ArcWake};
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct CountWakes(AtomicUsize);
+
+    impl ArcWake for CountWakes {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn wakes_up_once_the_mailbox_has_room() {
+        let wakes = Arc::new(CountWakes::default());
+        let waker = waker(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let capacity = Capacity::default();
+        assert_eq!(capacity.poll_room(&mut cx), Poll::Ready(()));
+
+        capacity.set_full(true);
+        assert_eq!(capacity.poll_room(&mut cx), Poll::Pending);
+        assert_eq!(capacity.poll_room(&mut cx), Poll::Pending);
+        capacity.set_full(true);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        // The task is only woken up once, even though it was
+        // polled twice.
+        capacity.set_full(false);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(capacity.poll_room(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn resizes_within_bounds() {
+        let id = BastionId::new();
+        let mut resizer = MailboxResizer::new(Resizer::new(2, 8));
+        let mut backpressure = resizer.initial(Some(Backpressure::new(4, 2)));
+        assert_eq!(backpressure, Backpressure::new(4, 2));
+
+        // The mailbox needs to stay full for a while to grow...
+        for _ in 1..RESIZE_AFTER {
+            assert_eq!(resizer.sample(&id, 4, backpressure), None);
+        }
+        backpressure = resizer.sample(&id, 5, backpressure).unwrap();
+        assert_eq!(backpressure, Backpressure::new(8, 4));
+
+        // ...but not over the maximum capacity.
+        for _ in 0..RESIZE_AFTER {
+            assert_eq!(resizer.sample(&id, 8, backpressure), None);
+        }
+
+        // Being empty for a while shrinks it...
+        assert_eq!(resizer.sample(&id, 0, backpressure), None);
+        assert_eq!(resizer.sample(&id, 1, backpressure), None);
+        for _ in 1..RESIZE_AFTER {
+            assert_eq!(resizer.sample(&id, 0, backpressure), None);
+        }
+        backpressure = resizer.sample(&id, 0, backpressure).unwrap();
+        assert_eq!(backpressure, Backpressure::new(4, 2));
+
+        // ...down to the minimum capacity.
+        for _ in 0..RESIZE_AFTER {
+            resizer.sample(&id, 0, backpressure);
+        }
+        assert_eq!(resizer.sample(&id, 0, Backpressure::new(2, 1)), None,);
+        assert_eq!(resizer.initial(None), Backpressure::new(2, 0),);
+    }
+}
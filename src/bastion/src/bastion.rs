@@ -4,18 +4,22 @@ use crate::children_ref::ChildrenRef;
 use crate::config::Config;
 use crate::context::{BastionContext, BastionId};
 use crate::envelope::Envelope;
+use crate::errors::BastionError;
 use crate::message::{BastionMessage, Message};
 use crate::path::BastionPathElement;
+use crate::scope::Scope;
 use crate::supervisor::{Supervisor, SupervisorRef};
 use crate::system::SYSTEM;
+use crate::topology::TopologyNode;
+use bastion_executor::{load_balancer, placement, pool};
 
 use core::future::Future;
+use std::sync::Arc;
 use tracing::{debug, trace};
 
 use std::fmt::{self, Debug, Formatter};
 
 distributed_api! {
-    use std::sync::Arc;
     use crate::distributed::*;
     use artillery_core::cluster::ap::*;
 }
@@ -220,9 +224,85 @@ impl Bastion {
             std::panic::set_hook(Box::new(|_| ()));
         }
 
+        if let Some(executor) = config.executor() {
+            debug!("Bastion: Using executor: {:?}", executor);
+            crate::executor::set_executor(executor.clone());
+        }
+
+        if let Some(smoothing) = config.mean_smoothing() {
+            debug!("Bastion: Smoothing the mean load with {}.", smoothing);
+            load_balancer::set_mean_smoothing(smoothing);
+        }
+
+        if let Some(threshold) = config.steal_threshold() {
+            debug!(
+                "Bastion: Throttling the steals under a mean load of {}.",
+                threshold
+            );
+            load_balancer::set_steal_threshold(threshold);
+        }
+
+        if let Some(max) = config.max_tasks() {
+            debug!("Bastion: Limiting the live processes to {}.", max);
+            load_balancer::set_max_tasks(max);
+        }
+
+        if let Some(policy) = config.over_limit_policy() {
+            debug!(
+                "Bastion: Handling the spawns over the limit with {:?}.",
+                policy
+            );
+            load_balancer::set_over_limit_policy(policy);
+        }
+
+        if let Some(priority) = config.worker_priority() {
+            debug!("Bastion: Running the workers at {:?} priority.", priority);
+            placement::set_worker_priority(priority);
+        }
+
         lazy_static::initialize(&SYSTEM);
     }
 
+    /// Initializes the system like [`Bastion::init_with`] does,
+    /// but returns an error instead of panicking later on if
+    /// bastion's executor can't start.
+    ///
+    /// This method returns [`BastionError::NoCores`] if none of
+    /// the executor's worker threads could be started. The
+    /// executor isn't started if the configuration sets a custom
+    /// one (see [`Config::with_executor`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration used to initialize the system.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// Bastion::try_init_with(Config::new()).expect("Couldn't initialize the system.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Bastion::init_with`]: #method.init_with
+    /// [`BastionError::NoCores`]: errors/enum.BastionError.html#variant.NoCores
+    /// [`Config::with_executor`]: struct.Config.html#method.with_executor
+    pub fn try_init_with(config: Config) -> Result<(), BastionError> {
+        if config.executor().is_none() {
+            if let Err(err) = pool::try_get() {
+                debug!("Bastion: Couldn't start the executor: {}", err);
+                return Err(BastionError::NoCores);
+            }
+        }
+
+        Bastion::init_with(config);
+        Ok(())
+    }
+
     /// Creates a new [`Supervisor`], passes it through the specified
     /// `init` closure and then sends it to the system for it to
     /// start supervising children.
@@ -375,6 +455,70 @@ impl Bastion {
     {
         Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))
     }
+
+    /// Runs the given closure with a [`Scope`] in which it can
+    /// create children groups, and blocks the current thread until
+    /// all of their elements stopped, like `std::thread::scope`
+    /// does for threads.
+    ///
+    /// The elements faulting are restarted as usual and waited for,
+    /// while a group faulting or being stopped counts as finished.
+    /// If the closure panics, the groups it created are killed
+    /// before the panic resumes.
+    ///
+    /// Because it blocks, this method shouldn't be called from
+    /// within an element, and the system must be started for the
+    /// elements to run.
+    ///
+    /// This method returns the output of the closure.
+    ///
+    /// # Arguments
+    ///
+    /// * `scoped` - The closure creating the scoped groups.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// #
+    /// # Bastion::init();
+    /// Bastion::start();
+    ///
+    /// static DONE: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// Bastion::scoped(|scope| {
+    ///     for _ in 0..2 {
+    ///         scope
+    ///             .spawn(|_ctx: BastionContext| async move {
+    ///                 DONE.fetch_add(1, Ordering::SeqCst);
+    ///                 Ok(())
+    ///             })
+    ///             .expect("Couldn't create the children group.");
+    ///     }
+    /// });
+    ///
+    /// // The elements all stopped once the scope returned.
+    /// assert_eq!(DONE.load(Ordering::SeqCst), 2);
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Scope`]: scope/struct.Scope.html
+    pub fn scoped<S, R>(scoped: S) -> R
+    where
+        S: FnOnce(&Scope) -> R,
+    {
+        debug!("Bastion: Running scope.");
+        // Dropping the scope kills the groups which are still
+        // running, including when the closure panics.
+        let scope = Scope::default();
+        let output = scoped(&scope);
+        scope.wait();
+
+        output
+    }
     distributed_api! {
         // FIXME!
         #[allow(missing_docs)]
@@ -444,6 +588,98 @@ impl Bastion {
             .map_err(|err| err.into_inner().into_msg().unwrap())
     }
 
+    /// Sends an already allocated message to the system which will
+    /// then send it to all the root-level supervisors and their
+    /// supervised children and supervisors, etc., like [`broadcast`]
+    /// does.
+    ///
+    /// Every recipient receives the same allocation instead of a
+    /// copy of the message each, which avoids copying large
+    /// messages around.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::Arc;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let msg = Arc::new(vec![0u8; 1024 * 1024]);
+    /// Bastion::broadcast_shared(msg).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    pub fn broadcast_shared<M: Message>(msg: Arc<M>) -> Result<(), Arc<M>> {
+        debug!("Bastion: Broadcasting shared message: {:?}", msg);
+        let msg = BastionMessage::shared(msg);
+        let envelope = Envelope::from_dead_letters(msg);
+        trace!("Bastion: Sending envelope: {:?}", envelope);
+        SYSTEM
+            .sender()
+            .unbounded_send(envelope)
+            .map_err(|err| err.into_inner().into_shared().unwrap())
+    }
+
+    /// Sends a message to the system which will then forward it
+    /// down the tree to every element of every children group, for
+    /// at most `ttl` levels.
+    ///
+    /// Unlike with [`broadcast`], every element of the children
+    /// groups receives the message, whatever their dispatch mode.
+    /// Every supervisor and children group forwards it one level
+    /// deeper, the system forwarding it to the root supervisor:
+    /// the elements of the groups supervised by the root
+    /// supervisor are three levels down.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `ttl` - How many levels down the tree the message is
+    ///     forwarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// // Reaches the elements of the groups supervised by the
+    /// // root supervisor.
+    /// Bastion::announce("Reload the configuration.", 3).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    pub fn announce<M: Message>(msg: M, ttl: usize) -> Result<(), M> {
+        debug!("Bastion: Announcing message: {:?}", msg);
+        let msg = BastionMessage::tree_broadcast(msg, ttl);
+        let envelope = Envelope::from_dead_letters(msg);
+        trace!("Bastion: Sending envelope: {:?}", envelope);
+        SYSTEM
+            .sender()
+            .unbounded_send(envelope)
+            .map_err(|err| err.into_inner().into_msg().unwrap())
+    }
+
     /// Sends a message to the system to tell it to start
     /// handling messages and running children.
     ///
@@ -539,6 +775,49 @@ impl Bastion {
         SYSTEM.notify_stopped();
     }
 
+    /// Walks the supervision tree and returns a snapshot of it,
+    /// with the identifier, name and state of each supervisor,
+    /// children group and element.
+    ///
+    /// The snapshot is assembled by asking each element for its
+    /// own children, so it resolves once every element answered.
+    /// The elements only answer once the system was started, and
+    /// those stopping in the meantime are left out.
+    ///
+    /// This method returns a [`TopologyNode`] describing the
+    /// system if it succeeded, or `Err(())` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// Bastion::init();
+    ///
+    /// // Use bastion, spawn children and supervisors...
+    ///
+    /// Bastion::start();
+    ///
+    /// # run!(async {
+    /// let root = Bastion::dump_topology().await.expect("Couldn't dump the topology.");
+    /// assert_eq!(root.kind, TopologyKind::System);
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`TopologyNode`]: topology/struct.TopologyNode.html
+    pub async fn dump_topology() -> Result<TopologyNode, ()> {
+        debug!("Bastion: Dumping topology.");
+        let (msg, recver) = BastionMessage::topology();
+        let envelope = Envelope::from_dead_letters(msg);
+        trace!("Bastion: Sending envelope: {:?}", envelope);
+        SYSTEM.sender().unbounded_send(envelope).map_err(|_| ())?;
+
+        recver.await.map_err(|_| ())
+    }
+
     /// Blocks the current thread until the system is stopped
     /// (either by calling [`Bastion::stop()`] or
     /// [`Bastion::kill`]).
@@ -1,27 +1,159 @@
-use crate::children_ref::ChildrenRef;
+use crate::backpressure::BackpressureSignal;
+use crate::child_ref::ChildRef;
+use crate::children_ref::{ChildrenRef, SendError, StopReason};
 use crate::context::BastionId;
 use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::errors::BastionError;
+use crate::message::{BastionMessage, DeathNotice, Message};
+use crate::middleware::{Middleware, MiddlewareAction};
 use crate::path::{BastionPath, BastionPathElement};
-use crate::supervisor::SupervisorRef;
+use crate::supervisor::{FaultInfo, PollBias, SupervisorRef};
 use crate::system::SYSTEM;
-use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use crate::topology::TopologyNode;
+use futures::channel::mpsc::{
+    self, TrySendError as ChannelError, UnboundedReceiver, UnboundedSender,
+};
+use futures::channel::oneshot;
 use futures::prelude::*;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use tracing::{debug, warn};
 
-pub(crate) type Sender = UnboundedSender<Envelope>;
-pub(crate) type Receiver = UnboundedReceiver<Envelope>;
+#[derive(Debug, Clone)]
+/// The sending half of the mailbox of a broadcast.
+///
+/// The notices of the children which faulted (see
+/// `BastionMessage::is_fault_notice`) are sent on a channel of
+/// their own, which is received from first, so that they never
+/// wait behind the other messages (e.g. when a flood of messages
+/// is sent to a group whose elements are all faulting). The other
+/// messages, including the ones telling a child to stop, are
+/// received in the order they were sent in.
+///
+/// Once the receiving broadcast is polled using
+/// `PollBias::Fifo`, all the messages are sent on the same
+/// channel so that they are received in the order they were sent
+/// in.
+pub(crate) struct Sender {
+    // Both senders are kept behind an `Arc` so that the envelopes,
+    // which hold one in their signature, don't grow.
+    inner: Arc<SenderInner>,
+}
+
+#[derive(Debug)]
+struct SenderInner {
+    msgs: UnboundedSender<Envelope>,
+    faults: UnboundedSender<Envelope>,
+    // Whether the fault notices are sent along with the other
+    // messages.
+    fifo: AtomicBool,
+}
+
+#[derive(Debug)]
+/// The receiving half of the mailbox of a broadcast.
+pub(crate) struct Receiver {
+    msgs: UnboundedReceiver<Envelope>,
+    faults: UnboundedReceiver<Envelope>,
+}
+
+/// Creates the mailbox of a broadcast.
+pub(crate) fn channel() -> (Sender, Receiver) {
+    let (msgs_sender, msgs) = mpsc::unbounded();
+    let (faults_sender, faults) = mpsc::unbounded();
+
+    let sender = Sender {
+        inner: Arc::new(SenderInner {
+            msgs: msgs_sender,
+            faults: faults_sender,
+            fifo: AtomicBool::new(false),
+        }),
+    };
+    (sender, Receiver { msgs, faults })
+}
 
 #[derive(Debug)]
 pub(crate) struct Broadcast {
     sender: Sender,
     recver: Receiver,
+    // The mailboxes of the broadcasts merged into this one.
+    merged: Vec<Receiver>,
     path: Arc<BastionPath>, // Arc is needed because we put path to Envelope
     parent: Parent,
     children: FxHashMap<BastionId, Sender>,
+    // The weights of the children, used to distribute messages
+    // using smooth weighted round-robin.
+    weights: FxHashMap<BastionId, Weight>,
+    // The children subscribed to each topic.
+    subscriptions: FxHashMap<String, FxHashSet<BastionId>>,
+    // The children registered under each name, in the order the
+    // messages sent to the name are dispatched to them.
+    names: FxHashMap<String, VecDeque<BastionId>>,
+    // The children moved to another parent which didn't
+    // acknowledge it yet, with the sender of their new parent.
+    reparented: FxHashMap<BastionId, Sender>,
+    // The middlewares run on the messages sent to the children,
+    // in their registration order.
+    middlewares: Vec<Middleware>,
+    // The last children which stopped or faulted...
+    exits: VecDeque<(BastionId, StopReason)>,
+    // ...and the ones awaited to do so.
+    exit_waiters: FxHashMap<BastionId, Vec<oneshot::Sender<StopReason>>>,
+    // The registered children which reported being ready, and
+    // the amounts of them awaited.
+    ready: FxHashSet<BastionId>,
+    ready_waiters: Vec<(usize, oneshot::Sender<()>)>,
+    // The children at capacity, which the messages aren't
+    // dispatched to until they resume.
+    saturated: FxHashSet<BastionId>,
+    // The order in which the envelopes are received, and the
+    // data messages set aside while control ones are received.
+    bias: PollBias,
+    deferred_data: VecDeque<Envelope>,
+}
+
+/// The maximum amount of exits remembered to answer
+/// `Broadcast::await_exit` right away.
+const EXITS_CAPACITY: usize = 1024;
+
+/// The maximum amount of data messages put aside to receive the
+/// control messages sent after them first, which also bounds how
+/// far a single poll looks ahead for control messages.
+const DEFERRED_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why `Broadcast::try_send_child` couldn't enqueue an envelope.
+pub(crate) enum TrySendError {
+    /// The mailbox of the child is full. Mailboxes being
+    /// unbounded, this only happens if it is changed.
+    Full,
+    /// The mailbox of the child is closed because it died, in
+    /// which case it was unregistered.
+    Closed,
+    /// No child with the given identifier is registered.
+    NotFound,
+}
+
+#[derive(Debug)]
+struct Weight {
+    weight: usize,
+    current: isize,
+}
+
+#[derive(Debug)]
+/// The future returned by [`Broadcast::recv`].
+///
+/// [`Broadcast::recv`]: struct.Broadcast.html#method.recv
+pub(crate) struct Recv<'a> {
+    bcast: &'a mut Broadcast,
 }
 
 #[derive(Debug, Clone)]
@@ -50,8 +182,21 @@ impl Parent {
 
 impl Broadcast {
     pub(crate) fn new(parent: Parent, element: BastionPathElement) -> Self {
-        let (sender, recver) = mpsc::unbounded();
+        let (sender, recver) = channel();
+        let merged = Vec::new();
         let children = FxHashMap::default();
+        let weights = FxHashMap::default();
+        let subscriptions = FxHashMap::default();
+        let names = FxHashMap::default();
+        let reparented = FxHashMap::default();
+        let middlewares = Vec::new();
+        let exits = VecDeque::new();
+        let exit_waiters = FxHashMap::default();
+        let ready = FxHashSet::default();
+        let ready_waiters = Vec::new();
+        let saturated = FxHashSet::default();
+        let bias = PollBias::Fifo;
+        let deferred_data = VecDeque::new();
 
         let parent_path: BastionPath = match &parent {
             Parent::None | Parent::System => BastionPath::root(),
@@ -69,8 +214,21 @@ impl Broadcast {
             parent,
             sender,
             recver,
+            merged,
             path,
             children,
+            weights,
+            subscriptions,
+            names,
+            reparented,
+            middlewares,
+            exits,
+            exit_waiters,
+            ready,
+            ready_waiters,
+            saturated,
+            bias,
+            deferred_data,
         }
     }
 
@@ -78,8 +236,21 @@ impl Broadcast {
         // FIXME
         assert!(parent.is_none() || parent.is_system());
 
-        let (sender, recver) = mpsc::unbounded();
+        let (sender, recver) = channel();
+        let merged = Vec::new();
         let children = FxHashMap::default();
+        let weights = FxHashMap::default();
+        let subscriptions = FxHashMap::default();
+        let names = FxHashMap::default();
+        let reparented = FxHashMap::default();
+        let middlewares = Vec::new();
+        let exits = VecDeque::new();
+        let exit_waiters = FxHashMap::default();
+        let ready = FxHashSet::default();
+        let ready_waiters = Vec::new();
+        let saturated = FxHashSet::default();
+        let bias = PollBias::Fifo;
+        let deferred_data = VecDeque::new();
         let path = BastionPath::root();
         let path = Arc::new(path);
 
@@ -87,8 +258,21 @@ impl Broadcast {
             parent,
             sender,
             recver,
+            merged,
             path,
             children,
+            weights,
+            subscriptions,
+            names,
+            reparented,
+            middlewares,
+            exits,
+            exit_waiters,
+            ready,
+            ready_waiters,
+            saturated,
+            bias,
+            deferred_data,
         }
     }
 
@@ -108,27 +292,700 @@ impl Broadcast {
         &self.parent
     }
 
-    pub(crate) fn register(&mut self, child: &Self) {
+    /// Returns a future resolving to the next envelope sent to
+    /// this broadcast, without requiring `StreamExt` to be in
+    /// scope.
+    ///
+    /// The future resolves to `None` if the channel was closed,
+    /// which never happens as long as the broadcast exists.
+    pub(crate) fn recv(&mut self) -> Recv<'_> {
+        Recv { bcast: self }
+    }
+
+    /// Returns the next envelope sent to this broadcast if there
+    /// is one, or `None` without waiting otherwise.
+    pub(crate) fn try_recv(&mut self) -> Option<Envelope> {
+        let next = self.next_biased(|recver| match recver.try_recv() {
+            Some(env) => Poll::Ready(Some(env)),
+            None => Poll::Pending,
+        });
+
+        match next {
+            Poll::Ready(env) => env,
+            Poll::Pending => None,
+        }
+    }
+
+    /// Sets the order in which the envelopes sent to this
+    /// broadcast are received.
+    pub(crate) fn set_poll_bias(&mut self, bias: PollBias) {
+        self.bias = bias;
+        self.sender.set_fifo(bias == PollBias::Fifo);
+    }
+
+    /// Returns the next envelope using `next` to receive them
+    /// from the channel, according to the poll bias.
+    fn next_biased<F>(&mut self, mut next: F) -> Poll<Option<Envelope>>
+    where
+        F: FnMut(&mut Receiver) -> Poll<Option<Envelope>>,
+    {
+        loop {
+            // Once enough data messages were put aside, they are
+            // received before looking for control messages further.
+            if self.deferred_data.len() >= DEFERRED_CAPACITY {
+                return Poll::Ready(self.deferred_data.pop_front());
+            }
+
+            match self.next_envelope(&mut next) {
+                Poll::Ready(Some(env)) => {
+                    let env = match self.forward_reparented(env) {
+                        Some(env) => env,
+                        None => continue,
+                    };
+
+                    // Data messages are only received once all the
+                    // control messages already sent were, unless too
+                    // many of them were sent since.
+                    if self.bias == PollBias::ControlFirst && is_data(&env) {
+                        self.deferred_data.push_back(env);
+                        continue;
+                    }
+
+                    return Poll::Ready(Some(env));
+                }
+                poll => match self.deferred_data.pop_front() {
+                    Some(env) => return Poll::Ready(Some(env)),
+                    None => return poll,
+                },
+            }
+        }
+    }
+
+    /// Returns the next envelope using `next` to receive them
+    /// from this broadcast's channel, or from the ones of the
+    /// broadcasts merged into it, which are dropped once closed.
+    fn next_envelope<F>(&mut self, next: &mut F) -> Poll<Option<Envelope>>
+    where
+        F: FnMut(&mut Receiver) -> Poll<Option<Envelope>>,
+    {
+        if let Poll::Ready(env) = next(&mut self.recver) {
+            return Poll::Ready(env);
+        }
+
+        let mut i = 0;
+        while i < self.merged.len() {
+            match next(&mut self.merged[i]) {
+                Poll::Ready(Some(env)) => return Poll::Ready(Some(env)),
+                Poll::Ready(None) => {
+                    self.merged.swap_remove(i);
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        Poll::Pending
+    }
+
+    /// Returns a stream of the messages of type `T` sent to this
+    /// broadcast (usually by its children), with the identifier
+    /// of their sender, which makes writing elements aggregating
+    /// their children's results easier.
+    ///
+    /// The messages of other types are routed to the dead
+    /// letters, while the other envelopes are sent back to this
+    /// broadcast once the stream is dropped, so that they can be
+    /// handled as usual.
+    #[allow(dead_code)]
+    pub(crate) fn fan_in<T: Message>(&mut self) -> FanIn<'_, T> {
+        let dead_letters = SYSTEM.dead_letters().sender().clone();
+        self.fan_in_to(dead_letters)
+    }
+
+    /// Returns the same stream as [`fan_in`], routing the
+    /// messages of other types to `dead_letters`.
+    ///
+    /// [`fan_in`]: #method.fan_in
+    fn fan_in_to<T: Message>(&mut self, dead_letters: Sender) -> FanIn<'_, T> {
+        FanIn {
+            bcast: self,
+            dead_letters,
+            deferred: Vec::new(),
+            _msg: PhantomData,
+        }
+    }
+
+    /// Registers `child` as a child of this broadcast.
+    ///
+    /// Returns [`BastionError::AlreadyRegistered`] without
+    /// registering it if a child with the same identifier already
+    /// is, as overwriting it would orphan that child.
+    ///
+    /// [`BastionError::AlreadyRegistered`]: ../errors/enum.BastionError.html#variant.AlreadyRegistered
+    pub(crate) fn register(&mut self, child: &Self) -> Result<(), BastionError> {
+        if self.children.contains_key(child.id()) {
+            warn!(
+                "Broadcast({}): Child({}) is already registered.",
+                self.id(),
+                child.id()
+            );
+            return Err(BastionError::AlreadyRegistered(child.id().clone()));
+        }
+
+        self.children
+            .insert(child.id().clone(), child.sender.clone());
+        Ok(())
+    }
+
+    /// Registers `child` in place of the faulted child with the
+    /// same identifier it is restarting, which keeps its weight
+    /// and subscriptions unless the faulted child was unregistered
+    /// after a message couldn't be sent to it.
+    pub(crate) fn register_restarted(&mut self, child: &Self) {
         self.children
             .insert(child.id().clone(), child.sender.clone());
+        // The restarted child tells whether it is at capacity
+        // itself, and didn't exit yet.
+        self.saturated.remove(child.id());
+        self.exits.retain(|(id, _)| id != child.id());
+    }
+
+    /// Returns whether a child with the given identifier is
+    /// registered.
+    pub(crate) fn is_registered(&self, id: &BastionId) -> bool {
+        self.children.contains_key(id)
+    }
+
+    /// Returns whether the child with the given identifier
+    /// reported being ready since it was registered.
+    pub(crate) fn is_ready(&self, id: &BastionId) -> bool {
+        self.ready.contains(id)
     }
 
     pub(crate) fn unregister(&mut self, id: &BastionId) {
         self.children.remove(id);
+        self.weights.remove(id);
+        self.ready.remove(id);
+        self.saturated.remove(id);
+        self.subscriptions.retain(|_, subscribers| {
+            subscribers.remove(id);
+            !subscribers.is_empty()
+        });
+        self.names.retain(|_, ids| {
+            ids.retain(|named| named != id);
+            !ids.is_empty()
+        });
+    }
+
+    /// Moves the registered child with the given identifier to
+    /// `new_parent` without restarting it, and tells the child
+    /// that its parent is now `parent` (which should be the
+    /// reference of `new_parent`'s owner).
+    ///
+    /// The messages already sent to the child stay in its
+    /// mailbox. Until the child acknowledges the move, the
+    /// notices it sends to this broadcast (e.g. because it
+    /// faulted during the transfer) are forwarded to
+    /// `new_parent`.
+    ///
+    /// Returns `false` if no child with the given identifier is
+    /// registered, or if `new_parent` already has one.
+    #[allow(dead_code)]
+    pub(crate) fn reparent(
+        &mut self,
+        id: &BastionId,
+        new_parent: &mut Broadcast,
+        parent: Parent,
+    ) -> bool {
+        let child = match self.children.get(id) {
+            Some(child) if !new_parent.children.contains_key(id) => child.clone(),
+            _ => return false,
+        };
+        self.unregister(id);
+
+        new_parent.children.insert(id.clone(), child.clone());
+        self.reparented
+            .insert(id.clone(), new_parent.sender.clone());
+
+        let msg = BastionMessage::reparent(parent, new_parent.path.clone());
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        if child.unbounded_send(env).is_err() {
+            // The child died meanwhile, which its new parent will
+            // notice when sending it a message.
+            debug!("Broadcast({}): Moved Child({}) is dead.", self.id(), id);
+        }
+
+        true
+    }
+
+    /// Moves all the children of `other` under this broadcast
+    /// without restarting them, along with their weights,
+    /// subscriptions, names and readiness, and tells them that
+    /// their parent is now `parent` (which should be the
+    /// reference of this broadcast's owner).
+    ///
+    /// `other`'s channel is kept and received from along with
+    /// this broadcast's one, so that the notices the children
+    /// send before handling the move (e.g. because they faulted
+    /// during the transfer), and the envelopes still sent to
+    /// `other`, are received by this broadcast.
+    ///
+    /// Returns [`BastionError::AlreadyRegistered`] along with
+    /// `other`, without moving any child, if one of its children
+    /// has the same identifier as a child of this broadcast.
+    ///
+    /// [`BastionError::AlreadyRegistered`]: ../errors/enum.BastionError.html#variant.AlreadyRegistered
+    #[allow(dead_code)]
+    pub(crate) fn merge_children(
+        &mut self,
+        mut other: Broadcast,
+        parent: Parent,
+    ) -> Result<(), (BastionError, Box<Broadcast>)> {
+        if let Some(id) = other
+            .children
+            .keys()
+            .find(|id| self.children.contains_key(id))
+        {
+            warn!(
+                "Broadcast({}): Child({}) of Broadcast({}) is already registered.",
+                self.id(),
+                id,
+                other.id()
+            );
+            let err = BastionError::AlreadyRegistered(id.clone());
+            return Err((err, Box::new(other)));
+        }
+
+        debug!(
+            "Broadcast({}): Merging the {} children of Broadcast({}).",
+            self.id(),
+            other.children.len(),
+            other.id()
+        );
+        for (id, child) in other.children.drain() {
+            let msg = BastionMessage::reparent(parent.clone(), self.path.clone());
+            let env = Envelope::new(msg, other.path.clone(), other.sender.clone());
+            if child.unbounded_send(env).is_err() {
+                // The child died meanwhile, which this broadcast
+                // will notice when sending it a message.
+                debug!("Broadcast({}): Merged Child({}) is dead.", self.id(), id);
+            }
+
+            self.children.insert(id, child);
+        }
+
+        self.weights.extend(other.weights.drain());
+        self.saturated.extend(other.saturated.drain());
+        for (topic, subscribers) in other.subscriptions.drain() {
+            self.subscriptions
+                .entry(topic)
+                .or_default()
+                .extend(subscribers);
+        }
+        for (name, ids) in other.names.drain() {
+            self.names.entry(name).or_default().extend(ids);
+        }
+        for (id, waiters) in other.exit_waiters.drain() {
+            self.exit_waiters.entry(id).or_default().extend(waiters);
+        }
+        self.reparented.extend(other.reparented.drain());
+        for id in other.ready.drain() {
+            self.record_ready(id);
+        }
+
+        self.deferred_data.extend(other.deferred_data.drain(..));
+        self.merged.push(other.recver);
+        self.merged.append(&mut other.merged);
+
+        Ok(())
+    }
+
+    /// Makes `parent`, whose path is `parent_path`, this
+    /// broadcast's parent and acknowledges the move to the
+    /// previous parent.
+    pub(crate) fn reparented(&mut self, parent: Parent, parent_path: Arc<BastionPath>) {
+        if let Some(elem) = self.path.elem().clone() {
+            if let Ok(path) = BastionPath::clone(&parent_path).append(elem) {
+                self.path = Arc::new(path);
+            }
+        }
+
+        let previous = mem::replace(&mut self.parent, parent);
+
+        let msg = BastionMessage::reparented(self.id().clone());
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        if previous.send(env).is_err() {
+            debug!(
+                "Broadcast({}): Previous parent stopped before the move was acknowledged.",
+                self.id()
+            );
+        }
+    }
+
+    /// Forwards the envelope to the new parent of the child it
+    /// comes from if the child was moved, recording it and
+    /// returning it otherwise (see `record_notice`).
+    fn forward_reparented(&mut self, env: Envelope) -> Option<Envelope> {
+        let new_parent = match &env.msg {
+            BastionMessage::Reparented { id } => {
+                self.reparented.remove(id);
+                return None;
+            }
+            BastionMessage::Stopped { id }
+            | BastionMessage::Faulted { id }
+            | BastionMessage::Escalate { id, .. }
+            | BastionMessage::Ready { id }
+            | BastionMessage::Backpressure { id, .. } => self.reparented.get(id),
+            // Sent by the elements of a moved group, or by the
+            // moved group itself on behalf of its elements.
+            BastionMessage::RestartRequired { id, parent_id }
+            | BastionMessage::Panicked { id, parent_id }
+            | BastionMessage::FinishedChild { id, parent_id }
+            | BastionMessage::InstantiatedChild {
+                child_id: id,
+                parent_id,
+                ..
+            } => self
+                .reparented
+                .get(id)
+                .or_else(|| self.reparented.get(parent_id)),
+            _ => None,
+        };
+
+        match new_parent {
+            Some(new_parent) => {
+                if new_parent.unbounded_send(env).is_err() {
+                    debug!(
+                        "Broadcast({}): New parent of moved child stopped.",
+                        self.id()
+                    );
+                }
+                None
+            }
+            None => self.record_notice(env),
+        }
+    }
+
+    /// Records the readiness and backpressure notices sent by
+    /// the children, which are handled by the broadcast itself,
+    /// and the exits of the children, returning the envelope
+    /// unless it was handled.
+    fn record_notice(&mut self, env: Envelope) -> Option<Envelope> {
+        match &env.msg {
+            BastionMessage::Ready { id } => {
+                let id = id.clone();
+                self.record_ready(id);
+                return None;
+            }
+            BastionMessage::Backpressure { id, signal } => {
+                let (id, signal) = (id.clone(), *signal);
+                self.record_backpressure(id, signal);
+                return None;
+            }
+            _ => (),
+        }
+
+        self.record_exit(&env.msg);
+        Some(env)
+    }
+
+    /// Acknowledges to `ack` once at least `n` of the registered
+    /// children reported being ready, which they do once they
+    /// started (after running the initializer of their group, if
+    /// any), or right away if they already did.
+    ///
+    /// The children which are unregistered (e.g. because they
+    /// stopped) don't count as ready anymore.
+    pub(crate) fn wait_for_children(&mut self, n: usize, ack: oneshot::Sender<()>) {
+        if self.ready.len() >= n {
+            ack.send(()).ok();
+        } else {
+            self.ready_waiters.push((n, ack));
+        }
+    }
+
+    fn record_backpressure(&mut self, id: BastionId, signal: BackpressureSignal) {
+        if !self.children.contains_key(&id) {
+            return;
+        }
+
+        debug!(
+            "Broadcast({}): Child({}) signaled backpressure: {:?}",
+            self.id(),
+            id,
+            signal
+        );
+        match signal {
+            BackpressureSignal::AtCapacity => self.saturated.insert(id),
+            BackpressureSignal::Resume => self.saturated.remove(&id),
+        };
+    }
+
+    fn record_ready(&mut self, id: BastionId) {
+        if !self.children.contains_key(&id) {
+            return;
+        }
+
+        self.ready.insert(id);
+        let ready = self.ready.len();
+        let (resolved, waiters) = mem::take(&mut self.ready_waiters)
+            .into_iter()
+            .partition(|(n, _)| *n <= ready);
+        self.ready_waiters = waiters;
+
+        for (_, waiter) in resolved {
+            waiter.send(()).ok();
+        }
+    }
+
+    /// Sends to `ack` why the child with the given identifier
+    /// exited once it stopped or faulted, or right away if it
+    /// already did.
+    ///
+    /// `ack` is dropped if no child with this identifier is
+    /// registered and none exited recently.
+    pub(crate) fn await_exit(&mut self, id: &BastionId, ack: oneshot::Sender<StopReason>) {
+        match self.exits.iter().rev().find(|(exited, _)| exited == id) {
+            Some((_, reason)) => {
+                ack.send(*reason).ok();
+            }
+            None if self.children.contains_key(id) => {
+                self.exit_waiters.entry(id.clone()).or_default().push(ack)
+            }
+            None => (),
+        }
+    }
+
+    fn record_exit(&mut self, msg: &BastionMessage) {
+        let (id, reason) = match msg {
+            BastionMessage::Stopped { id } => (id, StopReason::Stopped),
+            // The elements of children groups tell their group that
+            // they need to be restarted instead.
+            BastionMessage::Faulted { id }
+            | BastionMessage::RestartRequired { id, .. }
+            | BastionMessage::Panicked { id, .. } => (id, StopReason::Faulted),
+            _ => return,
+        };
+
+        for waiter in self.exit_waiters.remove(id).into_iter().flatten() {
+            waiter.send(reason).ok();
+        }
+
+        if self.exits.len() == EXITS_CAPACITY {
+            self.exits.pop_front();
+        }
+        self.exits.push_back((id.clone(), reason));
     }
 
     pub(crate) fn clear_children(&mut self) {
         self.children.clear();
+        self.weights.clear();
+        self.ready.clear();
+        self.saturated.clear();
+        self.subscriptions.clear();
+        self.names.clear();
+    }
+
+    /// Subscribes the registered child with the given identifier
+    /// to the given topic, making it receive the messages sent
+    /// to it using [`publish`].
+    ///
+    /// [`publish`]: #method.publish
+    pub(crate) fn subscribe(&mut self, id: &BastionId, topic: String) {
+        if self.children.contains_key(id) {
+            self.subscriptions
+                .entry(topic)
+                .or_default()
+                .insert(id.clone());
+        }
+    }
+
+    pub(crate) fn unsubscribe(&mut self, id: &BastionId, topic: &str) {
+        if let Some(subscribers) = self.subscriptions.get_mut(topic) {
+            subscribers.remove(id);
+            if subscribers.is_empty() {
+                self.subscriptions.remove(topic);
+            }
+        }
+    }
+
+    /// Sends the envelope to all the children subscribed to the
+    /// given topic.
+    pub(crate) fn publish(&self, topic: &str, env: Envelope) {
+        let subscribers = match self.subscriptions.get(topic) {
+            Some(subscribers) => subscribers,
+            None => return,
+        };
+
+        for id in subscribers {
+            // FIXME: Err(Error) if None
+            if let Some(env) = env.try_clone() {
+                self.send_child_or_log(id, env);
+            }
+        }
+    }
+
+    /// Registers the registered child with the given identifier
+    /// under the given name, making it receive the messages sent
+    /// to it using [`send_named`]. The child stays registered
+    /// under the name when it is restarted.
+    ///
+    /// [`send_named`]: #method.send_named
+    pub(crate) fn register_name(&mut self, name: String, id: &BastionId) {
+        if self.children.contains_key(id) {
+            let ids = self.names.entry(name).or_default();
+            if !ids.contains(id) {
+                ids.push_back(id.clone());
+            }
+        }
+    }
+
+    /// Sends the envelope to one of the children registered under
+    /// the given name, each of them receiving the envelopes sent
+    /// to it in turn, or returns it if none is.
+    ///
+    /// The children whose mailbox is closed because they died are
+    /// unregistered and skipped. Messages dropped by a middleware
+    /// count as sent.
+    pub(crate) fn send_named(&mut self, name: &str, mut env: Envelope) -> Result<(), Envelope> {
+        let id = match self.apply_middlewares(&mut env) {
+            MiddlewareAction::Forward => None,
+            MiddlewareAction::Drop => return Ok(()),
+            MiddlewareAction::Redirect(id) => Some(id),
+        };
+
+        let ids: Vec<_> = match id {
+            Some(id) => vec![id],
+            None => match self.names.get_mut(name) {
+                Some(ids) => {
+                    let ordered = ids.iter().cloned().collect();
+                    // The next envelope goes to the next child.
+                    ids.rotate_left(1);
+                    ordered
+                }
+                None => return Err(env),
+            },
+        };
+
+        for id in ids {
+            let child = match self.children.get(&id) {
+                Some(child) => child,
+                None => continue,
+            };
+
+            match child.unbounded_send(env) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    debug!("Broadcast({}): Child({}) is dead.", self.id(), id);
+                    self.unregister(&id);
+                    env = err.into_inner();
+                }
+            }
+        }
+
+        Err(env)
+    }
+
+    /// Sets the weight of the registered child with the given
+    /// identifier. Children default to a weight of `1` and
+    /// children with a weight of `0` won't receive any message
+    /// sent using [`send_weighted`].
+    ///
+    /// [`send_weighted`]: #method.send_weighted
+    pub(crate) fn set_child_weight(&mut self, id: &BastionId, weight: usize) {
+        if self.children.contains_key(id) {
+            self.weights.entry(id.clone()).or_default().weight = weight;
+        }
+    }
+
+    /// Picks the next child that should receive a message
+    /// using smooth weighted round-robin: every child's current
+    /// weight is increased by its weight, the child with the
+    /// highest current weight is picked and its current weight
+    /// is decreased by the sum of all the weights.
+    fn next_weighted(&mut self) -> Option<BastionId> {
+        let mut total: isize = 0;
+        let mut picked: Option<(&BastionId, isize)> = None;
+
+        for id in self.children.keys() {
+            let weight = self.weights.entry(id.clone()).or_default();
+            if weight.weight == 0 || self.saturated.contains(id) {
+                continue;
+            }
+
+            weight.current += weight.weight as isize;
+            total += weight.weight as isize;
+
+            match picked {
+                Some((_, current)) if current >= weight.current => (),
+                _ => picked = Some((id, weight.current)),
+            }
+        }
+
+        let id = picked?.0.clone();
+        if let Some(weight) = self.weights.get_mut(&id) {
+            weight.current -= total;
+        }
+
+        Some(id)
+    }
+
+    /// Sends the envelope to the next child picked by weight,
+    /// leaving out the children at capacity, or returns it if
+    /// none of the children can receive it.
+    pub(crate) fn send_weighted(&mut self, envelope: Envelope) -> Option<Envelope> {
+        match self.next_weighted() {
+            Some(id) => {
+                self.send_child_or_log(&id, envelope);
+                None
+            }
+            None => Some(envelope),
+        }
     }
 
     pub(crate) fn stop_child(&mut self, id: &BastionId) {
         let msg = BastionMessage::stop();
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        self.send_child(id, env);
+        self.try_send_child_or_log(id, env);
 
         self.unregister(id);
     }
 
+    /// Sends a poison pill to the given child, returning a
+    /// notice which resolves once the child acknowledged it, or
+    /// [`TrySendError::Closed`] if the child already died.
+    ///
+    /// [`TrySendError::Closed`]: enum.TrySendError.html#variant.Closed
+    pub(crate) fn poison_pill_child(
+        &mut self,
+        child: ChildRef,
+        handover: Option<Sender>,
+    ) -> Result<DeathNotice, TrySendError> {
+        let id = child.id().clone();
+        let (msg, notice) = BastionMessage::poison_pill(child, handover);
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        let sent = self.try_send_child(&id, env);
+
+        self.unregister(&id);
+        match sent {
+            Err(TrySendError::Closed) => Err(TrySendError::Closed),
+            // The notice is resolved once the pill was dropped.
+            _ => Ok(notice),
+        }
+    }
+
+    /// Asks every child for its topology, returning the
+    /// receivers of their answers.
+    pub(crate) fn request_topologies(&self) -> Vec<oneshot::Receiver<TopologyNode>> {
+        self.children
+            .values()
+            .filter_map(|child| {
+                let (msg, recver) = BastionMessage::topology();
+                let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+                child.unbounded_send(env).ok().map(|_| recver)
+            })
+            .collect()
+    }
+
     pub(crate) fn stop_children(&mut self) {
         let msg = BastionMessage::stop();
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
@@ -140,7 +997,7 @@ impl Broadcast {
     pub(crate) fn kill_child(&mut self, id: &BastionId) {
         let msg = BastionMessage::kill();
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        self.send_child(id, env);
+        self.try_send_child_or_log(id, env);
 
         self.unregister(id);
     }
@@ -158,8 +1015,7 @@ impl Broadcast {
 
         let msg = BastionMessage::stopped(self.id().clone());
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        // FIXME: Err(msg)
-        self.send_parent(env).ok();
+        self.send_parent_or_log(env);
     }
 
     pub(crate) fn faulted(&mut self) {
@@ -167,124 +1023,575 @@ impl Broadcast {
 
         let msg = BastionMessage::faulted(self.id().clone());
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        // FIXME: Err(msg)
-        self.send_parent(env).ok();
+        self.send_parent_or_log(env);
     }
 
-    pub(crate) fn send_parent(&self, envelope: Envelope) -> Result<(), Envelope> {
-        self.parent.send(envelope)
+    pub(crate) fn escalate(&self, info: FaultInfo) {
+        let msg = BastionMessage::escalate(self.id().clone(), info);
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        self.send_parent_or_log(env);
     }
 
-    pub(crate) fn send_child(&self, id: &BastionId, envelope: Envelope) {
-        // FIXME: Err if None?
-        if let Some(child) = self.children.get(id) {
-            // FIXME: handle errors
-            child.unbounded_send(envelope).ok();
-        }
+    #[cfg(test)]
+    /// Notifies the parent that the element with the given
+    /// identifier faulted, as if it crashed.
+    pub(crate) fn inject_fault(&self, id: &BastionId) {
+        let msg = BastionMessage::faulted(id.clone());
+        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
+        self.send_parent_or_log(env);
     }
 
-    pub(crate) fn send_children(&self, env: Envelope) {
-        for child in self.children.values() {
-            // FIXME: Err(Error) if None
-            if let Some(env) = env.try_clone() {
-                // FIXME: handle errors
-                child.unbounded_send(env).ok();
-            }
-        }
+    /// Sends the envelope to the parent, returning
+    /// [`BastionError::SendFailed`] if it stopped.
+    ///
+    /// [`BastionError::SendFailed`]: ../errors/enum.BastionError.html#variant.SendFailed
+    pub(crate) fn send_parent(&self, envelope: Envelope) -> Result<(), BastionError> {
+        self.parent
+            .send(envelope)
+            .map_err(|_| BastionError::SendFailed)
     }
 
-    pub(crate) fn send_self(&self, env: Envelope) {
-        // FIXME: handle errors
-        self.sender.unbounded_send(env).ok();
+    /// Sends the envelope to the parent like `send_parent` does,
+    /// logging the failure for the callers which can't do
+    /// anything about it.
+    pub(crate) fn send_parent_or_log(&self, envelope: Envelope) {
+        if let Err(err) = self.send_parent(envelope) {
+            debug!("Broadcast({}): Couldn't notify parent: {}", self.id(), err);
+        }
     }
-}
 
-impl Parent {
-    pub(crate) fn none() -> Self {
-        Parent::None
+    /// Registers a middleware which will see every message sent
+    /// to the children before it is delivered, after the
+    /// middlewares registered before it.
+    pub(crate) fn add_middleware(&mut self, middleware: Middleware) {
+        self.middlewares.push(middleware);
     }
 
-    pub(crate) fn system() -> Self {
-        Parent::System
+    fn apply_middlewares(&self, envelope: &mut Envelope) -> MiddlewareAction {
+        for middleware in &self.middlewares {
+            match middleware.apply(&mut envelope.msg) {
+                MiddlewareAction::Forward => (),
+                action => return action,
+            }
+        }
+
+        MiddlewareAction::Forward
     }
 
-    pub(crate) fn supervisor(supervisor: SupervisorRef) -> Self {
-        Parent::Supervisor(supervisor)
+    /// Sends the envelope to the child with the given identifier
+    /// once the middlewares saw it, returning
+    /// [`BastionError::ChildNotFound`] if no such child is
+    /// registered and [`BastionError::SendFailed`] if its mailbox
+    /// is closed. Messages dropped by a middleware count as sent.
+    ///
+    /// [`BastionError::ChildNotFound`]: ../errors/enum.BastionError.html#variant.ChildNotFound
+    /// [`BastionError::SendFailed`]: ../errors/enum.BastionError.html#variant.SendFailed
+    pub(crate) fn send_child(
+        &self,
+        id: &BastionId,
+        mut envelope: Envelope,
+    ) -> Result<(), BastionError> {
+        match self.apply_middlewares(&mut envelope) {
+            MiddlewareAction::Forward => self.deliver(id, envelope),
+            MiddlewareAction::Drop => Ok(()),
+            MiddlewareAction::Redirect(id) => self.deliver(&id, envelope),
+        }
     }
 
-    pub(crate) fn children(children: ChildrenRef) -> Self {
-        Parent::Children(children)
+    /// Sends the envelope to the child with the given identifier
+    /// like `send_child` does, logging the failure for the
+    /// callers which can't do anything about it (e.g. because
+    /// the child dying is handled once its parent is notified).
+    pub(crate) fn send_child_or_log(&self, id: &BastionId, envelope: Envelope) {
+        if let Err(err) = self.send_child(id, envelope) {
+            debug!(
+                "Broadcast({}): Couldn't send to Child({}): {}",
+                self.id(),
+                id,
+                err
+            );
+        }
     }
 
-    pub(crate) fn into_supervisor(self) -> Option<SupervisorRef> {
-        if let Parent::Supervisor(supervisor) = self {
-            Some(supervisor)
-        } else {
-            None
+    /// Tries to enqueue the envelope into the mailbox of the
+    /// child with the given identifier once the middlewares saw
+    /// it, without waiting, which makes it usable by the loops
+    /// which must not block (e.g. to send control messages).
+    ///
+    /// If the mailbox is closed, the child died and is
+    /// unregistered, its parent being notified of its death
+    /// separately. Messages dropped by a middleware count as
+    /// sent.
+    pub(crate) fn try_send_child(
+        &mut self,
+        id: &BastionId,
+        mut envelope: Envelope,
+    ) -> Result<(), TrySendError> {
+        let id = match self.apply_middlewares(&mut envelope) {
+            MiddlewareAction::Forward => id.clone(),
+            MiddlewareAction::Drop => return Ok(()),
+            MiddlewareAction::Redirect(id) => id,
+        };
+
+        let child = self.children.get(&id).ok_or(TrySendError::NotFound)?;
+        match child.unbounded_send(envelope) {
+            Ok(()) => Ok(()),
+            Err(err) if err.is_full() => Err(TrySendError::Full),
+            Err(_) => {
+                debug!("Broadcast({}): Child({}) is dead.", self.id(), id);
+                self.unregister(&id);
+                Err(TrySendError::Closed)
+            }
         }
     }
 
-    pub(crate) fn into_children(self) -> Option<ChildrenRef> {
-        if let Parent::Children(children) = self {
-            Some(children)
-        } else {
-            None
+    /// Tries to send the envelope to the child with the given
+    /// identifier like `try_send_child` does, logging the
+    /// failure.
+    fn try_send_child_or_log(&mut self, id: &BastionId, envelope: Envelope) {
+        if let Err(err) = self.try_send_child(id, envelope) {
+            debug!(
+                "Broadcast({}): Couldn't send to Child({}): {}",
+                self.id(),
+                id,
+                err
+            );
         }
     }
 
-    fn send(&self, env: Envelope) -> Result<(), Envelope> {
-        match self {
-            // FIXME
-            Parent::None => unimplemented!(),
-            Parent::System => SYSTEM
-                .sender()
-                .unbounded_send(env)
-                .map_err(|err| err.into_inner()),
-            Parent::Supervisor(supervisor) => supervisor.send(env),
-            Parent::Children(children) => children.send(env),
+    /// Sends the envelope to the child with the given identifier
+    /// like `send_child` does, but tells whether it was enqueued
+    /// into its mailbox.
+    pub(crate) fn send_child_confirmed(
+        &self,
+        id: &BastionId,
+        mut envelope: Envelope,
+    ) -> Result<(), SendError> {
+        let id = match self.apply_middlewares(&mut envelope) {
+            MiddlewareAction::Forward => id.clone(),
+            MiddlewareAction::Drop => return Err(SendError::Dropped),
+            MiddlewareAction::Redirect(id) => id,
+        };
+
+        match self.children.get(&id) {
+            Some(child) => child
+                .unbounded_send(envelope)
+                .map_err(|_| SendError::Closed),
+            None => Err(SendError::UnknownChild),
         }
     }
-}
 
-impl Stream for Broadcast {
-    type Item = Envelope;
+    fn deliver(&self, id: &BastionId, envelope: Envelope) -> Result<(), BastionError> {
+        match self.children.get(id) {
+            Some(child) => child
+                .unbounded_send(envelope)
+                .map_err(|_| BastionError::SendFailed),
+            None => Err(BastionError::ChildNotFound(id.clone())),
+        }
+    }
 
-    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.get_mut().recver).poll_next(ctx)
+    /// Sends the envelope to all the registered children,
+    /// unregistering the ones whose mailbox is closed because
+    /// they died, and returns their identifiers.
+    pub(crate) fn send_children(&mut self, env: Envelope) -> Vec<BastionId> {
+        self.send_to_children(env, false)
     }
-}
 
-#[cfg(test)]
+    /// Sends the envelope to all the registered children like
+    /// `send_children` does, leaving out the children at
+    /// capacity, or returns it if all of them are.
+    pub(crate) fn send_available(&mut self, env: Envelope) -> Result<Vec<BastionId>, Envelope> {
+        let saturated = &self.saturated;
+        if !self.children.is_empty() && self.children.keys().all(|id| saturated.contains(id)) {
+            return Err(env);
+        }
+
+        Ok(self.send_to_children(env, true))
+    }
+
+    fn send_to_children(&mut self, mut env: Envelope, skip_saturated: bool) -> Vec<BastionId> {
+        match self.apply_middlewares(&mut env) {
+            MiddlewareAction::Forward => (),
+            MiddlewareAction::Drop => return vec![],
+            MiddlewareAction::Redirect(id) => {
+                if let Err(err) = self.deliver(&id, env) {
+                    debug!(
+                        "Broadcast({}): Couldn't redirect to Child({}): {}",
+                        self.id(),
+                        id,
+                        err
+                    );
+                }
+                return vec![];
+            }
+        }
+
+        let mut pruned = vec![];
+        for (id, child) in &self.children {
+            if skip_saturated && self.saturated.contains(id) {
+                continue;
+            }
+
+            // FIXME: Err(Error) if None
+            if let Some(env) = env.try_clone() {
+                if child.unbounded_send(env).is_err() {
+                    pruned.push(id.clone());
+                }
+            }
+        }
+
+        for id in &pruned {
+            self.unregister(id);
+        }
+
+        pruned
+    }
+
+    /// Returns a future resolving once all the envelopes sent by
+    /// this broadcast so far were accepted by the channels of
+    /// their recipients (its parent, its children and the new
+    /// parents of the children it moved), using their
+    /// `Sink::poll_flush`.
+    ///
+    /// This only waits for the envelopes to be enqueued, not for
+    /// their recipients to handle them. The channels which were
+    /// closed meanwhile are skipped.
+    #[allow(dead_code)]
+    pub(crate) fn flush(&mut self) -> impl Future<Output = ()> {
+        let senders: Vec<Sender> = self
+            .children
+            .values()
+            .chain(self.reparented.values())
+            .chain(self.parent.sender())
+            .cloned()
+            .collect();
+
+        async move {
+            for sender in &senders {
+                // The channel being closed means that there is
+                // nothing left to flush.
+                future::poll_fn(|ctx| sender.poll_flush(ctx)).await.ok();
+            }
+        }
+    }
+
+    /// Sends the envelope to this broadcast, returning
+    /// [`BastionError::SendFailed`] if its receiver was dropped.
+    ///
+    /// [`BastionError::SendFailed`]: ../errors/enum.BastionError.html#variant.SendFailed
+    pub(crate) fn send_self(&self, env: Envelope) -> Result<(), BastionError> {
+        self.sender
+            .unbounded_send(env)
+            .map_err(|_| BastionError::SendFailed)
+    }
+}
+
+impl Sender {
+    /// Sends the envelope on the channel of its kind of message,
+    /// failing if the receiver was dropped.
+    pub(crate) fn unbounded_send(&self, env: Envelope) -> Result<(), ChannelError<Envelope>> {
+        if env.msg.is_fault_notice() && !self.inner.fifo.load(Ordering::Acquire) {
+            self.inner.faults.unbounded_send(env)
+        } else {
+            self.inner.msgs.unbounded_send(env)
+        }
+    }
+
+    /// Sets whether the fault notices are sent along with the
+    /// other messages, to be received in the order they were sent
+    /// in.
+    fn set_fifo(&self, fifo: bool) {
+        self.inner.fifo.store(fifo, Ordering::Release);
+    }
+
+    /// Resolves once the envelopes sent so far were accepted by
+    /// both channels (see `Sink::poll_flush`), failing if the
+    /// receiver was dropped.
+    pub(crate) fn poll_flush(&self, ctx: &mut Context) -> Poll<Result<(), mpsc::SendError>> {
+        let inner = &*self.inner;
+        match Pin::new(&mut &inner.faults).poll_flush(ctx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut &inner.msgs).poll_flush(ctx),
+            poll => poll,
+        }
+    }
+}
+
+impl Receiver {
+    /// Returns the next envelope if there is one, the fault
+    /// notices first, or `None` without waiting otherwise.
+    fn try_recv(&mut self) -> Option<Envelope> {
+        self.faults
+            .try_recv()
+            .or_else(|_| self.msgs.try_recv())
+            .ok()
+    }
+}
+
+impl Stream for Receiver {
+    type Item = Envelope;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let recver = self.get_mut();
+        // Both channels are closed at the same time, once all the
+        // senders were dropped.
+        match recver.faults.poll_next_unpin(ctx) {
+            Poll::Ready(Some(env)) => Poll::Ready(Some(env)),
+            _ => recver.msgs.poll_next_unpin(ctx),
+        }
+    }
+}
+
+impl Parent {
+    pub(crate) fn none() -> Self {
+        Parent::None
+    }
+
+    pub(crate) fn system() -> Self {
+        Parent::System
+    }
+
+    pub(crate) fn supervisor(supervisor: SupervisorRef) -> Self {
+        Parent::Supervisor(supervisor)
+    }
+
+    pub(crate) fn children(children: ChildrenRef) -> Self {
+        Parent::Children(children)
+    }
+
+    pub(crate) fn into_supervisor(self) -> Option<SupervisorRef> {
+        if let Parent::Supervisor(supervisor) = self {
+            Some(supervisor)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn into_children(self) -> Option<ChildrenRef> {
+        if let Parent::Children(children) = self {
+            Some(children)
+        } else {
+            None
+        }
+    }
+
+    fn sender(&self) -> Option<&Sender> {
+        match self {
+            Parent::None => None,
+            Parent::System => Some(SYSTEM.sender()),
+            Parent::Supervisor(supervisor) => Some(supervisor.sender()),
+            Parent::Children(children) => Some(children.sender()),
+        }
+    }
+
+    fn send(&self, env: Envelope) -> Result<(), Envelope> {
+        match self {
+            // FIXME
+            Parent::None => unimplemented!(),
+            Parent::System => SYSTEM
+                .sender()
+                .unbounded_send(env)
+                .map_err(|err| err.into_inner()),
+            Parent::Supervisor(supervisor) => supervisor.send(env),
+            Parent::Children(children) => children.send(env),
+        }
+    }
+}
+
+impl Display for TrySendError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            TrySendError::Full => write!(fmt, "the mailbox is full"),
+            TrySendError::Closed => write!(fmt, "the mailbox is closed"),
+            TrySendError::NotFound => write!(fmt, "no child with this identifier"),
+        }
+    }
+}
+
+impl Error for TrySendError {}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight {
+            weight: 1,
+            current: 0,
+        }
+    }
+}
+
+impl Future for Recv<'_> {
+    type Output = Option<Envelope>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().bcast).poll_next(ctx)
+    }
+}
+
+impl Stream for Broadcast {
+    type Item = Envelope;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let bcast = self.get_mut();
+        bcast.next_biased(|recver| Pin::new(recver).poll_next(ctx))
+    }
+}
+
+fn is_data(env: &Envelope) -> bool {
+    matches!(
+        env.msg,
+        BastionMessage::Message(_)
+            | BastionMessage::Publish { .. }
+            | BastionMessage::SendNamed { .. }
+    ) || env.msg.is_broadcast()
+}
+
+/// The stream returned by [`Broadcast::fan_in`].
+///
+/// [`Broadcast::fan_in`]: struct.Broadcast.html#method.fan_in
+pub(crate) struct FanIn<'a, T> {
+    bcast: &'a mut Broadcast,
+    // Where the messages of other types are routed to.
+    dead_letters: Sender,
+    // The envelopes which aren't messages, to send back once
+    // the stream is dropped.
+    deferred: Vec<Envelope>,
+    _msg: PhantomData<fn() -> T>,
+}
+
+impl<T: Message> Stream for FanIn<'_, T> {
+    type Item = (BastionId, T);
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let fan_in = self.get_mut();
+        loop {
+            match Pin::new(&mut *fan_in.bcast).poll_next(ctx) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                    ..
+                })) => match msg.try_unwrap::<T>() {
+                    Ok(msg) => return Poll::Ready(Some((sign.path().id().clone(), msg))),
+                    Err(msg) => {
+                        let msg = BastionMessage::Message(msg);
+                        let env = Envelope::new_with_sign(msg, sign);
+                        fan_in.dead_letters.unbounded_send(env).ok();
+                    }
+                },
+                Poll::Ready(Some(env)) => fan_in.deferred.push(env),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Drop for FanIn<'_, T> {
+    fn drop(&mut self) {
+        for env in self.deferred.drain(..) {
+            // The remaining messages can't be requeued either.
+            if self.bcast.send_self(env).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
 mod tests {
-    use super::{BastionMessage, Broadcast, Parent};
+    use super::{
+        channel, BackpressureSignal, BastionMessage, Broadcast, Parent, PollBias, StopReason,
+        TrySendError, DEFERRED_CAPACITY,
+    };
+    use crate::children_ref::ChildrenRef;
     use crate::context::{BastionId, NIL_ID};
     use crate::envelope::Envelope;
+    use crate::errors::BastionError;
+    use crate::message::Msg;
+    use crate::middleware::{Middleware, MiddlewareAction};
     use crate::path::{BastionPath, BastionPathElement};
-    use futures::channel::mpsc;
+    use futures::channel::oneshot;
     use futures::executor;
     use futures::poll;
     use futures::prelude::*;
     use std::sync::Arc;
     use std::task::Poll;
 
+    #[test]
+    fn register_rejects_duplicates() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let id = BastionId::new();
+        let mut child = Broadcast::new(Parent::System, BastionPathElement::Supervisor(id.clone()));
+        let mut duplicate =
+            Broadcast::new(Parent::System, BastionPathElement::Supervisor(id.clone()));
+
+        assert!(parent.register(&child).is_ok());
+        assert_eq!(
+            parent.register(&duplicate),
+            Err(BastionError::AlreadyRegistered(id.clone()))
+        );
+        assert_eq!(parent.children.len(), 1);
+
+        let env = envelope(BastionMessage::start());
+
+        // The first child wasn't orphaned.
+        parent.send_child(&id, env).unwrap();
+        assert!(child.try_recv().is_some());
+        assert!(duplicate.try_recv().is_none());
+    }
+
+    #[test]
+    fn send_child_errors() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let child = register_children(&mut parent, 1).remove(0);
+
+        let env = || envelope(BastionMessage::start());
+
+        let unknown = BastionId::new();
+        assert_eq!(
+            parent.send_child(&unknown, env()),
+            Err(BastionError::ChildNotFound(unknown))
+        );
+
+        let id = child.id().clone();
+        drop(child);
+        assert_eq!(parent.send_child(&id, env()), Err(BastionError::SendFailed));
+    }
+
+    #[test]
+    fn try_send_child() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let mut child = register_children(&mut parent, 1).remove(0);
+
+        let env = || envelope(BastionMessage::start());
+
+        let id = child.id().clone();
+        parent.try_send_child(&id, env()).unwrap();
+        assert!(child.try_recv().is_some());
+        assert_eq!(
+            parent.try_send_child(&BastionId::new(), env()),
+            Err(TrySendError::NotFound)
+        );
+
+        // The dead children are unregistered.
+        drop(child);
+        assert_eq!(parent.try_send_child(&id, env()), Err(TrySendError::Closed));
+        assert!(!parent.is_registered(&id));
+        assert_eq!(
+            parent.try_send_child(&id, env()),
+            Err(TrySendError::NotFound)
+        );
+    }
+
     #[test]
     fn send_children() {
         let mut parent = Broadcast::new_root(Parent::System);
 
-        let mut children = vec![];
-        for _ in 0..4 {
-            let child = Broadcast::new(
-                Parent::System,
-                BastionPathElement::Supervisor(BastionId::new()),
-            );
-            parent.register(&child);
-            children.push(child);
-        }
+        let mut children = register_children(&mut parent, 4);
 
         let msg = BastionMessage::start();
 
         // need manual construction because SYSTEM is not running in this test
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = channel();
         let env = Envelope::new(
             msg,
             Arc::new(
@@ -334,4 +1641,791 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn middlewares() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let mut children = register_children(&mut parent, 3);
+
+        let redirect_to = children[0].id().clone();
+        parent.add_middleware(Middleware::new(|msg: &mut BastionMessage| match msg {
+            BastionMessage::Kill => MiddlewareAction::Drop,
+            _ => MiddlewareAction::Forward,
+        }));
+        parent.add_middleware(Middleware::new(move |msg: &mut BastionMessage| match msg {
+            BastionMessage::Stop => MiddlewareAction::Redirect(redirect_to.clone()),
+            _ => MiddlewareAction::Forward,
+        }));
+
+        parent.send_children(envelope(BastionMessage::kill()));
+        parent.send_children(envelope(BastionMessage::stop()));
+        parent
+            .send_child(children[1].id(), envelope(BastionMessage::start()))
+            .unwrap();
+
+        executor::block_on(async {
+            match poll!(children[0].next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Stop,
+                    ..
+                })) => (),
+                _ => panic!(),
+            }
+            match poll!(children[1].next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Start,
+                    ..
+                })) => (),
+                _ => panic!(),
+            }
+
+            for child in &mut children {
+                assert!(poll!(child.next()).is_pending());
+            }
+        });
+    }
+
+    #[test]
+    fn fan_in() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let (dead_letters, mut dead_letters_recver) = channel();
+
+        let children = register_children(&mut parent, 2);
+        for (i, child) in children.iter().enumerate() {
+            let msg = BastionMessage::tell(i as u8);
+            let env = Envelope::new(msg, child.path().clone(), child.sender().clone());
+            parent.send_self(env).unwrap();
+        }
+        for msg in [BastionMessage::tell("mistyped"), BastionMessage::start()] {
+            let env = Envelope::new(
+                msg,
+                children[0].path().clone(),
+                children[0].sender().clone(),
+            );
+            parent.send_self(env).unwrap();
+        }
+
+        executor::block_on(async {
+            let mut fan_in = parent.fan_in_to::<u8>(dead_letters);
+            for (i, child) in children.iter().enumerate() {
+                assert_eq!(fan_in.next().await, Some((child.id().clone(), i as u8)));
+            }
+            assert!(poll!(fan_in.next()).is_pending());
+            drop(fan_in);
+
+            // The messages of other types are routed to the dead
+            // letters...
+            match dead_letters_recver.try_recv() {
+                Some(Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                }) => {
+                    assert_eq!(msg.try_unwrap::<&str>().unwrap(), "mistyped");
+                    assert_eq!(sign.path().id(), children[0].id());
+                }
+                _ => panic!(),
+            }
+
+            // ...while the other envelopes are handled as usual.
+            match poll!(parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Start,
+                    ..
+                })) => (),
+                _ => panic!(),
+            }
+        });
+    }
+
+    #[test]
+    fn poll_bias() {
+        let mut bcast = Broadcast::new_root(Parent::System);
+
+        let data = |n: usize| envelope(BastionMessage::Message(Msg::tell(n)));
+        let start = envelope(BastionMessage::start());
+
+        bcast.send_self(data(0)).unwrap();
+        bcast.send_self(start.try_clone().unwrap()).unwrap();
+        assert!(matches!(
+            bcast.try_recv().unwrap().msg,
+            BastionMessage::Message(_)
+        ));
+        assert!(matches!(
+            bcast.try_recv().unwrap().msg,
+            BastionMessage::Start
+        ));
+
+        bcast.set_poll_bias(PollBias::ControlFirst);
+        bcast.send_self(data(1)).unwrap();
+        bcast.send_self(data(2)).unwrap();
+        bcast.send_self(start).unwrap();
+        bcast.send_self(data(3)).unwrap();
+
+        executor::block_on(async {
+            assert!(matches!(
+                bcast.next().await.unwrap().msg,
+                BastionMessage::Start
+            ));
+            // Data messages are still received in order.
+            for n in 1..=3 {
+                match bcast.next().await.unwrap().msg {
+                    BastionMessage::Message(msg) => {
+                        assert_eq!(msg.try_unwrap::<usize>().unwrap(), n)
+                    }
+                    _ => panic!(),
+                }
+            }
+
+            assert!(poll!(bcast.next()).is_pending());
+        });
+        assert!(bcast.try_recv().is_none());
+
+        // A flood of data messages is only looked through up to
+        // the capacity of the deferred messages.
+        for n in 0..DEFERRED_CAPACITY * 2 {
+            bcast.send_self(data(n)).unwrap();
+        }
+        bcast.send_self(envelope(BastionMessage::start())).unwrap();
+        for n in 0..DEFERRED_CAPACITY + 1 {
+            match bcast.try_recv().unwrap().msg {
+                BastionMessage::Message(msg) => assert_eq!(msg.try_unwrap::<usize>().unwrap(), n),
+                _ => panic!(),
+            }
+            assert!(bcast.deferred_data.len() <= DEFERRED_CAPACITY);
+        }
+    }
+
+    #[test]
+    fn fault_notices_first() {
+        let mut bcast = Broadcast::new_root(Parent::System);
+        let data = |n: usize| envelope(BastionMessage::Message(Msg::tell(n)));
+        let fault = || envelope(BastionMessage::faulted(BastionId::new()));
+
+        bcast.send_self(data(0)).unwrap();
+        bcast.send_self(envelope(BastionMessage::stop())).unwrap();
+        bcast.send_self(fault()).unwrap();
+
+        // The fault notices don't wait behind the other messages...
+        assert!(matches!(
+            bcast.try_recv().unwrap().msg,
+            BastionMessage::Faulted { .. }
+        ));
+        // ...which are still received in order...
+        match bcast.try_recv().unwrap().msg {
+            BastionMessage::Message(msg) => assert_eq!(msg.try_unwrap::<usize>().unwrap(), 0),
+            _ => panic!(),
+        }
+        assert!(matches!(
+            bcast.try_recv().unwrap().msg,
+            BastionMessage::Stop
+        ));
+        assert!(bcast.try_recv().is_none());
+
+        // ...unless all of them have to be.
+        bcast.set_poll_bias(PollBias::Fifo);
+        bcast.send_self(data(1)).unwrap();
+        bcast.send_self(fault()).unwrap();
+        executor::block_on(async {
+            match bcast.next().await.unwrap().msg {
+                BastionMessage::Message(msg) => assert_eq!(msg.try_unwrap::<usize>().unwrap(), 1),
+                _ => panic!(),
+            }
+            assert!(matches!(
+                bcast.next().await.unwrap().msg,
+                BastionMessage::Faulted { .. }
+            ));
+        });
+    }
+
+    #[test]
+    fn tell_then_stop() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let mut children = register_children(&mut parent, 1);
+        let child = &mut children[0];
+
+        let msg = BastionMessage::Message(Msg::tell(42usize));
+        parent.send_child(child.id(), envelope(msg)).unwrap();
+        parent
+            .send_child(child.id(), envelope(BastionMessage::stop()))
+            .unwrap();
+
+        // The message told to the child is received before it is
+        // told to stop.
+        match child.try_recv().unwrap().msg {
+            BastionMessage::Message(msg) => assert_eq!(msg.try_unwrap::<usize>().unwrap(), 42),
+            _ => panic!(),
+        }
+        assert!(matches!(
+            child.try_recv().unwrap().msg,
+            BastionMessage::Stop
+        ));
+    }
+
+    #[test]
+    fn flush() {
+        let mut parent = Broadcast::new_root(Parent::None);
+        let mut child = Broadcast::new(
+            Parent::children(children_ref(&parent)),
+            BastionPathElement::Child(BastionId::new()),
+        );
+        parent.register(&child).unwrap();
+
+        // The closed channels are skipped.
+        let dead = Broadcast::new(
+            Parent::children(children_ref(&parent)),
+            BastionPathElement::Child(BastionId::new()),
+        );
+        parent.register(&dead).unwrap();
+        drop(dead);
+
+        let msg = BastionMessage::Message(Msg::tell(42usize));
+        let env = Envelope::new(msg, parent.path().clone(), parent.sender().clone());
+        parent.send_child(child.id(), env).unwrap();
+
+        executor::block_on(async {
+            parent.flush().await;
+            child.flush().await;
+        });
+        assert!(matches!(
+            child.try_recv().unwrap().msg,
+            BastionMessage::Message(_)
+        ));
+    }
+
+    #[test]
+    fn send_children_prunes_dead_children() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let mut children = register_children(&mut parent, 3);
+
+        // Dropping a child closes its mailbox, as if it died.
+        let dead = children.remove(1);
+        let dead_id = dead.id().clone();
+        drop(dead);
+
+        let env = envelope(BastionMessage::start());
+
+        assert_eq!(
+            parent.send_children(env.try_clone().unwrap()),
+            vec![dead_id.clone()]
+        );
+        assert!(!parent.children.contains_key(&dead_id));
+        assert_eq!(parent.children.len(), 2);
+
+        assert!(parent.send_children(env).is_empty());
+        executor::block_on(async {
+            for child in &mut children {
+                for _ in 0..2 {
+                    match poll!(child.next()) {
+                        Poll::Ready(Some(Envelope {
+                            msg: BastionMessage::Start,
+                            ..
+                        })) => (),
+                        _ => panic!(),
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn await_exit() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let children = register_children(&mut parent, 2);
+
+        let stopped = children[0].id().clone();
+        let faulted = children[1].id().clone();
+        let (sender, mut stop) = oneshot::channel();
+        parent.await_exit(&stopped, sender);
+
+        executor::block_on(async {
+            assert!(poll!(&mut stop).is_pending());
+
+            let msg = BastionMessage::stopped(stopped.clone());
+            let env = Envelope::new(
+                msg,
+                children[0].path().clone(),
+                children[0].sender().clone(),
+            );
+            parent.send_self(env).unwrap();
+            let msg = BastionMessage::faulted(faulted.clone());
+            let env = Envelope::new(
+                msg,
+                children[1].path().clone(),
+                children[1].sender().clone(),
+            );
+            parent.send_self(env).unwrap();
+
+            while let Poll::Ready(Some(_)) = poll!(parent.next()) {}
+
+            assert_eq!(stop.await, Ok(StopReason::Stopped));
+            // The children which already exited are answered right
+            // away, unlike the unknown ones.
+            let (sender, exit) = oneshot::channel();
+            parent.await_exit(&faulted, sender);
+            assert_eq!(exit.await, Ok(StopReason::Faulted));
+            let (sender, exit) = oneshot::channel();
+            parent.await_exit(&BastionId::new(), sender);
+            assert!(exit.await.is_err());
+        });
+    }
+
+    #[test]
+    fn wait_for_children() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let children = register_children(&mut parent, 2);
+
+        let ready = |parent: &Broadcast, child: &Broadcast| {
+            let msg = BastionMessage::ready(child.id().clone());
+            let env = Envelope::new(msg, child.path().clone(), child.sender().clone());
+            parent.send_self(env).unwrap();
+        };
+        let wait_for_children = |parent: &mut Broadcast, n| {
+            let (sender, recver) = oneshot::channel();
+            parent.wait_for_children(n, sender);
+            recver
+        };
+        let mut one = wait_for_children(&mut parent, 1);
+        let mut two = wait_for_children(&mut parent, 2);
+
+        executor::block_on(async {
+            // Reporting twice only counts once.
+            ready(&parent, &children[0]);
+            ready(&parent, &children[0]);
+            // The ready signals are handled by the broadcast itself.
+            assert!(poll!(parent.next()).is_pending());
+
+            assert!(poll!(&mut one).is_ready());
+            assert!(poll!(&mut two).is_pending());
+
+            ready(&parent, &children[1]);
+            assert!(poll!(parent.next()).is_pending());
+            assert!(poll!(&mut two).is_ready());
+
+            // The unregistered children aren't ready anymore.
+            parent.unregister(&children[1].id().clone());
+            wait_for_children(&mut parent, 1).await.unwrap();
+            assert!(poll!(wait_for_children(&mut parent, 2)).is_pending());
+        });
+    }
+
+    #[test]
+    fn recv() {
+        let parent = Broadcast::new_root(Parent::System);
+        let mut child = Broadcast::new(
+            Parent::System,
+            BastionPathElement::Supervisor(BastionId::new()),
+        );
+
+        assert!(child.try_recv().is_none());
+
+        let msg = BastionMessage::start();
+        let env = Envelope::new(msg, parent.path().clone(), parent.sender().clone());
+        child.send_self(env.try_clone().unwrap()).unwrap();
+        child.send_self(env).unwrap();
+
+        match child.try_recv() {
+            Some(Envelope {
+                msg: BastionMessage::Start,
+                ..
+            }) => (),
+            _ => panic!(),
+        }
+
+        executor::block_on(async {
+            match child.recv().await {
+                Some(Envelope {
+                    msg: BastionMessage::Start,
+                    ..
+                }) => (),
+                _ => panic!(),
+            }
+
+            assert!(poll!(child.recv()).is_pending());
+        });
+    }
+
+    #[test]
+    fn publish() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let mut children = register_children(&mut parent, 3);
+
+        parent.subscribe(children[0].id(), "news".to_string());
+        parent.subscribe(children[1].id(), "news".to_string());
+        parent.subscribe(children[1].id(), "weather".to_string());
+
+        let msg = BastionMessage::broadcast("A message containing data.");
+        let env = Envelope::new(msg, parent.path().clone(), parent.sender().clone());
+
+        parent.publish("news", env.try_clone().unwrap());
+        executor::block_on(async {
+            for child in &mut children[..2] {
+                match poll!(child.next()) {
+                    Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Message(_),
+                        ..
+                    })) => (),
+                    _ => panic!(),
+                }
+            }
+
+            assert!(poll!(children[2].next()).is_pending());
+        });
+
+        parent.unsubscribe(children[0].id(), "news");
+        parent.unregister(children[1].id());
+        assert!(parent.subscriptions.is_empty());
+
+        parent.publish("news", env);
+        executor::block_on(async {
+            for child in &mut children {
+                assert!(poll!(child.next()).is_pending());
+            }
+        });
+    }
+
+    #[test]
+    fn send_named() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let mut children = register_children(&mut parent, 3);
+
+        parent.register_name("workers".to_string(), children[0].id());
+        parent.register_name("workers".to_string(), children[1].id());
+        parent.register_name("workers".to_string(), children[1].id());
+
+        let msg = BastionMessage::broadcast("A message containing data.");
+        let env = Envelope::new(msg, parent.path().clone(), parent.sender().clone());
+
+        // The registered children receive the envelopes in turn.
+        for _ in 0..4 {
+            parent
+                .send_named("workers", env.try_clone().unwrap())
+                .unwrap();
+        }
+        executor::block_on(async {
+            for child in &mut children[..2] {
+                for _ in 0..2 {
+                    match poll!(child.next()) {
+                        Poll::Ready(Some(Envelope {
+                            msg: BastionMessage::Message(_),
+                            ..
+                        })) => (),
+                        _ => panic!(),
+                    }
+                }
+                assert!(poll!(child.next()).is_pending());
+            }
+
+            assert!(poll!(children[2].next()).is_pending());
+        });
+
+        // The dead children are skipped and unregistered.
+        drop(children.remove(0));
+        parent
+            .send_named("workers", env.try_clone().unwrap())
+            .unwrap();
+        assert_eq!(parent.names["workers"].len(), 1);
+
+        parent.unregister(children[0].id());
+        assert!(parent.names.is_empty());
+        assert!(parent.send_named("workers", env).is_err());
+    }
+
+    #[test]
+    fn send_weighted() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let children = register_children(&mut parent, 4);
+        for (child, weight) in children.iter().zip(&[5, 1, 1, 0]) {
+            parent.set_child_weight(child.id(), *weight);
+        }
+
+        let mut picked = vec![];
+        for _ in 0..14 {
+            picked.push(parent.next_weighted().unwrap());
+        }
+
+        let counts = children
+            .iter()
+            .map(|child| picked.iter().filter(|id| *id == child.id()).count())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![10, 2, 2, 0]);
+
+        // The heaviest child shouldn't receive all of its
+        // messages in a row.
+        let heaviest = children[0].id();
+        assert!(picked
+            .windows(5)
+            .all(|window| window.iter().any(|id| id != heaviest)));
+    }
+
+    #[test]
+    fn backpressure() {
+        let mut parent = Broadcast::new_root(Parent::System);
+
+        let mut children = register_children(&mut parent, 2);
+
+        let signal = |parent: &mut Broadcast, child: &Broadcast, signal| {
+            let msg = BastionMessage::backpressure(child.id().clone(), signal);
+            let env = Envelope::new(msg, child.path().clone(), child.sender().clone());
+            parent.send_self(env).unwrap();
+            // The signals are handled by the broadcast itself.
+            assert!(parent.try_recv().is_none());
+        };
+        let env = || envelope(BastionMessage::start());
+
+        // The messages are only dispatched to the children which
+        // aren't at capacity...
+        signal(&mut parent, &children[0], BackpressureSignal::AtCapacity);
+        for _ in 0..3 {
+            assert!(parent.send_weighted(env()).is_none());
+        }
+        assert!(parent.send_available(env()).is_ok());
+        assert!(children[0].try_recv().is_none());
+        for _ in 0..4 {
+            assert!(children[1].try_recv().is_some());
+        }
+
+        // ...and returned if all of them are...
+        signal(&mut parent, &children[1], BackpressureSignal::AtCapacity);
+        assert!(parent.send_weighted(env()).is_some());
+        assert!(parent.send_available(env()).is_err());
+
+        // ...until they resume.
+        signal(&mut parent, &children[0], BackpressureSignal::Resume);
+        assert!(parent.send_weighted(env()).is_none());
+        assert!(children[0].try_recv().is_some());
+        assert!(children[1].try_recv().is_none());
+
+        // The control messages are still sent to all the children.
+        parent.send_children(env());
+        assert!(children[0].try_recv().is_some());
+        assert!(children[1].try_recv().is_some());
+    }
+
+    #[test]
+    fn inject_fault() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let parent_id = BastionId::new();
+        let parent_path = BastionPath::root()
+            .append(BastionPathElement::Supervisor(NIL_ID))
+            .unwrap()
+            .append(BastionPathElement::Children(parent_id.clone()))
+            .unwrap();
+        let parent_ref = ChildrenRef::new(
+            parent_id,
+            parent.sender().clone(),
+            Arc::new(parent_path),
+            vec![],
+            vec![],
+            Arc::default(),
+            Arc::default(),
+        );
+
+        let child = Broadcast::new(
+            Parent::children(parent_ref),
+            BastionPathElement::Child(BastionId::new()),
+        );
+        parent.register(&child).unwrap();
+
+        child.inject_fault(child.id());
+        executor::block_on(async {
+            match poll!(parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Faulted { id },
+                    ..
+                })) => assert_eq!(&id, child.id()),
+                _ => panic!(),
+            }
+        });
+    }
+
+    /// Returns `n` new children registered by `parent`.
+    fn register_children(parent: &mut Broadcast, n: usize) -> Vec<Broadcast> {
+        (0..n)
+            .map(|_| {
+                let child = Broadcast::new(
+                    Parent::System,
+                    BastionPathElement::Supervisor(BastionId::new()),
+                );
+                parent.register(&child).unwrap();
+                child
+            })
+            .collect()
+    }
+
+    /// Returns an envelope containing `msg`.
+    fn envelope(msg: BastionMessage) -> Envelope {
+        // need manual construction because SYSTEM is not running in this test
+        let (sender, _) = channel();
+        Envelope::new(msg, Arc::new(BastionPath::root()), sender)
+    }
+
+    fn children_ref(bcast: &Broadcast) -> ChildrenRef {
+        let id = BastionId::new();
+        let path = BastionPath::root()
+            .append(BastionPathElement::Supervisor(NIL_ID))
+            .unwrap()
+            .append(BastionPathElement::Children(id.clone()))
+            .unwrap();
+
+        ChildrenRef::new(
+            id,
+            bcast.sender().clone(),
+            Arc::new(path),
+            vec![],
+            vec![],
+            Arc::default(),
+            Arc::default(),
+        )
+    }
+
+    #[test]
+    fn reparent() {
+        let mut old_parent = Broadcast::new_root(Parent::System);
+        let mut new_parent = Broadcast::new_root(Parent::System);
+
+        let mut child = Broadcast::new(
+            Parent::children(children_ref(&old_parent)),
+            BastionPathElement::Child(BastionId::new()),
+        );
+        old_parent.register(&child).unwrap();
+        let msg = BastionMessage::Message(Msg::tell(42usize));
+        old_parent.send_child(child.id(), envelope(msg)).unwrap();
+
+        let parent = Parent::children(children_ref(&new_parent));
+        assert!(old_parent.reparent(child.id(), &mut new_parent, parent));
+        assert!(!old_parent.reparent(child.id(), &mut new_parent, Parent::System));
+
+        executor::block_on(async {
+            // The child faults before handling the move...
+            child.inject_fault(child.id());
+            assert!(poll!(old_parent.next()).is_pending());
+            match poll!(new_parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Faulted { id },
+                    ..
+                })) => assert_eq!(&id, child.id()),
+                _ => panic!(),
+            }
+
+            // ...still receives the messages sent before the move...
+            match poll!(child.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Message(msg),
+                    ..
+                })) => assert_eq!(msg.try_unwrap::<usize>().unwrap(), 42),
+                _ => panic!(),
+            }
+
+            // ...then handles the move and acknowledges it...
+            match poll!(child.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Reparent { parent, path },
+                    ..
+                })) => child.reparented(*parent, path),
+                _ => panic!(),
+            }
+            assert!(poll!(old_parent.next()).is_pending());
+            assert!(old_parent.reparented.is_empty());
+
+            // ...and now talks to its new parent.
+            child.inject_fault(child.id());
+            match poll!(new_parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Faulted { id },
+                    ..
+                })) => assert_eq!(&id, child.id()),
+                _ => panic!(),
+            }
+
+            let msg = BastionMessage::start();
+            let env = Envelope::new(msg, new_parent.path().clone(), new_parent.sender().clone());
+            new_parent.send_child(child.id(), env).unwrap();
+            match poll!(child.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Start,
+                    ..
+                })) => (),
+                _ => panic!(),
+            }
+        });
+    }
+
+    #[test]
+    fn merge_children() {
+        let mut parent = Broadcast::new_root(Parent::System);
+        let mut other = Broadcast::new_root(Parent::System);
+
+        let id = BastionId::new();
+        let sibling = Broadcast::new(
+            Parent::children(children_ref(&parent)),
+            BastionPathElement::Child(id.clone()),
+        );
+        parent.register(&sibling).unwrap();
+        let mut child = Broadcast::new(
+            Parent::children(children_ref(&other)),
+            BastionPathElement::Child(BastionId::new()),
+        );
+        other.register(&child).unwrap();
+        other.subscribe(child.id(), "topic".to_string());
+
+        // The groups having a child with the same identifier
+        // aren't merged...
+        let mut colliding = Broadcast::new_root(Parent::System);
+        let duplicate = Broadcast::new(
+            Parent::children(children_ref(&colliding)),
+            BastionPathElement::Child(id.clone()),
+        );
+        colliding.register(&duplicate).unwrap();
+        let (err, colliding) = parent
+            .merge_children(colliding, Parent::System)
+            .unwrap_err();
+        assert_eq!(err, BastionError::AlreadyRegistered(id));
+        assert!(colliding.is_registered(duplicate.id()));
+        assert_eq!(parent.children.len(), 1);
+
+        // ...unlike the other ones.
+        let parent_ref = Parent::children(children_ref(&parent));
+        assert!(parent.merge_children(other, parent_ref).is_ok());
+        assert!(parent.is_registered(child.id()));
+        assert!(parent.subscriptions["topic"].contains(child.id()));
+
+        executor::block_on(async {
+            // The child faults before handling the move...
+            child.inject_fault(child.id());
+            match poll!(parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Faulted { id },
+                    ..
+                })) => assert_eq!(&id, child.id()),
+                _ => panic!(),
+            }
+
+            // ...then handles it...
+            match poll!(child.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Reparent { parent, path },
+                    ..
+                })) => child.reparented(*parent, path),
+                _ => panic!(),
+            }
+            assert!(poll!(parent.next()).is_pending());
+
+            // ...and now talks to its new parent.
+            child.inject_fault(child.id());
+            match poll!(parent.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Faulted { id },
+                    ..
+                })) => assert_eq!(&id, child.id()),
+                _ => panic!(),
+            }
+        });
+    }
 }
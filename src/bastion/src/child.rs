@@ -1,22 +1,29 @@
 //!
 //! Child is a element of Children group executing user-defined computation
-use crate::broadcast::Broadcast;
+use crate::backpressure::{Backpressure, BackpressureSignal};
+use crate::broadcast::{Broadcast, Sender};
 use crate::callbacks::{CallbackType, Callbacks};
 use crate::child_ref::ChildRef;
+use crate::children::{AtomicChildrenState, ChildrenState};
 use crate::context::{BastionContext, BastionId, ContextState};
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::dedup::Dedup;
+use crate::envelope::{Envelope, SignedMessage};
+use crate::executor::spawn_with;
+use crate::journal::SharedJournal;
+use crate::message::{BastionMessage, Dead, DeathReason};
 use crate::system::SYSTEM;
+use crate::topology::{TopologyKind, TopologyNode};
 use anyhow::Result as AnyResult;
 use async_mutex::Mutex;
-use bastion_executor::pool;
 use futures::pending;
 use futures::poll;
-use futures::prelude::*;
+use futures::FutureExt;
 use lightproc::prelude::*;
 use lightproc::proc_state::EmptyProcState;
+use std::any::TypeId;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -24,6 +31,8 @@ use tracing::{debug, error, trace, warn};
 
 pub(crate) struct Init(pub(crate) Box<dyn Fn(BastionContext) -> Exec + Send>);
 pub(crate) struct Exec(pub(crate) Pin<Box<dyn Future<Output = Result<(), ()>> + Send>>);
+#[derive(Clone)]
+pub(crate) struct Setup(Arc<dyn Fn() -> Exec + Send + Sync>);
 
 #[derive(Debug)]
 pub(crate) struct Child {
@@ -45,6 +54,34 @@ pub(crate) struct Child {
     // A shortcut for accessing to this actor by others.
     child_ref: ChildRef,
     started: bool,
+    // The state used to drop the duplicate messages, if the
+    // group deduplicates them.
+    dedup: Option<Box<dyn Dedup>>,
+    // The journal of the messages received by the child, if
+    // the group journals them, and whether it should be
+    // replayed once the child starts.
+    journal: Option<SharedJournal>,
+    replay_journal: bool,
+    // The types of the messages of which only the latest one
+    // is kept in the mailbox.
+    coalesced: Vec<TypeId>,
+    // The initializer run before the child starts, if the group
+    // has one, and the state of the group which becomes running
+    // once it completed.
+    setup: Option<Setup>,
+    group_state: Option<Arc<AtomicChildrenState>>,
+    // Whether the child told its group that its mailbox is at
+    // capacity, and the high watermark its mailbox reached then
+    // (the watermarks are kept in the child's state, where its
+    // group can resize them).
+    at_capacity: bool,
+    full_at: usize,
+    #[cfg(feature = "testing")]
+    // The message on which the child will panic, and the
+    // number of messages received so far.
+    panic_on_message: Option<usize>,
+    #[cfg(feature = "testing")]
+    received: usize,
 }
 
 impl Init {
@@ -64,6 +101,20 @@ impl Init {
     }
 }
 
+impl Setup {
+    pub(crate) fn new<S, F>(setup: S) -> Self
+    where
+        S: Fn() -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        Setup(Arc::new(move || Exec(Box::pin(setup()))))
+    }
+
+    fn run(&self) -> Exec {
+        (self.0)()
+    }
+}
+
 impl Child {
     pub(crate) fn new(
         exec: Exec,
@@ -84,9 +135,62 @@ impl Child {
             pre_start_msgs,
             child_ref,
             started,
+            dedup: None,
+            journal: None,
+            replay_journal: false,
+            coalesced: Vec::new(),
+            setup: None,
+            group_state: None,
+            at_capacity: false,
+            full_at: 0,
+            #[cfg(feature = "testing")]
+            panic_on_message: None,
+            #[cfg(feature = "testing")]
+            received: 0,
         }
     }
 
+    pub(crate) fn with_dedup(mut self, dedup: Option<Box<dyn Dedup>>) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub(crate) fn with_journal(mut self, journal: Option<SharedJournal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    pub(crate) fn with_coalesced(mut self, coalesced: Vec<TypeId>) -> Self {
+        self.coalesced = coalesced;
+        self
+    }
+
+    /// Makes the child run `setup`, if any, once it is started
+    /// and before it handles any message, marking `group_state`
+    /// as running once it completed.
+    pub(crate) fn with_setup(
+        mut self,
+        setup: Option<Setup>,
+        group_state: Arc<AtomicChildrenState>,
+    ) -> Self {
+        self.setup = setup;
+        self.group_state = Some(group_state);
+        self
+    }
+
+    /// Makes the child replay its journal once it starts, as
+    /// it is being restarted.
+    pub(crate) fn replaying_journal(mut self) -> Self {
+        self.replay_journal = true;
+        self
+    }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn with_panic_on_message(mut self, n: Option<usize>) -> Self {
+        self.panic_on_message = n;
+        self
+    }
+
     fn stack(&self) -> ProcStack {
         trace!("Child({}): Creating ProcStack.", self.id());
         let id = self.bcast.id().clone();
@@ -109,7 +213,7 @@ impl Child {
             }
 
             let id = id.clone();
-            let msg = BastionMessage::restart_required(id, parent.id().clone());
+            let msg = BastionMessage::panicked(id, parent.id().clone());
             let env = Envelope::new(msg, path.clone(), sender.clone());
             // TODO: handle errors
             parent.send(env).ok();
@@ -140,7 +244,26 @@ impl Child {
         parent.send(env).ok();
     }
 
+    /// Notifies the parent that the child panicked, which is
+    /// then handled depending on the group's panic policy, once
+    /// the panic was caught.
+    fn panicked(&mut self) {
+        warn!("Child({}): Panicked.", self.id());
+        self.remove_from_dispatchers();
+
+        let parent = self.bcast.parent().clone().into_children().unwrap();
+        let path = self.bcast.path().clone();
+        let sender = self.bcast.sender().clone();
+
+        let msg = BastionMessage::panicked(self.id().clone(), parent.id().clone());
+        let env = Envelope::new(msg, path, sender);
+        // TODO: handle errors
+        parent.send(env).ok();
+    }
+
     async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
+        #[cfg(feature = "mailbox-latency")]
+        let enqueued_at = env.enqueued_at;
         match env {
             Envelope {
                 msg: BastionMessage::Start,
@@ -162,6 +285,20 @@ impl Child {
                 self.callbacks.before_restart();
                 return Err(());
             }
+            Envelope {
+                msg: BastionMessage::PoisonPill { ack, handover, .. },
+                ..
+            } => {
+                if let Some(handover) = handover {
+                    self.hand_over(&handover).await;
+                }
+
+                self.stopped();
+                self.callbacks.after_stop();
+                let dead = Dead::new(self.id().clone(), DeathReason::PoisonPilled);
+                ack.send(dead).ok();
+                return Err(());
+            }
             // FIXME
             Envelope {
                 msg: BastionMessage::Deploy(_),
@@ -188,16 +325,68 @@ impl Child {
             Envelope {
                 msg: BastionMessage::Message(msg),
                 sign,
+                ..
             } => {
                 debug!("Child({}): Received a message: {:?}", self.id(), msg);
+                if let Some(dedup) = &mut self.dedup {
+                    if dedup.is_duplicate(&msg) {
+                        debug!(
+                            "Child({}): Dropping a duplicate message: {:?}",
+                            self.id(),
+                            msg
+                        );
+                        return Ok(());
+                    }
+                }
+
+                #[cfg(feature = "testing")]
+                {
+                    self.received += 1;
+                    if self.panic_on_message == Some(self.received) {
+                        panic!(
+                            "Child({}): Injected fault on message #{}.",
+                            self.id(),
+                            self.received
+                        );
+                    }
+                }
+
+                if let Some(journal) = &self.journal {
+                    journal.append(self.bcast.id(), &msg, &sign);
+                }
+
                 let state = self.state.clone();
                 let mut guard = state.lock().await;
+                if !msg.is_ask() && self.coalesced.contains(&msg.payload_type_id()) {
+                    let replaced = guard.remove_pending(msg.payload_type_id());
+                    if replaced > 0 {
+                        trace!(
+                            "Child({}): Replacing {} pending message(s): {:?}",
+                            self.id(),
+                            replaced,
+                            msg
+                        );
+                    }
+                }
+
+                #[cfg(feature = "mailbox-latency")]
+                guard.push_enqueued_message(msg, sign, enqueued_at);
+                #[cfg(not(feature = "mailbox-latency"))]
                 guard.push_message(msg, sign);
+
+                let len = guard.mailbox_len();
+                let backpressure = guard.backpressure();
+                drop(guard);
+                self.check_backpressure(len, backpressure);
             }
             Envelope {
                 msg: BastionMessage::RestartRequired { .. },
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Panicked { .. },
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::RestartSubtree,
                 ..
@@ -231,6 +420,121 @@ impl Child {
                 msg: BastionMessage::Faulted { .. },
                 ..
             } => unimplemented!(),
+            Envelope {
+                msg: BastionMessage::Escalate { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SetChildWeight { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::GracefulRestart { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Topology { ack },
+                ..
+            } => {
+                let name = Some(self.child_ref.name().to_string());
+                let node = TopologyNode::new(self.id(), TopologyKind::Child, name, "Running");
+                ack.send(node).ok();
+            }
+            Envelope {
+                msg: BastionMessage::Ping,
+                sign,
+                ..
+            } => {
+                let msg = BastionMessage::pong(self.id().clone());
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                // The health check might have timed out already.
+                sign.sender().unbounded_send(env).ok();
+            }
+            Envelope {
+                msg: BastionMessage::Pong { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendChild { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::ChildrenOlderThan { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::CancelChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::DrainMailbox { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::AwaitExit { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::WaitForChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Subscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Unsubscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Publish { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::RegisterName { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendNamed { .. },
+                ..
+            } => unreachable!(),
+            // The group sends the payload as a message.
+            Envelope {
+                msg: BastionMessage::Broadcast { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Suspend,
+                ..
+            } => {
+                debug!("Child({}): Suspended.", self.id());
+                self.state.lock().await.set_suspended(true);
+            }
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("Child({}): Resumed.", self.id());
+                self.state.lock().await.set_suspended(false);
+            }
+            Envelope {
+                msg: BastionMessage::Reparent { parent, path },
+                ..
+            } => self.bcast.reparented(*parent, path),
+            // Handled by the broadcast itself.
+            Envelope {
+                msg: BastionMessage::Reparented { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Ready { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Backpressure { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -244,8 +548,28 @@ impl Child {
         );
         debug!("Child({}): Starting.", self.id());
         self.callbacks.before_start();
+
+        // The messages received meanwhile are queued, as the
+        // child isn't started yet.
+        if let Some(setup) = &self.setup {
+            debug!("Child({}): Running the initializer.", self.id());
+            if setup.run().await.is_err() {
+                warn!("Child({}): The initializer returned an error.", self.id());
+                self.faulted();
+                return Err(());
+            }
+
+            if let Some(group_state) = &self.group_state {
+                group_state.set(ChildrenState::Running);
+            }
+        }
+
         self.started = true;
 
+        let msg = BastionMessage::ready(self.id().clone());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent_or_log(env);
+
         let msgs = self.pre_start_msgs.drain(..).collect::<Vec<_>>();
         self.pre_start_msgs.shrink_to_fit();
 
@@ -260,9 +584,74 @@ impl Child {
             }
         }
 
+        if self.replay_journal {
+            self.replay_journal().await;
+        }
+
         Ok(())
     }
 
+    async fn replay_journal(&mut self) {
+        let journal = match &self.journal {
+            Some(journal) => journal,
+            None => return,
+        };
+
+        debug!("Child({}): Replaying the journal.", self.id());
+        let msgs = journal.replay(self.bcast.id());
+
+        // The journal also contains the messages of its type the
+        // child didn't handle before faulting, which are replaced
+        // to keep them in order, while the other ones are left
+        // pending.
+        let state = self.state.clone();
+        let mut guard = state.lock().await;
+        guard.replace_pending(journal.message_type(), msgs);
+    }
+
+    /// Forwards the messages the child received but didn't handle
+    /// yet to the given sender, as it is being replaced.
+    async fn hand_over(&mut self, to: &Sender) {
+        let state = self.state.clone();
+        let mut guard = state.lock().await;
+        while let Some(SignedMessage { msg, sign, .. }) = guard.pop_message() {
+            trace!("Child({}): Handing over message: {:?}", self.id(), msg);
+            let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+            to.unbounded_send(env).ok();
+        }
+    }
+
+    /// Tells the group when the child's mailbox, which holds
+    /// `len` messages, crosses one of the watermarks of
+    /// `backpressure`.
+    fn check_backpressure(&mut self, len: usize, backpressure: Option<Backpressure>) {
+        let backpressure = match backpressure {
+            Some(backpressure) => backpressure,
+            None => return,
+        };
+
+        let high = backpressure.high_watermark();
+        let signal = if !self.at_capacity && len >= high {
+            self.full_at = high;
+            BackpressureSignal::AtCapacity
+        } else if self.at_capacity
+            && (len <= backpressure.low_watermark() || (high > self.full_at && len < high))
+        {
+            // The mailbox is also available again once its group
+            // made room for the waiting messages.
+            BackpressureSignal::Resume
+        } else {
+            return;
+        };
+
+        debug!("Child({}): Signaling backpressure: {:?}", self.id(), signal);
+        self.at_capacity = signal == BackpressureSignal::AtCapacity;
+        self.child_ref.at_capacity().set_full(self.at_capacity);
+        let msg = BastionMessage::backpressure(self.id().clone(), signal);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent_or_log(env);
+    }
+
     fn apply_callback(&mut self, callback_type: CallbackType) {
         match callback_type {
             CallbackType::BeforeStart => self.callbacks.before_start(),
@@ -280,15 +669,19 @@ impl Child {
         };
 
         loop {
-            match poll!(&mut self.bcast.next()) {
+            match poll!(self.bcast.recv()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
                     msg: BastionMessage::Start,
                     ..
                 })) => {
-                    if self.initialize().await.is_err() {
-                        error!("couldn't initialize Child with id: {}", self.id());
-                        return;
+                    match AssertUnwindSafe(self.initialize()).catch_unwind().await {
+                        Ok(Ok(())) => (),
+                        Ok(Err(())) => {
+                            error!("couldn't initialize Child with id: {}", self.id());
+                            return;
+                        }
+                        Err(_) => return self.panicked(),
                     }
 
                     continue;
@@ -309,9 +702,15 @@ impl Child {
                         self.id(),
                         msg
                     );
-                    if self.handle(msg).await.is_err() {
-                        error!("Child({}): Couldn't handle message", self.id());
-                        return;
+                    // The panics are caught before they unwind through
+                    // the child, so that it notifies its parent itself.
+                    match AssertUnwindSafe(self.handle(msg)).catch_unwind().await {
+                        Ok(Ok(())) => (),
+                        Ok(Err(())) => {
+                            error!("Child({}): Couldn't handle message", self.id());
+                            return;
+                        }
+                        Err(_) => return self.panicked(),
                     }
 
                     continue;
@@ -329,28 +728,38 @@ impl Child {
                 continue;
             }
 
-            match poll!(&mut self.exec) {
-                Poll::Ready(Ok(())) => {
+            match poll!(AssertUnwindSafe(&mut self.exec).catch_unwind()) {
+                Poll::Ready(Err(_)) => return self.panicked(),
+                Poll::Ready(Ok(Ok(()))) => {
                     debug!(
                         "Child({}): The future finished executing successfully.",
                         self.id()
                     );
                     return self.stopped();
                 }
-                Poll::Ready(Err(())) => {
+                Poll::Ready(Ok(Err(()))) => {
                     warn!("Child({}): The future returned an error.", self.id());
                     return self.faulted();
                 }
                 Poll::Pending => (),
             }
 
+            // The future might have caught up with its messages.
+            if self.at_capacity {
+                let state = self.state.clone();
+                let guard = state.lock().await;
+                let (len, backpressure) = (guard.mailbox_len(), guard.backpressure());
+                drop(guard);
+                self.check_backpressure(len, backpressure);
+            }
+
             pending!();
         }
     }
 
     pub(crate) fn launch(self) -> RecoverableHandle<()> {
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawn_with(self.run(), stack)
     }
 
     /// Adds the actor into each registry declared in the parent node.
@@ -399,6 +808,12 @@ impl Debug for Init {
     }
 }
 
+impl Debug for Setup {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Setup").finish()
+    }
+}
+
 impl Debug for Exec {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.debug_struct("Exec").finish()
@@ -1,16 +1,29 @@
 //!
 //! Allows users to communicate with Child through the mailboxes.
+use crate::backpressure::Capacity;
 use crate::broadcast::Sender;
 use crate::context::BastionId;
+use crate::delivery::DeliveryMode;
 use crate::envelope::{Envelope, RefAddr};
 use crate::message::{Answer, BastionMessage, Message};
 use crate::path::BastionPath;
+use crate::sink::MessageSink;
+use futures::future::{self, Either};
+use futures_timer::Delay;
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace};
 
+// How long sending a message at least once waits at first before
+// trying again to enqueue it in a full mailbox, if the mailbox
+// doesn't have room again before...
+const MIN_BACKOFF: Duration = Duration::from_millis(1);
+// ...and at most, the wait doubling after each attempt.
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Clone)]
 /// A "reference" to an element of a children group, allowing to
 /// communicate with it.
@@ -19,6 +32,9 @@ pub struct ChildRef {
     sender: Sender,
     name: String,
     path: Arc<BastionPath>,
+    // Whether the child told its group that its mailbox is at
+    // capacity, shared by all the references to the child.
+    at_capacity: Arc<Capacity>,
 }
 
 impl ChildRef {
@@ -33,9 +49,28 @@ impl ChildRef {
             sender,
             name,
             path,
+            at_capacity: Arc::default(),
         }
     }
 
+    pub(crate) fn with_at_capacity(mut self, at_capacity: Arc<Capacity>) -> Self {
+        self.at_capacity = at_capacity;
+        self
+    }
+
+    pub(crate) fn at_capacity(&self) -> &Arc<Capacity> {
+        &self.at_capacity
+    }
+
+    /// Returns whether the mailbox of the child this `ChildRef` is
+    /// referencing is at capacity, in which case its group stops
+    /// dispatching messages to it (see [`Backpressure`]).
+    ///
+    /// [`Backpressure`]: ../backpressure/struct.Backpressure.html
+    pub fn is_at_capacity(&self) -> bool {
+        self.at_capacity.is_full()
+    }
+
     /// Returns the identifier of the children group element this
     /// `ChildRef` is referencing.
     ///
@@ -126,6 +161,58 @@ impl ChildRef {
         self.send(env).map_err(|env| env.into_msg().unwrap())
     }
 
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// like [`tell_anonymously`], with the given delivery guarantee.
+    ///
+    /// The returned future resolves to `()` once the message was
+    /// enqueued in the child's mailbox, or to `Err(msg)` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `mode` - How hard enqueuing the message is tried while
+    ///     the child's mailbox is full.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # let child_ref = &children_ref.elems()[0];
+    /// # run!(async {
+    /// // The message is dropped if the child can't keep up...
+    /// if child_ref
+    ///     .tell_anonymously_with("A sample.", DeliveryMode::BestEffort)
+    ///     .await
+    ///     .is_err()
+    /// {
+    ///     // ...
+    /// }
+    /// # });
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    pub async fn tell_anonymously_with<M: Message>(
+        &self,
+        msg: M,
+        mode: DeliveryMode,
+    ) -> Result<(), M> {
+        debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send_with(env, mode)
+            .await
+            .map_err(|env| env.into_msg().unwrap())
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing,
     /// allowing it to answer.
     /// This message is intended to be used outside of Bastion context when
@@ -214,6 +301,37 @@ impl ChildRef {
         Ok(answer)
     }
 
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// like [`ask_anonymously`], with the given delivery guarantee.
+    ///
+    /// The returned future resolves to [`Answer`] once the message
+    /// was enqueued in the child's mailbox, or to `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `mode` - How hard enqueuing the message is tried while
+    ///     the child's mailbox is full.
+    ///
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`Answer`]: ../message/struct.Answer.html
+    pub async fn ask_anonymously_with<M: Message>(
+        &self,
+        msg: M,
+        mode: DeliveryMode,
+    ) -> Result<Answer, M> {
+        debug!("ChildRef({}): Asking message: {:?}", self.id(), msg);
+        let (msg, answer) = BastionMessage::ask(msg);
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send_with(env, mode)
+            .await
+            .map_err(|env| env.into_msg().unwrap())?;
+
+        Ok(answer)
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to stop its execution.
     ///
@@ -270,6 +388,31 @@ impl ChildRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Returns a [`MessageSink`] sending the items it is given as
+    /// messages to the child this `ChildRef` is referencing,
+    /// waiting while the child is at capacity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # let child_ref = &children_ref.elems()[0];
+    /// let sink: MessageSink<u32> = child_ref.sink();
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`MessageSink`]: ../sink/struct.MessageSink.html
+    pub fn sink<M: Message>(&self) -> MessageSink<M> {
+        MessageSink::child(self.clone())
+    }
+
     /// Returns [`RefAddr`] for the child
     pub fn addr(&self) -> RefAddr {
         RefAddr::new(self.path.clone(), self.sender.clone())
@@ -282,6 +425,36 @@ impl ChildRef {
             .map_err(|err| err.into_inner())
     }
 
+    async fn send_with(&self, env: Envelope, mode: DeliveryMode) -> Result<(), Envelope> {
+        match mode {
+            DeliveryMode::AtMostOnce => (),
+            DeliveryMode::AtLeastOnce { max_retries } => {
+                let mut retries = 0;
+                let mut backoff = MIN_BACKOFF;
+                while self.is_at_capacity() {
+                    if retries == max_retries {
+                        debug!("ChildRef({}): Mailbox still full, giving up.", self.id());
+                        return Err(env);
+                    }
+
+                    retries += 1;
+                    let room = future::poll_fn(|cx| self.at_capacity.poll_room(cx));
+                    if let Either::Right(_) = future::select(room, Delay::new(backoff)).await {
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            }
+            DeliveryMode::BestEffort => {
+                if self.is_at_capacity() {
+                    debug!("ChildRef({}): Mailbox full, dropping message.", self.id());
+                    return Err(env);
+                }
+            }
+        }
+
+        self.send(env)
+    }
+
     pub(crate) fn sender(&self) -> &Sender {
         &self.sender
     }
@@ -1,30 +1,48 @@
 //!
 //! Children are a group of child supervised under a supervisor
+use crate::autoscale::{AutoscalePolicy, Autoscaler, LoadSample};
+use crate::backpressure::{Backpressure, Capacity, MailboxResizer, Resizer};
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::callbacks::{CallbackType, Callbacks};
-use crate::child::{Child, Init};
+use crate::child::{Child, Init, Setup};
 use crate::child_ref::ChildRef;
 use crate::children_ref::ChildrenRef;
+use crate::circuit_breaker::{AtomicBreakerState, Breaker, CircuitBreaker};
+use crate::clock::{self, Sleep};
 use crate::context::{BastionContext, BastionId, ContextState};
+use crate::dedup::DedupFactory;
 use crate::dispatcher::Dispatcher;
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
-use crate::path::BastionPathElement;
+use crate::envelope::{Envelope, SignedMessage};
+use crate::executor::spawn_with;
+use crate::handlers::Handlers;
+use crate::journal::{Journal, SharedJournal};
+use crate::message::{BastionMessage, Dead, DeathNotice, DeathReason, Message, Msg};
+use crate::middleware::{Middleware, MiddlewareAction};
+use crate::path::{BastionPath, BastionPathElement};
+use crate::restart_history::{RestartHistory, SharedRestarts};
+use crate::scope::LiveElems;
+use crate::supervisor::ROOT_NAMED_PATH;
 use crate::system::SYSTEM;
+use crate::topology::{TopologyKind, TopologyNode};
 use anyhow::Result as AnyResult;
 use async_mutex::Mutex;
-use bastion_executor::pool;
+use bastion_executor::load_balancer::{self, SmpStats};
+use futures::channel::oneshot;
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use fxhash::FxHashMap;
 use lightproc::prelude::*;
+use std::any::TypeId;
 use std::fmt::Debug;
 use std::future::Future;
+use std::hash::Hash;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Arc;
 use std::task::Poll;
+use std::time::{Duration, Instant};
 use tracing::{debug, trace, warn};
 
 #[derive(Debug)]
@@ -73,11 +91,21 @@ use tracing::{debug, trace, warn};
 /// [`SupervisionStrategy`]: supervisor/enum.SupervisionStrategy.html
 pub struct Children {
     bcast: Broadcast,
-    // The currently launched elements of the group.
-    launched: FxHashMap<BastionId, (Sender, RecoverableHandle<()>)>,
+    // The currently launched elements of the group, along with
+    // whether their mailbox is at capacity.
+    launched: FxHashMap<BastionId, (Sender, RecoverableHandle<()>, Arc<Capacity>)>,
     // The closure returning the future that will be used by
     // every element of the group.
     init: Init,
+    // The closures used instead by the elements which replaced
+    // others when gracefully restarted.
+    inits: FxHashMap<BastionId, Init>,
+    // The elements gracefully restarting, waiting for the element
+    // replacing them to start before being poison pilled.
+    replacing: Vec<Replacement>,
+    // The typed handlers the elements run on the messages they
+    // receive, if any were registered.
+    handlers: Handlers,
     redundancy: usize,
     // The callbacks called at the group's different lifecycle
     // events.
@@ -91,6 +119,64 @@ pub struct Children {
     dispatchers: Vec<Arc<Box<Dispatcher>>>,
     // The name of children
     name: Option<String>,
+    // The path made of the names of the group's parents.
+    parent_path: String,
+    // The lifecycle state of the group, shared with its references.
+    state: Arc<AtomicChildrenState>,
+    // How the messages sent to the group are dispatched to
+    // its elements.
+    dispatch_mode: DispatchMode,
+    // How long to wait for an element to acknowledge a poison
+    // pill before cancelling it.
+    poison_pill_timeout: Duration,
+    // The elements that were poison pilled and whose death
+    // wasn't acknowledged yet.
+    pills: Vec<PendingPill>,
+    // The circuit breaker of the group, if any.
+    breaker: Option<Breaker>,
+    breaker_state: Arc<AtomicBreakerState>,
+    // Creates the state used by each element to drop the
+    // duplicate messages, if any.
+    dedup: Option<DedupFactory>,
+    // The journal of the messages received by the elements,
+    // replayed when they are restarted.
+    journal: Option<SharedJournal>,
+    // What the supervisor does when an element panics.
+    panic_policy: PanicPolicy,
+    // Grows or shrinks the group depending on its load, if
+    // enabled.
+    autoscaler: Option<Autoscaler>,
+    // The state of each element, holding its mailbox.
+    mailboxes: FxHashMap<BastionId, Arc<Mutex<Pin<Box<ContextState>>>>>,
+    // When each element was launched.
+    started_at: FxHashMap<BastionId, Instant>,
+    // The weights set for the elements, which are set again once
+    // they restarted as their group forgets them if they died.
+    weights: FxHashMap<BastionId, usize>,
+    // The restarts of the elements, shared with the group's
+    // references.
+    restarts: Arc<SharedRestarts>,
+    // The types of the messages of which each element only
+    // keeps the latest one in its mailbox.
+    coalesced: Vec<TypeId>,
+    // The initializer run by each element before it handles
+    // any message, if any.
+    setup: Option<Setup>,
+    // The watermarks of the elements' mailboxes, if the group
+    // sheds its load.
+    backpressure: Option<Backpressure>,
+    // Resizes the capacity of the elements' mailboxes depending
+    // on their depth, if enabled.
+    resizer: Option<Box<MailboxResizer>>,
+    // How many elements the group runs, shared with its
+    // references.
+    live: Arc<LiveElems>,
+    // Whether the elements stopped receiving their messages
+    // because the subtree of the group was suspended.
+    suspended: bool,
+    #[cfg(feature = "testing")]
+    // The message on which the elements of the group will panic.
+    panic_on_message: Option<usize>,
 }
 
 impl Children {
@@ -98,23 +184,73 @@ impl Children {
         debug!("Children({}): Initializing.", bcast.id());
         let launched = FxHashMap::default();
         let init = Init::default();
+        let inits = FxHashMap::default();
+        let replacing = Vec::new();
+        let handlers = Handlers::default();
         let redundancy = 1;
         let callbacks = Callbacks::new();
         let pre_start_msgs = Vec::new();
         let started = false;
         let dispatchers = Vec::new();
         let name = None;
+        let parent_path = ROOT_NAMED_PATH.to_string();
+        let state = Arc::default();
+        let dispatch_mode = DispatchMode::default();
+        let poison_pill_timeout = Duration::from_secs(5);
+        let pills = Vec::new();
+        let breaker = None;
+        let breaker_state = Arc::default();
+        let dedup = None;
+        let journal = None;
+        let panic_policy = PanicPolicy::default();
+        let autoscaler = None;
+        let mailboxes = FxHashMap::default();
+        let started_at = FxHashMap::default();
+        let weights = FxHashMap::default();
+        let restarts = Arc::default();
+        let coalesced = Vec::new();
+        let setup = None;
+        let backpressure = None;
+        let resizer = None;
+        let live = Arc::default();
+        let suspended = false;
 
         Children {
             bcast,
             launched,
             init,
+            inits,
+            replacing,
+            handlers,
             redundancy,
             callbacks,
             pre_start_msgs,
             started,
             dispatchers,
             name,
+            parent_path,
+            state,
+            dispatch_mode,
+            poison_pill_timeout,
+            pills,
+            breaker,
+            breaker_state,
+            dedup,
+            journal,
+            panic_policy,
+            autoscaler,
+            mailboxes,
+            started_at,
+            weights,
+            restarts,
+            coalesced,
+            setup,
+            backpressure,
+            resizer,
+            live,
+            suspended,
+            #[cfg(feature = "testing")]
+            panic_on_message: None,
         }
     }
 
@@ -150,6 +286,10 @@ impl Children {
         self.bcast.id()
     }
 
+    pub(crate) fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy
+    }
+
     pub(crate) fn bcast(&self) -> &Broadcast {
         &self.bcast
     }
@@ -166,6 +306,18 @@ impl Children {
         }
     }
 
+    pub(crate) fn with_parent_path(mut self, parent_path: impl Into<String>) -> Self {
+        self.parent_path = parent_path.into();
+        self
+    }
+
+    pub(crate) fn named_path(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}/{}", self.parent_path, name),
+            None => format!("{}/{}", self.parent_path, self.id()),
+        }
+    }
+
     pub(crate) fn as_ref(&self) -> ChildrenRef {
         trace!(
             "Children({}): Creating new ChildrenRef({}).",
@@ -178,10 +330,14 @@ impl Children {
         let path = self.bcast.path().clone();
 
         let mut children = Vec::with_capacity(self.launched.len());
-        for (id, (sender, _)) in &self.launched {
+        for (id, (sender, _, at_capacity)) in &self.launched {
             trace!("Children({}): Creating new ChildRef({}).", self.id(), id);
             // TODO: clone or ref?
-            let child = ChildRef::new(id.clone(), sender.clone(), self.name(), path.clone());
+            let path = BastionPath::clone(&path)
+                .append(BastionPathElement::Child(id.clone()))
+                .unwrap();
+            let child = ChildRef::new(id.clone(), sender.clone(), self.name(), Arc::new(path))
+                .with_at_capacity(at_capacity.clone());
             children.push(child);
         }
 
@@ -191,15 +347,79 @@ impl Children {
             .map(|dispatcher| dispatcher.dispatcher_type())
             .collect();
 
-        ChildrenRef::new(id, sender, path, children, dispatchers)
+        let state = self.state.clone();
+        let breaker_state = self.breaker_state.clone();
+        let restarts = self.restarts.clone();
+        let live = self.live.clone();
+
+        ChildrenRef::new(
+            id,
+            sender,
+            path,
+            children,
+            dispatchers,
+            state,
+            breaker_state,
+        )
+        .with_restarts(restarts)
+        .with_live_elems(live)
+        .with_named_path(self.named_path())
     }
 
-    /// Sets the name of this children group.
+    /// Sets the name of this children group, which is also used
+    /// to build its human-readable path from the names of its
+    /// parents (e.g. `/root/supervisorA/workers`, see
+    /// [`ChildrenRef::named_path`]). An anonymous group appears
+    /// with its identifier in its path.
+    ///
+    /// [`ChildrenRef::named_path`]: children_ref/struct.ChildrenRef.html#method.named_path
     pub fn with_name(mut self, name: impl Into<String>) -> Self {
         self.name = Some(name.into());
         self
     }
 
+    #[cfg(feature = "testing")]
+    /// Makes every element of this children group panic when it
+    /// receives its `n`th message (starting from `1`), as if the
+    /// future itself panicked.
+    ///
+    /// The counter is reset when an element is restarted, so it
+    /// will panic again on the `n`th message it receives.
+    ///
+    /// This is only available with the `testing` feature and is
+    /// meant to deterministically trigger the supervision logic.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The message on which the elements will panic.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_panic_on_message(3)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn with_panic_on_message(mut self, n: usize) -> Self {
+        trace!("Children({}): Panicking on message #{}.", self.id(), n);
+        self.panic_on_message = Some(n);
+        self
+    }
+
     /// Sets the closure taking a [`BastionContext`] and returning a
     /// [`Future`] that will be used by every element of this children
     /// group.
@@ -248,6 +468,237 @@ impl Children {
     {
         trace!("Children({}): Setting exec closure.", self.id());
         self.init = Init::new(init);
+        self.handlers = Handlers::default();
+        self
+    }
+
+    /// Sets an asynchronous initializer that every element of
+    /// this children group runs once it is started, before it
+    /// handles any message or its future is polled, e.g. to open
+    /// a connection or load a cache.
+    ///
+    /// The messages sent to an element while it is initializing
+    /// are queued and handled once it is done, and the group's
+    /// state (see [`ChildrenRef::state`]) only becomes
+    /// [`ChildrenState::Running`] once one of its elements was
+    /// initialized.
+    ///
+    /// If the initializer returns `Err(())`, the element faults
+    /// and the supervisor restarts it depending on its
+    /// [`RestartStrategy`] (and backoff), after which the new
+    /// element runs the initializer again.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure returning the [`Future`] that each
+    ///     element runs before starting, whose output should be
+    ///     `Result<(), ()>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let connected = Arc::new(AtomicBool::new(false));
+    /// let connected_ = connected.clone();
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_init(move || {
+    ///             let connected = connected_.clone();
+    ///             async move {
+    ///                 // Open a connection...
+    ///                 connected.store(true, Ordering::SeqCst);
+    ///                 Ok(())
+    ///             }
+    ///         })
+    ///         .with_exec(move |ctx| {
+    ///             let connected = connected.clone();
+    ///             async move {
+    ///                 // ...which is open before any message is handled.
+    ///                 assert!(connected.load(Ordering::SeqCst));
+    ///                 let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildrenRef::state`]: children_ref/struct.ChildrenRef.html#method.state
+    /// [`ChildrenState::Running`]: children/enum.ChildrenState.html#variant.Running
+    /// [`RestartStrategy`]: supervisor/struct.RestartStrategy.html
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    pub fn with_init<I, F>(mut self, init: I) -> Self
+    where
+        I: Fn() -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!("Children({}): Setting initializer.", self.id());
+        self.setup = Some(Setup::new(init));
+        self
+    }
+
+    /// Registers a handler that every element of this children
+    /// group will run on the messages of type `T` it receives,
+    /// instead of matching on them in a closure set with
+    /// [`with_exec`].
+    ///
+    /// The elements receive their messages in a loop, and run the
+    /// first registered handler whose type matches the message's
+    /// type. The messages matching no handler are passed to the
+    /// handler set with [`on_unmatched`] if any, or dropped (see
+    /// [`with_unmatched_to_dead_letters`]).
+    ///
+    /// Broadcasted messages are only cloned when they can't be
+    /// taken from the other elements, and the handlers can't answer
+    /// the messages sent with `ask`. If a handler returns `Err(())`,
+    /// the element stops as if its [`with_exec`] future did.
+    ///
+    /// Note that registering a handler replaces the closure set with
+    /// [`with_exec`], and that calling it afterwards removes the
+    /// handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure taking a message of type `T` and
+    ///     the element's [`BastionContext`] and returning a
+    ///     [`Future`] handling the message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug, Clone)]
+    /// struct Deposit(u64);
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .on(|deposit: Deposit, _ctx| async move {
+    ///             println!("deposit: {}", deposit.0);
+    ///             Ok(())
+    ///         })
+    ///         .on(|msg: &'static str, ctx| async move {
+    ///             println!("{} received: {}", ctx.current().id(), msg);
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`with_exec`]: #method.with_exec
+    /// [`on_unmatched`]: #method.on_unmatched
+    /// [`with_unmatched_to_dead_letters`]: #method.with_unmatched_to_dead_letters
+    pub fn on<T, H, F>(mut self, handler: H) -> Self
+    where
+        T: Message + Clone,
+        H: Fn(T, Arc<BastionContext>) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!(
+            "Children({}): Adding a handler for: {}",
+            self.id(),
+            std::any::type_name::<T>()
+        );
+        self.handlers.on(handler);
+        self.init = self.handlers.init();
+        self
+    }
+
+    /// Sets the handler that every element of this children group
+    /// will run on the messages matching none of the handlers
+    /// registered with [`on`].
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure taking the unmatched message and
+    ///     the element's [`BastionContext`] and returning a
+    ///     [`Future`] handling the message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .on(|msg: &'static str, _ctx| async move {
+    ///             println!("received: {}", msg);
+    ///             Ok(())
+    ///         })
+    ///         .on_unmatched(|msg: SignedMessage, _ctx| async move {
+    ///             println!("unexpected message: {}", msg.type_tag());
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`on`]: #method.on
+    pub fn on_unmatched<H, F>(mut self, handler: H) -> Self
+    where
+        H: Fn(SignedMessage, Arc<BastionContext>) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!("Children({}): Setting the unmatched handler.", self.id());
+        self.handlers.on_unmatched(handler);
+        self.init = self.handlers.init();
+        self
+    }
+
+    /// Makes every element of this children group send the
+    /// messages matching none of the handlers registered with
+    /// [`on`] to the dead letters instead of dropping them, unless
+    /// a handler was set with [`on_unmatched`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .on(|msg: &'static str, _ctx| async move {
+    ///             println!("received: {}", msg);
+    ///             Ok(())
+    ///         })
+    ///         .with_unmatched_to_dead_letters()
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`on`]: #method.on
+    /// [`on_unmatched`]: #method.on_unmatched
+    pub fn with_unmatched_to_dead_letters(mut self) -> Self {
+        trace!(
+            "Children({}): Sending unmatched messages to the dead letters.",
+            self.id()
+        );
+        self.handlers.unmatched_to_dead_letters();
+        self.init = self.handlers.init();
         self
     }
 
@@ -328,39 +779,26 @@ impl Children {
         self
     }
 
-    /// Sets the callbacks that will get called at this children group's
-    /// different lifecycle events.
+    /// Sets how long this children group waits for one of its
+    /// elements to acknowledge a poison pill (sent using
+    /// [`ChildrenRef::poison_pill_child`]) before cancelling it.
     ///
-    /// See [`Callbacks`]'s documentation for more information about the
-    /// different callbacks available.
+    /// The default timeout is 5 seconds.
     ///
     /// # Arguments
     ///
-    /// * `callbacks` - The callbacks that will get called for this
-    ///     children group.
+    /// * `timeout` - How long to wait for the acknowledgement.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
     /// #
     /// # Bastion::init();
     /// #
     /// Bastion::children(|children| {
-    ///     let callbacks = Callbacks::new()
-    ///         .with_before_start(|| println!("Children group started."))
-    ///         .with_after_stop(|| println!("Children group stopped."));
-    ///
-    ///     children
-    ///         .with_callbacks(callbacks)
-    ///         .with_exec(|ctx| {
-    ///             // -- Children group started.
-    ///             async move {
-    ///                 // ...
-    ///                 # Ok(())
-    ///             }
-    ///             // -- Children group stopped.
-    ///         })
+    ///     children.with_poison_pill_timeout(Duration::from_millis(500))
     /// }).expect("Couldn't create the children group.");
     /// #
     /// # Bastion::start();
@@ -368,38 +806,705 @@ impl Children {
     /// # Bastion::block_until_stopped();
     /// ```
     ///
-    /// [`Callbacks`]: struct.Callbacks.html
-    pub fn with_callbacks(mut self, callbacks: Callbacks) -> Self {
+    /// [`ChildrenRef::poison_pill_child`]: children/struct.ChildrenRef.html#method.poison_pill_child
+    pub fn with_poison_pill_timeout(mut self, timeout: Duration) -> Self {
         trace!(
-            "Children({}): Setting callbacks: {:?}",
+            "Children({}): Setting poison pill timeout: {:?}",
             self.id(),
-            callbacks
+            timeout
         );
-        self.callbacks = callbacks;
+        self.poison_pill_timeout = timeout;
         self
     }
 
-    async fn kill(&mut self) {
-        debug!("Children({}): Killing.", self.id());
-        self.bcast.kill_children();
-
-        let mut children = FuturesOrdered::new();
-        for (_, (_, launched)) in self.launched.drain() {
-            launched.cancel();
-
-            children.push(launched);
-        }
+    /// Sets what the supervisor of this children group does when
+    /// one of its elements panics.
+    ///
+    /// The default policy is [`PanicPolicy::Restart`]. Note that
+    /// this policy only applies to panics: an element whose future
+    /// returned an error is always handled by the supervisor's
+    /// strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy to apply:
+    ///     - [`PanicPolicy::Restart`] would let the supervisor
+    ///         restart the element using its strategy.
+    ///     - [`PanicPolicy::Escalate`] would remove the element
+    ///         from the group and make the supervisor escalate the
+    ///         fault to its own parent right away.
+    ///     - [`PanicPolicy::Stop`] would remove the element from
+    ///         the group without restarting it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.on_panic(PanicPolicy::Escalate)
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`PanicPolicy::Restart`]: children/enum.PanicPolicy.html#variant.Restart
+    /// [`PanicPolicy::Escalate`]: children/enum.PanicPolicy.html#variant.Escalate
+    /// [`PanicPolicy::Stop`]: children/enum.PanicPolicy.html#variant.Stop
+    pub fn on_panic(mut self, policy: PanicPolicy) -> Self {
+        trace!(
+            "Children({}): Setting panic policy: {:?}",
+            self.id(),
+            policy
+        );
+        self.panic_policy = policy;
+        self
+    }
 
-        let id = self.id();
-        children
-            .for_each_concurrent(None, |_| async {
-                trace!("Children({}): Unknown child stopped.", id);
-            })
-            .await;
+    /// Makes this children group grow or shrink depending on its
+    /// load, between `min` and `max` elements.
+    ///
+    /// The load of the group is sampled periodically (see
+    /// [`AutoscalePolicy::interval`]) and given to `policy`, which
+    /// returns how many elements the group should have. New
+    /// elements are then launched, or the elements with the fewest
+    /// pending messages are retired. A retired element hands the
+    /// messages it didn't handle yet over to one of the remaining
+    /// elements before stopping, so that none of them is lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum number of elements of the group (at
+    ///     least one).
+    /// * `max` - The maximum number of elements of the group.
+    /// * `policy` - The [`AutoscalePolicy`] deciding how many
+    ///     elements the group should have.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     // Aims for at most ten waiting messages per element.
+    ///     children.with_autoscale(1, 8, |sample: &LoadSample| (sample.mailbox_depth + 9) / 10)
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`AutoscalePolicy`]: autoscale/trait.AutoscalePolicy.html
+    /// [`AutoscalePolicy::interval`]: autoscale/trait.AutoscalePolicy.html#method.interval
+    pub fn with_autoscale<P: AutoscalePolicy>(mut self, min: usize, max: usize, policy: P) -> Self {
+        trace!(
+            "Children({}): Setting autoscale bounds: {}..={}",
+            self.id(),
+            min,
+            max
+        );
+        self.autoscaler = Some(Autoscaler::new(min, max, policy));
+        self
+    }
+
+    /// Makes the elements of this children group tell it when
+    /// they can't keep up with the messages dispatched to them,
+    /// so that it stops dispatching messages to them until they
+    /// caught up.
+    ///
+    /// An element is at capacity once `backpressure`'s high
+    /// watermark of messages are waiting in its mailbox, until
+    /// only its low watermark of messages or fewer are. The
+    /// messages sent to the group are dispatched to the other
+    /// elements meanwhile, or routed to the dead letters if all
+    /// of them are at capacity (see [`Backpressure`] for more
+    /// information).
+    ///
+    /// # Arguments
+    ///
+    /// * `backpressure` - The watermarks of the mailboxes of the
+    ///     elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(2)
+    ///         .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+    ///         .with_backpressure(Backpressure::new(50, 5))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Backpressure`]: backpressure/struct.Backpressure.html
+    pub fn with_backpressure(mut self, backpressure: Backpressure) -> Self {
+        trace!(
+            "Children({}): Setting backpressure: {:?}",
+            self.id(),
+            backpressure
+        );
+        self.backpressure = Some(backpressure);
+        self
+    }
+
+    /// Makes this children group resize the capacity of the
+    /// mailbox of each of its elements depending on how full it
+    /// stays, within the bounds of `resizer`.
+    ///
+    /// The capacity of a mailbox is the high watermark of the
+    /// group's [`Backpressure`], which is clamped within the
+    /// bounds. If the group has no backpressure, its elements
+    /// start with the minimum capacity and a low watermark of
+    /// zero. Resizing a mailbox never drops the messages waiting
+    /// in it (see [`Resizer`] for more information).
+    ///
+    /// # Arguments
+    ///
+    /// * `resizer` - The bounds of the capacities and how often
+    ///     the mailboxes are sampled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(2)
+    ///         .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+    ///         .with_backpressure(Backpressure::new(50, 5))
+    ///         .with_resizer(Resizer::new(10, 500))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Backpressure`]: backpressure/struct.Backpressure.html
+    /// [`Resizer`]: backpressure/struct.Resizer.html
+    pub fn with_resizer(mut self, resizer: Resizer) -> Self {
+        trace!("Children({}): Setting resizer: {:?}", self.id(), resizer);
+        self.resizer = Some(Box::new(MailboxResizer::new(resizer)));
+        self
+    }
+
+    /// Protects this children group with a [`CircuitBreaker`].
+    ///
+    /// Once the elements of the group faulted too many times, they
+    /// aren't restarted anymore and the messages sent to the group
+    /// are routed to the dead letters until the breaker's cooldown
+    /// elapsed and a restarted element ran without faulting (see
+    /// [`CircuitBreaker`] for more information). The current state
+    /// of the breaker can be retrieved using
+    /// [`ChildrenRef::breaker_state`].
+    ///
+    /// # Arguments
+    ///
+    /// * `breaker` - The configuration of the circuit breaker.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_circuit_breaker(CircuitBreaker::new(5, Duration::from_secs(30)))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`CircuitBreaker`]: circuit_breaker/struct.CircuitBreaker.html
+    /// [`ChildrenRef::breaker_state`]: children/struct.ChildrenRef.html#method.breaker_state
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        trace!(
+            "Children({}): Setting circuit breaker: {:?}",
+            self.id(),
+            breaker
+        );
+        self.breaker = Some(Breaker::new(breaker, self.breaker_state.clone()));
+        self
+    }
+
+    /// Sets how this children group keeps track of the restarts of
+    /// its elements, which can be retrieved using
+    /// [`ChildrenRef::restart_count`], [`ChildrenRef::last_restart`]
+    /// and [`ChildrenRef::restart_history`].
+    ///
+    /// By default, the group counts the restarts over its whole
+    /// lifetime and remembers the times of the last
+    /// [`DEFAULT_HISTORY_CAPACITY`] ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The configuration of the restart history.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     // Only counts the restarts of the last minute...
+    ///     children
+    ///         .with_restart_history(RestartHistory::default().with_window(Duration::from_secs(60)))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildrenRef::restart_count`]: children/struct.ChildrenRef.html#method.restart_count
+    /// [`ChildrenRef::last_restart`]: children/struct.ChildrenRef.html#method.last_restart
+    /// [`ChildrenRef::restart_history`]: children/struct.ChildrenRef.html#method.restart_history
+    /// [`DEFAULT_HISTORY_CAPACITY`]: restart_history/constant.DEFAULT_HISTORY_CAPACITY.html
+    pub fn with_restart_history(self, history: RestartHistory) -> Self {
+        trace!(
+            "Children({}): Setting restart history: {:?}",
+            self.id(),
+            history
+        );
+        self.restarts.configure(history);
+        self
+    }
+
+    /// Returns how many times the elements of this children group
+    /// were restarted (see [`ChildrenRef::restart_count`]).
+    ///
+    /// [`ChildrenRef::restart_count`]: children/struct.ChildrenRef.html#method.restart_count
+    pub fn restart_count(&self) -> usize {
+        self.restarts.count()
+    }
+
+    /// Returns when an element of this children group was last
+    /// restarted, if it ever was.
+    pub fn last_restart(&self) -> Option<Instant> {
+        self.restarts.last()
+    }
+
+    /// Makes every element of this children group drop the
+    /// messages of type `M` whose key (as returned by `key`) is
+    /// the same as the one of a message it received less than
+    /// `window` ago. This prevents retried commands from being
+    /// processed twice.
+    ///
+    /// Each element remembers at most 1024 keys, so the oldest
+    /// ones are forgotten early if it receives more messages than
+    /// that within the window. The messages of other types are
+    /// never dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The closure returning the key identifying a
+    ///     message.
+    /// * `window` - How long a key is remembered for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug)]
+    /// struct Command {
+    ///     id: u64,
+    ///     // ...
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .dedup_by(|cmd: &Command| cmd.id, Duration::from_secs(10))
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 // A `Command` with the same `id` won't be
+    ///                 // received twice within 10 seconds...
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn dedup_by<M, K, F>(mut self, key: F, window: Duration) -> Self
+    where
+        M: Message,
+        K: Hash + Eq + Clone + Send + 'static,
+        F: Fn(&M) -> K + Send + Sync + 'static,
+    {
+        trace!(
+            "Children({}): Deduplicating messages within: {:?}",
+            self.id(),
+            window
+        );
+        self.dedup = Some(DedupFactory::new(key, window));
+        self
+    }
+
+    /// Makes every element of this children group only keep the
+    /// latest message of type `M` in its mailbox: when a message
+    /// of this type is received while others are still waiting to
+    /// be handled, they are dropped and only the new one is.
+    ///
+    /// This suits the messages carrying a snapshot of some state,
+    /// of which a slow element only needs the latest one, and
+    /// prevents its mailbox from growing without bounds when they
+    /// are sent faster than it handles them. The questions (see
+    /// [`ChildRef::ask_anonymously`]) are never dropped, since
+    /// their senders wait for an answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug)]
+    /// struct Position {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .coalesce::<Position>()
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             loop {
+    ///                 // Only the latest `Position` sent while the
+    ///                 // element was busy is received...
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildRef::ask_anonymously`]: children/struct.ChildRef.html#method.ask_anonymously
+    pub fn coalesce<M: Message>(mut self) -> Self {
+        trace!(
+            "Children({}): Coalescing messages of type: {}",
+            self.id(),
+            std::any::type_name::<M>()
+        );
+        let type_id = TypeId::of::<M>();
+        if !self.coalesced.contains(&type_id) {
+            self.coalesced.push(type_id);
+        }
+
+        self
+    }
+
+    /// Makes every element of this children group append the
+    /// messages it receives to the given [`Journal`], and replay
+    /// them when it is restarted before handling the messages it
+    /// didn't handle yet. This allows the elements to rebuild
+    /// their state after faulting.
+    ///
+    /// Only the messages of type [`Journal::Message`] that were
+    /// told or broadcasted are journaled, and the replayed ones are
+    /// received as if they were told by their original senders.
+    ///
+    /// # Arguments
+    ///
+    /// * `journal` - The journal storing the messages received by
+    ///     the elements of this group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// #[derive(Debug, Clone)]
+    /// struct Increment;
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_journal(InMemoryJournal::<Increment>::new())
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             let mut counter = 0;
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     // `counter` is rebuilt after a restart...
+    ///                     _inc: Increment => counter += 1;
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Journal`]: journal/trait.Journal.html
+    /// [`Journal::Message`]: journal/trait.Journal.html#associatedtype.Message
+    pub fn with_journal<J: Journal>(mut self, journal: J) -> Self {
+        trace!("Children({}): Journaling messages.", self.id());
+        self.journal = Some(SharedJournal::new(journal));
+        self
+    }
+
+    /// Registers a middleware which will see every message sent
+    /// to the elements of this children group before it is
+    /// delivered, and decide whether it should be delivered,
+    /// dropped or redirected to a specific element (see
+    /// [`MiddlewareAction`]).
+    ///
+    /// The middlewares are run in their registration order, each
+    /// of them seeing the messages forwarded by the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `middleware` - The closure run on every message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// static SENT: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// #[derive(Debug)]
+    /// struct Heartbeat;
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         // Counts the messages sent to the elements...
+    ///         .with_middleware(|_msg: &mut Msg| {
+    ///             SENT.fetch_add(1, Ordering::Relaxed);
+    ///             MiddlewareAction::Forward
+    ///         })
+    ///         // ...and drops the heartbeats.
+    ///         .with_middleware(|msg: &mut Msg| {
+    ///             if Msg::type_tag(msg) == std::any::type_name::<Heartbeat>() {
+    ///                 MiddlewareAction::Drop
+    ///             } else {
+    ///                 MiddlewareAction::Forward
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`MiddlewareAction`]: middleware/enum.MiddlewareAction.html
+    pub fn with_middleware<F>(mut self, middleware: F) -> Self
+    where
+        F: Fn(&mut Msg) -> MiddlewareAction + Send + Sync + 'static,
+    {
+        trace!("Children({}): Adding a middleware.", self.id());
+        // Only the messages sent by the users go through the
+        // middlewares, the ones used internally are always
+        // delivered.
+        let middleware = Middleware::new(move |msg: &mut BastionMessage| match msg {
+            BastionMessage::Message(msg) => middleware(msg),
+            _ => MiddlewareAction::Forward,
+        });
+        self.bcast.add_middleware(middleware);
+        self
+    }
+
+    /// Sets how the messages sent to this children group (using
+    /// [`ChildrenRef::broadcast`]) are dispatched to its elements.
+    ///
+    /// The default mode is [`DispatchMode::Broadcast`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dispatch_mode` - The dispatch mode to use:
+    ///     - [`DispatchMode::Broadcast`] would send every message
+    ///         to all the elements of the group.
+    ///     - [`DispatchMode::WeightedRoundRobin`] would send every
+    ///         message to a single element of the group, picked
+    ///         proportionally to the weights set with
+    ///         [`ChildrenRef::set_child_weight`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildrenRef::broadcast`]: children/struct.ChildrenRef.html#method.broadcast
+    /// [`ChildrenRef::set_child_weight`]: children/struct.ChildrenRef.html#method.set_child_weight
+    /// [`DispatchMode::Broadcast`]: children/enum.DispatchMode.html#variant.Broadcast
+    /// [`DispatchMode::WeightedRoundRobin`]: children/enum.DispatchMode.html#variant.WeightedRoundRobin
+    pub fn with_dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        trace!(
+            "Children({}): Setting dispatch mode: {:?}",
+            self.id(),
+            dispatch_mode
+        );
+        self.dispatch_mode = dispatch_mode;
+        self
+    }
+
+    /// Sets the callbacks that will get called at this children group's
+    /// different lifecycle events.
+    ///
+    /// See [`Callbacks`]'s documentation for more information about the
+    /// different callbacks available.
+    ///
+    /// # Arguments
+    ///
+    /// * `callbacks` - The callbacks that will get called for this
+    ///     children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     let callbacks = Callbacks::new()
+    ///         .with_before_start(|| println!("Children group started."))
+    ///         .with_after_stop(|| println!("Children group stopped."));
+    ///
+    ///     children
+    ///         .with_callbacks(callbacks)
+    ///         .with_exec(|ctx| {
+    ///             // -- Children group started.
+    ///             async move {
+    ///                 // ...
+    ///                 # Ok(())
+    ///             }
+    ///             // -- Children group stopped.
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Callbacks`]: struct.Callbacks.html
+    pub fn with_callbacks(mut self, callbacks: Callbacks) -> Self {
+        trace!(
+            "Children({}): Setting callbacks: {:?}",
+            self.id(),
+            callbacks
+        );
+        self.callbacks = callbacks;
+        self
+    }
+
+    async fn kill(&mut self) {
+        debug!("Children({}): Killing.", self.id());
+        self.bcast.kill_children();
+
+        let mut children = FuturesOrdered::new();
+        for pill in self.pills.drain(..) {
+            pill.ack
+                .send(Dead::new(pill.id, DeathReason::Cancelled))
+                .ok();
+        }
+        self.mailboxes.clear();
+        self.started_at.clear();
+        self.weights.clear();
+        self.inits.clear();
+        self.replacing.clear();
+        for (id, (_, launched, _)) in self.launched.drain() {
+            launched.cancel();
+            if let Some(journal) = &self.journal {
+                journal.remove(&id);
+            }
+
+            children.push(launched);
+        }
+
+        let id = self.id();
+        children
+            .for_each_concurrent(None, |_| async {
+                trace!("Children({}): Unknown child stopped.", id);
+            })
+            .await;
     }
 
     fn stopped(&mut self) {
         debug!("Children({}): Stopped.", self.id());
+        self.state.set(ChildrenState::Stopped);
+        self.live.set(0);
+        // The envelopes still queued won't be handled, and dropping
+        // them lets their senders know it (e.g. for
+        // `ChildrenRef::send_child_confirmed`).
+        while self.bcast.try_recv().is_some() {}
         if let Err(e) = self.remove_dispatchers() {
             warn!("couldn't remove all dispatchers from the registry: {}", e);
         };
@@ -408,6 +1513,8 @@ impl Children {
 
     fn faulted(&mut self) {
         debug!("Children({}): Faulted.", self.id());
+        self.state.set(ChildrenState::Faulted);
+        self.live.set(0);
         if let Err(e) = self.remove_dispatchers() {
             warn!("couldn't remove all dispatchers from the registry: {}", e);
         };
@@ -428,13 +1535,13 @@ impl Children {
 
     async fn handle_stopped_child(&mut self, id: &BastionId) -> Result<(), ()> {
         // FIXME: Err if false?
-        if self.launched.contains_key(&id) {
+        if self.launched.contains_key(id) {
             debug!("Children({}): Child({}) stopped.", self.id(), id);
             self.drop_child(id);
 
             let msg = BastionMessage::finished_child(id.clone(), self.bcast.id().clone());
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+            self.bcast.send_parent_or_log(env);
         }
 
         Ok(())
@@ -453,15 +1560,267 @@ impl Children {
         Ok(())
     }
 
-    fn request_restarting_child(&mut self, id: &BastionId, parent_id: &BastionId) {
+    async fn poison_pill_child(
+        &mut self,
+        child: ChildRef,
+        ack: oneshot::Sender<Dead>,
+        handover: Option<Sender>,
+    ) -> Result<(), ()> {
+        let id = child.id().clone();
+        if !self.launched.contains_key(&id) {
+            // Dropping the acknowledgement lets the caller know
+            // that the element wasn't found.
+            return Ok(());
+        }
+
+        debug!("Children({}): Poison pilling Child({}).", self.id(), id);
+        let notice = match self.bcast.poison_pill_child(child.clone(), handover) {
+            Ok(notice) => notice,
+            Err(_) => {
+                // The element already died, so there is nothing
+                // left to wait for.
+                self.handle_stopped_child(&id).await?;
+                ack.send(Dead::new(id, DeathReason::PoisonPilled)).ok();
+                return Ok(());
+            }
+        };
+        let timeout = clock::sleep(self.poison_pill_timeout);
+
+        // The death of the element is awaited by `run` so that the
+        // group keeps handling its messages meanwhile.
+        self.pills.push(PendingPill {
+            id,
+            notice,
+            timeout,
+            ack,
+        });
+
+        Ok(())
+    }
+
+    /// Acknowledges the poison pills of the elements that died and
+    /// cancels the elements that didn't die in time.
+    async fn poll_pills(&mut self) -> Result<(), ()> {
+        let mut i = 0;
+        while i < self.pills.len() {
+            let pill = &mut self.pills[i];
+            let dead = match poll!(&mut pill.notice) {
+                Poll::Ready(Ok(dead)) => Some(dead),
+                Poll::Ready(Err(())) => None,
+                Poll::Pending => match poll!(&mut pill.timeout) {
+                    Poll::Ready(()) => None,
+                    Poll::Pending => {
+                        i += 1;
+                        continue;
+                    }
+                },
+            };
+
+            let pill = self.pills.swap_remove(i);
+            let dead = match dead {
+                Some(dead) => dead,
+                None => {
+                    warn!(
+                        "Children({}): Child({}) didn't acknowledge its poison pill, cancelling it.",
+                        self.id(),
+                        pill.id
+                    );
+                    self.cancel_child(&pill.id).await?;
+
+                    Dead::new(pill.id, DeathReason::Cancelled)
+                }
+            };
+
+            pill.ack.send(dead).ok();
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the element's future, without waiting for it to
+    /// stop by itself.
+    async fn cancel_child(&mut self, id: &BastionId) -> Result<(), ()> {
+        let sender = match self.launched.get(id) {
+            Some((sender, launched, _)) => {
+                launched.cancel();
+                sender.clone()
+            }
+            None => return Ok(()),
+        };
+
+        debug!("Children({}): Cancelling Child({}).", self.id(), id);
+        // The element won't be able to do it itself.
+        let path = BastionPath::clone(self.bcast.path())
+            .append(BastionPathElement::Child(id.clone()))
+            .unwrap();
+        let child = ChildRef::new(id.clone(), sender, self.name(), Arc::new(path));
+        let used_dispatchers = self
+            .dispatchers
+            .iter()
+            .map(|dispatcher| dispatcher.dispatcher_type())
+            .collect::<Vec<_>>();
+        SYSTEM.dispatcher().remove(&used_dispatchers, &child);
+
+        self.handle_stopped_child(id).await
+    }
+
+    /// Returns the identifiers of the elements launched at least
+    /// `age` ago, from the oldest to the youngest.
+    fn children_older_than(&self, age: Duration) -> Vec<BastionId> {
+        let now = clock::now();
+        let mut old = self
+            .started_at
+            .iter()
+            .filter(|(_, started_at)| now.duration_since(**started_at) >= age)
+            .collect::<Vec<_>>();
+        old.sort_by_key(|(_, started_at)| **started_at);
+
+        old.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    async fn graceful_restart_child(
+        &mut self,
+        child: ChildRef,
+        init: Init,
+        ack: oneshot::Sender<Dead>,
+    ) -> Result<(), ()> {
+        if !self.launched.contains_key(child.id()) {
+            // Dropping the acknowledgement lets the caller know
+            // that the element wasn't found.
+            return Ok(());
+        }
+
+        debug!(
+            "Children({}): Gracefully restarting Child({}).",
+            self.id(),
+            child.id()
+        );
+        let id = self.launch_elem_with(Some(init));
+        // The new element takes the weight of the one it replaces.
+        if let Some(weight) = self.weights.get(child.id()).copied() {
+            self.weights.insert(id.clone(), weight);
+            self.bcast.set_child_weight(&id, weight);
+        }
+
+        // The new element buffers the messages it receives until
+        // it started, after which it handles them in order.
+        let msg = BastionMessage::start();
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_child_or_log(&id, env);
+
+        // The old element keeps handling its messages until the
+        // new one is ready to take over.
+        self.replacing.push(Replacement {
+            old: child,
+            new: id,
+            ack,
+        });
+
+        Ok(())
+    }
+
+    /// Poison pills the gracefully restarting elements whose
+    /// replacement started.
+    async fn poll_replacing(&mut self) -> Result<(), ()> {
+        let mut i = 0;
+        while i < self.replacing.len() {
+            let new = &self.replacing[i].new;
+            let handover = match self.launched.get(new) {
+                Some(_) if !self.bcast.is_ready(new) => {
+                    i += 1;
+                    continue;
+                }
+                Some((sender, _, _)) => Some(sender.clone()),
+                // Dropping the acknowledgement lets the caller know
+                // that the new element stopped before starting.
+                None => None,
+            };
+
+            let replacement = self.replacing.swap_remove(i);
+            if handover.is_some() {
+                // Poison pilling the old element stops the messages
+                // sent to the group from reaching it, while the ones
+                // it didn't handle yet are handed over to the new
+                // element.
+                self.poison_pill_child(replacement.old, replacement.ack, handover)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request_restarting_child(&mut self, id: &BastionId, parent_id: &BastionId, panicked: bool) {
         if parent_id == self.bcast.id() && self.launched.contains_key(id) {
-            let parent_id = self.bcast.id().clone();
-            let msg = BastionMessage::restart_required(id.clone(), parent_id);
-            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+            if let Some(breaker) = &mut self.breaker {
+                if !breaker.failed(id) {
+                    warn!(
+                        "Children({}): Circuit breaker is {:?}, not restarting Child({}).",
+                        self.bcast.id(),
+                        breaker.state(),
+                        id
+                    );
+                    return;
+                }
+            }
+
+            if panicked {
+                self.send_panicked(id);
+            } else {
+                self.send_restart_required(id);
+            }
+        }
+    }
+
+    fn handle_breaker_cooldown(&mut self) {
+        let ids = match &mut self.breaker {
+            Some(breaker) => {
+                let ids = breaker.cooled_down();
+                debug!(
+                    "Children({}): Circuit breaker is now {:?}.",
+                    self.bcast.id(),
+                    breaker.state()
+                );
+                ids
+            }
+            None => return,
+        };
+
+        for id in ids {
+            if self.launched.contains_key(&id) {
+                self.send_restart_required(&id);
+            }
         }
     }
 
+    fn accepts_messages(&self, env: &Envelope) -> bool {
+        match &self.breaker {
+            Some(breaker) if !breaker.accepts_messages() => {
+                debug!(
+                    "Children({}): Circuit breaker is open, routing message to dead letters: {:?}",
+                    self.id(),
+                    env
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn send_restart_required(&self, id: &BastionId) {
+        let parent_id = self.bcast.id().clone();
+        let msg = BastionMessage::restart_required(id.clone(), parent_id);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent_or_log(env);
+    }
+
+    fn send_panicked(&self, id: &BastionId) {
+        let parent_id = self.bcast.id().clone();
+        let msg = BastionMessage::panicked(id.clone(), parent_id);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent_or_log(env);
+    }
+
     fn restart_child(&mut self, old_id: &BastionId, old_state: Arc<Mutex<Pin<Box<ContextState>>>>) {
         let parent = Parent::children(self.as_ref());
         let bcast = Broadcast::new(parent, BastionPathElement::Child(old_id.clone()));
@@ -470,11 +1829,14 @@ impl Children {
         let sender = bcast.sender().clone();
         let path = bcast.path().clone();
         let child_ref = ChildRef::new(id.clone(), sender.clone(), self.name(), path);
+        let at_capacity = child_ref.at_capacity().clone();
 
         let children = self.as_ref();
         let supervisor = self.bcast.parent().clone().into_supervisor();
 
-        let state = Arc::new(Mutex::new(Box::pin(ContextState::new())));
+        // The restarted element keeps the state of the faulted
+        // one, which is also the state tracked by the supervisor.
+        let state = old_state.clone();
 
         let ctx = BastionContext::new(
             id.clone(),
@@ -483,42 +1845,173 @@ impl Children {
             supervisor,
             state.clone(),
         );
-        let exec = (self.init.0)(ctx);
+        let exec = (self.init_of(&id).0)(ctx);
 
-        self.bcast.register(&bcast);
+        self.bcast.register_restarted(&bcast);
+        if let Some(weight) = self.weights.get(&id) {
+            self.bcast.set_child_weight(&id, *weight);
+        }
+        self.mailboxes.insert(id.clone(), state.clone());
+        self.started_at.insert(id.clone(), clock::now());
+        self.restarts.record();
 
         let msg = BastionMessage::set_state(old_state);
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_child(&id, env);
+        self.bcast.send_child_or_log(&id, env);
+
+        let msg = BastionMessage::apply_callback(CallbackType::AfterRestart);
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_child_or_log(&id, env);
+
+        let msg = BastionMessage::start();
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_child_or_log(&id, env);
+
+        debug!("Children({}): Restarting Child({}).", self.id(), bcast.id());
+        let callbacks = self.callbacks.clone();
+        let child = Child::new(exec, callbacks, bcast, state, child_ref)
+            .with_dedup(self.dedup.as_ref().map(DedupFactory::build))
+            .with_journal(self.journal.clone())
+            .with_coalesced(self.coalesced.clone())
+            .with_setup(self.setup.clone(), self.state.clone())
+            .replaying_journal();
+        #[cfg(feature = "testing")]
+        let child = child.with_panic_on_message(self.panic_on_message);
+        debug!(
+            "Children({}): Launching faulted Child({}).",
+            self.id(),
+            child.id(),
+        );
+        let id = child.id().clone();
+        let launched = child.launch();
+        self.launched.insert(id, (sender, launched, at_capacity));
+        self.live.set(self.launched.len());
+    }
+
+    fn drop_child(&mut self, id: &BastionId) {
+        debug!(
+            "Children({}): Dropping Child({:?}): reached restart limits.",
+            self.id(),
+            id,
+        );
+        self.launched.remove_entry(id);
+        self.live.set(self.launched.len());
+        self.mailboxes.remove(id);
+        self.started_at.remove(id);
+        self.weights.remove(id);
+        self.inits.remove(id);
+        self.bcast.unregister(id);
+        if let Some(journal) = &self.journal {
+            journal.remove(id);
+        }
+    }
+
+    async fn autoscale(&mut self) -> Result<(), ()> {
+        // The retired elements are left out, even though they
+        // might not have stopped yet.
+        let mut depths = Vec::with_capacity(self.launched.len());
+        for (id, state) in &self.mailboxes {
+            if self.bcast.is_registered(id) {
+                depths.push((id.clone(), state.lock().await.mailbox_len()));
+            }
+        }
+
+        let sample = LoadSample {
+            workers: depths.len(),
+            mailbox_depth: depths.iter().map(|(_, depth)| depth).sum(),
+            run_queue_load: load_balancer::stats().mean(),
+        };
+        let target = match &mut self.autoscaler {
+            Some(autoscaler) => autoscaler.target(&sample),
+            None => return Ok(()),
+        };
+        trace!(
+            "Children({}): Sampled load {:?}, targeting {} elements.",
+            self.id(),
+            sample,
+            target
+        );
+
+        if target > sample.workers {
+            debug!(
+                "Children({}): Scaling up from {} to {} elements.",
+                self.id(),
+                sample.workers,
+                target
+            );
+            for _ in sample.workers..target {
+                let id = self.launch_elem();
+
+                let msg = BastionMessage::start();
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                self.bcast.send_child_or_log(&id, env);
+            }
+        } else if target < sample.workers {
+            debug!(
+                "Children({}): Scaling down from {} to {} elements.",
+                self.id(),
+                sample.workers,
+                target
+            );
+            depths.sort_by_key(|(_, depth)| *depth);
+            let retired = depths.len() - target;
+            // The remaining element with the fewest pending
+            // messages takes over the ones of the retired elements.
+            let handover = self
+                .launched
+                .get(&depths[retired].0)
+                .map(|(sender, _, _)| sender.clone());
+
+            for (id, _) in depths.drain(..retired) {
+                let sender = match self.launched.get(&id) {
+                    Some((sender, _, _)) => sender.clone(),
+                    None => continue,
+                };
+                let path = BastionPath::clone(self.bcast.path())
+                    .append(BastionPathElement::Child(id.clone()))
+                    .unwrap();
+                let child = ChildRef::new(id, sender, self.name(), Arc::new(path));
+
+                // Nobody waits for the retired elements to die, and
+                // they are all given the same time to do it.
+                let (ack, _) = oneshot::channel();
+                self.poison_pill_child(child, ack, handover.clone()).await?;
+            }
+        }
+
+        Ok(())
+    }
 
-        let msg = BastionMessage::apply_callback(CallbackType::AfterRestart);
-        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_child(&id, env);
+    async fn resize_mailboxes(&mut self) {
+        let resizer = match &mut self.resizer {
+            Some(resizer) => resizer,
+            None => return,
+        };
 
-        let msg = BastionMessage::start();
-        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_child(&id, env);
+        let mut ids = Vec::with_capacity(self.mailboxes.len());
+        for (id, state) in &self.mailboxes {
+            let mut state = state.lock().await;
+            let backpressure = match state.backpressure() {
+                Some(backpressure) => backpressure,
+                None => continue,
+            };
 
-        debug!("Children({}): Restarting Child({}).", self.id(), bcast.id());
-        let callbacks = self.callbacks.clone();
-        let child = Child::new(exec, callbacks, bcast, state, child_ref);
-        debug!(
-            "Children({}): Launching faulted Child({}).",
-            self.id(),
-            child.id(),
-        );
-        let id = child.id().clone();
-        let launched = child.launch();
-        self.launched.insert(id, (sender, launched));
-    }
+            let len = state.mailbox_len();
+            if let Some(resized) = resizer.sample(id, len, backpressure) {
+                debug!(
+                    "Children({}): Resizing the mailbox of Child({}) holding {} messages: {:?}",
+                    self.bcast.id(),
+                    id,
+                    len,
+                    resized
+                );
+                state.set_backpressure(resized);
+            }
+            ids.push(id.clone());
+        }
 
-    fn drop_child(&mut self, id: &BastionId) {
-        debug!(
-            "Children({}): Dropping Child({:?}): reached restart limits.",
-            self.id(),
-            id,
-        );
-        self.launched.remove_entry(id);
+        resizer.restart(&ids);
     }
 
     async fn handle(&mut self, envelope: Envelope) -> Result<(), ()> {
@@ -561,18 +2054,44 @@ impl Children {
             Envelope {
                 msg: BastionMessage::Message(ref message),
                 ..
-            } => {
-                debug!(
-                    "Children({}): Broadcasting a message: {:?}",
-                    self.id(),
-                    message
-                );
-                self.bcast.send_children(envelope);
+            } if !self.accepts_messages(&envelope) => {
+                SYSTEM.dead_letters().sender().unbounded_send(envelope).ok();
             }
+            Envelope {
+                msg: BastionMessage::Message(ref message),
+                ..
+            } => match self.dispatch_mode {
+                DispatchMode::Broadcast => {
+                    debug!(
+                        "Children({}): Broadcasting a message: {:?}",
+                        self.id(),
+                        message
+                    );
+                    if let Err(envelope) = self.bcast.send_available(envelope) {
+                        debug!("Children({}): All elements are at capacity.", self.id());
+                        SYSTEM.dead_letters().sender().unbounded_send(envelope).ok();
+                    }
+                }
+                DispatchMode::WeightedRoundRobin => {
+                    debug!(
+                        "Children({}): Dispatching a message: {:?}",
+                        self.id(),
+                        message
+                    );
+                    if let Some(envelope) = self.bcast.send_weighted(envelope) {
+                        debug!("Children({}): No element can receive it.", self.id());
+                        SYSTEM.dead_letters().sender().unbounded_send(envelope).ok();
+                    }
+                }
+            },
             Envelope {
                 msg: BastionMessage::RestartRequired { id, parent_id },
                 ..
-            } => self.request_restarting_child(&id, &parent_id),
+            } => self.request_restarting_child(&id, &parent_id, false),
+            Envelope {
+                msg: BastionMessage::Panicked { id, parent_id },
+                ..
+            } => self.request_restarting_child(&id, &parent_id, true),
             Envelope {
                 msg: BastionMessage::FinishedChild { .. },
                 ..
@@ -601,6 +2120,243 @@ impl Children {
                 msg: BastionMessage::Faulted { id },
                 ..
             } => self.handle_faulted_child(&id).await?,
+            Envelope {
+                msg: BastionMessage::Escalate { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SetChildWeight { id, weight },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Setting Child({}) weight: {}",
+                    self.id(),
+                    id,
+                    weight
+                );
+                if self.launched.contains_key(&id) {
+                    self.weights.insert(id.clone(), weight);
+                }
+                self.bcast.set_child_weight(&id, weight);
+            }
+            Envelope {
+                msg:
+                    BastionMessage::PoisonPill {
+                        child,
+                        ack,
+                        handover,
+                    },
+                ..
+            } => self.poison_pill_child(child, ack, handover).await?,
+            Envelope {
+                msg: BastionMessage::GracefulRestart { child, init, ack },
+                ..
+            } => self.graceful_restart_child(child, init, ack).await?,
+            Envelope {
+                msg: BastionMessage::Topology { ack },
+                ..
+            } => {
+                let state = format!("{:?}", self.state.get());
+                let node =
+                    TopologyNode::new(self.id(), TopologyKind::Children, Some(self.name()), state)
+                        .with_path(self.named_path());
+                node.assemble(&self.bcast, ack);
+            }
+            Envelope {
+                msg: BastionMessage::Ping,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pong { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendChild { id, msg, ack },
+                sign,
+                ..
+            } => {
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                // The sender might not wait for the confirmation.
+                ack.send(self.bcast.send_child_confirmed(&id, env)).ok();
+            }
+            Envelope {
+                msg: BastionMessage::ChildrenOlderThan { age, ack },
+                ..
+            } => {
+                ack.send(self.children_older_than(age)).ok();
+            }
+            Envelope {
+                msg: BastionMessage::AwaitExit { id, ack },
+                ..
+            } => self.bcast.await_exit(&id, ack),
+            Envelope {
+                msg: BastionMessage::WaitForChildren { n, ack },
+                ..
+            } => self.bcast.wait_for_children(n, ack),
+            Envelope {
+                msg: BastionMessage::CancelChildren { ids },
+                ..
+            } => {
+                for id in ids {
+                    self.cancel_child(&id).await?;
+                }
+            }
+            Envelope {
+                msg: BastionMessage::DrainMailbox { id, ack },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Draining the mailbox of Child({}).",
+                    self.id(),
+                    id
+                );
+                let msgs = match self.mailboxes.get(&id) {
+                    Some(state) => {
+                        let mut guard = state.lock().await;
+                        guard.set_suspended(true);
+                        guard.drain_messages()
+                    }
+                    None => Vec::new(),
+                };
+
+                ack.send(msgs).ok();
+            }
+            Envelope {
+                msg: BastionMessage::Subscribe { id, topic },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Subscribing Child({}) to topic: {}",
+                    self.id(),
+                    id,
+                    topic
+                );
+                self.bcast.subscribe(&id, topic);
+            }
+            Envelope {
+                msg: BastionMessage::Unsubscribe { id, topic },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Unsubscribing Child({}) from topic: {}",
+                    self.id(),
+                    id,
+                    topic
+                );
+                self.bcast.unsubscribe(&id, &topic);
+            }
+            Envelope {
+                msg: BastionMessage::RegisterName { id, name },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Registering Child({}) under name: {}",
+                    self.id(),
+                    id,
+                    name
+                );
+                self.bcast.register_name(name, &id);
+            }
+            Envelope {
+                msg: BastionMessage::SendNamed { name, msg },
+                sign,
+                ..
+            } => {
+                debug!(
+                    "Children({}): Sending a message to name {}: {:?}",
+                    self.id(),
+                    name,
+                    msg
+                );
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                if !self.accepts_messages(&env) {
+                    SYSTEM.dead_letters().sender().unbounded_send(env).ok();
+                } else if let Err(env) = self.bcast.send_named(&name, env) {
+                    debug!(
+                        "Children({}): No element is registered under name: {}",
+                        self.id(),
+                        name
+                    );
+                    SYSTEM.dead_letters().sender().unbounded_send(env).ok();
+                }
+            }
+            Envelope {
+                msg: BastionMessage::Publish { topic, msg },
+                sign,
+                ..
+            } => {
+                debug!(
+                    "Children({}): Publishing a message to topic {}: {:?}",
+                    self.id(),
+                    topic,
+                    msg
+                );
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                if self.accepts_messages(&env) {
+                    self.bcast.publish(&topic, env);
+                } else {
+                    SYSTEM.dead_letters().sender().unbounded_send(env).ok();
+                }
+            }
+            Envelope {
+                msg: BastionMessage::Broadcast { payload, ttl },
+                sign,
+                ..
+            } => {
+                let env = Envelope::new_with_sign(BastionMessage::Message(payload), sign);
+                if ttl == 0 {
+                    debug!(
+                        "Children({}): Dropping an expired tree-wide broadcast: {:?}",
+                        self.id(),
+                        env.msg
+                    );
+                } else if self.accepts_messages(&env) {
+                    // Every element receives it, whatever the
+                    // dispatch mode of the group.
+                    debug!(
+                        "Children({}): Forwarding a tree-wide broadcast: {:?}",
+                        self.id(),
+                        env.msg
+                    );
+                    self.bcast.send_children(env);
+                } else {
+                    SYSTEM.dead_letters().sender().unbounded_send(env).ok();
+                }
+            }
+            env @ Envelope {
+                msg: BastionMessage::Suspend,
+                ..
+            }
+            | env @ Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                // The elements launched later on are suspended too.
+                self.suspended = env.msg.is_suspend();
+                debug!(
+                    "Children({}): Forwarding {:?} to the elements.",
+                    self.id(),
+                    env.msg
+                );
+                self.bcast.send_children(env);
+            }
+            Envelope {
+                msg: BastionMessage::Reparent { parent, path },
+                ..
+            } => self.bcast.reparented(*parent, path),
+            // Handled by the broadcast itself.
+            Envelope {
+                msg: BastionMessage::Reparented { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Ready { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Backpressure { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -614,6 +2370,11 @@ impl Children {
         );
         debug!("Children({}): Starting.", self.id());
         self.started = true;
+        // Otherwise, the group is running once one of its
+        // elements completed its initializer.
+        if self.setup.is_none() {
+            self.state.set(ChildrenState::Running);
+        }
 
         let msg = BastionMessage::start();
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
@@ -637,14 +2398,22 @@ impl Children {
     }
 
     async fn run(mut self) -> Self {
-        debug!("Children({}): Launched.", self.id());
+        debug!(
+            "Children({}): Launched at {}.",
+            self.id(),
+            self.named_path()
+        );
 
         loop {
-            for (_, launched) in self.launched.values_mut() {
+            for (_, launched, _) in self.launched.values_mut() {
                 let _ = poll!(launched);
             }
 
-            match poll!(&mut self.bcast.next()) {
+            if self.poll_pills().await.is_err() || self.poll_replacing().await.is_err() {
+                return self;
+            }
+
+            match poll!(self.bcast.recv()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
                     msg: BastionMessage::Start,
@@ -676,7 +2445,42 @@ impl Children {
                 //      `Receiver` of the same channel, this would only be
                 //      possible if the channel was closed, which never happens.
                 Poll::Ready(None) => unreachable!(),
-                Poll::Pending => pending!(),
+                Poll::Pending => {
+                    if let Some(cooldown) = self.breaker.as_mut().and_then(Breaker::cooldown) {
+                        if let Poll::Ready(()) = poll!(cooldown) {
+                            self.handle_breaker_cooldown();
+                            continue;
+                        }
+                    }
+
+                    if self.started {
+                        if let Some(autoscaler) = &mut self.autoscaler {
+                            if let Poll::Ready(()) = poll!(autoscaler.timer()) {
+                                if self.autoscale().await.is_err() {
+                                    return self;
+                                }
+
+                                continue;
+                            }
+                        }
+
+                        if let Some(resizer) = &mut self.resizer {
+                            if let Poll::Ready(()) = poll!(resizer.timer()) {
+                                self.resize_mailboxes().await;
+                                continue;
+                            }
+                        }
+                    }
+
+                    // The broadcast records the elements which started
+                    // while being polled.
+                    let started = |replacement: &Replacement| self.bcast.is_ready(&replacement.new);
+                    if self.replacing.iter().any(started) {
+                        continue;
+                    }
+
+                    pending!()
+                }
             }
         }
     }
@@ -684,56 +2488,97 @@ impl Children {
     pub(crate) fn launch_elems(&mut self) {
         debug!("Children({}): Launching elements.", self.id());
 
-        let name = self.name();
         for _ in 0..self.redundancy {
-            let parent = Parent::children(self.as_ref());
-            let bcast = Broadcast::new(parent, BastionPathElement::Child(BastionId::new()));
+            self.launch_elem();
+        }
+    }
 
-            // TODO: clone or ref?
-            let id = bcast.id().clone();
-            let sender = bcast.sender().clone();
-            let path = bcast.path().clone();
-            let child_ref = ChildRef::new(id.clone(), sender.clone(), name.clone(), path);
-
-            let children = self.as_ref();
-            let supervisor = self.bcast.parent().clone().into_supervisor();
-
-            let state = Arc::new(Mutex::new(Box::pin(ContextState::new())));
-
-            let ctx = BastionContext::new(
-                id.clone(),
-                child_ref.clone(),
-                children,
-                supervisor,
-                state.clone(),
-            );
-            let exec = (self.init.0)(ctx);
+    /// Returns the closure run by the element with the given
+    /// identifier.
+    fn init_of(&self, id: &BastionId) -> &Init {
+        self.inits.get(id).unwrap_or(&self.init)
+    }
 
-            let parent_id = self.bcast.id().clone();
-            let msg = BastionMessage::instantiated_child(parent_id, id.clone(), state.clone());
-            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_parent(env).ok();
+    /// Launches a new element, returning its identifier.
+    fn launch_elem(&mut self) -> BastionId {
+        self.launch_elem_with(None)
+    }
+
+    /// Launches a new element running `init` if given, instead of
+    /// the closure of the group.
+    fn launch_elem_with(&mut self, init: Option<Init>) -> BastionId {
+        let name = self.name();
+        let parent = Parent::children(self.as_ref());
+        let bcast = Broadcast::new(parent, BastionPathElement::Child(BastionId::new()));
 
-            self.bcast.register(&bcast);
+        // TODO: clone or ref?
+        let id = bcast.id().clone();
+        let sender = bcast.sender().clone();
+        let path = bcast.path().clone();
+        let child_ref = ChildRef::new(id.clone(), sender.clone(), name, path);
+        let at_capacity = child_ref.at_capacity().clone();
 
-            debug!(
-                "Children({}): Initializing Child({}).",
-                self.id(),
-                bcast.id()
-            );
-            let callbacks = self.callbacks.clone();
-            let child = Child::new(exec, callbacks, bcast, state, child_ref);
-            debug!("Children({}): Launching Child({}).", self.id(), child.id());
-            let id = child.id().clone();
-            let launched = child.launch();
-            self.launched.insert(id, (sender, launched));
+        let children = self.as_ref();
+        let supervisor = self.bcast.parent().clone().into_supervisor();
+
+        let backpressure = match &self.resizer {
+            Some(resizer) => Some(resizer.initial(self.backpressure)),
+            None => self.backpressure,
+        };
+        let state = ContextState::new()
+            .with_backpressure(backpressure)
+            .with_suspended(self.suspended);
+        let state = Arc::new(Mutex::new(Box::pin(state)));
+
+        let ctx = BastionContext::new(
+            id.clone(),
+            child_ref.clone(),
+            children,
+            supervisor,
+            state.clone(),
+        );
+        if let Some(init) = init {
+            self.inits.insert(id.clone(), init);
         }
+        let exec = (self.init_of(&id).0)(ctx);
+
+        let parent_id = self.bcast.id().clone();
+        let msg = BastionMessage::instantiated_child(parent_id, id.clone(), state.clone());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_parent_or_log(env);
+
+        // The identifier was just generated and can't be taken.
+        self.bcast.register(&bcast).ok();
+        self.mailboxes.insert(id.clone(), state.clone());
+        self.started_at.insert(id.clone(), clock::now());
+
+        debug!(
+            "Children({}): Initializing Child({}).",
+            self.id(),
+            bcast.id()
+        );
+        let callbacks = self.callbacks.clone();
+        let child = Child::new(exec, callbacks, bcast, state, child_ref)
+            .with_dedup(self.dedup.as_ref().map(DedupFactory::build))
+            .with_journal(self.journal.clone())
+            .with_coalesced(self.coalesced.clone())
+            .with_setup(self.setup.clone(), self.state.clone());
+        #[cfg(feature = "testing")]
+        let child = child.with_panic_on_message(self.panic_on_message);
+        debug!("Children({}): Launching Child({}).", self.id(), child.id());
+        let id = child.id().clone();
+        let launched = child.launch();
+        self.launched
+            .insert(id.clone(), (sender, launched, at_capacity));
+        self.live.set(self.launched.len());
+
+        id
     }
 
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Children({}): Launching.", self.id());
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawn_with(self.run(), stack)
     }
 
     /// Registers all declared local dispatchers in the global dispatcher.
@@ -756,3 +2601,151 @@ impl Children {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How the messages sent to a children group are dispatched
+/// to its elements (see [`Children::with_dispatch_mode`]).
+///
+/// The default mode is `Broadcast`.
+///
+/// [`Children::with_dispatch_mode`]: children/struct.Children.html#method.with_dispatch_mode
+pub enum DispatchMode {
+    /// Every message is sent to all the elements of the group.
+    #[default]
+    Broadcast,
+    /// Every message is sent to a single element of the group,
+    /// picked using smooth weighted round-robin so that each
+    /// element receives a share of the messages proportional to
+    /// its weight (`1` by default).
+    WeightedRoundRobin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// What the supervisor of a children group does when one of its
+/// elements panics (see [`Children::on_panic`]).
+///
+/// The default policy is `Restart`.
+///
+/// [`Children::on_panic`]: children/struct.Children.html#method.on_panic
+pub enum PanicPolicy {
+    /// The element is restarted following the supervisor's
+    /// strategy, as for any other fault.
+    #[default]
+    Restart,
+    /// The element is removed from the group, and the supervisor
+    /// escalates the fault to its own parent without trying to
+    /// recover from it. The element is relaunched if the parent
+    /// restarts the supervisor's subtree.
+    Escalate,
+    /// The element is removed from the group without being
+    /// restarted.
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+/// The lifecycle state of a children group, as returned by
+/// [`ChildrenRef::state`].
+///
+/// A children group starts as `Init`, becomes `Running` once
+/// started and ends up either `Faulted` or `Stopped`. A group
+/// never goes back to a previous state.
+///
+/// [`ChildrenRef::state`]: ../children_ref/struct.ChildrenRef.html#method.state
+pub enum ChildrenState {
+    /// The group was created but not started yet.
+    Init = 0,
+    /// The group was started and its elements are running.
+    Running = 1,
+    /// The group faulted because one of its elements did.
+    Faulted = 2,
+    /// The group was stopped or killed.
+    Stopped = 3,
+}
+
+impl ChildrenState {
+    fn from_u8(state: u8) -> Self {
+        match state {
+            0 => ChildrenState::Init,
+            1 => ChildrenState::Running,
+            2 => ChildrenState::Faulted,
+            _ => ChildrenState::Stopped,
+        }
+    }
+
+    /// Returns whether the group can't change its state anymore.
+    pub fn is_terminal(self) -> bool {
+        match self {
+            ChildrenState::Faulted | ChildrenState::Stopped => true,
+            ChildrenState::Init | ChildrenState::Running => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+// An element gracefully restarting, replaced by `new` and whose
+// death is acknowledged to `ack`.
+struct Replacement {
+    old: ChildRef,
+    new: BastionId,
+    ack: oneshot::Sender<Dead>,
+}
+
+#[derive(Debug)]
+// An element that was poison pilled, along with the notice of its
+// death, how long it is given to die and who to acknowledge it to.
+struct PendingPill {
+    id: BastionId,
+    notice: DeathNotice,
+    timeout: Sleep,
+    ack: oneshot::Sender<Dead>,
+}
+
+#[derive(Debug, Default)]
+/// A [`ChildrenState`] shared between a children group and
+/// its references, which only moves forward.
+pub(crate) struct AtomicChildrenState(AtomicU8);
+
+impl AtomicChildrenState {
+    pub(crate) fn get(&self) -> ChildrenState {
+        ChildrenState::from_u8(self.0.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn set(&self, new: ChildrenState) {
+        // Terminal states are final and the group can't go back
+        // to an earlier state.
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let current = ChildrenState::from_u8(current);
+                if current.is_terminal() || new as u8 <= current as u8 {
+                    None
+                } else {
+                    Some(new as u8)
+                }
+            })
+            .ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtomicChildrenState, ChildrenState};
+
+    #[test]
+    fn state_transitions_are_monotonic() {
+        let state = AtomicChildrenState::default();
+        assert_eq!(state.get(), ChildrenState::Init);
+
+        state.set(ChildrenState::Running);
+        assert_eq!(state.get(), ChildrenState::Running);
+
+        state.set(ChildrenState::Init);
+        assert_eq!(state.get(), ChildrenState::Running);
+
+        state.set(ChildrenState::Faulted);
+        assert_eq!(state.get(), ChildrenState::Faulted);
+
+        state.set(ChildrenState::Stopped);
+        assert_eq!(state.get(), ChildrenState::Faulted);
+    }
+}
@@ -1,16 +1,31 @@
 //!
 //! Allows users to communicate with children through the mailboxes.
-use crate::broadcast::Sender;
+use crate::broadcast::{self, Sender};
+use crate::child::Init;
 use crate::child_ref::ChildRef;
-use crate::context::BastionId;
+use crate::children::{AtomicChildrenState, ChildrenState};
+use crate::circuit_breaker::{AtomicBreakerState, BreakerState};
+use crate::context::{BastionContext, BastionId};
 use crate::dispatcher::DispatcherType;
-use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Message};
+use crate::envelope::{Envelope, SignedMessage};
+use crate::message::{BastionMessage, DeathNotice, Message};
 use crate::path::BastionPath;
+use crate::restart_history::SharedRestarts;
+use crate::scope::LiveElems;
+use crate::sink::MessageSink;
+use crate::supervisor::ROOT_NAMED_PATH;
 use crate::system::SYSTEM;
+use futures::future::{self, Either};
+use futures::stream::{self, FuturesUnordered, Stream};
+use futures::{FutureExt, StreamExt};
+use futures_timer::Delay;
 use std::cmp::{Eq, PartialEq};
-use std::fmt::Debug;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, trace};
 
 #[derive(Debug, Clone)]
@@ -22,6 +37,11 @@ pub struct ChildrenRef {
     path: Arc<BastionPath>,
     children: Vec<ChildRef>,
     dispatchers: Vec<DispatcherType>,
+    state: Arc<AtomicChildrenState>,
+    breaker_state: Arc<AtomicBreakerState>,
+    restarts: Arc<SharedRestarts>,
+    live: Arc<LiveElems>,
+    named_path: Arc<str>,
 }
 
 impl ChildrenRef {
@@ -31,6 +51,8 @@ impl ChildrenRef {
         path: Arc<BastionPath>,
         children: Vec<ChildRef>,
         dispatchers: Vec<DispatcherType>,
+        state: Arc<AtomicChildrenState>,
+        breaker_state: Arc<AtomicBreakerState>,
     ) -> Self {
         ChildrenRef {
             id,
@@ -38,9 +60,33 @@ impl ChildrenRef {
             path,
             children,
             dispatchers,
+            state,
+            breaker_state,
+            restarts: Arc::default(),
+            live: Arc::default(),
+            named_path: Arc::from(ROOT_NAMED_PATH),
         }
     }
 
+    pub(crate) fn with_restarts(mut self, restarts: Arc<SharedRestarts>) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    pub(crate) fn with_live_elems(mut self, live: Arc<LiveElems>) -> Self {
+        self.live = live;
+        self
+    }
+
+    pub(crate) fn with_named_path(mut self, named_path: impl Into<Arc<str>>) -> Self {
+        self.named_path = named_path.into();
+        self
+    }
+
+    pub(crate) fn live_elems(&self) -> &LiveElems {
+        &self.live
+    }
+
     /// Returns the identifier of the children group this `ChildrenRef`
     /// is referencing.
     ///
@@ -69,6 +115,112 @@ impl ChildrenRef {
         &self.id
     }
 
+    /// Returns the current [`ChildrenState`] of the children group
+    /// this `ChildrenRef` is referencing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// // The group isn't started yet...
+    /// assert_eq!(children_ref.state(), ChildrenState::Init);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildrenState`]: ../children/enum.ChildrenState.html
+    pub fn state(&self) -> ChildrenState {
+        self.state.get()
+    }
+
+    /// Returns the current [`BreakerState`] of the circuit breaker
+    /// of the children group this `ChildrenRef` is referencing.
+    ///
+    /// Groups without a circuit breaker (see
+    /// [`Children::with_circuit_breaker`]) are always
+    /// [`BreakerState::Closed`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     // ...
+    /// # children
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// assert_eq!(children_ref.breaker_state(), BreakerState::Closed);
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BreakerState`]: ../circuit_breaker/enum.BreakerState.html
+    /// [`BreakerState::Closed`]: ../circuit_breaker/enum.BreakerState.html#variant.Closed
+    /// [`Children::with_circuit_breaker`]: ../children/struct.Children.html#method.with_circuit_breaker
+    pub fn breaker_state(&self) -> BreakerState {
+        self.breaker_state.get()
+    }
+
+    /// Returns how many times the elements of the children group
+    /// this `ChildrenRef` is referencing were restarted, over the
+    /// whole lifetime of the group or over the current window if
+    /// one was set (see [`Children::with_restart_history`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     // ...
+    /// # children
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// assert_eq!(children_ref.restart_count(), 0);
+    /// assert!(children_ref.last_restart().is_none());
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Children::with_restart_history`]: ../children/struct.Children.html#method.with_restart_history
+    pub fn restart_count(&self) -> usize {
+        self.restarts.count()
+    }
+
+    /// Returns when an element of the children group this
+    /// `ChildrenRef` is referencing was last restarted, if it
+    /// ever was.
+    pub fn last_restart(&self) -> Option<Instant> {
+        self.restarts.last()
+    }
+
+    /// Returns when the elements of the children group this
+    /// `ChildrenRef` is referencing were last restarted, oldest
+    /// first.
+    ///
+    /// Only the latest restarts are remembered (see
+    /// [`RestartHistory::new`]).
+    ///
+    /// [`RestartHistory::new`]: ../restart_history/struct.RestartHistory.html#method.new
+    pub fn restart_history(&self) -> Vec<Instant> {
+        self.restarts.times()
+    }
+
     /// Returns a list of dispatcher names that can be used for
     /// comminucation with other actors in the same group(s).
     ///
@@ -179,6 +331,257 @@ impl ChildrenRef {
         self.send(env).map_err(|err| err.into_msg().unwrap())
     }
 
+    /// Sends an already allocated message to the children group
+    /// this `ChildrenRef` is referencing which will then send it
+    /// to all of its elements, like [`broadcast`] does.
+    ///
+    /// All the elements receive the same allocation instead of a
+    /// copy of the message each, which avoids copying large
+    /// messages around.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::Arc;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let msg = Arc::new(vec![0u8; 1024 * 1024]);
+    /// children_ref.broadcast_shared(msg).expect("Couldn't send the message.");
+    ///
+    /// // And then in every of the children group's elements' futures...
+    /// # Bastion::children(|children| {
+    /// #     children.with_exec(|ctx: BastionContext| {
+    /// #         async move {
+    /// msg! { ctx.recv().await?,
+    ///     ref msg: Vec<u8> => {
+    ///         assert_eq!(msg.len(), 1024 * 1024);
+    ///     };
+    ///     _: _ => ();
+    /// }
+    /// #             Ok(())
+    /// #         }
+    /// #     })
+    /// # }).unwrap();
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    pub fn broadcast_shared<M: Message>(&self, msg: Arc<M>) -> Result<(), Arc<M>> {
+        debug!(
+            "ChildrenRef({}): Broadcasting shared message: {:?}",
+            self.id(),
+            msg
+        );
+        let msg = BastionMessage::shared(msg);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|err| err.into_shared().unwrap())
+    }
+
+    /// Returns a [`MessageSink`] broadcasting the items it is
+    /// given as messages to all the elements of the children group
+    /// this `ChildrenRef` is referencing (see [`broadcast`]),
+    /// waiting while any of them is at capacity.
+    ///
+    /// To send the items to a single element, use
+    /// [`ChildRef::sink`] on one of the [`elems`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let sink: MessageSink<u32> = children_ref.sink();
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`MessageSink`]: ../sink/struct.MessageSink.html
+    /// [`broadcast`]: #method.broadcast
+    /// [`elems`]: #method.elems
+    /// [`ChildRef::sink`]: ../child_ref/struct.ChildRef.html#method.sink
+    pub fn sink<M: Message>(&self) -> MessageSink<M> {
+        MessageSink::children(self.clone())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing which will then send it to all of its
+    /// elements that subscribed to the given topic (using
+    /// [`BastionContext::subscribe`]).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to publish the message to.
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.subscribe("news").expect("Couldn't subscribe.");
+    ///
+    ///             // Only messages published to "news" are received...
+    ///             msg! { ctx.recv().await?,
+    ///                 ref msg: &'static str => {
+    ///                     assert_eq!(msg, &"A message containing data.");
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let msg = "A message containing data.";
+    /// children_ref.publish("news", msg).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionContext::subscribe`]: context/struct.BastionContext.html#method.subscribe
+    pub fn publish<M: Message>(&self, topic: impl Into<String>, msg: M) -> Result<(), M> {
+        let topic = topic.into();
+        debug!(
+            "ChildrenRef({}): Publishing message to topic {}: {:?}",
+            self.id(),
+            topic,
+            msg
+        );
+        let msg = BastionMessage::publish(topic, msg);
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send(env).map_err(|err| err.into_msg().unwrap())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to register its element with the
+    /// given identifier under the given name, making it receive
+    /// some of the messages sent to that name using
+    /// [`send_named`].
+    ///
+    /// Several elements can be registered under the same name,
+    /// which lets the producers send messages to them without
+    /// tracking their identifiers. The elements stay registered
+    /// when they are restarted, until they are removed from the
+    /// group.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to register the element under.
+    /// * `id` - The identifier of the element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str => {
+    ///                     assert_eq!(msg, "A message containing data.");
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let id = children_ref.elems()[0].id().clone();
+    /// children_ref.register_name("logger", &id).expect("Couldn't send the message.");
+    ///
+    /// let msg = "A message containing data.";
+    /// children_ref.send_named("logger", msg).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`send_named`]: #method.send_named
+    pub fn register_name(&self, name: impl Into<String>, id: &BastionId) -> Result<(), ()> {
+        let name = name.into();
+        debug!(
+            "ChildrenRef({}): Registering Child({}) under name: {}",
+            self.id(),
+            id,
+            name
+        );
+        let msg = BastionMessage::register_name(id.clone(), name);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing which will then send it to one of its
+    /// elements registered under the given name (using
+    /// [`register_name`]), each of them receiving the messages
+    /// sent to the name in turn.
+    ///
+    /// The message is routed to the dead letters if no element is
+    /// registered under the name.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the receiving elements are registered
+    ///     under.
+    /// * `msg` - The message to send.
+    ///
+    /// [`register_name`]: #method.register_name
+    pub fn send_named<M: Message>(&self, name: impl Into<String>, msg: M) -> Result<(), M> {
+        let name = name.into();
+        debug!(
+            "ChildrenRef({}): Sending message to name {}: {:?}",
+            self.id(),
+            name,
+            msg
+        );
+        let msg = BastionMessage::send_named(name, msg);
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send(env).map_err(|err| err.into_msg().unwrap())
+    }
+
     /// Sends a message to the children group this `ChildrenRef`
     /// is referencing to tell it to stop all of its running
     /// elements.
@@ -235,20 +638,885 @@ impl ChildrenRef {
         self.send(env).map_err(|_| ())
     }
 
-    pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
-        trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
-        self.sender.unbounded_send(env).or_else(|err| {
-            SYSTEM
-                .dead_letters()
-                .sender
-                .unbounded_send(err.into_inner())
-                .map_err(|err| err.into_inner())
-        })
+    /// Sends a message to the children group this `ChildrenRef` is
+    /// referencing to tell it to suspend its elements.
+    ///
+    /// The suspended elements stop receiving their messages, which
+    /// keep being queued in their mailboxes until the subtree gets
+    /// resumed using [`resume_subtree`]. The elements added to the
+    /// subtree later on aren't suspended.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.suspend_subtree().expect("Couldn't send the message.");
+    /// // The messages sent now are only received once resumed...
+    /// children_ref.resume_subtree().expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`resume_subtree`]: #method.resume_subtree
+    pub fn suspend_subtree(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Suspending the subtree.", self.id());
+        let msg = BastionMessage::suspend();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
     }
 
-    /// Returns the [`BastionPath`] of this ChildrenRef
-    pub fn path(&self) -> &Arc<BastionPath> {
-        &self.path
+    /// Sends a message to the children group this `ChildrenRef` is
+    /// referencing to tell it to resume the subtree suspended
+    /// using [`suspend_subtree`], letting the elements receive
+    /// the messages queued in the meantime.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// [`suspend_subtree`]: #method.suspend_subtree
+    pub fn resume_subtree(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Resuming the subtree.", self.id());
+        let msg = BastionMessage::resume();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to send a poison pill to one of
+    /// its elements, which will then stop.
+    ///
+    /// Unlike [`ChildRef::stop`], this returns a [`DeathNotice`]
+    /// which resolves once the element acknowledged the pill,
+    /// allowing to tear elements down in a deterministic order.
+    /// If the element doesn't acknowledge it in time (see
+    /// [`Children::with_poison_pill_timeout`]), it is cancelled
+    /// and the notice's reason is [`DeathReason::Cancelled`].
+    ///
+    /// The notice resolves to `Err(())` if the element isn't
+    /// part of the group anymore.
+    ///
+    /// This method returns a [`DeathNotice`] if it succeeded, or
+    /// `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The element of the group that should stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// let child_ref = &children_ref.elems()[0];
+    /// let notice = children_ref
+    ///     .poison_pill_child(child_ref)
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// # run!(async {
+    /// let dead = notice.await.expect("Couldn't receive the notice.");
+    /// assert_eq!(&dead.id, child_ref.id());
+    /// assert_eq!(dead.reason, DeathReason::PoisonPilled);
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildRef::stop`]: children/struct.ChildRef.html#method.stop
+    /// [`DeathNotice`]: message/struct.DeathNotice.html
+    /// [`Children::with_poison_pill_timeout`]: children/struct.Children.html#method.with_poison_pill_timeout
+    /// [`DeathReason::Cancelled`]: message/enum.DeathReason.html#variant.Cancelled
+    pub fn poison_pill_child(&self, child: &ChildRef) -> Result<DeathNotice, ()> {
+        debug!(
+            "ChildrenRef({}): Poison pilling Child({}).",
+            self.id(),
+            child.id()
+        );
+        let (msg, notice) = BastionMessage::poison_pill(child.clone(), None);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(notice)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to replace one of its elements
+    /// with a new one running the given closure, without any
+    /// message sent to the group getting lost.
+    ///
+    /// The new element is launched first, and receives all the
+    /// messages sent to the group from then on (those it receives
+    /// before being started are handled once it started). Once the
+    /// new element started (after running the group's initializer,
+    /// if any), the old element is sent a poison pill, and the
+    /// messages it received but didn't handle yet are forwarded to
+    /// the new element before it stops. Messages sent directly to
+    /// the old element (e.g. using [`ChildRef::tell`]) after that
+    /// are lost.
+    ///
+    /// Only the new element runs the new closure, even when it is
+    /// restarted, while the group's other elements keep running
+    /// theirs, which allows upgrading them one at a time.
+    ///
+    /// The returned [`DeathNotice`] resolves once the old element
+    /// stopped, like with [`poison_pill_child`], or to `Err(())`
+    /// if the element isn't part of the group anymore or if the new
+    /// element stopped before starting.
+    ///
+    /// This method returns a [`DeathNotice`] if it succeeded, or
+    /// `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The element of the group that should be
+    ///     replaced.
+    /// * `init` - The closure the new element should run, as
+    ///     with [`Children::with_exec`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| {
+    /// #     children.with_exec(|ctx: BastionContext| async move {
+    /// #         loop {
+    /// #             ctx.recv().await?;
+    /// #         }
+    /// #     })
+    /// # }).unwrap();
+    /// # Bastion::start();
+    /// let old = &children_ref.elems()[0];
+    /// let notice = children_ref
+    ///     .graceful_restart_child(old, |ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str => println!("v2: {}", msg);
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// # run!(async {
+    /// let dead = notice.await.expect("Couldn't receive the notice.");
+    /// assert_eq!(&dead.id, old.id());
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildRef::tell`]: children/struct.ChildRef.html#method.tell
+    /// [`DeathNotice`]: message/struct.DeathNotice.html
+    /// [`poison_pill_child`]: #method.poison_pill_child
+    /// [`Children::with_exec`]: children/struct.Children.html#method.with_exec
+    pub fn graceful_restart_child<I, F>(&self, child: &ChildRef, init: I) -> Result<DeathNotice, ()>
+    where
+        I: Fn(BastionContext) -> F + Send + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        debug!(
+            "ChildrenRef({}): Gracefully restarting Child({}).",
+            self.id(),
+            child.id()
+        );
+        let (msg, notice) = BastionMessage::graceful_restart(child.clone(), Init::new(init));
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(notice)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to set the weight of one of its
+    /// elements.
+    ///
+    /// The weights are only used when the group was created with
+    /// [`DispatchMode::WeightedRoundRobin`], in which case every
+    /// message sent with [`broadcast`] is received by a single
+    /// element, picked proportionally to its weight. Elements
+    /// default to a weight of `1` and elements with a weight of
+    /// `0` won't receive any of those messages.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element whose weight
+    ///     should be set.
+    /// * `weight` - The weight of the element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(2)
+    ///         .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // The first element will receive three times more
+    /// // messages than the second one.
+    /// let fast = &children_ref.elems()[0];
+    /// children_ref
+    ///     .set_child_weight(fast.id(), 3)
+    ///     .expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`DispatchMode::WeightedRoundRobin`]: children/enum.DispatchMode.html#variant.WeightedRoundRobin
+    /// [`broadcast`]: #method.broadcast
+    pub fn set_child_weight(&self, id: &BastionId, weight: usize) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Setting Child({}) weight: {}",
+            self.id(),
+            id,
+            weight
+        );
+        let msg = BastionMessage::set_child_weight(id.clone(), weight);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    #[cfg(feature = "testing")]
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to notify it that the given element faulted,
+    /// as if it crashed.
+    ///
+    /// This is only available with the `testing` feature and is
+    /// meant to deterministically trigger the supervision logic.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The element of the group that should fault.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let child_ref = &children_ref.elems()[0];
+    /// children_ref.inject_fault(child_ref).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn inject_fault(&self, child: &ChildRef) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Injecting fault in Child({}).",
+            self.id(),
+            child.id()
+        );
+        let msg = BastionMessage::faulted(child.id().clone());
+        let env = Envelope::new(msg, child.path().clone(), child.sender().clone());
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the element of the children group this
+    /// `ChildrenRef` is referencing which has the given identifier,
+    /// and returns a [`Future`] resolving once the message was
+    /// enqueued into the element's mailbox.
+    ///
+    /// This is a stronger guarantee than the one given by sending
+    /// a message without waiting (which only tells whether it was
+    /// sent to the group), but a weaker one than [`ChildRef::ask`]
+    /// as the element might still stop before handling it. The
+    /// message goes through the middlewares of the group like the
+    /// other ones.
+    ///
+    /// The returned future resolves to `Ok(())` if the message was
+    /// enqueued, or to the [`SendError`] telling why it wasn't.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element to send the message
+    ///     to.
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// # Bastion::start();
+    /// #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             ctx.recv().await?;
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let id = children_ref.elems()[0].id().clone();
+    /// # run!(async {
+    /// children_ref
+    ///     .send_child_confirmed(&id, "A message containing data.")
+    ///     .await
+    ///     .expect("The message wasn't enqueued.");
+    ///
+    /// // The group itself isn't one of its elements...
+    /// let res = children_ref.send_child_confirmed(children_ref.id(), "Lost").await;
+    /// assert_eq!(res, Err(SendError::UnknownChild));
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`ChildRef::ask`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    /// [`SendError`]: enum.SendError.html
+    pub fn send_child_confirmed<M: Message>(
+        &self,
+        id: &BastionId,
+        msg: M,
+    ) -> impl Future<Output = Result<(), SendError>> {
+        debug!(
+            "ChildrenRef({}): Sending message to {} with confirmation: {:?}",
+            self.id(),
+            id,
+            msg
+        );
+        let (msg, mut recver) = BastionMessage::send_child(id.clone(), msg);
+        let env = Envelope::from_dead_letters(msg);
+        // A stopped group keeps its mailbox open, but won't handle
+        // the message anymore.
+        let sent = !self.state().is_terminal() && self.send(env).is_ok();
+        // If the group stopped in the meantime, the message was
+        // either already handled or won't ever be.
+        let stopped = self.state().is_terminal();
+
+        async move {
+            if !sent {
+                return Err(SendError::Closed);
+            } else if stopped {
+                return match recver.try_recv() {
+                    Ok(Some(res)) => res,
+                    _ => Err(SendError::Closed),
+                };
+            }
+
+            // The group stopped before handling the message if the
+            // acknowledgement was dropped.
+            recver.await.unwrap_or(Err(SendError::Closed))
+        }
+    }
+
+    /// Asks the children group this `ChildrenRef` is referencing
+    /// which of its elements were launched at least `age` ago,
+    /// e.g. to find the ones which might be stuck.
+    ///
+    /// The returned future resolves to the identifiers of those
+    /// elements, from the oldest to the youngest. Restarted
+    /// elements are as old as their last restart. It resolves to
+    /// an empty list if the group stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `age` - How long ago the elements must have been launched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// # run!(async {
+    /// let stuck = children_ref
+    ///     .children_older_than(Duration::from_secs(3600))
+    ///     .await;
+    /// children_ref
+    ///     .cancel_children(&stuck)
+    ///     .expect("Couldn't send the message.");
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn children_older_than(&self, age: Duration) -> impl Future<Output = Vec<BastionId>> {
+        debug!(
+            "ChildrenRef({}): Listing the children older than {:?}.",
+            self.id(),
+            age
+        );
+        let (msg, recver) = BastionMessage::children_older_than(age);
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self.send(env).is_ok();
+
+        async move {
+            if !sent {
+                return Vec::new();
+            }
+
+            recver.await.unwrap_or_default()
+        }
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to cancel some of its elements,
+    /// without waiting for them to stop by themselves.
+    ///
+    /// Cancelled elements are removed from the group and aren't
+    /// restarted. The identifiers of elements which aren't part
+    /// of the group anymore are ignored.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The identifiers of the elements to cancel, e.g.
+    ///     returned by [`ChildrenRef::children_older_than`].
+    ///
+    /// [`ChildrenRef::children_older_than`]: #method.children_older_than
+    pub fn cancel_children(&self, ids: &[BastionId]) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Cancelling {} children.",
+            self.id(),
+            ids.len()
+        );
+        let msg = BastionMessage::cancel_children(ids.to_vec());
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to suspend one of its elements
+    /// and remove all the messages waiting in its mailbox, e.g.
+    /// to migrate them to another element.
+    ///
+    /// The returned future resolves to the removed messages, in
+    /// the order the element would have received them, or to an
+    /// empty list if the element isn't part of the group or if
+    /// the group stopped. The messages keep their sender, so they
+    /// can be forwarded using [`BastionContext::relay`].
+    ///
+    /// The element stays suspended afterwards, queuing the
+    /// messages it receives, until it's resumed using
+    /// [`resume_subtree`] or stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element whose mailbox
+    ///     should be drained.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// # run!(async {
+    /// for elem in children_ref.elems() {
+    ///     let pending: Vec<SignedMessage> = children_ref.drain_mailbox(elem.id()).await;
+    ///     // ...
+    /// }
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionContext::relay`]: context/struct.BastionContext.html#method.relay
+    /// [`resume_subtree`]: #method.resume_subtree
+    pub fn drain_mailbox(&self, id: &BastionId) -> impl Future<Output = Vec<SignedMessage>> {
+        debug!(
+            "ChildrenRef({}): Draining the mailbox of Child({}).",
+            self.id(),
+            id
+        );
+        let (msg, recver) = BastionMessage::drain_mailbox(id.clone());
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self.send(env).is_ok();
+
+        async move {
+            if !sent {
+                return Vec::new();
+            }
+
+            recver.await.unwrap_or_default()
+        }
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to wait for at least `n` of its elements to
+    /// be ready, which they are once they started (after running
+    /// the initializer of the group, if any).
+    ///
+    /// The returned future resolves to `Ok(())` once enough
+    /// elements are ready, or right away if they already are,
+    /// and to `Err(())` if the group stopped before. The elements
+    /// which stopped don't count as ready anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The amount of elements to wait for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # Bastion::start();
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(3)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             // ...
+    /// #           ctx.recv().await?;
+    /// #           Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// # run!(async {
+    /// children_ref
+    ///     .wait_for_children(3)
+    ///     .await
+    ///     .expect("The children group stopped.");
+    /// // The elements are now started...
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn wait_for_children(&self, n: usize) -> impl Future<Output = Result<(), ()>> {
+        debug!(
+            "ChildrenRef({}): Waiting for {} children to be ready.",
+            self.id(),
+            n
+        );
+        let (msg, recver) = BastionMessage::wait_for_children(n);
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self.send(env).is_ok();
+
+        async move {
+            if !sent {
+                return Err(());
+            }
+
+            recver.await.map_err(|_| ())
+        }
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to wait for one of its elements to exit,
+    /// e.g. to start the elements depending on it once it
+    /// stopped.
+    ///
+    /// The returned future resolves to the [`StopReason`] of the
+    /// element once it stopped or faulted, or right away if it
+    /// recently did. It resolves to [`StopReason::Stopped`] if the
+    /// element isn't part of the group, or if the group stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The element to wait for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// # run!(async {
+    /// let elem = &children_ref.elems()[0];
+    /// if children_ref.await_exit(elem).await == StopReason::Faulted {
+    ///     // ...
+    /// }
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`StopReason`]: children_ref/enum.StopReason.html
+    /// [`StopReason::Stopped`]: children_ref/enum.StopReason.html#variant.Stopped
+    pub fn await_exit(&self, child: &ChildRef) -> impl Future<Output = StopReason> {
+        debug!(
+            "ChildrenRef({}): Waiting for Child({}) to exit.",
+            self.id(),
+            child.id()
+        );
+        let (msg, recver) = BastionMessage::await_exit(child.id().clone());
+        let env = Envelope::from_dead_letters(msg);
+        let sent = self.send(env).is_ok();
+
+        async move {
+            if !sent {
+                return StopReason::Stopped;
+            }
+
+            recver.await.unwrap_or(StopReason::Stopped)
+        }
+    }
+
+    /// Checks whether the elements of the children group this
+    /// `ChildrenRef` is referencing are alive, by sending them a
+    /// ping and waiting for them to answer.
+    ///
+    /// The returned future resolves to a [`HealthReport`] once all
+    /// the elements answered, or once `timeout` elapsed. The
+    /// elements answer the ping even if they aren't receiving
+    /// messages, as long as their future doesn't block the thread
+    /// it runs on. The elements which didn't answer in time are
+    /// only reported, and keep running.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the elements to answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// // e.g. when answering a readiness probe.
+    /// # run!(async {
+    /// let report = children_ref.health_check(Duration::from_secs(1)).await;
+    /// if !report.is_healthy() {
+    ///     println!("Unresponsive elements: {:?}", report.timed_out);
+    /// }
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`HealthReport`]: children_ref/struct.HealthReport.html
+    pub fn health_check(&self, timeout: Duration) -> impl Future<Output = HealthReport> {
+        debug!("ChildrenRef({}): Checking health.", self.id());
+        let (sender, mut pongs) = broadcast::channel();
+
+        let mut pending = Vec::with_capacity(self.children.len());
+        let mut timed_out = Vec::new();
+        for child in &self.children {
+            let env = Envelope::new(BastionMessage::ping(), self.path.clone(), sender.clone());
+            match child.send(env) {
+                Ok(()) => pending.push(child.id().clone()),
+                // The element stopped, and won't answer.
+                Err(_) => timed_out.push(child.id().clone()),
+            }
+        }
+
+        // The elements hold the only senders left, which lets the
+        // loop below stop early once they are all dropped.
+        drop(sender);
+
+        async move {
+            let mut responded = Vec::with_capacity(pending.len());
+            let mut timer = Delay::new(timeout);
+
+            while !pending.is_empty() {
+                match future::select(pongs.next(), &mut timer).await {
+                    Either::Left((
+                        Some(Envelope {
+                            msg: BastionMessage::Pong { id },
+                            ..
+                        }),
+                        _,
+                    )) => {
+                        if let Some(pos) = pending.iter().position(|pending| pending == &id) {
+                            responded.push(pending.swap_remove(pos));
+                        }
+                    }
+                    Either::Left((Some(_), _)) => (),
+                    Either::Left((None, _)) | Either::Right(_) => break,
+                }
+            }
+
+            timed_out.append(&mut pending);
+            HealthReport {
+                responded,
+                timed_out,
+            }
+        }
+    }
+
+    /// Asks a message to all the elements of the children group
+    /// this `ChildrenRef` is referencing, and returns a stream of
+    /// their replies in the order they arrive.
+    ///
+    /// Each element is reported exactly once, with the
+    /// [`AskReply`] it answered, or as unreachable if it died or
+    /// dropped the question without answering, or as timed out if
+    /// it didn't answer before `timeout` elapsed. The stream ends
+    /// once all the elements were reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to ask, cloned for each element.
+    /// * `timeout` - How long to wait for the elements to answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::StreamExt;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let children_ref = Bastion::children(|children| children).unwrap();
+    /// # Bastion::start();
+    /// # run!(async {
+    /// let mut replies = children_ref.ask_all("load?", Duration::from_secs(1));
+    /// while let Some((id, reply)) = replies.next().await {
+    ///     match reply {
+    ///         AskReply::Answered(answer) => { /* e.g. aggregate the answer... */ }
+    ///         AskReply::Unreachable => println!("Element {} is dead.", id),
+    ///         AskReply::TimedOut => println!("Element {} didn't answer.", id),
+    ///     }
+    /// }
+    /// # });
+    /// #
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`AskReply`]: children_ref/enum.AskReply.html
+    pub fn ask_all<M: Message + Clone>(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> impl Stream<Item = (BastionId, AskReply)> + Unpin {
+        debug!("ChildrenRef({}): Asking all elements: {:?}", self.id(), msg);
+        let mut ready = VecDeque::new();
+        let mut pending = Vec::with_capacity(self.children.len());
+        let answers = FuturesUnordered::new();
+        for child in &self.children {
+            let id = child.id().clone();
+            match child.ask_anonymously(msg.clone()) {
+                Ok(answer) => {
+                    pending.push(id.clone());
+                    answers.push(answer.map(move |answer| (id, answer)));
+                }
+                Err(_) => ready.push_back((id, AskReply::Unreachable)),
+            }
+        }
+
+        let timer = Delay::new(timeout);
+        Box::pin(stream::unfold(
+            (ready, pending, answers, timer),
+            |(mut ready, mut pending, mut answers, mut timer)| async move {
+                if ready.is_empty() && !answers.is_empty() {
+                    match future::select(answers.next(), &mut timer).await {
+                        Either::Left((Some((id, answer)), _)) => {
+                            pending.retain(|pending| pending != &id);
+                            let reply = match answer {
+                                Ok(answer) => AskReply::Answered(answer),
+                                // The element died without answering.
+                                Err(()) => AskReply::Unreachable,
+                            };
+                            ready.push_back((id, reply));
+                        }
+                        Either::Left((None, _)) => (),
+                        Either::Right(_) => {
+                            answers.clear();
+                            ready.extend(pending.drain(..).map(|id| (id, AskReply::TimedOut)));
+                        }
+                    }
+                }
+
+                let reply = ready.pop_front()?;
+                Some((reply, (ready, pending, answers, timer)))
+            },
+        ))
+    }
+
+    pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
+        trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
+        self.sender.unbounded_send(env).or_else(|err| {
+            SYSTEM
+                .dead_letters()
+                .sender
+                .unbounded_send(err.into_inner())
+                .map_err(|err| err.into_inner())
+        })
+    }
+
+    /// Returns the [`BastionPath`] of this ChildrenRef
+    pub fn path(&self) -> &Arc<BastionPath> {
+        &self.path
+    }
+
+    /// Returns the path made of the names of the children group
+    /// this `ChildrenRef` is referencing and of its parents (e.g.
+    /// `/root/supervisorA/workers`), which is only meant to be
+    /// read by humans.
+    ///
+    /// See [`Children::with_name`] and [`Supervisor::with_name`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let children_ref = Bastion::children(|children| children.with_name("workers"))
+    ///     .expect("Couldn't create the children group.");
+    ///
+    /// assert_eq!(children_ref.named_path(), "/root/workers");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`Supervisor::with_name`]: supervisor/struct.Supervisor.html#method.with_name
+    pub fn named_path(&self) -> &str {
+        &self.named_path
     }
 
     pub(crate) fn sender(&self) -> &Sender {
@@ -256,6 +1524,79 @@ impl ChildrenRef {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The reason why a message sent with
+/// [`ChildrenRef::send_child_confirmed`] wasn't enqueued.
+///
+/// [`ChildrenRef::send_child_confirmed`]: struct.ChildrenRef.html#method.send_child_confirmed
+pub enum SendError {
+    /// The children group has no element with the given
+    /// identifier.
+    UnknownChild,
+    /// The element or the children group stopped.
+    Closed,
+    /// A middleware of the children group dropped the message.
+    Dropped,
+}
+
+impl Display for SendError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            SendError::UnknownChild => write!(fmt, "no element with this identifier"),
+            SendError::Closed => write!(fmt, "the mailbox is closed"),
+            SendError::Dropped => write!(fmt, "the message was dropped by a middleware"),
+        }
+    }
+}
+
+impl Error for SendError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why an element exited, as returned by
+/// [`ChildrenRef::await_exit`].
+///
+/// [`ChildrenRef::await_exit`]: struct.ChildrenRef.html#method.await_exit
+pub enum StopReason {
+    /// The element stopped (or isn't part of the children group).
+    Stopped,
+    /// The element faulted.
+    Faulted,
+}
+
+#[derive(Debug)]
+/// The reply of an element of a children group to a message
+/// asked using [`ChildrenRef::ask_all`].
+///
+/// [`ChildrenRef::ask_all`]: struct.ChildrenRef.html#method.ask_all
+pub enum AskReply {
+    /// The element answered with this message.
+    Answered(SignedMessage),
+    /// The element was dead, or died before answering.
+    Unreachable,
+    /// The element didn't answer before the timeout elapsed.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The result of a [`ChildrenRef::health_check`], telling which
+/// elements of a children group answered in time.
+///
+/// [`ChildrenRef::health_check`]: struct.ChildrenRef.html#method.health_check
+pub struct HealthReport {
+    /// The identifiers of the elements which answered.
+    pub responded: Vec<BastionId>,
+    /// The identifiers of the elements which didn't answer before
+    /// the timeout elapsed.
+    pub timed_out: Vec<BastionId>,
+}
+
+impl HealthReport {
+    /// Returns whether all the elements answered.
+    pub fn is_healthy(&self) -> bool {
+        self.timed_out.is_empty()
+    }
+}
+
 impl PartialEq for ChildrenRef {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
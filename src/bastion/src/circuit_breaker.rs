@@ -0,0 +1,208 @@
+//!
+//! A circuit breaker protecting the system from children groups
+//! whose elements keep faulting.
+use crate::clock::{self, Sleep};
+use crate::context::BastionId;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+/// The configuration of a children group's circuit breaker (see
+/// [`Children::with_circuit_breaker`]).
+///
+/// Once the elements of the group faulted `failure_threshold`
+/// times, the breaker opens: the elements that fault aren't
+/// restarted anymore and the messages sent to the group are
+/// routed to the dead letters. After `cooldown`, the breaker
+/// becomes half-open and restarts one of the faulted elements to
+/// probe whether it is able to run again. If the probe doesn't
+/// fault for another `cooldown`, the breaker closes and the other
+/// faulted elements are restarted, otherwise it opens again.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| {
+///     children.with_circuit_breaker(CircuitBreaker::new(3, Duration::from_secs(10)))
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Children::with_circuit_breaker`]: ../children/struct.Children.html#method.with_circuit_breaker
+pub struct CircuitBreaker {
+    failure_threshold: usize,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Creates a new circuit breaker configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `failure_threshold` - How many times the elements of the
+    ///     group can fault before the breaker opens.
+    /// * `cooldown` - How long the breaker stays open before
+    ///     probing the group, and how long the probe has to run
+    ///     without faulting for the breaker to close.
+    pub fn new(failure_threshold: usize, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Returns how many times the elements of the group can fault
+    /// before the breaker opens.
+    pub fn failure_threshold(&self) -> usize {
+        self.failure_threshold
+    }
+
+    /// Returns how long the breaker stays open before probing the
+    /// group.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+/// The state of a children group's circuit breaker, as returned
+/// by [`ChildrenRef::breaker_state`].
+///
+/// [`ChildrenRef::breaker_state`]: ../children_ref/struct.ChildrenRef.html#method.breaker_state
+pub enum BreakerState {
+    /// The group is working normally (this is also the state of
+    /// the groups without a circuit breaker).
+    Closed = 0,
+    /// The group faulted too many times: its faulted elements
+    /// aren't restarted and its messages are routed to the dead
+    /// letters.
+    Open = 1,
+    /// One of the faulted elements of the group was restarted to
+    /// probe whether the group is able to run again.
+    HalfOpen = 2,
+}
+
+impl BreakerState {
+    fn from_u8(state: u8) -> Self {
+        match state {
+            0 => BreakerState::Closed,
+            1 => BreakerState::Open,
+            _ => BreakerState::HalfOpen,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// A [`BreakerState`] shared between a children group and its
+/// references.
+pub(crate) struct AtomicBreakerState(AtomicU8);
+
+impl AtomicBreakerState {
+    pub(crate) fn get(&self) -> BreakerState {
+        BreakerState::from_u8(self.0.load(Ordering::Acquire))
+    }
+
+    fn set(&self, state: BreakerState) {
+        self.0.store(state as u8, Ordering::Release);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Breaker {
+    config: CircuitBreaker,
+    state: Arc<AtomicBreakerState>,
+    // How many times the elements faulted since the breaker
+    // last closed.
+    failures: usize,
+    // The elements that faulted while the breaker was open and
+    // that are waiting to be restarted.
+    held: VecDeque<BastionId>,
+    cooldown: Option<Sleep>,
+}
+
+impl Breaker {
+    pub(crate) fn new(config: CircuitBreaker, state: Arc<AtomicBreakerState>) -> Self {
+        state.set(BreakerState::Closed);
+
+        Breaker {
+            config,
+            state,
+            failures: 0,
+            held: VecDeque::new(),
+            cooldown: None,
+        }
+    }
+
+    pub(crate) fn state(&self) -> BreakerState {
+        self.state.get()
+    }
+
+    pub(crate) fn accepts_messages(&self) -> bool {
+        self.state() != BreakerState::Open
+    }
+
+    pub(crate) fn cooldown(&mut self) -> Option<&mut Sleep> {
+        self.cooldown.as_mut()
+    }
+
+    /// Records that the given element faulted and returns whether
+    /// it can be restarted right away.
+    pub(crate) fn failed(&mut self, id: &BastionId) -> bool {
+        match self.state() {
+            BreakerState::Closed => {
+                self.failures += 1;
+                if self.failures < self.config.failure_threshold {
+                    return true;
+                }
+            }
+            // The probe faulted.
+            BreakerState::HalfOpen => (),
+            BreakerState::Open => {
+                self.held.push_back(id.clone());
+                return false;
+            }
+        }
+
+        self.held.push_back(id.clone());
+        self.open();
+        false
+    }
+
+    /// Moves the breaker to its next state once its cooldown
+    /// elapsed and returns the elements that can be restarted.
+    pub(crate) fn cooled_down(&mut self) -> Vec<BastionId> {
+        match self.state() {
+            BreakerState::Open => {
+                self.state.set(BreakerState::HalfOpen);
+                self.cooldown = Some(clock::sleep(self.config.cooldown));
+                self.held.pop_front().into_iter().collect()
+            }
+            BreakerState::HalfOpen => {
+                self.state.set(BreakerState::Closed);
+                self.cooldown = None;
+                self.failures = 0;
+                self.held.drain(..).collect()
+            }
+            BreakerState::Closed => {
+                self.cooldown = None;
+                vec![]
+            }
+        }
+    }
+
+    fn open(&mut self) {
+        self.state.set(BreakerState::Open);
+        self.cooldown = Some(clock::sleep(self.config.cooldown));
+    }
+}
@@ -0,0 +1,240 @@
+//!
+//! The clock the time-dependent features read the time from and
+//! sleep with, which can be driven manually in tests.
+//!
+//! The restart backoffs, the restart history windows, the ages of
+//! the elements, the deduplication windows, the circuit breakers'
+//! cooldowns, the fault debounce windows and the poison pills'
+//! timeouts all go through the current clock, which is a [`SystemClock`] unless the `testing`
+//! feature is enabled and another one was installed (see
+//! [`set_clock`]).
+//!
+//! [`SystemClock`]: struct.SystemClock.html
+//! [`set_clock`]: fn.set_clock.html
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use futures_timer::Delay;
+use lazy_static::lazy_static;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+#[cfg(feature = "testing")]
+use {std::sync::Mutex, std::task::Waker};
+
+lazy_static! {
+    static ref CLOCK: RwLock<Arc<dyn Clock>> = RwLock::new(Arc::new(SystemClock));
+}
+
+/// A source of time, telling what time it is and sleeping for a
+/// given duration.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time of this clock.
+    fn now(&self) -> Instant;
+
+    /// Returns a future resolving once `dur` elapsed on this
+    /// clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `dur` - How long to sleep for.
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The clock following the real time, which is used unless another
+/// one was installed.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+        Delay::new(dur).boxed()
+    }
+}
+
+#[cfg(feature = "testing")]
+#[derive(Debug, Clone)]
+/// A clock whose time only moves forward when it is advanced
+/// (see [`ManualClock::advance`]), waking up the futures sleeping
+/// on it whose deadline passed.
+///
+/// This is only available with the `testing` feature and is meant
+/// to test the time-dependent behaviors without sleeping.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// let clock = ManualClock::new();
+/// let start = clock.now();
+///
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(clock.now() - start, Duration::from_secs(60));
+/// ```
+///
+/// [`ManualClock::advance`]: #method.advance
+pub struct ManualClock(Arc<Mutex<ManualTime>>);
+
+#[cfg(feature = "testing")]
+#[derive(Debug)]
+struct ManualTime {
+    now: Instant,
+    // The wakers of the pending sleeps, with their deadline.
+    sleeping: Vec<(Instant, Waker)>,
+}
+
+#[cfg(feature = "testing")]
+struct ManualSleep {
+    deadline: Instant,
+    time: Arc<Mutex<ManualTime>>,
+}
+
+#[cfg(feature = "testing")]
+impl ManualClock {
+    /// Creates a new clock, starting at the current time.
+    pub fn new() -> Self {
+        ManualClock(Arc::new(Mutex::new(ManualTime {
+            now: Instant::now(),
+            sleeping: vec![],
+        })))
+    }
+
+    /// Moves the time of this clock forward, waking up the futures
+    /// sleeping on it whose deadline passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dur` - How much time elapses.
+    pub fn advance(&self, dur: Duration) {
+        let mut time = self.0.lock().unwrap();
+        time.now += dur;
+
+        let now = time.now;
+        let (elapsed, sleeping) = time
+            .sleeping
+            .drain(..)
+            .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+        time.sleeping = sleeping;
+        drop(time);
+
+        for (_, waker) in elapsed {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.0.lock().unwrap().now
+    }
+
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+        ManualSleep {
+            deadline: self.now() + dur,
+            time: self.0.clone(),
+        }
+        .boxed()
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Future for ManualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let mut time = self.time.lock().unwrap();
+        if time.now >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        // The sleep is polled again on each spurious wake-up, which
+        // shouldn't register the same waker once more.
+        let deadline = self.deadline;
+        let registered = time
+            .sleeping
+            .iter()
+            .any(|(other, waker)| *other == deadline && waker.will_wake(cx.waker()));
+        if !registered {
+            time.sleeping.push((deadline, cx.waker().clone()));
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "testing")]
+/// Installs the clock the time-dependent features use from now on,
+/// instead of the [`SystemClock`].
+///
+/// This is only available with the `testing` feature. The sleeps
+/// that already started keep using the previous clock.
+///
+/// # Arguments
+///
+/// * `clock` - The clock to install.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::clock;
+/// #
+/// let manual = ManualClock::new();
+/// clock::set_clock(manual.clone());
+/// # clock::set_clock(SystemClock);
+/// ```
+///
+/// [`SystemClock`]: struct.SystemClock.html
+pub fn set_clock<C: Clock + 'static>(clock: C) {
+    *CLOCK.write().unwrap() = Arc::new(clock);
+}
+
+fn current() -> Arc<dyn Clock> {
+    CLOCK.read().unwrap().clone()
+}
+
+/// Returns the current time of the current clock.
+pub(crate) fn now() -> Instant {
+    current().now()
+}
+
+/// Returns a future resolving once `dur` elapsed on the current
+/// clock.
+pub(crate) fn sleep(dur: Duration) -> Sleep {
+    Sleep(current().sleep(dur))
+}
+
+/// A sleep on the clock that was current when it started, which
+/// can be stored by the elements polling it along with their
+/// other timers.
+pub(crate) struct Sleep(BoxFuture<'static, ()>);
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+impl Debug for Sleep {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Sleep").finish()
+    }
+}
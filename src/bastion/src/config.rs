@@ -1,9 +1,22 @@
+use crate::executor::{Executor, OverLimitPolicy, ThreadPriority};
+use std::sync::Arc;
+
 #[derive(Default, Debug, Clone)]
 /// The configuration that should be used to initialize the
 /// system using [`Bastion::init_with`].
 ///
 /// The default behaviors are the following:
 /// - All backtraces are shown (see [`Config::show_backtraces`]).
+/// - Processes run on the [`BastionExecutor`].
+/// - The mean load of the run queues isn't smoothed (see
+///   [`Config::with_mean_smoothing`]).
+/// - The workers always steal processes from each other (see
+///   [`Config::with_steal_threshold`]).
+/// - The amount of live processes isn't limited (see
+///   [`Config::with_max_tasks`]), and the spawns over the limit
+///   are rejected (see [`Config::with_over_limit_policy`]).
+/// - The worker threads run at the normal OS priority (see
+///   [`Config::with_worker_priority`]).
 ///
 /// # Example
 ///
@@ -22,8 +35,20 @@
 /// ```
 ///
 /// [`Bastion::init_with`]: struct.Bastion.html#method.init_with
+/// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+/// [`Config::with_mean_smoothing`]: #method.with_mean_smoothing
+/// [`Config::with_steal_threshold`]: #method.with_steal_threshold
+/// [`Config::with_max_tasks`]: #method.with_max_tasks
+/// [`Config::with_over_limit_policy`]: #method.with_over_limit_policy
+/// [`Config::with_worker_priority`]: #method.with_worker_priority
 pub struct Config {
     backtraces: Backtraces,
+    executor: Option<Arc<dyn Executor>>,
+    mean_smoothing: Option<f64>,
+    steal_threshold: Option<usize>,
+    max_tasks: Option<usize>,
+    over_limit_policy: Option<OverLimitPolicy>,
+    worker_priority: Option<ThreadPriority>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -40,8 +65,10 @@ impl Config {
     /// Creates a new configuration with the following default
     /// behaviors:
     /// - All backtraces are shown (see [`Config::show_backtraces`]).
+    /// - Processes run on the [`BastionExecutor`].
     ///
     /// [`Config::show_backtraces`]: #method.show_backtraces
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
     pub fn new() -> Self {
         Config::default()
     }
@@ -101,9 +128,269 @@ impl Config {
         self
     }
 
+    /// Makes Bastion run its processes on the given [`Executor`]
+    /// instead of the [`BastionExecutor`].
+    ///
+    /// This has to be set before the system is initialized, as the
+    /// system's own processes are spawned during its initialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor that will run the processes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    /// use bastion::executor::BastionExecutor;
+    ///
+    /// let config = Config::new().with_executor(BastionExecutor);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion and it will run on the
+    /// // given executor...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`Executor`]: executor/trait.Executor.html
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    pub fn with_executor<E: Executor>(mut self, executor: E) -> Self {
+        self.executor = Some(Arc::new(executor));
+        self
+    }
+
+    /// Sets the smoothing factor of the mean load of the
+    /// [`BastionExecutor`]'s run queues, which its workers compare
+    /// their own load to when deciding whether to steal processes.
+    ///
+    /// The mean is an exponentially weighted moving average in
+    /// which each new sample is weighted by `smoothing`. Lower
+    /// factors make it less sensitive to bursts of processes, which
+    /// keeps the workers from oscillating between stealing and not
+    /// stealing. The default factor of `1.0` makes the mean follow
+    /// the instantaneous load.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - The weight of each new sample, in
+    ///     `(0.0, 1.0]`.
+    ///
+    /// # Panics
+    ///
+    /// The system panics when it is initialized if `smoothing`
+    /// isn't in `(0.0, 1.0]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// let config = Config::new().with_mean_smoothing(0.2);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion and its workers won't react
+    /// // as much to bursts of processes...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    pub fn with_mean_smoothing(mut self, smoothing: f64) -> Self {
+        self.mean_smoothing = Some(smoothing);
+        self
+    }
+
+    /// Sets the mean load of the [`BastionExecutor`]'s run queues
+    /// under which its workers stop stealing processes from each
+    /// other.
+    ///
+    /// Under a light load, stealing the few processes queued by
+    /// another worker moves them across cores for little benefit,
+    /// while the idle workers keep spinning over the other
+    /// workers' queues. Below the threshold, they only take the
+    /// processes from the global queues and park otherwise. The
+    /// default threshold of `0` never stops the steals.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The mean amount of processes per run queue
+    ///     under which the steals stop.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// let config = Config::new().with_steal_threshold(2);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion and its workers will park
+    /// // instead of stealing under a light load...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    pub fn with_steal_threshold(mut self, threshold: usize) -> Self {
+        self.steal_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the maximum amount of live processes spawned on the
+    /// [`BastionExecutor`], over which the spawns are rejected.
+    ///
+    /// This is a safety valve for bursty or untrusted workloads,
+    /// which would otherwise exhaust the memory. A process is
+    /// live from the moment it is spawned until it completes or
+    /// gets cancelled, and the processes spawned over the limit
+    /// are dropped without being run, their handles resolving to
+    /// `None`. By default, the amount of live processes isn't
+    /// limited.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum amount of live processes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// let config = Config::new().with_max_tasks(100_000);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    pub fn with_max_tasks(mut self, max: usize) -> Self {
+        self.max_tasks = Some(max);
+        self
+    }
+
+    /// Sets what spawning a process on the [`BastionExecutor`]
+    /// does once the maximum amount of live processes set with
+    /// [`Config::with_max_tasks`] was reached.
+    ///
+    /// By default, the spawns are rejected. They can instead
+    /// block the spawning thread until a live process completes,
+    /// optionally giving up after a timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - What the spawns over the limit do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::executor::OverLimitPolicy;
+    /// use bastion::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let config = Config::new()
+    ///     .with_max_tasks(100_000)
+    ///     .with_over_limit_policy(OverLimitPolicy::BlockWithTimeout(Duration::from_secs(1)));
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    /// [`Config::with_max_tasks`]: #method.with_max_tasks
+    pub fn with_over_limit_policy(mut self, policy: OverLimitPolicy) -> Self {
+        self.over_limit_policy = Some(policy);
+        self
+    }
+
+    /// Sets the OS scheduling priority that the worker threads
+    /// of the [`BastionExecutor`] run at, which is the normal
+    /// one by default.
+    ///
+    /// The priority is set when the workers start, right after
+    /// they were pinned to their core. Elevating it usually
+    /// requires privileges (e.g. `CAP_SYS_NICE` on Linux): if
+    /// the process lacks them, a warning is printed and the
+    /// workers keep the normal priority.
+    ///
+    /// This only has an effect if the workers didn't start yet,
+    /// which is the case unless a process was spawned before
+    /// [`Bastion::init_with`] was called.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the worker threads.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::executor::ThreadPriority;
+    /// use bastion::prelude::*;
+    ///
+    /// let config = Config::new().with_worker_priority(ThreadPriority::AboveNormal);
+    ///
+    /// Bastion::init_with(config);
+    ///
+    /// // You can now use bastion...
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`BastionExecutor`]: executor/struct.BastionExecutor.html
+    /// [`Bastion::init_with`]: struct.Bastion.html#method.init_with
+    pub fn with_worker_priority(mut self, priority: ThreadPriority) -> Self {
+        self.worker_priority = Some(priority);
+        self
+    }
+
     pub(crate) fn backtraces(&self) -> &Backtraces {
         &self.backtraces
     }
+
+    pub(crate) fn executor(&self) -> Option<&Arc<dyn Executor>> {
+        self.executor.as_ref()
+    }
+
+    pub(crate) fn mean_smoothing(&self) -> Option<f64> {
+        self.mean_smoothing
+    }
+
+    pub(crate) fn steal_threshold(&self) -> Option<usize> {
+        self.steal_threshold
+    }
+
+    pub(crate) fn max_tasks(&self) -> Option<usize> {
+        self.max_tasks
+    }
+
+    pub(crate) fn over_limit_policy(&self) -> Option<OverLimitPolicy> {
+        self.over_limit_policy
+    }
+
+    pub(crate) fn worker_priority(&self) -> Option<ThreadPriority> {
+        self.worker_priority
+    }
 }
 
 impl Backtraces {
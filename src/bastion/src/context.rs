@@ -2,19 +2,26 @@
 //! A context allows a child's future to access its received
 //! messages, parent and supervisor.
 
+use crate::backpressure::Backpressure;
 use crate::child_ref::ChildRef;
 use crate::children_ref::ChildrenRef;
+use crate::deadlock::AskEdge;
 use crate::dispatcher::{BroadcastTarget, DispatcherType, NotificationType};
 use crate::envelope::{Envelope, RefAddr, SignedMessage};
+#[cfg(feature = "mailbox-latency")]
+use crate::latency::LatencyHistogram;
 use crate::message::{Answer, BastionMessage, Message, Msg};
 use crate::supervisor::SupervisorRef;
 use crate::system::SYSTEM;
 use async_mutex::Mutex;
 use futures::pending;
+use std::any::TypeId;
 use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
 use std::pin::Pin;
 use std::sync::Arc;
+#[cfg(feature = "mailbox-latency")]
+use std::time::Instant;
 use tracing::{debug, trace};
 use uuid::Uuid;
 
@@ -109,6 +116,15 @@ pub struct BastionContext {
 #[derive(Debug)]
 pub(crate) struct ContextState {
     messages: VecDeque<SignedMessage>,
+    // The watermarks of the mailbox, if the group of the child
+    // sheds its load.
+    backpressure: Option<Backpressure>,
+    // Whether the messages are kept in the mailbox instead of
+    // being received, because the subtree of the child was
+    // suspended.
+    suspended: bool,
+    #[cfg(feature = "mailbox-latency")]
+    latency: LatencyHistogram,
 }
 
 impl BastionId {
@@ -296,7 +312,7 @@ impl BastionContext {
         let state = self.state.clone();
         let mut guard = state.lock().await;
 
-        if let Some(msg) = guard.pop_message() {
+        if let Some(msg) = guard.receive_message() {
             trace!("BastionContext({}): Received message: {:?}", self.id, msg);
             Some(msg)
         } else {
@@ -305,6 +321,61 @@ impl BastionContext {
         }
     }
 
+    /// Inspects the next message received by the element this
+    /// `BastionContext` is linked to, if there is one, without
+    /// retrieving it.
+    ///
+    /// `f` is called with the message, which stays in the mailbox
+    /// and is the next one retrieved by [`recv`] or [`try_recv`].
+    /// This allows deciding whether to retrieve it now, e.g.
+    /// depending on its priority.
+    ///
+    /// This method returns what `f` returned if a message was
+    /// available, or `None` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure called with the next message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let urgent = ctx.peek(|msg| msg.type_tag() == "&str").await;
+    ///             if urgent == Some(true) {
+    ///                 let msg: SignedMessage = ctx.recv().await?;
+    ///                 // Handle the message...
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`try_recv`]: #method.try_recv
+    pub async fn peek<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&SignedMessage) -> R,
+    {
+        debug!("BastionContext({}): Peeking at the next message.", self.id);
+        let state = self.state.clone();
+        let guard = state.lock().await;
+
+        guard.peek_message().map(f)
+    }
+
     /// Retrieves asynchronously a message received by the element
     /// this `BastionContext` is linked to and waits (always
     /// asynchronously) for one if none has been received yet.
@@ -346,7 +417,7 @@ impl BastionContext {
             let state = self.state.clone();
             let mut guard = state.lock().await;
 
-            if let Some(msg) = guard.pop_message() {
+            if let Some(msg) = guard.receive_message() {
                 trace!("BastionContext({}): Received message: {:?}", self.id, msg);
                 return Ok(msg);
             }
@@ -356,6 +427,24 @@ impl BastionContext {
         }
     }
 
+    /// Returns the histogram of the time the messages received by
+    /// the element this `BastionContext` is linked to spent in its
+    /// mailbox, since it was first started.
+    ///
+    /// The messages are stamped when they are sent and their
+    /// latency is recorded when they are received using [`recv`]
+    /// or [`try_recv`].
+    ///
+    /// This method is only available with the `mailbox-latency`
+    /// feature.
+    ///
+    /// [`recv`]: #method.recv
+    /// [`try_recv`]: #method.try_recv
+    #[cfg(feature = "mailbox-latency")]
+    pub async fn mailbox_latency(&self) -> LatencyHistogram {
+        self.state.lock().await.latency().clone()
+    }
+
     /// Returns [`RefAddr`] of the current `BastionContext`
     ///
     /// # Example
@@ -438,12 +527,181 @@ impl BastionContext {
             .map_err(|err| err.into_inner().into_msg().unwrap())
     }
 
+    /// Forwards a received message to the given [`RefAddr`] as is,
+    /// without copying it and keeping the signature of its original
+    /// sender, which allows building explicit routing topologies.
+    ///
+    /// Questions stay answerable once relayed: the element they are
+    /// relayed to answers the original sender directly.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The [`RefAddr`] to relay the message to.
+    /// * `msg` - The received message to relay.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let workers = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| async move {
+    ///         loop {
+    ///             msg! { ctx.recv().await?,
+    ///                 n: u32 =!> { answer!(ctx, n * 2).unwrap(); };
+    ///                 _: _ => ();
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let workers = workers.clone();
+    ///         async move {
+    ///             let worker = workers.elems()[0].addr();
+    ///             loop {
+    ///                 let msg: SignedMessage = ctx.recv().await?;
+    ///                 ctx.relay(&worker, msg).expect("Couldn't relay the message.");
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
+    pub fn relay(&self, to: &RefAddr, msg: SignedMessage) -> Result<(), SignedMessage> {
+        debug!(
+            "{:?}: Relaying message: {:?} from: {:?} to: {:?}",
+            self.current().path(),
+            msg.msg,
+            msg.sign.path(),
+            to.path()
+        );
+        let env = Envelope::new_with_sign(BastionMessage::Message(msg.msg), msg.sign);
+        to.sender().unbounded_send(env).map_err(|err| {
+            let env = err.into_inner();
+            match env.msg {
+                BastionMessage::Message(msg) => SignedMessage::new(msg, env.sign),
+                _ => unreachable!(),
+            }
+        })
+    }
+
+    /// Subscribes the current element to the given topic, making
+    /// it receive the messages published to it using
+    /// [`ChildrenRef::publish`] on its children group.
+    ///
+    /// The subscription is removed when the element stops.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.subscribe("news").expect("Couldn't subscribe.");
+    ///             // ...
+    ///             # Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`ChildrenRef::publish`]: children/struct.ChildrenRef.html#method.publish
+    pub fn subscribe(&self, topic: impl Into<String>) -> Result<(), ()> {
+        let topic = topic.into();
+        debug!(
+            "{:?}: Subscribing to topic: {}",
+            self.current().path(),
+            topic
+        );
+        let msg = BastionMessage::subscribe(self.id.clone(), topic);
+        let env = Envelope::new_with_sign(msg, self.signature());
+        self.children.send(env).map_err(|_| ())
+    }
+
+    /// Unsubscribes the current element from the given topic (see
+    /// [`subscribe`]).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to unsubscribe from.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.subscribe("news").expect("Couldn't subscribe.");
+    ///             // ...
+    ///             ctx.unsubscribe("news").expect("Couldn't unsubscribe.");
+    ///             # Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`subscribe`]: #method.subscribe
+    pub fn unsubscribe(&self, topic: impl Into<String>) -> Result<(), ()> {
+        let topic = topic.into();
+        debug!(
+            "{:?}: Unsubscribing from topic: {}",
+            self.current().path(),
+            topic
+        );
+        let msg = BastionMessage::unsubscribe(self.id.clone(), topic);
+        let env = Envelope::new_with_sign(msg, self.signature());
+        self.children.send(env).map_err(|_| ())
+    }
+
     /// Sends a message from behalf of current context to the addr,
     /// allowing to addr owner answer.
     ///
     /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
     /// otherwise.
     ///
+    /// If the element owning `to` is itself (transitively) waiting
+    /// for an answer from this element, the two would wait for each
+    /// other forever: the deadlock is logged and the returned
+    /// [`Answer`] resolves to an error right away instead.
+    ///
     /// # Argument
     ///
     /// * `msg` - The message to send.
@@ -522,6 +780,13 @@ impl BastionContext {
             to
         );
         let (msg, answer) = BastionMessage::ask(msg);
+        let edge = AskEdge::register(self.current().id(), to.path().id());
+        let answer = answer.with_edge(edge);
+        if answer.is_deadlocked() {
+            // The question would never be answered.
+            return Ok(answer);
+        }
+
         let env = Envelope::new_with_sign(msg, self.signature());
         // FIXME: panics?
         to.sender()
@@ -555,10 +820,10 @@ impl BastionContext {
     ///
     /// [`BroadcastTarget`]: ../dispatcher/enum.DispatcherType.html
     pub fn broadcast_message<M: Message>(&self, target: BroadcastTarget, message: M) {
-        let msg = Arc::new(SignedMessage {
-            msg: Msg::broadcast(message),
-            sign: self.signature(),
-        });
+        let msg = Arc::new(SignedMessage::new(
+            Msg::broadcast(message),
+            self.signature(),
+        ));
 
         let global_dispatcher = SYSTEM.dispatcher();
         global_dispatcher.broadcast_message(target, &msg);
@@ -569,6 +834,10 @@ impl ContextState {
     pub(crate) fn new() -> Self {
         ContextState {
             messages: VecDeque::new(),
+            backpressure: None,
+            suspended: false,
+            #[cfg(feature = "mailbox-latency")]
+            latency: LatencyHistogram::new(),
         }
     }
 
@@ -576,8 +845,100 @@ impl ContextState {
         self.messages.push_back(SignedMessage::new(msg, sign))
     }
 
+    #[cfg(feature = "mailbox-latency")]
+    pub(crate) fn push_enqueued_message(&mut self, msg: Msg, sign: RefAddr, enqueued_at: Instant) {
+        let mut msg = SignedMessage::new(msg, sign);
+        msg.enqueued_at = enqueued_at;
+        self.messages.push_back(msg)
+    }
+
+    #[cfg(feature = "mailbox-latency")]
+    pub(crate) fn latency(&self) -> &LatencyHistogram {
+        &self.latency
+    }
+
+    /// Returns the number of messages waiting to be received.
+    pub(crate) fn mailbox_len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub(crate) fn with_backpressure(mut self, backpressure: Option<Backpressure>) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    pub(crate) fn backpressure(&self) -> Option<Backpressure> {
+        self.backpressure
+    }
+
+    pub(crate) fn set_backpressure(&mut self, backpressure: Backpressure) {
+        self.backpressure = Some(backpressure);
+    }
+
+    pub(crate) fn with_suspended(mut self, suspended: bool) -> Self {
+        self.suspended = suspended;
+        self
+    }
+
+    pub(crate) fn set_suspended(&mut self, suspended: bool) {
+        self.suspended = suspended;
+    }
+
+    /// Removes the messages of the given type waiting to be
+    /// received, except the questions, and returns how many were
+    /// removed.
+    pub(crate) fn remove_pending(&mut self, type_id: TypeId) -> usize {
+        let len = self.messages.len();
+        self.messages
+            .retain(|pending| pending.msg.is_ask() || pending.msg.payload_type_id() != type_id);
+
+        len - self.messages.len()
+    }
+
+    /// Replaces the messages of the given type waiting to be
+    /// received, except the questions, by the given ones, which
+    /// will be received first.
+    pub(crate) fn replace_pending(&mut self, type_id: TypeId, msgs: Vec<(Msg, RefAddr)>) {
+        self.remove_pending(type_id);
+        for (msg, sign) in msgs.into_iter().rev() {
+            self.messages.push_front(SignedMessage::new(msg, sign));
+        }
+    }
+
+    /// Removes all the messages waiting to be received and returns
+    /// them in the order they would have been received.
+    pub(crate) fn drain_messages(&mut self) -> Vec<SignedMessage> {
+        self.messages.drain(..).collect()
+    }
+
+    pub(crate) fn peek_message(&self) -> Option<&SignedMessage> {
+        if self.suspended {
+            return None;
+        }
+
+        self.messages.front()
+    }
+
+    /// Pops the next message to be received by the child, unless
+    /// it is suspended.
+    pub(crate) fn receive_message(&mut self) -> Option<SignedMessage> {
+        if self.suspended {
+            return None;
+        }
+
+        self.pop_message()
+    }
+
     pub(crate) fn pop_message(&mut self) -> Option<SignedMessage> {
-        self.messages.pop_front()
+        let msg = self.messages.pop_front();
+        #[cfg(feature = "mailbox-latency")]
+        {
+            if let Some(msg) = &msg {
+                self.latency.record(msg.enqueued_at.elapsed());
+            }
+        }
+
+        msg
     }
 }
 
@@ -0,0 +1,265 @@
+//!
+//! Detection of the deadlocks caused by elements asking each
+//! other (see `BastionContext::ask`).
+//!
+//! Each pending ask is an edge from the asking element to the
+//! asked one, the edges being indexed by the asking element. When
+//! an element asks an element which is already waiting for its
+//! answer, the ask is failed right away without being sent. The
+//! longer cycles are looked for by the load balancer thread each
+//! time it samples the runtime (about four times a second), which
+//! keeps walking the graph off the path of the asks. One ask of
+//! each cycle found is then resolved with an error, which unblocks
+//! the element that sent it and lets it answer the other asks of
+//! the cycle.
+//!
+//! An element is considered waiting for its answers as long as it
+//! didn't drop them, which makes this a heuristic: an element
+//! keeping an [`Answer`] around without awaiting it can be wrongly
+//! reported as deadlocked.
+//!
+//! [`Answer`]: ../message/struct.Answer.html
+use crate::context::BastionId;
+use bastion_executor::load_balancer;
+use futures::task::AtomicWaker;
+use fxhash::{FxHashMap, FxHashSet};
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::task::Context;
+use tracing::error;
+
+lazy_static! {
+    static ref ASKS: Mutex<AskGraph> = Mutex::new(AskGraph::default());
+}
+
+#[derive(Debug, Default)]
+struct AskGraph {
+    next_edge: u64,
+    // The pending asks, by the element which sent them and their
+    // edge identifier.
+    asks: FxHashMap<BastionId, FxHashMap<u64, (BastionId, Arc<Deadlock>)>>,
+}
+
+#[derive(Debug, Default)]
+/// Whether a pending ask was found to be part of a cycle, shared
+/// by the graph and the answer waiting for it.
+pub(crate) struct Deadlock {
+    found: AtomicBool,
+    waker: AtomicWaker,
+}
+
+#[derive(Debug)]
+/// A pending ask registered in the graph, which is removed from it
+/// once dropped.
+pub(crate) enum AskEdge {
+    Pending {
+        from: BastionId,
+        id: u64,
+        deadlock: Arc<Deadlock>,
+    },
+    /// The ask closed a cycle and wasn't sent.
+    Deadlocked,
+}
+
+impl AskGraph {
+    /// Returns whether `from` is waiting for an answer from `to`.
+    fn is_asking(&self, from: &BastionId, to: &BastionId) -> bool {
+        self.asks
+            .get(from)
+            .map(|asks| asks.values().any(|(asked, _)| asked == to))
+            .unwrap_or(false)
+    }
+
+    /// Returns the elements `from` is waiting for an answer from,
+    /// leaving out the asks already found to be deadlocked.
+    fn asked(&self, from: &BastionId) -> Vec<(&BastionId, &Arc<Deadlock>)> {
+        self.asks
+            .get(from)
+            .into_iter()
+            .flat_map(|asks| asks.values())
+            .filter(|(_, deadlock)| !deadlock.is_found())
+            .map(|(asked, deadlock)| (asked, deadlock))
+            .collect()
+    }
+
+    /// Marks one ask of each cycle of the graph as deadlocked,
+    /// returning the cycles.
+    fn break_cycles(&self) -> Vec<Vec<BastionId>> {
+        let mut cycles = Vec::new();
+        let mut visited = FxHashSet::default();
+
+        for start in self.asks.keys() {
+            if visited.contains(start) {
+                continue;
+            }
+
+            // The elements waiting for each other from `start`,
+            // along with the asks left to follow for each.
+            let mut path = vec![(start, self.asked(start))];
+            let mut on_path = FxHashSet::default();
+            on_path.insert(start);
+
+            while let Some((from, asked)) = path.last_mut() {
+                let from = *from;
+                let (to, deadlock) = match asked.pop() {
+                    Some(ask) => ask,
+                    None => {
+                        on_path.remove(from);
+                        visited.insert(from.clone());
+                        path.pop();
+                        continue;
+                    }
+                };
+
+                if on_path.contains(to) {
+                    let cycle = path
+                        .iter()
+                        .map(|(id, _)| (*id).clone())
+                        .skip_while(|id| id != to)
+                        .collect();
+                    deadlock.find();
+                    cycles.push(cycle);
+                } else if !visited.contains(to) {
+                    on_path.insert(to);
+                    path.push((to, self.asked(to)));
+                }
+            }
+        }
+
+        cycles
+    }
+}
+
+impl Deadlock {
+    fn is_found(&self) -> bool {
+        self.found.load(Ordering::Acquire)
+    }
+
+    fn find(&self) {
+        self.found.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+}
+
+impl AskEdge {
+    /// Registers that `from` is waiting for an answer from `to`,
+    /// unless `to` already is waiting for an answer from `from`.
+    pub(crate) fn register(from: &BastionId, to: &BastionId) -> Self {
+        static DETECTION: Once = Once::new();
+        DETECTION.call_once(|| {
+            // The asks are still failed when they directly wait for
+            // each other.
+            if let Err(err) = load_balancer::on_sample(detect) {
+                error!("Couldn't start the deadlock detection: {}", err);
+            }
+        });
+
+        let mut graph = ASKS.lock().unwrap();
+        if from == to || graph.is_asking(to, from) {
+            error!(
+                "Deadlock detected: {:?} asked {:?} while it is waiting for its answer",
+                from, to
+            );
+            return AskEdge::Deadlocked;
+        }
+
+        let id = graph.next_edge;
+        graph.next_edge += 1;
+        let deadlock = Arc::new(Deadlock::default());
+        graph
+            .asks
+            .entry(from.clone())
+            .or_default()
+            .insert(id, (to.clone(), deadlock.clone()));
+
+        AskEdge::Pending {
+            from: from.clone(),
+            id,
+            deadlock,
+        }
+    }
+
+    pub(crate) fn is_deadlocked(&self) -> bool {
+        match self {
+            AskEdge::Deadlocked => true,
+            AskEdge::Pending { deadlock, .. } => deadlock.is_found(),
+        }
+    }
+
+    /// Returns whether the ask is deadlocked, waking up the task
+    /// once it is found to be otherwise.
+    pub(crate) fn poll_deadlocked(&self, ctx: &mut Context) -> bool {
+        if let AskEdge::Pending { deadlock, .. } = self {
+            deadlock.waker.register(ctx.waker());
+        }
+
+        self.is_deadlocked()
+    }
+}
+
+impl Drop for AskEdge {
+    fn drop(&mut self) {
+        if let AskEdge::Pending { from, id, .. } = self {
+            if let Ok(mut graph) = ASKS.lock() {
+                if let Some(asks) = graph.asks.get_mut(from) {
+                    asks.remove(id);
+                    if asks.is_empty() {
+                        graph.asks.remove(from);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fails one ask of each cycle of elements waiting for each other.
+fn detect() {
+    let cycles = match ASKS.lock() {
+        Ok(graph) => graph.break_cycles(),
+        Err(_) => return,
+    };
+
+    for cycle in cycles {
+        error!(
+            "Deadlock detected: elements are waiting for each other: {:?}",
+            cycle
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect, AskEdge};
+    use crate::context::BastionId;
+
+    #[test]
+    fn detects_cycles() {
+        let (a, b, c) = (BastionId::new(), BastionId::new(), BastionId::new());
+
+        let a_b = AskEdge::register(&a, &b);
+        let b_c = AskEdge::register(&b, &c);
+        assert!(!a_b.is_deadlocked());
+        assert!(!b_c.is_deadlocked());
+
+        // The asks directly waiting for each other are failed right
+        // away...
+        assert!(AskEdge::register(&b, &a).is_deadlocked());
+        assert!(AskEdge::register(&a, &a).is_deadlocked());
+
+        // ...while the longer cycles are broken once detected.
+        let c_a = AskEdge::register(&c, &a);
+        detect();
+        let deadlocked = [&a_b, &b_c, &c_a]
+            .iter()
+            .filter(|edge| edge.is_deadlocked())
+            .count();
+        assert_eq!(deadlocked, 1);
+
+        // Once answered, the asks don't block anymore.
+        drop(c_a);
+        drop(a_b);
+        assert!(!AskEdge::register(&c, &a).is_deadlocked());
+        drop(b_c);
+    }
+}
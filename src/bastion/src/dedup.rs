@@ -0,0 +1,152 @@
+//!
+//! Receive-side deduplication of the messages sent to the elements
+//! of a children group (see `Children::dedup_by`).
+use crate::clock;
+use crate::message::{Message, Msg};
+use fxhash::FxHashMap;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The maximum amount of keys remembered by each element, whatever
+/// the window is.
+pub(crate) const DEDUP_CAPACITY: usize = 1024;
+
+pub(crate) trait Dedup: Send {
+    /// Returns whether a message with the same key as this one was
+    /// received within the window, and remembers its key otherwise.
+    fn is_duplicate(&mut self, msg: &Msg) -> bool;
+}
+
+#[derive(Clone)]
+/// Creates the deduplication state of each element of a group.
+pub(crate) struct DedupFactory(Arc<dyn Fn() -> Box<dyn Dedup> + Send + Sync>);
+
+pub(crate) struct DedupBy<M, K, F> {
+    key: Arc<F>,
+    window: Duration,
+    // The keys seen within the window, with the time they were
+    // first seen at...
+    seen: FxHashMap<K, Instant>,
+    // ...in the order they were seen in, to evict the oldest ones.
+    order: VecDeque<(K, Instant)>,
+    _msg: PhantomData<fn(&M)>,
+}
+
+impl DedupFactory {
+    pub(crate) fn new<M, K, F>(key: F, window: Duration) -> Self
+    where
+        M: Message,
+        K: Hash + Eq + Clone + Send + 'static,
+        F: Fn(&M) -> K + Send + Sync + 'static,
+    {
+        let key = Arc::new(key);
+        let factory = move || {
+            let dedup: Box<dyn Dedup> = Box::new(DedupBy::new(key.clone(), window));
+            dedup
+        };
+
+        DedupFactory(Arc::new(factory))
+    }
+
+    pub(crate) fn build(&self) -> Box<dyn Dedup> {
+        (self.0)()
+    }
+}
+
+impl<M, K, F> DedupBy<M, K, F>
+where
+    K: Hash + Eq + Clone,
+{
+    fn new(key: Arc<F>, window: Duration) -> Self {
+        DedupBy {
+            key,
+            window,
+            seen: FxHashMap::default(),
+            order: VecDeque::new(),
+            _msg: PhantomData,
+        }
+    }
+
+    fn evict(&mut self, now: Instant) {
+        while let Some((_, seen_at)) = self.order.front() {
+            let expired = now.duration_since(*seen_at) >= self.window;
+            if !expired && self.order.len() <= DEDUP_CAPACITY {
+                break;
+            }
+
+            let (key, seen_at) = self.order.pop_front().unwrap();
+            // The key might have been seen again since then.
+            if self.seen.get(&key) == Some(&seen_at) {
+                self.seen.remove(&key);
+            }
+        }
+    }
+}
+
+impl<M, K, F> Dedup for DedupBy<M, K, F>
+where
+    M: Message,
+    K: Hash + Eq + Clone + Send,
+    F: Fn(&M) -> K + Send + Sync,
+{
+    fn is_duplicate(&mut self, msg: &Msg) -> bool {
+        let key = match msg.inner_ref::<M>() {
+            Some(msg) => (self.key)(msg),
+            // Only the messages of the given type are deduplicated.
+            None => return false,
+        };
+
+        let now = clock::now();
+        self.evict(now);
+
+        if self.seen.contains_key(&key) {
+            return true;
+        }
+
+        self.seen.insert(key.clone(), now);
+        self.order.push_back((key, now));
+        self.evict(now);
+
+        false
+    }
+}
+
+impl Debug for DedupFactory {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("DedupFactory").finish()
+    }
+}
+
+impl Debug for dyn Dedup {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Dedup").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupFactory;
+    use crate::message::Msg;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn drops_duplicates_within_window() {
+        let factory = DedupFactory::new(|msg: &(u8, &str)| msg.0, Duration::from_millis(50));
+        let mut dedup = factory.build();
+
+        assert!(!dedup.is_duplicate(&Msg::tell((1u8, "first"))));
+        assert!(dedup.is_duplicate(&Msg::tell((1u8, "retry"))));
+        assert!(!dedup.is_duplicate(&Msg::tell((2u8, "other"))));
+        // Messages of other types aren't deduplicated.
+        assert!(!dedup.is_duplicate(&Msg::tell("untracked")));
+        assert!(!dedup.is_duplicate(&Msg::tell("untracked")));
+
+        thread::sleep(Duration::from_millis(60));
+        assert!(!dedup.is_duplicate(&Msg::tell((1u8, "later"))));
+    }
+}
@@ -0,0 +1,60 @@
+//!
+//! The delivery guarantees which can be chosen when sending a
+//! message to an element of a children group.
+
+/// How hard sending a message to an element of a children group
+/// (e.g. using [`ChildRef::tell_anonymously_with`]) tries to
+/// enqueue it in the element's mailbox.
+///
+/// A mailbox is transiently full while its element is at
+/// capacity, which only happens when its group sheds its load
+/// (see [`Children::with_backpressure`]).
+///
+/// Exactly-once delivery isn't offered: a message which was
+/// enqueued is still lost if its element stops or gets killed
+/// before handling it, and a message which is sent again after
+/// its sender gave up on it might be handled twice.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # Bastion::init();
+/// #
+/// # let children_ref = Bastion::children(|children| children).unwrap();
+/// # let child_ref = &children_ref.elems()[0];
+/// # Bastion::start();
+/// # run!(async {
+/// child_ref
+///     .tell_anonymously_with("A message.", DeliveryMode::AtLeastOnce { max_retries: 100 })
+///     .await
+///     .expect("Couldn't send the message.");
+/// # });
+/// #
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`ChildRef::tell_anonymously_with`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously_with
+/// [`Children::with_backpressure`]: ../children/struct.Children.html#method.with_backpressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeliveryMode {
+    /// The message is enqueued once, even if the mailbox is full,
+    /// and sending it only fails if the element stopped. This is
+    /// how the messages are sent by default.
+    #[default]
+    AtMostOnce,
+    /// While the mailbox is full, enqueuing the message is retried
+    /// up to `max_retries` times, after which sending it fails.
+    /// Between two attempts, the sending task waits for the mailbox
+    /// to have room again, backing off exponentially from 1ms up to
+    /// 100ms, without blocking its thread.
+    AtLeastOnce {
+        /// How many times enqueuing the message is retried.
+        max_retries: usize,
+    },
+    /// The message is dropped right away if the mailbox is full,
+    /// sending it failing.
+    BestEffort,
+}
@@ -199,7 +199,7 @@ impl Dispatcher {
     /// The logic of who and how should receive the message relies onto
     /// the handler implementation.
     pub fn broadcast_message(&self, message: &Arc<SignedMessage>) {
-        self.handler.broadcast_message(&self.actors, &message);
+        self.handler.broadcast_message(&self.actors, message);
     }
 }
 
@@ -384,13 +384,13 @@ impl GlobalDispatcher {
 
 #[cfg(test)]
 mod tests {
+    use crate::broadcast;
     use crate::child_ref::ChildRef;
     use crate::context::BastionId;
     use crate::dispatcher::*;
     use crate::envelope::{RefAddr, SignedMessage};
     use crate::message::Msg;
     use crate::path::BastionPath;
-    use futures::channel::mpsc;
     use std::sync::{Arc, Mutex};
 
     #[derive(Clone)]
@@ -453,7 +453,7 @@ mod tests {
     fn test_local_dispatcher_append_child_ref() {
         let instance = Dispatcher::default();
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -470,7 +470,7 @@ mod tests {
     fn test_dispatcher_remove_child_ref() {
         let instance = Dispatcher::default();
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -489,7 +489,7 @@ mod tests {
         let handler = Box::new(CustomHandler::new(false));
         let instance = Dispatcher::default().with_handler(handler.clone());
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -503,7 +503,7 @@ mod tests {
     fn test_local_dispatcher_broadcast_message() {
         let handler = Box::new(CustomHandler::new(false));
         let instance = Dispatcher::default().with_handler(handler.clone());
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
 
         const DATA: &str = "A message containing data (ask).";
@@ -563,7 +563,7 @@ mod tests {
     #[test]
     fn test_global_dispatcher_register_actor() {
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -589,7 +589,7 @@ mod tests {
     #[test]
     fn test_global_dispatcher_remove_actor() {
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -616,7 +616,7 @@ mod tests {
     #[test]
     fn test_global_dispatcher_notify() {
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -645,7 +645,7 @@ mod tests {
     #[test]
     fn test_global_dispatcher_broadcast_message() {
         let bastion_id = BastionId::new();
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         let name = "test_name".to_string();
         let child_ref = ChildRef::new(bastion_id, sender, name, path);
@@ -666,7 +666,7 @@ mod tests {
             .register(&actor_groups, &child_ref, module_name)
             .unwrap();
 
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = broadcast::channel();
         let path = Arc::new(BastionPath::root());
         const DATA: &str = "A message containing data (ask).";
         let message = Arc::new(SignedMessage::new(
@@ -7,11 +7,17 @@ use crate::message::{BastionMessage, Message, Msg};
 use crate::path::BastionPath;
 use crate::system::SYSTEM;
 use std::sync::Arc;
+#[cfg(feature = "mailbox-latency")]
+use std::time::Instant;
 
 #[derive(Debug)]
 pub(crate) struct Envelope {
     pub(crate) msg: BastionMessage,
     pub(crate) sign: RefAddr,
+    // When the envelope was sent, to measure how long it stays in
+    // the mailbox of its recipient.
+    #[cfg(feature = "mailbox-latency")]
+    pub(crate) enqueued_at: Instant,
 }
 
 #[derive(Debug)]
@@ -41,11 +47,18 @@ pub(crate) struct Envelope {
 pub struct SignedMessage {
     pub(crate) msg: Msg,
     pub(crate) sign: RefAddr,
+    #[cfg(feature = "mailbox-latency")]
+    pub(crate) enqueued_at: Instant,
 }
 
 impl SignedMessage {
     pub(crate) fn new(msg: Msg, sign: RefAddr) -> Self {
-        SignedMessage { msg, sign }
+        SignedMessage {
+            msg,
+            sign,
+            #[cfg(feature = "mailbox-latency")]
+            enqueued_at: Instant::now(),
+        }
     }
 
     #[doc(hidden)]
@@ -79,6 +92,46 @@ impl SignedMessage {
     pub fn signature(&self) -> &RefAddr {
         &self.sign
     }
+
+    /// Returns the [type tag] of the message, which allows
+    /// switching on the message's type before trying to
+    /// downcast it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 let msg: SignedMessage = ctx.recv().await?;
+    ///                 match msg.type_tag() {
+    ///                     "&str" => msg! { msg,
+    ///                         msg: &'static str => {
+    ///                             // Handle the message...
+    ///                         };
+    ///                         _: _ => ();
+    ///                     },
+    ///                     _ => (),
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [type tag]: message/trait.Message.html#method.type_tag
+    pub fn type_tag(&self) -> &'static str {
+        self.msg.type_tag()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -218,17 +271,26 @@ impl Envelope {
         Envelope {
             msg,
             sign: RefAddr::new(path, sender),
+            #[cfg(feature = "mailbox-latency")]
+            enqueued_at: Instant::now(),
         }
     }
 
     pub(crate) fn new_with_sign(msg: BastionMessage, sign: RefAddr) -> Self {
-        Envelope { msg, sign }
+        Envelope {
+            msg,
+            sign,
+            #[cfg(feature = "mailbox-latency")]
+            enqueued_at: Instant::now(),
+        }
     }
 
     pub(crate) fn from_dead_letters(msg: BastionMessage) -> Self {
         Envelope {
             msg,
             sign: RefAddr::dead_letters(),
+            #[cfg(feature = "mailbox-latency")]
+            enqueued_at: Instant::now(),
         }
     }
 
@@ -236,10 +298,16 @@ impl Envelope {
         self.msg.try_clone().map(|msg| Envelope {
             msg,
             sign: self.sign.clone(),
+            #[cfg(feature = "mailbox-latency")]
+            enqueued_at: self.enqueued_at,
         })
     }
 
     pub(crate) fn into_msg<M: Message>(self) -> Option<M> {
         self.msg.into_msg()
     }
+
+    pub(crate) fn into_shared<M: Message>(self) -> Option<Arc<M>> {
+        self.msg.into_shared()
+    }
 }
@@ -0,0 +1,38 @@
+//!
+//! The errors returned by bastion's fallible operations.
+use crate::context::BastionId;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error telling why one of bastion's operations failed.
+pub enum BastionError {
+    /// The message couldn't be enqueued because the mailbox of
+    /// its recipient was closed, e.g. because it stopped.
+    SendFailed,
+    /// No child with this identifier is registered by the
+    /// supervisor or children group it was sent to.
+    ChildNotFound(BastionId),
+    /// A child with this identifier is already registered.
+    AlreadyRegistered(BastionId),
+    /// The executor couldn't start any of its worker threads.
+    NoCores,
+    /// The operation didn't complete before its timeout elapsed.
+    Timeout,
+}
+
+impl Display for BastionError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BastionError::SendFailed => write!(fmt, "the mailbox is closed"),
+            BastionError::ChildNotFound(id) => write!(fmt, "no child with identifier {}", id),
+            BastionError::AlreadyRegistered(id) => {
+                write!(fmt, "a child with identifier {} is already registered", id)
+            }
+            BastionError::NoCores => write!(fmt, "no worker thread could be started"),
+            BastionError::Timeout => write!(fmt, "the operation timed out"),
+        }
+    }
+}
+
+impl Error for BastionError {}
@@ -1,8 +1,187 @@
 //! A module that exposes the functions used under the hoods from `bastion`s macros: `spawn!`, `run!`
 //! and `blocking!`.
+pub use bastion_executor::current_thread::CurrentThread;
+pub use bastion_executor::load_balancer::OverLimitPolicy;
+pub use bastion_executor::placement::ThreadPriority;
+pub use bastion_executor::pool::YieldNow;
+use lazy_static::lazy_static;
+pub use lightproc::lightproc::LightProc;
 pub use lightproc::proc_stack::ProcStack;
 use lightproc::recoverable_handle::RecoverableHandle;
+use std::fmt::Debug;
 use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    static ref EXECUTOR: RwLock<Arc<dyn Executor>> = RwLock::new(Arc::new(BastionExecutor));
+}
+
+/// The executor on which Bastion runs its processes.
+///
+/// Bastion wraps every supervisor, children group and child (and the
+/// futures spawned with [`spawn`] and [`blocking`]) in a [`LightProc`],
+/// which keeps its lifecycle callbacks and panic recovery. An `Executor`
+/// only decides where and when those processes run: each time a process
+/// needs to be polled, it is handed over to [`schedule`] (or to
+/// [`schedule_blocking`] for the ones spawned with [`blocking`]) and
+/// the executor should call [`LightProc::run`] once.
+///
+/// The default executor is [`BastionExecutor`]. Another one can be used
+/// with [`Config::with_executor`], which allows to run Bastion on top
+/// of another runtime by forwarding the processes to it.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::executor::{Executor, LightProc};
+/// use std::thread;
+///
+/// // An executor running every process on a new thread. An
+/// // adapter for another runtime would spawn a task calling
+/// // `proc.run()` on it instead.
+/// #[derive(Debug)]
+/// struct ThreadExecutor;
+///
+/// impl Executor for ThreadExecutor {
+///     fn schedule(&self, proc: LightProc) {
+///         thread::spawn(move || proc.run());
+///     }
+/// }
+///
+/// let config = Config::new().with_executor(ThreadExecutor);
+/// Bastion::init_with(config);
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`spawn`]: fn.spawn.html
+/// [`blocking`]: fn.blocking.html
+/// [`schedule`]: #tymethod.schedule
+/// [`schedule_blocking`]: #method.schedule_blocking
+/// [`Config::with_executor`]: ../struct.Config.html#method.with_executor
+pub trait Executor: Debug + Send + Sync + 'static {
+    /// Schedules the given process for running.
+    fn schedule(&self, proc: LightProc);
+
+    /// Schedules the given process, which might block the thread
+    /// it runs on, for running.
+    ///
+    /// Defaults to [`schedule`].
+    ///
+    /// [`schedule`]: #tymethod.schedule
+    fn schedule_blocking(&self, proc: LightProc) {
+        self.schedule(proc)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The default [`Executor`], running the processes on the
+/// core-pinned workers of `bastion-executor` and the blocking
+/// ones on its dedicated blocking pool.
+///
+/// [`Executor`]: trait.Executor.html
+pub struct BastionExecutor;
+
+impl Executor for BastionExecutor {
+    fn schedule(&self, proc: LightProc) {
+        bastion_executor::pool::schedule(proc)
+    }
+
+    fn schedule_blocking(&self, proc: LightProc) {
+        bastion_executor::blocking::schedule_blocking(proc)
+    }
+}
+
+/// Runs every process, including the blocking ones, on the thread
+/// driving the executor (with [`CurrentThread::block_on`]), one at
+/// a time and in the order they were scheduled in.
+///
+/// This makes the interleavings of the processes reproducible, which
+/// is useful to test supervision logic deterministically. As blocking
+/// processes also run on this thread, they block all the others.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// use bastion::executor::CurrentThread;
+///
+/// let executor = CurrentThread::new();
+/// let config = Config::new().with_executor(executor.clone());
+/// Bastion::init_with(config);
+/// Bastion::start();
+///
+/// let children = Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| async move {
+///         msg! { ctx.recv().await?,
+///             msg: &'static str =!> {
+///                 answer!(ctx, msg).unwrap();
+///             };
+///             _: _ => ();
+///         }
+///         Ok(())
+///     })
+/// })
+/// .unwrap();
+///
+/// // Nothing runs until the executor is driven...
+/// let reply = executor.block_on(async {
+///     let answer = children.elems()[0].ask_anonymously("ping").unwrap();
+///     msg! { answer.await.unwrap(),
+///         reply: &'static str => reply;
+///         _: _ => unreachable!();
+///     }
+/// });
+/// assert_eq!(reply, "ping");
+/// #
+/// # Bastion::stop();
+/// ```
+///
+/// [`CurrentThread::block_on`]: struct.CurrentThread.html#method.block_on
+impl Executor for CurrentThread {
+    fn schedule(&self, proc: LightProc) {
+        CurrentThread::schedule(self, proc)
+    }
+}
+
+pub(crate) fn set_executor(executor: Arc<dyn Executor>) {
+    // FIXME: panics?
+    *EXECUTOR.write().unwrap() = executor;
+}
+
+fn executor() -> Arc<dyn Executor> {
+    // FIXME: panics?
+    EXECUTOR.read().unwrap().clone()
+}
+
+/// Spawns the future with the given stack on the configured [`Executor`].
+pub(crate) fn spawn_with<F, T>(future: F, stack: ProcStack) -> RecoverableHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let executor = executor();
+    let schedule = move |proc| executor.schedule(proc);
+    let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+    proc.schedule();
+    handle
+}
+
+/// Spawns the blocking future with the given stack on the configured [`Executor`].
+pub(crate) fn blocking_with<F, R>(future: F, stack: ProcStack) -> RecoverableHandle<R>
+where
+    F: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let executor = executor();
+    let schedule = move |proc| executor.schedule_blocking(proc);
+    let (proc, handle) = LightProc::recoverable(future, schedule, stack);
+    proc.schedule();
+    handle
+}
 
 /// Spawns a blocking task, which will run on the blocking thread pool,
 /// and returns the handle.
@@ -20,7 +199,7 @@ where
     F: Future<Output = R> + Send + 'static,
     R: Send + 'static,
 {
-    bastion_executor::blocking::spawn_blocking(future, lightproc::proc_stack::ProcStack::default())
+    blocking_with(future, ProcStack::default())
 }
 
 /// Block the current thread until passed
@@ -53,6 +232,32 @@ where
     bastion_executor::run::run(future, lightproc::proc_stack::ProcStack::default())
 }
 
+/// Drives the passed future to completion on the current thread
+/// and returns its output, while the processes it spawns run on the
+/// executor.
+///
+/// This is the entry point to use Bastion as the main runtime (e.g.
+/// from `main`). Unlike [`run`], it can't be nested: calling it from
+/// within the future passed to another `block_on` panics.
+///
+/// # Example
+/// ```
+/// # use bastion::prelude::*;
+/// use bastion::executor::{block_on, spawn};
+/// let result = block_on(async {
+///     spawn(async { 10 / 2 }).await
+/// });
+/// assert_eq!(result, Some(5));
+/// ```
+///
+/// [`run`]: fn.run.html
+pub fn block_on<F, T>(future: F) -> T
+where
+    F: Future<Output = T>,
+{
+    bastion_executor::run::block_on(future)
+}
+
 /// Spawn a given future onto the executor from the global level.
 ///
 /// # Example
@@ -69,5 +274,27 @@ where
     F: Future<Output = T> + Send + 'static,
     T: Send + 'static,
 {
-    bastion_executor::pool::spawn(future, lightproc::proc_stack::ProcStack::default())
+    spawn_with(future, ProcStack::default())
+}
+
+/// Yields the execution of the current task back to the executor,
+/// allowing the other tasks to make progress.
+///
+/// This is useful for long CPU-bound computations that would
+/// otherwise starve the core they are running on.
+///
+/// # Example
+/// ```
+/// # use bastion::prelude::*;
+/// use bastion::executor::{run, spawn, yield_now};
+/// let handle = spawn(async {
+///     for _ in 0..10 {
+///         // Some heavy computation...
+///         yield_now().await;
+///     }
+/// });
+/// run(handle);
+/// ```
+pub fn yield_now() -> YieldNow {
+    bastion_executor::pool::yield_now()
 }
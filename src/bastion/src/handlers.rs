@@ -0,0 +1,123 @@
+//!
+//! Typed message handlers run by the elements of a children group
+//! (see `Children::on`).
+use crate::child::{Exec, Init};
+use crate::context::BastionContext;
+use crate::envelope::{Envelope, SignedMessage};
+use crate::message::{BastionMessage, Message, Msg};
+use crate::system::SYSTEM;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::trace;
+
+/// Runs the handler of a message's type, or gives the message back
+/// if it isn't of this type.
+type Route = Arc<dyn Fn(Msg, &Arc<BastionContext>) -> Result<Exec, Msg> + Send + Sync>;
+
+/// Runs the handler of the messages which didn't match any route.
+type Unmatched = Arc<dyn Fn(SignedMessage, Arc<BastionContext>) -> Exec + Send + Sync>;
+
+#[derive(Clone, Default)]
+/// The handlers registered on a children group, in their
+/// registration order.
+pub(crate) struct Handlers {
+    routes: Vec<Route>,
+    unmatched: Option<Unmatched>,
+    // Whether the messages which didn't match any route are sent
+    // to the dead letters when there is no default handler.
+    dead_letters: bool,
+}
+
+impl Handlers {
+    pub(crate) fn on<T, H, F>(&mut self, handler: H)
+    where
+        T: Message + Clone,
+        H: Fn(T, Arc<BastionContext>) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let route = move |msg: Msg, ctx: &Arc<BastionContext>| {
+            if !msg.is::<T>() {
+                return Err(msg);
+            }
+
+            // Broadcasted messages are shared between the elements,
+            // so they are only copied when they can't be taken.
+            let msg = match msg.try_unwrap::<T>() {
+                Ok(msg) => msg,
+                Err(msg) => T::clone(&msg.downcast_ref::<T>().unwrap()),
+            };
+
+            Ok(Exec(Box::pin(handler(msg, ctx.clone()))))
+        };
+
+        self.routes.push(Arc::new(route));
+    }
+
+    pub(crate) fn on_unmatched<H, F>(&mut self, handler: H)
+    where
+        H: Fn(SignedMessage, Arc<BastionContext>) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let unmatched =
+            move |msg: SignedMessage, ctx: Arc<BastionContext>| Exec(Box::pin(handler(msg, ctx)));
+
+        self.unmatched = Some(Arc::new(unmatched));
+    }
+
+    pub(crate) fn unmatched_to_dead_letters(&mut self) {
+        self.dead_letters = true;
+    }
+
+    /// Returns the closure making the elements receive their
+    /// messages and run the matching handlers.
+    pub(crate) fn init(&self) -> Init {
+        let handlers = Arc::new(self.clone());
+        Init::new(move |ctx: BastionContext| {
+            let handlers = handlers.clone();
+            async move { handlers.run(ctx).await }
+        })
+    }
+
+    async fn run(&self, ctx: BastionContext) -> Result<(), ()> {
+        let ctx = Arc::new(ctx);
+        loop {
+            let msg = ctx.recv().await?;
+            self.handle(msg, &ctx).await?;
+        }
+    }
+
+    async fn handle(&self, msg: SignedMessage, ctx: &Arc<BastionContext>) -> Result<(), ()> {
+        let (mut msg, sign) = msg.extract();
+        for route in &self.routes {
+            match route(msg, ctx) {
+                Ok(exec) => return exec.await,
+                Err(unmatched) => msg = unmatched,
+            }
+        }
+
+        trace!("Handlers: No handler matched: {:?}", msg);
+        if let Some(unmatched) = &self.unmatched {
+            let msg = SignedMessage::new(msg, sign);
+            unmatched(msg, ctx.clone()).await
+        } else {
+            if self.dead_letters {
+                let msg = BastionMessage::Message(msg);
+                let env = Envelope::new_with_sign(msg, sign);
+                SYSTEM.dead_letters().sender().unbounded_send(env).ok();
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl Debug for Handlers {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Handlers")
+            .field("routes", &self.routes.len())
+            .field("unmatched", &self.unmatched.is_some())
+            .field("dead_letters", &self.dead_letters)
+            .finish()
+    }
+}
@@ -0,0 +1,189 @@
+//!
+//! Journaling of the messages received by the elements of a children
+//! group, replayed to rebuild their state when they are restarted.
+use crate::context::BastionId;
+use crate::envelope::RefAddr;
+use crate::message::{Message, Msg};
+use fxhash::FxHashMap;
+use std::any::TypeId;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// A storage for the messages received by the elements of a children
+/// group (see [`Children::with_journal`]).
+///
+/// Every message of type `Message` told or broadcasted to an element
+/// is appended to the journal, along with its sender, before the
+/// element gets to handle it. When an element is restarted, the
+/// messages appended for it are replayed to it before the ones it
+/// didn't handle yet, which allows it to rebuild its state. Once an
+/// element is removed from its group, its messages are removed from
+/// the journal.
+///
+/// The trait doesn't make any assumption on how the messages are
+/// stored, see [`InMemoryJournal`] for an implementation keeping them
+/// in memory.
+///
+/// [`Children::with_journal`]: ../children/struct.Children.html#method.with_journal
+/// [`InMemoryJournal`]: struct.InMemoryJournal.html
+pub trait Journal: Send + 'static {
+    /// The type of the messages journaled, the other ones are
+    /// neither appended nor replayed.
+    type Message: Message;
+
+    /// Appends a message received by the element with the given
+    /// identifier, from the given sender.
+    fn append(&mut self, id: &BastionId, msg: &Self::Message, sign: &RefAddr);
+
+    /// Returns the messages appended for the element with the given
+    /// identifier and their senders, in the order they were appended
+    /// in.
+    fn replay(&mut self, id: &BastionId) -> Vec<(Self::Message, RefAddr)>;
+
+    /// Removes the messages appended for the element with the given
+    /// identifier, which won't be restarted anymore.
+    fn remove(&mut self, id: &BastionId);
+}
+
+#[derive(Debug)]
+/// A [`Journal`] keeping clones of the messages in memory.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # Bastion::init();
+/// #
+/// #[derive(Debug, Clone)]
+/// struct Deposit(u64);
+///
+/// Bastion::children(|children| {
+///     children
+///         .with_journal(InMemoryJournal::<Deposit>::new())
+///         .with_exec(|ctx: BastionContext| async move {
+///             let mut balance = 0;
+///             loop {
+///                 msg! { ctx.recv().await?,
+///                     // Deposits received before a restart are
+///                     // replayed after it...
+///                     deposit: Deposit => balance += deposit.0;
+///                     _: _ => ();
+///                 }
+///             }
+///         })
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Journal`]: trait.Journal.html
+pub struct InMemoryJournal<M> {
+    entries: FxHashMap<BastionId, Vec<(M, RefAddr)>>,
+}
+
+impl<M> InMemoryJournal<M> {
+    /// Creates an empty journal.
+    pub fn new() -> Self {
+        InMemoryJournal {
+            entries: FxHashMap::default(),
+        }
+    }
+}
+
+impl<M> Default for InMemoryJournal<M> {
+    fn default() -> Self {
+        InMemoryJournal::new()
+    }
+}
+
+impl<M: Message + Clone> Journal for InMemoryJournal<M> {
+    type Message = M;
+
+    fn append(&mut self, id: &BastionId, msg: &M, sign: &RefAddr) {
+        self.entries
+            .entry(id.clone())
+            .or_default()
+            .push((msg.clone(), sign.clone()));
+    }
+
+    fn replay(&mut self, id: &BastionId) -> Vec<(M, RefAddr)> {
+        self.entries.get(id).cloned().unwrap_or_default()
+    }
+
+    fn remove(&mut self, id: &BastionId) {
+        self.entries.remove(id);
+    }
+}
+
+trait ErasedJournal: Send {
+    fn message_type(&self) -> TypeId;
+
+    fn append(&mut self, id: &BastionId, msg: &Msg, sign: &RefAddr);
+
+    fn replay(&mut self, id: &BastionId) -> Vec<(Msg, RefAddr)>;
+
+    fn remove(&mut self, id: &BastionId);
+}
+
+impl<J: Journal> ErasedJournal for J {
+    fn message_type(&self) -> TypeId {
+        TypeId::of::<J::Message>()
+    }
+
+    fn append(&mut self, id: &BastionId, msg: &Msg, sign: &RefAddr) {
+        // The questions can only be answered once.
+        if msg.is_ask() {
+            return;
+        }
+
+        if let Some(msg) = msg.inner_ref::<J::Message>() {
+            Journal::append(self, id, msg, sign);
+        }
+    }
+
+    fn replay(&mut self, id: &BastionId) -> Vec<(Msg, RefAddr)> {
+        Journal::replay(self, id)
+            .into_iter()
+            .map(|(msg, sign)| (Msg::tell(msg), sign))
+            .collect()
+    }
+
+    fn remove(&mut self, id: &BastionId) {
+        Journal::remove(self, id)
+    }
+}
+
+#[derive(Clone)]
+/// A journal shared by the elements of a children group.
+pub(crate) struct SharedJournal(Arc<Mutex<dyn ErasedJournal>>);
+
+impl SharedJournal {
+    pub(crate) fn new<J: Journal>(journal: J) -> Self {
+        SharedJournal(Arc::new(Mutex::new(journal)))
+    }
+
+    pub(crate) fn message_type(&self) -> TypeId {
+        self.0.lock().unwrap().message_type()
+    }
+
+    pub(crate) fn append(&self, id: &BastionId, msg: &Msg, sign: &RefAddr) {
+        self.0.lock().unwrap().append(id, msg, sign)
+    }
+
+    pub(crate) fn replay(&self, id: &BastionId) -> Vec<(Msg, RefAddr)> {
+        self.0.lock().unwrap().replay(id)
+    }
+
+    pub(crate) fn remove(&self, id: &BastionId) {
+        self.0.lock().unwrap().remove(id)
+    }
+}
+
+impl Debug for SharedJournal {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("SharedJournal").finish()
+    }
+}
@@ -0,0 +1,179 @@
+//!
+//! Measurement of how long messages sit in the mailboxes of the
+//! children before being received (enabled by the `mailbox-latency`
+//! feature).
+//!
+//! Messages are stamped when they are sent and the time elapsed
+//! since then is recorded when a child receives them using
+//! `BastionContext::recv` or `BastionContext::try_recv`. A growing
+//! latency reveals that a child can't keep up with the messages it
+//! is sent, before its mailbox grows out of bounds.
+use std::time::Duration;
+
+// Bucket `i` counts the latencies lower than `2^i` microseconds,
+// the last one counting all the latencies above.
+const BUCKETS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A histogram of the time messages spent in the mailbox of a child
+/// before it received them (see [`BastionContext::mailbox_latency`]).
+///
+/// The latencies are counted in buckets whose upper bounds are
+/// powers of two microseconds, which keeps the histogram small while
+/// being precise enough to tell microseconds from milliseconds and
+/// seconds.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| async move {
+///         loop {
+///             ctx.recv().await?;
+///
+///             let latency = ctx.mailbox_latency().await;
+///             if latency.percentile(0.99) > std::time::Duration::from_millis(100) {
+///                 println!("Falling behind, the mean latency is {:?}.", latency.mean());
+///             }
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`BastionContext::mailbox_latency`]: ../context/struct.BastionContext.html#method.mailbox_latency
+pub struct LatencyHistogram {
+    buckets: [u64; BUCKETS],
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    pub(crate) fn new() -> Self {
+        LatencyHistogram {
+            buckets: [0; BUCKETS],
+            count: 0,
+            total: Duration::default(),
+            max: Duration::default(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros();
+        // The index of the first power of two above `micros`.
+        let bucket = (128 - micros.leading_zeros()) as usize;
+
+        self.buckets[bucket.min(BUCKETS - 1)] += 1;
+        self.count += 1;
+        self.total = self.total.saturating_add(latency);
+        self.max = self.max.max(latency);
+    }
+
+    /// Returns the number of messages whose latency was recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the mean latency of the messages, or zero if none
+    /// was recorded.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::default();
+        }
+
+        let mean = self.total.as_nanos() / u128::from(self.count);
+        Duration::from_nanos(mean.min(u128::from(u64::MAX)) as u64)
+    }
+
+    /// Returns the highest latency recorded.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns an upper bound of the latency of the given fraction
+    /// of the messages, e.g. `0.99` for the 99th percentile.
+    ///
+    /// The bound is the one of the bucket the percentile falls in,
+    /// capped by the highest latency recorded.
+    pub fn percentile(&self, fraction: f64) -> Duration {
+        let target = (self.count as f64 * fraction.clamp(0.0, 1.0)).ceil() as u64;
+
+        let mut seen = 0;
+        for (bound, count) in self.buckets() {
+            seen += count;
+            if seen >= target.max(1) {
+                return bound.min(self.max);
+            }
+        }
+
+        self.max
+    }
+
+    /// Returns the upper bound of each bucket along with the number
+    /// of latencies it counts.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.buckets.iter().enumerate().map(|(i, count)| {
+            let bound = if i == BUCKETS - 1 {
+                Duration::from_secs(u64::MAX)
+            } else {
+                Duration::from_micros(1 << i)
+            };
+
+            (bound, *count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LatencyHistogram;
+    use crate::context::ContextState;
+    use crate::envelope::RefAddr;
+    use crate::message::Msg;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn records_latencies() {
+        let mut histogram = LatencyHistogram::new();
+        assert_eq!(histogram.mean(), Duration::default());
+        assert_eq!(histogram.percentile(0.5), Duration::default());
+
+        for _ in 0..98 {
+            histogram.record(Duration::from_micros(3));
+        }
+        histogram.record(Duration::from_millis(10));
+        histogram.record(Duration::from_secs(1 << 40));
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.max(), Duration::from_secs(1 << 40));
+        assert_eq!(histogram.percentile(0.5), Duration::from_micros(4));
+        assert_eq!(histogram.percentile(0.99), Duration::from_micros(16384));
+        assert_eq!(histogram.percentile(1.0), Duration::from_secs(1 << 40));
+
+        let counted: u64 = histogram.buckets().map(|(_, count)| count).sum();
+        assert_eq!(counted, 100);
+    }
+
+    #[test]
+    fn records_mailbox_latency() {
+        let mut state = ContextState::new();
+        let enqueued_at = Instant::now() - Duration::from_millis(5);
+        state.push_enqueued_message(Msg::tell(()), RefAddr::dead_letters(), enqueued_at);
+        state.push_message(Msg::tell(()), RefAddr::dead_letters());
+        assert_eq!(state.latency().count(), 0);
+
+        state.pop_message().unwrap();
+        state.pop_message().unwrap();
+        assert!(state.pop_message().is_none());
+        assert_eq!(state.latency().count(), 2);
+        assert!(state.latency().max() >= Duration::from_millis(5));
+    }
+}
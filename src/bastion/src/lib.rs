@@ -56,6 +56,7 @@
 pub use self::bastion::Bastion;
 pub use self::callbacks::Callbacks;
 pub use self::config::Config;
+pub use self::executor::yield_now;
 
 #[macro_use]
 mod macros;
@@ -65,51 +66,86 @@ mod broadcast;
 mod callbacks;
 mod child;
 mod config;
+mod deadlock;
+mod dedup;
+mod handlers;
 mod system;
 
+pub mod autoscale;
+pub mod backpressure;
 pub mod child_ref;
 pub mod children;
 pub mod children_ref;
+pub mod circuit_breaker;
+pub mod clock;
 pub mod context;
+pub mod delivery;
 pub mod dispatcher;
 pub mod envelope;
+pub mod errors;
 pub mod executor;
+pub mod journal;
+#[cfg(feature = "mailbox-latency")]
+pub mod latency;
 pub mod message;
+pub mod middleware;
 pub mod path;
+pub mod restart_history;
+pub mod scope;
+pub mod sink;
 pub mod supervisor;
+pub mod topology;
 
 distributed_api! {
     // pub mod dist_messages;
     pub mod distributed;
+    pub mod serialization;
 }
 
 ///
 /// Prelude of Bastion
 pub mod prelude {
+    pub use crate::autoscale::{AutoscalePolicy, LoadSample};
+    pub use crate::backpressure::{Backpressure, Resizer};
     pub use crate::bastion::Bastion;
     pub use crate::callbacks::Callbacks;
     pub use crate::child_ref::ChildRef;
-    pub use crate::children::Children;
-    pub use crate::children_ref::ChildrenRef;
+    pub use crate::children::{Children, ChildrenState, DispatchMode, PanicPolicy};
+    pub use crate::children_ref::{AskReply, ChildrenRef, HealthReport, SendError, StopReason};
+    pub use crate::circuit_breaker::{BreakerState, CircuitBreaker};
+    #[cfg(feature = "testing")]
+    pub use crate::clock::ManualClock;
+    pub use crate::clock::{Clock, SystemClock};
     pub use crate::config::Config;
     pub use crate::context::{BastionContext, BastionId, NIL_ID};
+    pub use crate::delivery::DeliveryMode;
     pub use crate::dispatcher::{
         BroadcastTarget, DefaultDispatcherHandler, Dispatcher, DispatcherHandler, DispatcherMap,
         DispatcherType, NotificationType,
     };
     pub use crate::envelope::{RefAddr, SignedMessage};
-    pub use crate::message::{Answer, AnswerSender, Message, Msg};
+    pub use crate::errors::BastionError;
+    pub use crate::journal::{InMemoryJournal, Journal};
+    #[cfg(feature = "mailbox-latency")]
+    pub use crate::latency::LatencyHistogram;
+    pub use crate::message::{Answer, AnswerSender, Dead, DeathNotice, DeathReason, Message, Msg};
+    pub use crate::middleware::MiddlewareAction;
     pub use crate::msg;
     pub use crate::path::{BastionPath, BastionPathElement};
+    pub use crate::restart_history::RestartHistory;
+    pub use crate::scope::Scope;
+    pub use crate::sink::MessageSink;
     pub use crate::supervisor::{
-        ActorRestartStrategy, RestartPolicy, RestartStrategy, SupervisionStrategy, Supervisor,
-        SupervisorRef,
+        ActorRestartStrategy, PollBias, RestartPolicy, RestartStrategy, SupervisionStrategy,
+        Supervisor, SupervisorRef,
     };
+    pub use crate::topology::{TopologyKind, TopologyNode};
     pub use crate::{answer, blocking, children, run, spawn, supervisor};
 
     distributed_api! {
         // pub use crate::dist_messages::*;
         pub use crate::distributed::*;
+        pub use crate::serialization::{MessageRegistry, SerializableMessage, SerializationError};
         pub use artillery_core::cluster::ap::*;
         pub use artillery_core::epidemic::prelude::*;
     }
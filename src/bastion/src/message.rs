@@ -6,19 +6,28 @@
 //! * All message communication relies on at-most-once delivery guarantee.
 //! * Messages are not guaranteed to be ordered, all message's order is causal.
 //!
+use crate::backpressure::BackpressureSignal;
+use crate::broadcast::{Parent, Sender};
 use crate::callbacks::CallbackType;
+use crate::child::Init;
+use crate::child_ref::ChildRef;
 use crate::children::Children;
+use crate::children_ref::{SendError, StopReason};
 use crate::context::{BastionId, ContextState};
+use crate::deadlock::AskEdge;
 use crate::envelope::{RefAddr, SignedMessage};
-use crate::supervisor::{SupervisionStrategy, Supervisor};
+use crate::path::BastionPath;
+use crate::supervisor::{FaultInfo, SupervisionStrategy, Supervisor};
+use crate::topology::TopologyNode;
 use async_mutex::Mutex;
 use futures::channel::oneshot::{self, Receiver};
-use std::any::{type_name, Any};
+use std::any::{type_name, Any, TypeId};
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tracing::{debug, trace};
 
 /// A trait that any message sent needs to implement (it is
@@ -30,7 +39,28 @@ use tracing::{debug, trace};
 /// [`Send`]: https://doc.rust-lang.org/std/marker/trait.Send.html
 /// [`Sync`]: https://doc.rust-lang.org/std/marker/trait.Sync.html
 /// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
-pub trait Message: Any + Send + Sync + Debug {}
+pub trait Message: Any + Send + Sync + Debug {
+    /// Returns a tag identifying the message's type (its
+    /// [`type_name`]), which allows routing messages without
+    /// trying to downcast them to every possible type.
+    ///
+    /// Note that the returned tag is only meant to be used for
+    /// routing and shouldn't be relied upon across compilations.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let msg = "A message containing data.";
+    /// assert_eq!(msg.type_tag(), "&str");
+    /// ```
+    ///
+    /// [`type_name`]: https://doc.rust-lang.org/std/any/fn.type_name.html
+    fn type_tag(&self) -> &'static str {
+        type_name::<Self>()
+    }
+}
 impl<T> Message for T where T: Any + Send + Sync + Debug {}
 
 #[derive(Debug)]
@@ -113,7 +143,43 @@ pub struct AnswerSender(oneshot::Sender<SignedMessage>);
 /// [`ChildRef::ask`]: ../children/struct.ChildRef.html#method.ask
 /// [`Msg`]: message/struct.Msg.html
 /// [`msg!`]: macro.msg.html
-pub struct Answer(Receiver<SignedMessage>);
+pub struct Answer(Receiver<SignedMessage>, Option<AskEdge>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The reason why an element of a children group died, as
+/// reported by a [`Dead`] notice.
+///
+/// [`Dead`]: message/struct.Dead.html
+pub enum DeathReason {
+    /// The element received a poison pill and stopped.
+    PoisonPilled,
+    /// The element didn't acknowledge its poison pill in time
+    /// and was cancelled.
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A notice that an element of a children group died, as
+/// resolved by the [`DeathNotice`] returned by
+/// [`ChildrenRef::poison_pill_child`].
+///
+/// [`DeathNotice`]: message/struct.DeathNotice.html
+/// [`ChildrenRef::poison_pill_child`]: children/struct.ChildrenRef.html#method.poison_pill_child
+pub struct Dead {
+    /// The identifier of the element that died.
+    pub id: BastionId,
+    /// The reason why the element died.
+    pub reason: DeathReason,
+}
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`ChildrenRef::poison_pill_child`]
+/// which resolves to a `Result<Dead, ()>` once the element of
+/// the children group died.
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildrenRef::poison_pill_child`]: children/struct.ChildrenRef.html#method.poison_pill_child
+pub struct DeathNotice(Receiver<Dead>);
 
 #[derive(Debug)]
 /// A message returned by [`BastionContext::recv`] or
@@ -174,7 +240,7 @@ pub struct Answer(Receiver<SignedMessage>);
 /// [`BastionContext::recv`]: context/struct.BastionContext.html#method.recv
 /// [`BastionContext::try_recv`]: context/struct.BastionContext.html#method.try_recv
 /// [`msg!`]: macro.msg.html
-pub struct Msg(MsgInner);
+pub struct Msg(MsgInner, &'static str);
 
 #[derive(Debug)]
 enum MsgInner {
@@ -207,6 +273,10 @@ pub(crate) enum BastionMessage {
         id: BastionId,
         parent_id: BastionId,
     },
+    Panicked {
+        id: BastionId,
+        parent_id: BastionId,
+    },
     FinishedChild {
         id: BastionId,
         parent_id: BastionId,
@@ -228,9 +298,105 @@ pub(crate) enum BastionMessage {
     Faulted {
         id: BastionId,
     },
+    Escalate {
+        id: BastionId,
+        info: FaultInfo,
+    },
+    SetChildWeight {
+        id: BastionId,
+        weight: usize,
+    },
+    PoisonPill {
+        child: ChildRef,
+        ack: oneshot::Sender<Dead>,
+        // Where the messages the child didn't receive yet should
+        // be forwarded to before it stops, if anywhere.
+        handover: Option<Sender>,
+    },
+    GracefulRestart {
+        child: ChildRef,
+        init: Init,
+        ack: oneshot::Sender<Dead>,
+    },
+    Topology {
+        ack: oneshot::Sender<TopologyNode>,
+    },
+    Ping,
+    Pong {
+        id: BastionId,
+    },
+    SendChild {
+        id: BastionId,
+        msg: Msg,
+        ack: oneshot::Sender<Result<(), SendError>>,
+    },
+    ChildrenOlderThan {
+        age: Duration,
+        ack: oneshot::Sender<Vec<BastionId>>,
+    },
+    CancelChildren {
+        ids: Vec<BastionId>,
+    },
+    DrainMailbox {
+        id: BastionId,
+        ack: oneshot::Sender<Vec<SignedMessage>>,
+    },
+    AwaitExit {
+        id: BastionId,
+        ack: oneshot::Sender<StopReason>,
+    },
+    WaitForChildren {
+        n: usize,
+        ack: oneshot::Sender<()>,
+    },
+    Subscribe {
+        id: BastionId,
+        topic: String,
+    },
+    Unsubscribe {
+        id: BastionId,
+        topic: String,
+    },
+    Publish {
+        topic: String,
+        msg: Msg,
+    },
+    RegisterName {
+        id: BastionId,
+        name: String,
+    },
+    SendNamed {
+        name: String,
+        msg: Msg,
+    },
+    Reparent {
+        parent: Box<Parent>,
+        path: Arc<BastionPath>,
+    },
+    Reparented {
+        id: BastionId,
+    },
+    Ready {
+        id: BastionId,
+    },
+    Backpressure {
+        id: BastionId,
+        signal: BackpressureSignal,
+    },
+    // A message forwarded down the tree to every element, for at
+    // most `ttl` more levels.
+    Broadcast {
+        payload: Msg,
+        ttl: usize,
+    },
+    // Makes the elements of the subtree stop receiving their
+    // messages, which keep being queued in their mailboxes.
+    Suspend,
+    Resume,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum Deployment {
     Supervisor(Supervisor),
     Children(Children),
@@ -252,25 +418,40 @@ impl AnswerSender {
 
 impl Msg {
     pub(crate) fn broadcast<M: Message>(msg: M) -> Self {
-        let inner = MsgInner::Broadcast(Arc::new(msg));
-        Msg(inner)
+        Msg::shared(Arc::new(msg))
+    }
+
+    pub(crate) fn shared<M: Message>(msg: Arc<M>) -> Self {
+        let tag = msg.type_tag();
+        let inner = MsgInner::Broadcast(msg);
+        Msg(inner, tag)
     }
 
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
+        let tag = msg.type_tag();
         let inner = MsgInner::Tell(Box::new(msg));
-        Msg(inner)
+        Msg(inner, tag)
     }
 
     pub(crate) fn ask<M: Message>(msg: M) -> (Self, Answer) {
+        let tag = msg.type_tag();
         let msg = Box::new(msg);
         let (sender, recver) = oneshot::channel();
         let sender = AnswerSender(sender);
-        let answer = Answer(recver);
+        let answer = Answer(recver, None);
 
         let sender = Some(sender);
         let inner = MsgInner::Ask { msg, sender };
 
-        (Msg(inner), answer)
+        (Msg(inner, tag), answer)
+    }
+
+    /// Returns the [type tag] of the message, which allows
+    /// switching on the message's type before downcasting it.
+    ///
+    /// [type tag]: trait.Message.html#method.type_tag
+    pub fn type_tag(&self) -> &'static str {
+        self.1
     }
 
     #[doc(hidden)]
@@ -310,6 +491,25 @@ impl Msg {
         }
     }
 
+    #[cfg(feature = "distributed")]
+    /// Returns the message itself, unless it was sent as a question.
+    pub(crate) fn payload(&self) -> Option<&(dyn Any + Send + Sync)> {
+        match &self.0 {
+            MsgInner::Broadcast(msg) => Some(&**msg),
+            MsgInner::Tell(msg) => Some(&**msg),
+            MsgInner::Ask { .. } => None,
+        }
+    }
+
+    /// Returns the [`TypeId`] of the message itself.
+    pub(crate) fn payload_type_id(&self) -> TypeId {
+        match &self.0 {
+            MsgInner::Tell(msg) => (**msg).type_id(),
+            MsgInner::Ask { msg, .. } => (**msg).type_id(),
+            MsgInner::Broadcast(msg) => (**msg).type_id(),
+        }
+    }
+
     #[doc(hidden)]
     pub fn is<M: Message>(&self) -> bool {
         match &self.0 {
@@ -322,6 +522,7 @@ impl Msg {
     #[doc(hidden)]
     pub fn downcast<M: Message>(self) -> Result<M, Self> {
         trace!("{:?}: Downcasting to {}.", self, type_name::<M>());
+        let tag = self.1;
         match self.0 {
             MsgInner::Tell(msg) => {
                 if msg.is::<M>() {
@@ -329,7 +530,7 @@ impl Msg {
                     Ok(*msg.downcast().unwrap())
                 } else {
                     let inner = MsgInner::Tell(msg);
-                    Err(Msg(inner))
+                    Err(Msg(inner, tag))
                 }
             }
             MsgInner::Ask { msg, sender } => {
@@ -338,7 +539,7 @@ impl Msg {
                     Ok(*msg.downcast().unwrap())
                 } else {
                     let inner = MsgInner::Ask { msg, sender };
-                    Err(Msg(inner))
+                    Err(Msg(inner, tag))
                 }
             }
             _ => Err(self),
@@ -361,26 +562,35 @@ impl Msg {
         trace!("{:?}: Trying to clone.", self);
         if let MsgInner::Broadcast(msg) = &self.0 {
             let inner = MsgInner::Broadcast(msg.clone());
-            Some(Msg(inner))
+            Some(Msg(inner, self.1))
         } else {
             None
         }
     }
 
+    pub(crate) fn inner_ref<M: Message>(&self) -> Option<&M> {
+        match &self.0 {
+            MsgInner::Tell(msg) => msg.downcast_ref(),
+            MsgInner::Ask { msg, .. } => msg.downcast_ref(),
+            MsgInner::Broadcast(msg) => msg.downcast_ref(),
+        }
+    }
+
     pub(crate) fn try_unwrap<M: Message>(self) -> Result<M, Self> {
         debug!("{:?}: Trying to unwrap.", self);
+        let tag = self.1;
         if let MsgInner::Broadcast(msg) = self.0 {
             match msg.downcast() {
                 Ok(msg) => match Arc::try_unwrap(msg) {
                     Ok(msg) => Ok(msg),
                     Err(msg) => {
                         let inner = MsgInner::Broadcast(msg);
-                        Err(Msg(inner))
+                        Err(Msg(inner, tag))
                     }
                 },
                 Err(msg) => {
                     let inner = MsgInner::Broadcast(msg);
-                    Err(Msg(inner))
+                    Err(Msg(inner, tag))
                 }
             }
         } else {
@@ -443,6 +653,13 @@ impl BastionMessage {
         BastionMessage::Message(msg)
     }
 
+    /// Creates a broadcasted message from an already allocated
+    /// message, whose clones only bump its reference count.
+    pub(crate) fn shared<M: Message>(msg: Arc<M>) -> Self {
+        let msg = Msg::shared(msg);
+        BastionMessage::Message(msg)
+    }
+
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
         let msg = Msg::tell(msg);
         BastionMessage::Message(msg)
@@ -457,6 +674,10 @@ impl BastionMessage {
         BastionMessage::RestartRequired { id, parent_id }
     }
 
+    pub(crate) fn panicked(id: BastionId, parent_id: BastionId) -> Self {
+        BastionMessage::Panicked { id, parent_id }
+    }
+
     pub(crate) fn finished_child(id: BastionId, parent_id: BastionId) -> Self {
         BastionMessage::FinishedChild { id, parent_id }
     }
@@ -485,6 +706,163 @@ impl BastionMessage {
         BastionMessage::Faulted { id }
     }
 
+    pub(crate) fn escalate(id: BastionId, info: FaultInfo) -> Self {
+        BastionMessage::Escalate { id, info }
+    }
+
+    pub(crate) fn set_child_weight(id: BastionId, weight: usize) -> Self {
+        BastionMessage::SetChildWeight { id, weight }
+    }
+
+    pub(crate) fn subscribe(id: BastionId, topic: String) -> Self {
+        BastionMessage::Subscribe { id, topic }
+    }
+
+    pub(crate) fn unsubscribe(id: BastionId, topic: String) -> Self {
+        BastionMessage::Unsubscribe { id, topic }
+    }
+
+    pub(crate) fn publish<M: Message>(topic: String, msg: M) -> Self {
+        let msg = Msg::broadcast(msg);
+        BastionMessage::Publish { topic, msg }
+    }
+
+    pub(crate) fn register_name(id: BastionId, name: String) -> Self {
+        BastionMessage::RegisterName { id, name }
+    }
+
+    pub(crate) fn send_named<M: Message>(name: String, msg: M) -> Self {
+        let msg = Msg::tell(msg);
+        BastionMessage::SendNamed { name, msg }
+    }
+
+    pub(crate) fn reparent(parent: Parent, path: Arc<BastionPath>) -> Self {
+        let parent = Box::new(parent);
+        BastionMessage::Reparent { parent, path }
+    }
+
+    pub(crate) fn reparented(id: BastionId) -> Self {
+        BastionMessage::Reparented { id }
+    }
+
+    pub(crate) fn ready(id: BastionId) -> Self {
+        BastionMessage::Ready { id }
+    }
+
+    pub(crate) fn backpressure(id: BastionId, signal: BackpressureSignal) -> Self {
+        BastionMessage::Backpressure { id, signal }
+    }
+
+    pub(crate) fn tree_broadcast<M: Message>(msg: M, ttl: usize) -> Self {
+        let payload = Msg::broadcast(msg);
+        BastionMessage::Broadcast { payload, ttl }
+    }
+
+    pub(crate) fn is_broadcast(&self) -> bool {
+        matches!(self, BastionMessage::Broadcast { .. })
+    }
+
+    /// Returns whether this message tells a parent that one of
+    /// its children faulted, which is received before the other
+    /// messages sent to the parent.
+    pub(crate) fn is_fault_notice(&self) -> bool {
+        matches!(
+            self,
+            BastionMessage::Faulted { .. }
+                | BastionMessage::Escalate { .. }
+                | BastionMessage::RestartRequired { .. }
+                | BastionMessage::Panicked { .. }
+        )
+    }
+
+    pub(crate) fn suspend() -> Self {
+        BastionMessage::Suspend
+    }
+
+    pub(crate) fn resume() -> Self {
+        BastionMessage::Resume
+    }
+
+    pub(crate) fn is_suspend(&self) -> bool {
+        matches!(self, BastionMessage::Suspend)
+    }
+
+    pub(crate) fn ping() -> Self {
+        BastionMessage::Ping
+    }
+
+    pub(crate) fn pong(id: BastionId) -> Self {
+        BastionMessage::Pong { id }
+    }
+
+    pub(crate) fn send_child<M: Message>(
+        id: BastionId,
+        msg: M,
+    ) -> (Self, oneshot::Receiver<Result<(), SendError>>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = Msg::tell(msg);
+        let msg = BastionMessage::SendChild { id, msg, ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn children_older_than(age: Duration) -> (Self, oneshot::Receiver<Vec<BastionId>>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::ChildrenOlderThan { age, ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn cancel_children(ids: Vec<BastionId>) -> Self {
+        BastionMessage::CancelChildren { ids }
+    }
+
+    pub(crate) fn drain_mailbox(id: BastionId) -> (Self, oneshot::Receiver<Vec<SignedMessage>>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::DrainMailbox { id, ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn await_exit(id: BastionId) -> (Self, oneshot::Receiver<StopReason>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::AwaitExit { id, ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn wait_for_children(n: usize) -> (Self, oneshot::Receiver<()>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::WaitForChildren { n, ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn topology() -> (Self, oneshot::Receiver<TopologyNode>) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::Topology { ack };
+
+        (msg, recver)
+    }
+
+    pub(crate) fn poison_pill(child: ChildRef, handover: Option<Sender>) -> (Self, DeathNotice) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::PoisonPill {
+            child,
+            ack,
+            handover,
+        };
+
+        (msg, DeathNotice(recver))
+    }
+
+    pub(crate) fn graceful_restart(child: ChildRef, init: Init) -> (Self, DeathNotice) {
+        let (ack, recver) = oneshot::channel();
+        let msg = BastionMessage::GracefulRestart { child, init, ack };
+
+        (msg, DeathNotice(recver))
+    }
+
     pub(crate) fn try_clone(&self) -> Option<Self> {
         trace!("{:?}: Trying to clone.", self);
         let clone = match self {
@@ -513,6 +891,9 @@ impl BastionMessage {
             BastionMessage::RestartRequired { id, parent_id } => {
                 BastionMessage::restart_required(id.clone(), parent_id.clone())
             }
+            BastionMessage::Panicked { id, parent_id } => {
+                BastionMessage::panicked(id.clone(), parent_id.clone())
+            }
             BastionMessage::FinishedChild { id, parent_id } => {
                 BastionMessage::finished_child(id.clone(), parent_id.clone())
             }
@@ -524,25 +905,126 @@ impl BastionMessage {
             BastionMessage::SetState { state } => BastionMessage::set_state(state.clone()),
             BastionMessage::Stopped { id } => BastionMessage::stopped(id.clone()),
             BastionMessage::Faulted { id } => BastionMessage::faulted(id.clone()),
+            BastionMessage::Escalate { id, info } => {
+                BastionMessage::escalate(id.clone(), info.clone())
+            }
+            BastionMessage::SetChildWeight { id, weight } => {
+                BastionMessage::set_child_weight(id.clone(), *weight)
+            }
+            // The acknowledgement can only be sent once.
+            BastionMessage::PoisonPill { .. } => return None,
+            BastionMessage::GracefulRestart { .. } => return None,
+            BastionMessage::Topology { .. } => return None,
+            BastionMessage::SendChild { .. } => return None,
+            BastionMessage::ChildrenOlderThan { .. } => return None,
+            BastionMessage::CancelChildren { ids } => BastionMessage::cancel_children(ids.clone()),
+            BastionMessage::DrainMailbox { .. } => return None,
+            BastionMessage::AwaitExit { .. } => return None,
+            BastionMessage::WaitForChildren { .. } => return None,
+            BastionMessage::Ping => BastionMessage::ping(),
+            BastionMessage::Pong { id } => BastionMessage::pong(id.clone()),
+            BastionMessage::Subscribe { id, topic } => {
+                BastionMessage::subscribe(id.clone(), topic.clone())
+            }
+            BastionMessage::Unsubscribe { id, topic } => {
+                BastionMessage::unsubscribe(id.clone(), topic.clone())
+            }
+            BastionMessage::Publish { topic, msg } => BastionMessage::Publish {
+                topic: topic.clone(),
+                msg: msg.try_clone()?,
+            },
+            BastionMessage::RegisterName { id, name } => {
+                BastionMessage::register_name(id.clone(), name.clone())
+            }
+            BastionMessage::SendNamed { name, msg } => BastionMessage::SendNamed {
+                name: name.clone(),
+                msg: msg.try_clone()?,
+            },
+            BastionMessage::Reparent { parent, path } => {
+                BastionMessage::reparent(Parent::clone(parent), path.clone())
+            }
+            BastionMessage::Reparented { id } => BastionMessage::reparented(id.clone()),
+            BastionMessage::Ready { id } => BastionMessage::ready(id.clone()),
+            BastionMessage::Backpressure { id, signal } => {
+                BastionMessage::backpressure(id.clone(), *signal)
+            }
+            BastionMessage::Broadcast { payload, ttl } => BastionMessage::Broadcast {
+                payload: payload.try_clone()?,
+                ttl: *ttl,
+            },
+            BastionMessage::Suspend => BastionMessage::suspend(),
+            BastionMessage::Resume => BastionMessage::resume(),
         };
 
         Some(clone)
     }
 
     pub(crate) fn into_msg<M: Message>(self) -> Option<M> {
-        if let BastionMessage::Message(msg) = self {
-            msg.try_unwrap().ok()
-        } else {
-            None
+        match self {
+            BastionMessage::Message(msg)
+            | BastionMessage::Publish { msg, .. }
+            | BastionMessage::SendNamed { msg, .. }
+            | BastionMessage::Broadcast { payload: msg, .. } => msg.try_unwrap().ok(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn into_shared<M: Message>(self) -> Option<Arc<M>> {
+        match self {
+            BastionMessage::Message(msg)
+            | BastionMessage::Publish { msg, .. }
+            | BastionMessage::Broadcast { payload: msg, .. } => msg.downcast_ref(),
+            _ => None,
         }
     }
 }
 
+impl Dead {
+    pub(crate) fn new(id: BastionId, reason: DeathReason) -> Self {
+        Dead { id, reason }
+    }
+}
+
+impl Future for DeathNotice {
+    type Output = Result<Dead, ()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        debug!("{:?}: Polling.", self);
+        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+    }
+}
+
+impl Answer {
+    pub(crate) fn with_edge(mut self, edge: AskEdge) -> Self {
+        self.1 = Some(edge);
+        self
+    }
+
+    /// Returns whether the element this answer is expected from
+    /// was found to be itself (transitively) waiting for an answer
+    /// from the element which sent the question, in which case this
+    /// answer resolves to an error instead of never resolving.
+    ///
+    /// The question isn't sent if the element it was asked to is
+    /// directly waiting for an answer from the element which asked
+    /// it. The longer cycles are only looked for periodically
+    /// (about four times a second), after which one of their
+    /// questions is resolved with an error.
+    pub fn is_deadlocked(&self) -> bool {
+        self.1.as_ref().map(AskEdge::is_deadlocked).unwrap_or(false)
+    }
+}
+
 impl Future for Answer {
     type Output = Result<SignedMessage, ()>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         debug!("{:?}: Polling.", self);
+        let deadlocked = self.1.as_ref().map(|edge| edge.poll_deadlocked(ctx));
+        if deadlocked == Some(true) {
+            return Poll::Ready(Err(()));
+        }
+
         Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
     }
 }
@@ -0,0 +1,46 @@
+//!
+//! Middlewares seeing every message sent by an element to its
+//! children before it is delivered.
+use crate::context::BastionId;
+use crate::message::BastionMessage;
+use std::fmt::{self, Debug, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// What should be done with a message once a middleware saw it
+/// (see [`Children::with_middleware`]).
+///
+/// [`Children::with_middleware`]: ../children/struct.Children.html#method.with_middleware
+pub enum MiddlewareAction {
+    /// Passes the message to the next middleware, or delivers it
+    /// if this was the last one.
+    Forward,
+    /// Drops the message without running the next middlewares.
+    Drop,
+    /// Delivers the message to the element with the given
+    /// identifier instead, without running the next middlewares.
+    /// The message is dropped if the element isn't a child of the
+    /// one sending it.
+    Redirect(BastionId),
+}
+
+/// A middleware registered with `Broadcast::add_middleware`.
+pub(crate) struct Middleware(Box<dyn Fn(&mut BastionMessage) -> MiddlewareAction + Send + Sync>);
+
+impl Middleware {
+    pub(crate) fn new<F>(middleware: F) -> Self
+    where
+        F: Fn(&mut BastionMessage) -> MiddlewareAction + Send + Sync + 'static,
+    {
+        Middleware(Box::new(middleware))
+    }
+
+    pub(crate) fn apply(&self, msg: &mut BastionMessage) -> MiddlewareAction {
+        (self.0)(msg)
+    }
+}
+
+impl Debug for Middleware {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Middleware").finish()
+    }
+}
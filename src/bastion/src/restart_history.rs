@@ -0,0 +1,172 @@
+//!
+//! The history of the restarts of a children group's elements,
+//! allowing to spot the groups whose elements keep faulting.
+use crate::clock;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How many restart times a children group remembers, if it
+/// isn't changed (see [`RestartHistory::new`]).
+///
+/// [`RestartHistory::new`]: struct.RestartHistory.html#method.new
+pub const DEFAULT_HISTORY_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone)]
+/// The configuration of the restart history of a children group
+/// (see [`Children::with_restart_history`]).
+///
+/// The group counts how many times its elements were restarted
+/// and remembers when the last `capacity` restarts happened.
+///
+/// By default, the count covers the whole lifetime of the group.
+/// When a window is set (see [`RestartHistory::with_window`]),
+/// the count is reset to zero once `window` elapsed since the
+/// first restart it counted, so that it only covers the current
+/// window. The remembered restart times are never reset.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| {
+///     children.with_restart_history(
+///         RestartHistory::new(32).with_window(Duration::from_secs(60)),
+///     )
+/// }).expect("Couldn't create the children group.");
+/// #
+/// # Bastion::start();
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Children::with_restart_history`]: ../children/struct.Children.html#method.with_restart_history
+/// [`RestartHistory::with_window`]: #method.with_window
+pub struct RestartHistory {
+    capacity: usize,
+    window: Option<Duration>,
+}
+
+impl RestartHistory {
+    /// Creates a new restart history configuration, counting the
+    /// restarts over the whole lifetime of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - How many restart times the group remembers
+    ///     (the oldest ones are forgotten first).
+    pub fn new(capacity: usize) -> Self {
+        RestartHistory {
+            capacity,
+            window: None,
+        }
+    }
+
+    /// Makes the restart count only cover the restarts that
+    /// happened in the current window, which starts at the first
+    /// restart after the previous window elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How long the restarts are counted for before
+    ///     the count is reset.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Returns how many restart times the group remembers.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns how long the restarts are counted for before the
+    /// count is reset, or `None` if it covers the whole lifetime
+    /// of the group.
+    pub fn window(&self) -> Option<Duration> {
+        self.window
+    }
+}
+
+impl Default for RestartHistory {
+    fn default() -> Self {
+        RestartHistory::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+#[derive(Debug, Default)]
+/// The restarts of a children group's elements, shared between
+/// the group and its references.
+pub(crate) struct SharedRestarts(Mutex<Restarts>);
+
+#[derive(Debug, Default)]
+struct Restarts {
+    config: RestartHistory,
+    count: usize,
+    // When the first restart being counted happened, if the
+    // count is reset after a window.
+    window_start: Option<Instant>,
+    last: Option<Instant>,
+    // The latest restart times, oldest first.
+    times: VecDeque<Instant>,
+}
+
+impl Restarts {
+    fn expire(&mut self, now: Instant) {
+        if let (Some(window), Some(start)) = (self.config.window, self.window_start) {
+            if now.duration_since(start) >= window {
+                self.count = 0;
+                self.window_start = None;
+            }
+        }
+    }
+}
+
+impl SharedRestarts {
+    pub(crate) fn configure(&self, config: RestartHistory) {
+        let mut restarts = self.0.lock().unwrap();
+        while restarts.times.len() > config.capacity {
+            restarts.times.pop_front();
+        }
+
+        restarts.config = config;
+    }
+
+    pub(crate) fn record(&self) {
+        let now = clock::now();
+        let mut restarts = self.0.lock().unwrap();
+        restarts.expire(now);
+
+        restarts.count += 1;
+        if restarts.window_start.is_none() {
+            restarts.window_start = Some(now);
+        }
+
+        restarts.last = Some(now);
+
+        if restarts.config.capacity > 0 {
+            if restarts.times.len() == restarts.config.capacity {
+                restarts.times.pop_front();
+            }
+            restarts.times.push_back(now);
+        }
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        let mut restarts = self.0.lock().unwrap();
+        restarts.expire(clock::now());
+        restarts.count
+    }
+
+    pub(crate) fn last(&self) -> Option<Instant> {
+        self.0.lock().unwrap().last
+    }
+
+    pub(crate) fn times(&self) -> Vec<Instant> {
+        self.0.lock().unwrap().times.iter().copied().collect()
+    }
+}
@@ -0,0 +1,111 @@
+//!
+//! Scopes tying the lifetime of children groups to a closure (see
+//! [`Bastion::scoped`]).
+//!
+//! [`Bastion::scoped`]: ../struct.Bastion.html#method.scoped
+use crate::bastion::Bastion;
+use crate::children::Children;
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionContext;
+use std::future::Future;
+use std::sync::{Condvar, Mutex};
+use tracing::debug;
+
+#[derive(Debug, Default)]
+/// A scope in which children groups are created, which are killed
+/// if they are still running when it is dropped (see
+/// [`Bastion::scoped`]).
+///
+/// [`Bastion::scoped`]: ../struct.Bastion.html#method.scoped
+pub struct Scope {
+    groups: Mutex<Vec<ChildrenRef>>,
+}
+
+#[derive(Debug, Default)]
+/// How many elements a children group runs, shared between the
+/// group and its references to let them wait for the elements to
+/// stop.
+pub(crate) struct LiveElems {
+    count: Mutex<usize>,
+    changed: Condvar,
+}
+
+impl Scope {
+    /// Creates a new children group like [`Bastion::children`]
+    /// does, tying its lifetime to this scope.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the newly
+    /// created children group if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once modified.
+    ///
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
+    /// [`Children`]: ../children/struct.Children.html
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    pub fn children<C>(&self, init: C) -> Result<ChildrenRef, ()>
+    where
+        C: FnOnce(Children) -> Children,
+    {
+        let children_ref = Bastion::children(init)?;
+        self.groups.lock().unwrap().push(children_ref.clone());
+
+        Ok(children_ref)
+    }
+
+    /// Creates a new children group running the given closure like
+    /// [`Bastion::spawn`] does, tying its lifetime to this scope.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the newly
+    /// created children group if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The closure which gets executed by the child.
+    ///
+    /// [`Bastion::spawn`]: ../struct.Bastion.html#method.spawn
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    pub fn spawn<I, F>(&self, action: I) -> Result<ChildrenRef, ()>
+    where
+        I: Fn(BastionContext) -> F + Send + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        self.children(|children| children.with_exec(action))
+    }
+
+    /// Blocks the current thread until the elements of all the
+    /// groups created in this scope stopped.
+    pub(crate) fn wait(&self) {
+        for group in self.groups.lock().unwrap().iter() {
+            group.live_elems().wait_until_none();
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        for group in self.groups.get_mut().unwrap().drain(..) {
+            debug!("Scope: Killing ChildrenRef({}).", group.id());
+            // The group might already be stopped.
+            group.kill().ok();
+        }
+    }
+}
+
+impl LiveElems {
+    pub(crate) fn set(&self, count: usize) {
+        *self.count.lock().unwrap() = count;
+        self.changed.notify_all();
+    }
+
+    pub(crate) fn wait_until_none(&self) {
+        let mut count = self.count.lock().unwrap();
+        while *count > 0 {
+            count = self.changed.wait(count).unwrap();
+        }
+    }
+}
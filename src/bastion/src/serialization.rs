@@ -0,0 +1,216 @@
+//!
+//! Encoding of messages to bytes and back, which allows sending them
+//! to other processes (enabled by the `distributed` feature).
+//!
+//! A message can be encoded if its type implements
+//! [`SerializableMessage`] and was registered in the
+//! [`MessageRegistry`]. The encoded message carries the
+//! [`SerializableMessage::TAG`] of its type, which the process
+//! receiving it uses to find how to decode it, so both processes
+//! need to register the type with the same tag.
+//!
+//! Only the messages sent with `tell` or `broadcast` can be encoded,
+//! since the answer to a question can't be sent back from another
+//! process yet. Decoded messages are received as if they were sent
+//! with `tell`.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use bastion::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! struct Ping(u32);
+//!
+//! impl SerializableMessage for Ping {
+//!     const TAG: &'static str = "ping";
+//! }
+//!
+//! MessageRegistry::register::<Ping>();
+//!
+//! // e.g. in another process.
+//! let bytes = Ping(42).to_bytes().expect("Couldn't encode the message.");
+//! let msg = Msg::from_bytes(&bytes).expect("Couldn't decode the message.");
+//!
+//! assert_eq!(msg.downcast::<Ping>().unwrap(), Ping(42));
+//! ```
+use crate::message::{Message, Msg};
+use fxhash::FxHashMap;
+use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Codecs> = RwLock::default();
+}
+
+/// A message which can be encoded to bytes, to be sent to another
+/// process, and decoded back.
+///
+/// Its type needs to be registered using
+/// [`MessageRegistry::register`] in both processes.
+pub trait SerializableMessage: Message + Serialize + DeserializeOwned {
+    /// The tag identifying the message's type once encoded, which
+    /// must be unique and the same in all the processes.
+    const TAG: &'static str;
+
+    /// Encodes the message, along with its tag.
+    ///
+    /// This doesn't need the message's type to be registered.
+    fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let payload = serde_json::to_value(self)?;
+        Wire::new(Self::TAG, payload).to_bytes()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The registry of the message types which can be encoded and
+/// decoded, mapping their tags to the way they are decoded.
+pub struct MessageRegistry;
+
+impl MessageRegistry {
+    /// Registers a message type, allowing the messages of this type
+    /// to be encoded and decoded.
+    ///
+    /// Registering another type with the same tag replaces the
+    /// type previously registered with it.
+    pub fn register<M: SerializableMessage>() {
+        let codec = Codec {
+            tag: M::TAG,
+            encode: encode::<M>,
+            decode: decode::<M>,
+        };
+
+        let mut registry = REGISTRY.write().unwrap();
+        registry.tags.insert(TypeId::of::<M>(), M::TAG);
+        registry.codecs.insert(M::TAG, codec);
+    }
+
+    /// Returns whether a message type was registered with this tag.
+    pub fn is_registered(tag: &str) -> bool {
+        REGISTRY.read().unwrap().codecs.contains_key(tag)
+    }
+}
+
+#[derive(Debug)]
+/// The error returned when a message couldn't be encoded or decoded.
+pub enum SerializationError {
+    /// The message's type wasn't registered.
+    Unregistered,
+    /// The message can't be sent to another process, e.g. because
+    /// it is a question.
+    Unsupported,
+    /// No message type was registered with the tag of the decoded
+    /// message.
+    UnknownTag(String),
+    /// The message or its tag couldn't be encoded or decoded.
+    Codec(serde_json::Error),
+}
+
+#[derive(Default)]
+struct Codecs {
+    tags: FxHashMap<TypeId, &'static str>,
+    codecs: FxHashMap<&'static str, Codec>,
+}
+
+struct Codec {
+    tag: &'static str,
+    encode: fn(&(dyn Any + Send + Sync)) -> serde_json::Result<Value>,
+    decode: fn(Value) -> serde_json::Result<Msg>,
+}
+
+#[derive(Serialize, Deserialize)]
+/// A message once encoded.
+struct Wire {
+    tag: String,
+    payload: Value,
+}
+
+fn encode<M: SerializableMessage>(msg: &(dyn Any + Send + Sync)) -> serde_json::Result<Value> {
+    // The registry only uses this for messages of type `M`.
+    serde_json::to_value(msg.downcast_ref::<M>().unwrap())
+}
+
+fn decode<M: SerializableMessage>(payload: Value) -> serde_json::Result<Msg> {
+    serde_json::from_value::<M>(payload).map(Msg::tell)
+}
+
+impl Wire {
+    fn new(tag: &str, payload: Value) -> Self {
+        let tag = tag.to_string();
+        Wire { tag, payload }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+}
+
+impl Msg {
+    /// Encodes the message if its type was registered in the
+    /// [`MessageRegistry`] and it wasn't sent as a question.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let payload = self.payload().ok_or(SerializationError::Unsupported)?;
+
+        let registry = REGISTRY.read().unwrap();
+        let codec = registry
+            .tags
+            .get(&Any::type_id(payload))
+            .and_then(|tag| registry.codecs.get(tag))
+            .ok_or(SerializationError::Unregistered)?;
+
+        let payload = (codec.encode)(payload)?;
+        Wire::new(codec.tag, payload).to_bytes()
+    }
+
+    /// Decodes a message encoded by [`Msg::to_bytes`] or
+    /// [`SerializableMessage::to_bytes`], possibly in another
+    /// process, as if it was sent with `tell`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let wire: Wire = serde_json::from_slice(bytes)?;
+
+        let registry = REGISTRY.read().unwrap();
+        let codec = registry
+            .codecs
+            .get(wire.tag.as_str())
+            .ok_or(SerializationError::UnknownTag(wire.tag))?;
+
+        Ok((codec.decode)(wire.payload)?)
+    }
+}
+
+impl From<serde_json::Error> for SerializationError {
+    fn from(err: serde_json::Error) -> Self {
+        SerializationError::Codec(err)
+    }
+}
+
+impl Display for SerializationError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            SerializationError::Unregistered => write!(fmt, "the message's type isn't registered"),
+            SerializationError::Unsupported => {
+                write!(fmt, "the message can't be sent to another process")
+            }
+            SerializationError::UnknownTag(tag) => {
+                write!(fmt, "no message type is registered with the tag {:?}", tag)
+            }
+            SerializationError::Codec(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl Error for SerializationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SerializationError::Codec(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//!
+//! Sinks sending the items they are given to elements of a
+//! children group, waiting while the elements are at capacity.
+use crate::child_ref::ChildRef;
+use crate::children_ref::{ChildrenRef, SendError};
+use crate::message::Message;
+use futures::sink::Sink;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A [`Sink`] sending the items it is given as messages to an
+/// element of a children group or to all of them, which allows
+/// forwarding a stream to actors.
+///
+/// The sink is only ready to send an item once the elements it
+/// targets aren't at capacity anymore (see
+/// [`ChildRef::is_at_capacity`]), which only happens when their
+/// group sheds its load (see [`Children::with_backpressure`]).
+/// The items are enqueued right away, so flushing the sink waits
+/// for the targeted elements to go back under capacity. The sink
+/// is woken up as soon as they do so.
+///
+/// A sink is created for a single element using
+/// [`ChildRef::sink`], or for all the elements of a group using
+/// [`ChildrenRef::sink`], in which case each item is broadcasted
+/// to them.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use futures::stream::{self, StreamExt};
+/// #
+/// # Bastion::init();
+/// #
+/// let children_ref = Bastion::children(|children| {
+///     // ...
+/// # children.with_exec(|ctx: BastionContext| {
+/// #     async move {
+/// #         loop {
+/// #             ctx.recv().await?;
+/// #         }
+/// #     }
+/// # })
+/// }).expect("Couldn't create the children group.");
+///
+/// # Bastion::start();
+/// # run!(async {
+/// stream::iter(vec![1u32, 2, 3])
+///     .map(Ok)
+///     .forward(children_ref.elems()[0].sink())
+///     .await
+///     .expect("Couldn't forward the stream.");
+/// # });
+/// #
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Sink`]: https://docs.rs/futures/0.3/futures/sink/trait.Sink.html
+/// [`ChildRef::is_at_capacity`]: ../child_ref/struct.ChildRef.html#method.is_at_capacity
+/// [`ChildRef::sink`]: ../child_ref/struct.ChildRef.html#method.sink
+/// [`ChildrenRef::sink`]: ../children_ref/struct.ChildrenRef.html#method.sink
+/// [`Children::with_backpressure`]: ../children/struct.Children.html#method.with_backpressure
+pub struct MessageSink<M> {
+    target: Target,
+    _marker: PhantomData<fn(M)>,
+}
+
+enum Target {
+    Child(ChildRef),
+    Children(ChildrenRef),
+}
+
+impl<M> MessageSink<M> {
+    pub(crate) fn child(child: ChildRef) -> Self {
+        MessageSink::new(Target::Child(child))
+    }
+
+    pub(crate) fn children(children: ChildrenRef) -> Self {
+        MessageSink::new(Target::Children(children))
+    }
+
+    fn new(target: Target) -> Self {
+        MessageSink {
+            target,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Resolves once the targeted elements aren't at capacity,
+    /// waking up the task as soon as one of them has room again.
+    fn poll_capacity(&self, cx: &mut Context) -> Poll<()> {
+        match &self.target {
+            Target::Child(child) => child.at_capacity().poll_room(cx),
+            Target::Children(children) => {
+                // Every element at capacity has to wake the task up.
+                let mut room = Poll::Ready(());
+                for child in children.elems() {
+                    if child.at_capacity().poll_room(cx).is_pending() {
+                        room = Poll::Pending;
+                    }
+                }
+
+                room
+            }
+        }
+    }
+}
+
+impl<M: Message> Sink<M> for MessageSink<M> {
+    type Error = SendError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), SendError>> {
+        self.poll_capacity(cx).map(Ok)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: M) -> Result<(), SendError> {
+        let sent = match &self.target {
+            Target::Child(child) => child.tell_anonymously(item),
+            Target::Children(children) => children.broadcast(item),
+        };
+
+        sent.map_err(|_| SendError::Closed)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), SendError>> {
+        self.poll_capacity(cx).map(Ok)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), SendError>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<M> Unpin for MessageSink<M> {}
+
+impl<M> Debug for MessageSink<M> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let mut debug = fmt.debug_struct("MessageSink");
+        match &self.target {
+            Target::Child(child) => debug.field("child", child.id()),
+            Target::Children(children) => debug.field("children", children.id()),
+        };
+        debug.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast;
+    use crate::context::BastionId;
+    use crate::path::BastionPath;
+    use futures::task::{waker, ArcWake};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct CountWakes(AtomicUsize);
+
+    impl ArcWake for CountWakes {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn wakes_up_once_the_child_has_room() {
+        let wakes = Arc::new(CountWakes::default());
+        let waker = waker(wakes.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let (sender, _recver) = broadcast::channel();
+        let path = Arc::new(BastionPath::root());
+        let child = ChildRef::new(BastionId::new(), sender, "child".to_string(), path);
+        let mut sink = MessageSink::<u32>::child(child.clone());
+
+        child.at_capacity().set_full(true);
+        assert!(Pin::new(&mut sink).poll_ready(&mut cx).is_pending());
+        assert!(Pin::new(&mut sink).poll_flush(&mut cx).is_pending());
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        // The sink is woken up as soon as the child has room again.
+        child.at_capacity().set_full(false);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            Pin::new(&mut sink).poll_ready(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+    }
+}
@@ -3,19 +3,20 @@
 //! or other supervisor trees under themselves.
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::callbacks::Callbacks;
-use crate::children::Children;
+use crate::children::{Children, PanicPolicy};
 use crate::children_ref::ChildrenRef;
+use crate::clock::{self, Sleep};
 use crate::context::{BastionId, ContextState};
 use crate::envelope::Envelope;
+use crate::executor::spawn_with;
 use crate::message::{BastionMessage, Deployment, Message};
 use crate::path::{BastionPath, BastionPathElement};
+use crate::topology::{TopologyKind, TopologyNode};
 use async_mutex::Mutex;
-use bastion_executor::pool;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use futures::{pending, poll};
-use futures_timer::Delay;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use lightproc::prelude::*;
 use std::cmp::{Eq, PartialEq};
 use std::ops::Range;
@@ -73,6 +74,12 @@ pub struct Supervisor {
     tracked_groups: FxHashMap<BastionId, Vec<TrackedChildState>>,
     // Hold the insertion order of the childs.
     tracked_groups_order: FxHashMap<BastionId, usize>,
+    // The policy applied when an element of each children group
+    // panics, keyed by the group's identifier.
+    panic_policies: FxHashMap<BastionId, PanicPolicy>,
+    // The supervised children groups (as opposed to the
+    // supervised supervisors).
+    groups: FxHashSet<BastionId>,
     // The currently launched supervised children and supervisors.
     // The last value is the amount of times a given actor has restarted.
     launched: FxHashMap<BastionId, (usize, RecoverableHandle<Supervised>)>,
@@ -101,6 +108,20 @@ pub struct Supervisor {
     subtree_restarts: usize,
     // Store the maximum acceptable restarts for the supervisor.
     subtree_restarts_limit: usize,
+    // The window over which faults are batched together before
+    // applying the strategy, if any.
+    fault_debounce: Option<Duration>,
+    // The faults received during the current debounce window,
+    // along with the timer which will end it.
+    pending_faults: Vec<(BastionId, BastionId)>,
+    debounce_timer: Option<Sleep>,
+    // The order in which the supervisor receives the envelopes
+    // sent to it.
+    poll_bias: PollBias,
+    // The name of the supervisor, if any, and the path made of
+    // the names of its parents.
+    name: Option<String>,
+    parent_path: String,
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +131,16 @@ struct TrackedChildState {
     restarts_counts: usize,
 }
 
+#[derive(Debug, Clone)]
+/// Describes a fault that a supervisor couldn't handle itself
+/// and is escalating to its own parent.
+pub(crate) struct FaultInfo {
+    // The identifier of the element that originally faulted.
+    origin: BastionId,
+    // The identifier of the originally faulted element's parent.
+    parent_id: BastionId,
+}
+
 #[derive(Debug)]
 enum RestartedElement {
     Supervisor(BastionId),
@@ -123,6 +154,9 @@ enum ActorSearchMethod {
     All,
 }
 
+/// The human-readable path of the root of the supervision tree.
+pub(crate) const ROOT_NAMED_PATH: &str = "/root";
+
 #[derive(Debug, Clone)]
 /// A "reference" to a [`Supervisor`], allowing to
 /// communicate with it.
@@ -132,6 +166,7 @@ pub struct SupervisorRef {
     id: BastionId,
     sender: Sender,
     path: Arc<BastionPath>,
+    named_path: Arc<str>,
 }
 
 #[derive(Debug, Clone)]
@@ -159,9 +194,17 @@ pub enum SupervisionStrategy {
     /// were stopped) in the same order they were added to
     /// the supervisor.
     RestForOne,
+    /// When a children group or a supervisor dies, the
+    /// supervisor doesn't try to handle the fault itself and
+    /// escalates it to its own parent instead, which will then
+    /// restart this supervisor's whole subtree according to its
+    /// own strategy (or escalate it further if it uses this
+    /// strategy too).
+    Escalate,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum Supervised {
     Supervisor(Supervisor),
     Children(Children),
@@ -223,12 +266,35 @@ pub enum ActorRestartStrategy {
     },
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+/// The order in which a supervisor receives the messages sent
+/// to it (see [`Supervisor::with_poll_bias`]).
+///
+/// The default bias is `ControlFirst`.
+///
+/// [`Supervisor::with_poll_bias`]: supervisor/struct.Supervisor.html#method.with_poll_bias
+pub enum PollBias {
+    /// Receive the lifecycle messages (e.g. that an element
+    /// faulted) before the data messages sent earlier. Up to 64
+    /// data messages are put aside to do so, after which they are
+    /// received before looking for lifecycle messages further, so
+    /// that a flood of data messages only delays fault handling by
+    /// a bounded amount of messages. The notices of the elements
+    /// which faulted are always received first.
+    #[default]
+    ControlFirst,
+    /// Receive the messages in the order they were sent in.
+    Fifo,
+}
+
 impl Supervisor {
-    pub(crate) fn new(bcast: Broadcast) -> Self {
+    pub(crate) fn new(mut bcast: Broadcast) -> Self {
         debug!("Supervisor({}): Initializing.", bcast.id());
         let order = Vec::new();
         let tracked_groups = FxHashMap::default();
         let tracked_groups_order = FxHashMap::default();
+        let panic_policies = FxHashMap::default();
+        let groups = FxHashSet::default();
         let launched = FxHashMap::default();
         let stopped = FxHashMap::default();
         let killed = FxHashMap::default();
@@ -240,12 +306,21 @@ impl Supervisor {
         let started = false;
         let subtree_restarts = 0;
         let subtree_restarts_limit = 3;
+        let fault_debounce = None;
+        let pending_faults = Vec::new();
+        let debounce_timer = None;
+        let poll_bias = PollBias::default();
+        let name = None;
+        let parent_path = ROOT_NAMED_PATH.to_string();
+        bcast.set_poll_bias(poll_bias);
 
         Supervisor {
             bcast,
             order,
             tracked_groups,
             tracked_groups_order,
+            panic_policies,
+            groups,
             launched,
             stopped,
             killed,
@@ -257,6 +332,12 @@ impl Supervisor {
             started,
             subtree_restarts,
             subtree_restarts_limit,
+            fault_debounce,
+            pending_faults,
+            debounce_timer,
+            poll_bias,
+            name,
+            parent_path,
         }
     }
 
@@ -291,7 +372,8 @@ impl Supervisor {
         // TODO: stop or kill?
         self.kill(0..self.order.len()).await;
 
-        if let Some(bcast) = bcast {
+        if let Some(mut bcast) = bcast {
+            bcast.set_poll_bias(self.poll_bias);
             self.bcast = bcast;
         } else {
             self.bcast.clear_children();
@@ -305,6 +387,10 @@ impl Supervisor {
         self.pre_start_msgs.clear();
         self.pre_start_msgs.shrink_to_fit();
 
+        // All the elements are restarted anyway.
+        self.pending_faults.clear();
+        self.debounce_timer = None;
+
         let restarted_objects = self.search_restarted_objects(ActorSearchMethod::All);
         self.restart(restarted_objects).await;
 
@@ -351,7 +437,7 @@ impl Supervisor {
     /// # Bastion::block_until_stopped();
     /// ```
     pub fn id(&self) -> &BastionId {
-        &self.bcast.id()
+        self.bcast.id()
     }
 
     pub(crate) fn bcast(&self) -> &Broadcast {
@@ -373,7 +459,61 @@ impl Supervisor {
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
 
-        SupervisorRef::new(id, sender, path)
+        SupervisorRef::new(id, sender, path).with_named_path(self.named_path())
+    }
+
+    pub(crate) fn with_parent_path(mut self, parent_path: impl Into<String>) -> Self {
+        self.parent_path = parent_path.into();
+        self
+    }
+
+    pub(crate) fn named_path(&self) -> String {
+        // The groups created with `Bastion::children` are at the
+        // root of the tree.
+        if self.is_system_supervisor {
+            return ROOT_NAMED_PATH.to_string();
+        }
+
+        match &self.name {
+            Some(name) => format!("{}/{}", self.parent_path, name),
+            None => format!("{}/{}", self.parent_path, self.id()),
+        }
+    }
+
+    /// Sets the name of this supervisor, which is used to build
+    /// the human-readable paths of the supervisor and of the
+    /// children groups and supervisors it supervises (e.g.
+    /// `/root/supervisorA/workers`). An anonymous supervisor
+    /// appears with its identifier in those paths.
+    ///
+    /// The elements only get the name of their parent if it was
+    /// set before they were added to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the supervisor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// let sp_ref = Bastion::supervisor(|sp| {
+    ///     sp.with_name("supervisorA")
+    ///         .children(|children| children.with_name("workers"))
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// assert_eq!(sp_ref.named_path(), "/root/supervisorA");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
     }
 
     /// Creates a new supervisor, passes it through the specified
@@ -423,7 +563,7 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let supervisor = Supervisor::new(bcast);
+        let supervisor = Supervisor::new(bcast).with_parent_path(self.named_path());
         let supervisor = init(supervisor);
         debug!("Supervisor({}): Initialized.", supervisor.id());
 
@@ -434,7 +574,13 @@ impl Supervisor {
         );
         let msg = BastionMessage::deploy_supervisor(supervisor);
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_self(env);
+        if let Err(err) = self.bcast.send_self(env) {
+            warn!(
+                "Supervisor({}): Couldn't deploy element: {}",
+                self.id(),
+                err
+            );
+        }
 
         self
     }
@@ -487,7 +633,7 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let supervisor = Supervisor::new(bcast);
+        let supervisor = Supervisor::new(bcast).with_parent_path(self.named_path());
         let supervisor = init(supervisor);
         debug!("Supervisor({}): Initialized.", supervisor.id());
         let supervisor_ref = supervisor.as_ref();
@@ -499,7 +645,13 @@ impl Supervisor {
         );
         let msg = BastionMessage::deploy_supervisor(supervisor);
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_self(env);
+        if let Err(err) = self.bcast.send_self(env) {
+            warn!(
+                "Supervisor({}): Couldn't deploy element: {}",
+                self.id(),
+                err
+            );
+        }
 
         supervisor_ref
     }
@@ -560,7 +712,7 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_parent_path(self.named_path());
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
         // FIXME: children group elems launched without the group itself being launched
@@ -576,7 +728,13 @@ impl Supervisor {
         );
         let msg = BastionMessage::deploy_children(children);
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_self(env);
+        if let Err(err) = self.bcast.send_self(env) {
+            warn!(
+                "Supervisor({}): Couldn't deploy element: {}",
+                self.id(),
+                err
+            );
+        }
 
         self
     }
@@ -638,7 +796,7 @@ impl Supervisor {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_parent_path(self.named_path());
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
         // FIXME: children group elems launched without the group itself being launched
@@ -652,7 +810,13 @@ impl Supervisor {
         );
         let msg = BastionMessage::deploy_children(children);
         let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-        self.bcast.send_self(env);
+        if let Err(err) = self.bcast.send_self(env) {
+            warn!(
+                "Supervisor({}): Couldn't deploy element: {}",
+                self.id(),
+                err
+            );
+        }
 
         children_ref
     }
@@ -681,6 +845,9 @@ impl Supervisor {
     ///         or supervisors that were added after them (even the
     ///         stopped ones), respecting the order in which they
     ///         were added.
+    ///     - [`SupervisionStrategy::Escalate`] would escalate the
+    ///         fault to the supervisor's parent, which would then
+    ///         restart the supervisor's whole subtree.
     ///
     /// # Example
     ///
@@ -702,6 +869,7 @@ impl Supervisor {
     /// [`SupervisionStrategy::OneForOne`]: supervisor/enum.SupervisionStrategy.html#variant.OneForOne
     /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
     /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
+    /// [`SupervisionStrategy::Escalate`]: supervisor/enum.SupervisionStrategy.html#variant.Escalate
     pub fn with_strategy(mut self, strategy: SupervisionStrategy) -> Self {
         trace!(
             "Supervisor({}): Setting strategy: {:?}",
@@ -753,6 +921,85 @@ impl Supervisor {
         self
     }
 
+    /// Makes the supervisor wait for the given amount of time
+    /// after one of its supervised elements faulted, batching
+    /// together all the faults that happen in the meantime, and
+    /// then apply its strategy only once for the whole batch.
+    ///
+    /// This avoids restarting the same elements over and over
+    /// when many of them fault at the same time (e.g. because a
+    /// dependency they share went down).
+    ///
+    /// By default, faults aren't debounced and the strategy is
+    /// applied as soon as an element faults.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The amount of time during which faults are
+    ///     batched together.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_strategy(SupervisionStrategy::OneForAll)
+    ///         .with_fault_debounce(Duration::from_millis(50))
+    /// }).expect("Couldn't create the supervisor");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn with_fault_debounce(mut self, window: Duration) -> Self {
+        trace!(
+            "Supervisor({}): Setting fault debounce window: {:?}",
+            self.id(),
+            window
+        );
+        self.fault_debounce = Some(window);
+        self
+    }
+
+    /// Sets the order in which this supervisor receives the
+    /// messages sent to it.
+    ///
+    /// By default, the lifecycle messages sent to a supervisor
+    /// (e.g. that one of its elements faulted) are received before
+    /// the data messages sent to it earlier, so that a flood of
+    /// data messages can't delay fault handling. Data messages are
+    /// still received in the order they were sent in.
+    ///
+    /// # Arguments
+    ///
+    /// * `bias` - The order in which the messages are received.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_poll_bias(PollBias::Fifo)
+    /// }).expect("Couldn't create the supervisor.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    pub fn with_poll_bias(mut self, bias: PollBias) -> Self {
+        trace!("Supervisor({}): Setting poll bias: {:?}", self.id(), bias);
+        self.poll_bias = bias;
+        self.bcast.set_poll_bias(bias);
+        self
+    }
+
     /// Sets the callbacks that will get called at this supervisor's
     /// different lifecycle events.
     ///
@@ -809,7 +1056,7 @@ impl Supervisor {
                     let msg = BastionMessage::restart_subtree();
                     let env =
                         Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-                    self.bcast.send_child(&supervisor_id, env);
+                    self.bcast.send_child_or_log(&supervisor_id, env);
                 }
                 RestartedElement::Child { id, parent_id } => {
                     let index = match self.tracked_groups_order.get(&id) {
@@ -858,7 +1105,7 @@ impl Supervisor {
 
         while let Some((receiver, msg)) = restart_futures.next().await {
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_child(&receiver, env);
+            self.bcast.send_child_or_log(&receiver, env);
         }
     }
 
@@ -879,23 +1126,41 @@ impl Supervisor {
         }
     }
 
-    async fn stop(&mut self, range: Range<usize>) {
-        debug!("Supervisor({}): Stopping range: {:?}", self.id(), range);
-        if range.start == 0 {
-            self.bcast.stop_children();
-        } else {
-            // FIXME: panics
-            for id in self.order.get(range.clone()).unwrap() {
+    /// Stops all the supervised elements, from the deepest ones
+    /// to the shallowest ones: the supervisors first, which stop
+    /// their own elements in the same order before they stop,
+    /// and then the children groups, which stop their elements
+    /// before they stop.
+    ///
+    /// This way, every element only stops once all of its
+    /// descendants did, and the children groups can still
+    /// interact with the shallower elements while the deeper
+    /// ones are stopping.
+    async fn stop_in_depth_order(&mut self) {
+        debug!("Supervisor({}): Stopping in depth order.", self.id());
+        let (groups, supervisors): (Vec<_>, Vec<_>) = self
+            .order
+            .iter()
+            .cloned()
+            .partition(|id| self.groups.contains(id));
+
+        for ids in &[supervisors, groups] {
+            for id in ids {
                 trace!("Supervised({}): Stopping Supervised({}).", self.id(), id);
                 self.bcast.stop_child(id);
             }
+
+            self.wait_stopped(ids).await;
         }
+    }
 
+    /// Waits for the supervised elements with the given
+    /// identifiers to stop.
+    async fn wait_stopped(&mut self, ids: &[BastionId]) {
         let mut supervised = FuturesOrdered::new();
-        // FIXME: panics?
-        for id in self.order.get(range.clone()).unwrap() {
+        for id in ids {
             // TODO: Err if None?
-            if let Some((_, launched)) = self.launched.remove(&id) {
+            if let Some((_, launched)) = self.launched.remove(id) {
                 // TODO: add a "stopped" list and poll from it instead of awaiting
                 supervised.push(launched);
             }
@@ -936,7 +1201,7 @@ impl Supervisor {
         // FIXME: panics?
         for id in self.order.get(range.clone()).unwrap() {
             // TODO: Err if None?
-            if let Some((_, launched)) = self.launched.remove(&id) {
+            if let Some((_, launched)) = self.launched.remove(id) {
                 // TODO: add a "stopped" list and poll from it instead of awaiting
                 supervised.push(launched);
             }
@@ -996,11 +1261,37 @@ impl Supervisor {
                 let objects = self.search_restarted_objects(search_method);
                 self.restart(objects).await;
             }
+            SupervisionStrategy::Escalate => {
+                let info = FaultInfo::new(id, parent_id);
+                self.escalate(info);
+            }
         }
 
         Ok(())
     }
 
+    fn escalate(&mut self, info: FaultInfo) {
+        warn!(
+            "Supervisor({}): Escalating fault of Supervised({}) in Supervised({}).",
+            self.id(),
+            info.origin(),
+            info.parent_id()
+        );
+        self.bcast.escalate(info);
+    }
+
+    async fn handle_escalated_fault(&mut self, id: BastionId, info: FaultInfo) -> Result<(), ()> {
+        if let SupervisionStrategy::Escalate = self.strategy {
+            self.escalate(info);
+            return Ok(());
+        }
+
+        // The supervisor that escalated the fault is the one
+        // that needs to be recovered, along with its subtree.
+        let parent_id = self.id().clone();
+        self.recover_supervised_object(id, parent_id).await
+    }
+
     fn search_restarted_objects(&self, search_method: ActorSearchMethod) -> Vec<RestartedElement> {
         let mut objects = Vec::new();
 
@@ -1013,22 +1304,32 @@ impl Supervisor {
                 objects.push(element)
             }
             ActorSearchMethod::FromActor { id, parent_id } => {
-                let childs = self.tracked_groups.get(&parent_id).unwrap();
-                let start_index = *self.tracked_groups_order.get(&id).unwrap();
-
-                // Adding all elements in the group from the given actor
-                childs.iter().skip(start_index).for_each(|tracked_state| {
-                    let id = tracked_state.id();
-                    let element = RestartedElement::Child {
-                        id,
-                        parent_id: parent_id.clone(),
-                    };
-                    objects.push(element)
-                });
+                let rest_index = match self.tracked_groups.get(&parent_id) {
+                    Some(childs) => {
+                        let start_index = *self.tracked_groups_order.get(&id).unwrap();
+
+                        // Adding all elements in the group from the given actor
+                        childs.iter().skip(start_index).for_each(|tracked_state| {
+                            let id = tracked_state.id();
+                            let element = RestartedElement::Child {
+                                id,
+                                parent_id: parent_id.clone(),
+                            };
+                            objects.push(element)
+                        });
+
+                        self.launched.get(&parent_id).unwrap().0
+                    }
+                    // The failed element is a supervisor (e.g. one
+                    // that escalated a fault).
+                    None => {
+                        objects.push(RestartedElement::Supervisor(id.clone()));
+                        self.launched.get(&id).unwrap().0
+                    }
+                };
 
                 // And then a rest after the failed group
-                let (rest_index, _) = self.launched.get(&parent_id).unwrap();
-                for index in *rest_index + 1..self.order.len() {
+                for index in rest_index + 1..self.order.len() {
                     let element_id = &self.order[index];
 
                     match self.tracked_groups.get(element_id) {
@@ -1042,7 +1343,8 @@ impl Supervisor {
                             }
                         }
                         None => {
-                            let restarted_element = RestartedElement::Supervisor(id.clone());
+                            let restarted_element =
+                                RestartedElement::Supervisor(element_id.clone());
                             objects.push(restarted_element);
                         }
                     }
@@ -1050,7 +1352,7 @@ impl Supervisor {
             }
             ActorSearchMethod::All => {
                 for id in self.order.iter() {
-                    match self.tracked_groups.get(&id) {
+                    match self.tracked_groups.get(id) {
                         Some(childs) => {
                             for tracked_state in childs {
                                 let restarted_element = RestartedElement::Child {
@@ -1081,7 +1383,7 @@ impl Supervisor {
     }
 
     async fn deinit_with_stop(&mut self) {
-        self.stop(0..self.order.len()).await;
+        self.stop_in_depth_order().await;
         self.stopped();
     }
 
@@ -1108,15 +1410,23 @@ impl Supervisor {
                     children.id()
                 );
                 children.callbacks().before_start();
+                self.panic_policies
+                    .insert(children.id().clone(), children.panic_policy());
+                self.groups.insert(children.id().clone());
                 Supervised::children(children)
             }
         };
 
-        self.bcast.register(supervised.bcast());
+        // The element would be unreachable, its identifier already
+        // being used by another one.
+        if self.bcast.register(supervised.bcast()).is_err() {
+            return;
+        }
+
         if self.started {
             let msg = BastionMessage::start();
             let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-            self.bcast.send_child(supervised.id(), env);
+            self.bcast.send_child_or_log(supervised.id(), env);
         }
 
         debug!(
@@ -1135,6 +1445,8 @@ impl Supervisor {
         // FIXME: Err if None?
         if let Some((_, launched)) = self.launched.remove(&id) {
             debug!("Supervisor({}): Supervised({}) stopped.", self.id(), id);
+            self.panic_policies.remove(&id);
+            self.groups.remove(&id);
             // TODO: add a "waiting" list an poll from it instead of awaiting
             // FIXME: panics?
             let supervised = launched.await.unwrap();
@@ -1165,6 +1477,107 @@ impl Supervisor {
         Ok(())
     }
 
+    async fn handle_fault(&mut self, id: BastionId, parent_id: BastionId) -> Result<(), ()> {
+        match self.fault_debounce {
+            Some(window) => {
+                self.debounce_fault(window, id, parent_id);
+                Ok(())
+            }
+            None => self.recover_supervised_object(id, parent_id).await,
+        }
+    }
+
+    async fn handle_panic(&mut self, id: BastionId, parent_id: BastionId) -> Result<(), ()> {
+        let policy = self
+            .panic_policies
+            .get(&parent_id)
+            .copied()
+            .unwrap_or_default();
+        debug!(
+            "Supervisor({}): Child({}) panicked, applying policy: {:?}",
+            self.id(),
+            id,
+            policy
+        );
+
+        match policy {
+            PanicPolicy::Restart => self.handle_fault(id, parent_id).await,
+            PanicPolicy::Escalate => {
+                // The element isn't left behind in its group until
+                // the supervisor's parent recovers from the fault,
+                // but it stays tracked so that restarting this
+                // supervisor's subtree relaunches it.
+                self.drop_panicked(&id, &parent_id);
+                self.escalate(FaultInfo::new(id, parent_id));
+                Ok(())
+            }
+            PanicPolicy::Stop => {
+                self.remove_child(&id, &parent_id);
+                self.drop_panicked(&id, &parent_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Tells the children group with the given identifier to
+    /// remove its panicked element without restarting it.
+    fn drop_panicked(&self, id: &BastionId, parent_id: &BastionId) {
+        let msg = BastionMessage::drop_child(id.clone());
+        let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+        self.bcast.send_child_or_log(parent_id, env);
+    }
+
+    fn debounce_fault(&mut self, window: Duration, id: BastionId, parent_id: BastionId) {
+        trace!(
+            "Supervisor({}): Debouncing fault of Supervised({}).",
+            self.id(),
+            id
+        );
+        let fault = (id, parent_id);
+        if !self.pending_faults.contains(&fault) {
+            self.pending_faults.push(fault);
+        }
+
+        if self.debounce_timer.is_none() {
+            self.debounce_timer = Some(clock::sleep(window));
+        }
+    }
+
+    async fn recover_debounced_faults(&mut self) -> Result<(), ()> {
+        self.debounce_timer = None;
+        let faults = self.pending_faults.drain(..).collect::<Vec<_>>();
+        debug!(
+            "Supervisor({}): Recovering {} debounced faults.",
+            self.id(),
+            faults.len()
+        );
+
+        if let SupervisionStrategy::OneForOne = self.strategy {
+            let mut objects = Vec::new();
+            for (id, parent_id) in faults {
+                warn!("Supervisor({}): Supervised({}) faulted.", self.id(), id);
+                let search_method = ActorSearchMethod::OneActor { id, parent_id };
+                objects.extend(self.search_restarted_objects(search_method));
+            }
+
+            self.restart(objects).await;
+            return Ok(());
+        }
+
+        // The other strategies restart a superset of what the
+        // earliest faulted element requires.
+        let earliest = faults.into_iter().min_by_key(|(id, parent_id)| {
+            let group_index = self.launched.get(parent_id).map(|(index, _)| *index);
+            let child_index = self.tracked_groups_order.get(id).copied();
+            (group_index, child_index)
+        });
+
+        match earliest {
+            Some((id, parent_id)) => self.recover_supervised_object(id, parent_id).await,
+            None => Ok(()),
+        }
+    }
+
     async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
         match env {
             Envelope {
@@ -1245,7 +1658,15 @@ impl Supervisor {
                 msg: BastionMessage::RestartRequired { id, parent_id },
                 ..
             } => {
-                if self.recover_supervised_object(id, parent_id).await.is_err() {
+                if self.handle_fault(id, parent_id).await.is_err() {
+                    return Err(());
+                }
+            }
+            Envelope {
+                msg: BastionMessage::Panicked { id, parent_id },
+                ..
+            } => {
+                if self.handle_panic(id, parent_id).await.is_err() {
                     return Err(());
                 }
             }
@@ -1253,6 +1674,147 @@ impl Supervisor {
                 msg: BastionMessage::FinishedChild { id, parent_id },
                 ..
             } => self.remove_child(&id, &parent_id),
+            Envelope {
+                msg: BastionMessage::Escalate { id, info },
+                ..
+            } => {
+                if self.handle_escalated_fault(id, info).await.is_err() {
+                    return Err(());
+                }
+            }
+            Envelope {
+                msg: BastionMessage::SetChildWeight { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::PoisonPill { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::GracefulRestart { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Topology { ack },
+                ..
+            } => {
+                let node = TopologyNode::new(
+                    self.id(),
+                    TopologyKind::Supervisor,
+                    self.name.clone(),
+                    "Running",
+                )
+                .with_path(self.named_path());
+                node.assemble(&self.bcast, ack);
+            }
+            Envelope {
+                msg: BastionMessage::Ping,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pong { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendChild { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::ChildrenOlderThan { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::CancelChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::DrainMailbox { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::AwaitExit { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::WaitForChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Subscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Unsubscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Publish { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::RegisterName { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendNamed { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Broadcast { payload, ttl },
+                sign,
+                ..
+            } => {
+                if ttl == 0 {
+                    debug!(
+                        "Supervisor({}): Dropping an expired tree-wide broadcast: {:?}",
+                        self.id(),
+                        payload
+                    );
+                } else {
+                    debug!(
+                        "Supervisor({}): Forwarding a tree-wide broadcast: {:?}",
+                        self.id(),
+                        payload
+                    );
+                    let msg = BastionMessage::Broadcast {
+                        payload,
+                        ttl: ttl - 1,
+                    };
+                    self.bcast.send_children(Envelope::new_with_sign(msg, sign));
+                }
+            }
+            env @ Envelope {
+                msg: BastionMessage::Suspend,
+                ..
+            }
+            | env @ Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Forwarding {:?} down the tree.",
+                    self.id(),
+                    env.msg
+                );
+                self.bcast.send_children(env);
+            }
+            Envelope {
+                msg: BastionMessage::Reparent { parent, path },
+                ..
+            } => self.bcast.reparented(*parent, path),
+            // Handled by the broadcast itself.
+            Envelope {
+                msg: BastionMessage::Reparented { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Ready { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Backpressure { .. },
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::RestartSubtree,
                 ..
@@ -1313,9 +1875,13 @@ impl Supervisor {
     }
 
     async fn run(mut self) -> Self {
-        debug!("Supervisor({}): Launched.", self.id());
+        debug!(
+            "Supervisor({}): Launched at {}.",
+            self.id(),
+            self.named_path()
+        );
         loop {
-            match poll!(&mut self.bcast.next()) {
+            match poll!(self.bcast.recv()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
                     msg: BastionMessage::Start,
@@ -1347,7 +1913,19 @@ impl Supervisor {
                 //      `Receiver` of the same channel, this would only be
                 //      possible if the channel was closed, which never happens.
                 Poll::Ready(None) => unreachable!(),
-                Poll::Pending => pending!(),
+                Poll::Pending => {
+                    if let Some(timer) = &mut self.debounce_timer {
+                        if let Poll::Ready(()) = poll!(timer) {
+                            if self.recover_debounced_faults().await.is_err() {
+                                return self;
+                            }
+
+                            continue;
+                        }
+                    }
+
+                    pending!()
+                }
             }
         }
     }
@@ -1355,13 +1933,36 @@ impl Supervisor {
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
         debug!("Supervisor({}): Launching.", self.id());
         let stack = self.stack();
-        pool::spawn(self.run(), stack)
+        spawn_with(self.run(), stack)
     }
 }
 
 impl SupervisorRef {
     pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> Self {
-        SupervisorRef { id, sender, path }
+        let named_path = Arc::from(ROOT_NAMED_PATH);
+        SupervisorRef {
+            id,
+            sender,
+            path,
+            named_path,
+        }
+    }
+
+    pub(crate) fn with_named_path(mut self, named_path: impl Into<Arc<str>>) -> Self {
+        self.named_path = named_path.into();
+        self
+    }
+
+    /// Returns the path made of the names of the supervisor this
+    /// `SupervisorRef` is referencing and of its parents (e.g.
+    /// `/root/supervisorA`), which is only meant to be read by
+    /// humans.
+    ///
+    /// See [`Supervisor::with_name`].
+    ///
+    /// [`Supervisor::with_name`]: supervisor/struct.Supervisor.html#method.with_name
+    pub fn named_path(&self) -> &str {
+        &self.named_path
     }
 
     /// Returns the identifier of the supervisor this `SupervisorRef`
@@ -1438,7 +2039,7 @@ impl SupervisorRef {
             self.id(),
             bcast.id()
         );
-        let supervisor = Supervisor::new(bcast);
+        let supervisor = Supervisor::new(bcast).with_parent_path(self.named_path());
         let supervisor = init(supervisor);
         let supervisor_ref = supervisor.as_ref();
         debug!("Supervisor({}): Initialized.", supervisor.id());
@@ -1517,7 +2118,7 @@ impl SupervisorRef {
             self.id(),
             bcast.id()
         );
-        let children = Children::new(bcast);
+        let children = Children::new(bcast).with_parent_path(self.named_path());
         let mut children = init(children);
         debug!("Children({}): Initialized.", children.id());
         // FIXME: children group elems launched without the group itself being launched
@@ -1565,6 +2166,9 @@ impl SupervisorRef {
     ///         or supervisors that were added after them (even the
     ///         stopped ones), respecting the order in which they
     ///         were added.
+    ///     - [`SupervisionStrategy::Escalate`] would escalate the
+    ///         fault to the supervisor's parent, which would then
+    ///         restart the supervisor's whole subtree.
     ///
     /// # Example
     ///
@@ -1585,6 +2189,7 @@ impl SupervisorRef {
     /// [`SupervisionStrategy::OneForOne`]: supervisor/enum.SupervisionStrategy.html#variant.OneForOne
     /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
     /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
+    /// [`SupervisionStrategy::Escalate`]: supervisor/enum.SupervisionStrategy.html#variant.Escalate
     pub fn strategy(&self, strategy: SupervisionStrategy) -> Result<(), ()> {
         debug!(
             "SupervisorRef({}): Setting strategy: {:?}",
@@ -1656,6 +2261,55 @@ impl SupervisorRef {
         self.send(env).map_err(|env| env.into_msg().unwrap())
     }
 
+    /// Sends a message to the supervisor this `SupervisorRef` is
+    /// referencing which will then forward it down its subtree to
+    /// every element of every children group, for at most `ttl`
+    /// levels.
+    ///
+    /// Unlike with [`broadcast`], every element of the children
+    /// groups receives the message, whatever their dispatch mode.
+    /// Every supervisor and children group forwards it one level
+    /// deeper: the elements of the groups supervised by this
+    /// supervisor are two levels down.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    /// * `ttl` - How many levels down the subtree the message is
+    ///     forwarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let sp_ref = Bastion::supervisor(|sp| sp).unwrap();
+    /// // Reaches the elements of the groups supervised by the
+    /// // supervisor and by the supervisors it supervises.
+    /// sp_ref.announce("Prepare to shut down.", 4).expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    pub fn announce<M: Message>(&self, msg: M, ttl: usize) -> Result<(), M> {
+        debug!(
+            "SupervisorRef({}): Announcing message: {:?}",
+            self.id(),
+            msg
+        );
+        let msg = BastionMessage::tree_broadcast(msg, ttl);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|env| env.into_msg().unwrap())
+    }
+
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing to tell it to stop every running children
     /// groups and supervisors that it is supervising.
@@ -1712,6 +2366,59 @@ impl SupervisorRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Sends a message to the supervisor this `SupervisorRef` is
+    /// referencing to tell it to suspend every children group and supervisor it is supervising,
+    /// and their own elements.
+    ///
+    /// The suspended elements stop receiving their messages, which
+    /// keep being queued in their mailboxes until the subtree gets
+    /// resumed using [`resume_subtree`]. The elements added to the
+    /// subtree later on aren't suspended.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # Bastion::init();
+    /// #
+    /// # let sp_ref = Bastion::supervisor(|sp| sp).unwrap();
+    /// sp_ref.suspend_subtree().expect("Couldn't send the message.");
+    /// // The messages sent now are only received once resumed...
+    /// sp_ref.resume_subtree().expect("Couldn't send the message.");
+    /// #
+    /// # Bastion::start();
+    /// # Bastion::stop();
+    /// # Bastion::block_until_stopped();
+    /// ```
+    ///
+    /// [`resume_subtree`]: #method.resume_subtree
+    pub fn suspend_subtree(&self) -> Result<(), ()> {
+        debug!("SupervisorRef({}): Suspending the subtree.", self.id());
+        let msg = BastionMessage::suspend();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the supervisor this `SupervisorRef` is
+    /// referencing to tell it to resume the subtree suspended
+    /// using [`suspend_subtree`], letting the elements receive
+    /// the messages queued in the meantime.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// [`suspend_subtree`]: #method.suspend_subtree
+    pub fn resume_subtree(&self) -> Result<(), ()> {
+        debug!("SupervisorRef({}): Resuming the subtree.", self.id());
+        let msg = BastionMessage::resume();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("SupervisorRef({}): Sending message: {:?}", self.id(), env);
         self.sender
@@ -1722,6 +2429,24 @@ impl SupervisorRef {
     pub(crate) fn path(&self) -> &Arc<BastionPath> {
         &self.path
     }
+
+    pub(crate) fn sender(&self) -> &Sender {
+        &self.sender
+    }
+}
+
+impl FaultInfo {
+    pub(crate) fn new(origin: BastionId, parent_id: BastionId) -> Self {
+        FaultInfo { origin, parent_id }
+    }
+
+    pub(crate) fn origin(&self) -> &BastionId {
+        &self.origin
+    }
+
+    pub(crate) fn parent_id(&self) -> &BastionId {
+        &self.parent_id
+    }
 }
 
 impl TrackedChildState {
@@ -1791,7 +2516,7 @@ impl Supervised {
         let stack = self.stack();
         match self {
             Supervised::Supervisor(supervisor) => {
-                pool::spawn(
+                spawn_with(
                     async {
                         // FIXME: panics?
                         let supervisor = supervisor.launch().await.unwrap();
@@ -1801,7 +2526,7 @@ impl Supervised {
                 )
             }
             Supervised::Children(children) => {
-                pool::spawn(
+                spawn_with(
                     async {
                         // FIXME: panics?
                         let children = children.launch().await.unwrap();
@@ -1891,7 +2616,7 @@ impl RestartStrategy {
         match self.strategy {
             ActorRestartStrategy::LinearBackOff { timeout } => {
                 let start_in = timeout.as_secs() + (timeout.as_secs() * restarts_count as u64);
-                Delay::new(Duration::from_secs(start_in)).await;
+                clock::sleep(Duration::from_secs(start_in)).await;
             }
             ActorRestartStrategy::ExponentialBackOff {
                 timeout,
@@ -1899,7 +2624,7 @@ impl RestartStrategy {
             } => {
                 let start_in =
                     timeout.as_secs() + (timeout.as_secs() * multiplier * restarts_count as u64);
-                Delay::new(Duration::from_secs(start_in)).await;
+                clock::sleep(Duration::from_secs(start_in)).await;
             }
             _ => {}
         };
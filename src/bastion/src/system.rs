@@ -3,11 +3,12 @@ use crate::children_ref::ChildrenRef;
 use crate::context::{BastionContext, BastionId, NIL_ID};
 use crate::dispatcher::GlobalDispatcher;
 use crate::envelope::Envelope;
+use crate::executor::spawn_with;
 use crate::message::{BastionMessage, Deployment};
 use crate::path::{BastionPath, BastionPathElement};
 use crate::supervisor::{Supervisor, SupervisorRef};
+use crate::topology::{TopologyKind, TopologyNode};
 use async_mutex::Mutex as AsyncMutex;
-use bastion_executor::pool;
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use futures::{pending, poll};
@@ -145,11 +146,13 @@ impl System {
             system.bcast.path().clone(),
             system.bcast.sender().clone(),
         );
-        system.bcast.send_self(env);
+        if let Err(err) = system.bcast.send_self(env) {
+            error!("System: Couldn't deploy the system supervisor: {}", err);
+        }
 
         debug!("System: Launching.");
         let stack = system.stack();
-        let handle = pool::spawn(system.run(), stack);
+        let handle = spawn_with(system.run(), stack);
 
         let dead_letters_ref =
             Self::spawn_dead_letters(&supervisor_ref).expect("Can't spawn dead letters");
@@ -191,7 +194,7 @@ impl System {
         supervisor.reset(bcast).await;
         supervisor.callbacks().after_restart();
 
-        self.bcast.register(supervisor.bcast());
+        self.bcast.register_restarted(supervisor.bcast());
 
         info!("System: Launching Supervisor({}).", supervisor.id());
         let id = supervisor.id().clone();
@@ -255,12 +258,17 @@ impl System {
                 debug!("System: Deploying Supervisor({}).", supervisor.id());
                 supervisor.callbacks().before_start();
 
-                self.bcast.register(supervisor.bcast());
+                // The supervisor would be unreachable, its identifier
+                // already being used by another one.
+                if self.bcast.register(supervisor.bcast()).is_err() {
+                    return;
+                }
+
                 if self.started {
                     let msg = BastionMessage::start();
                     let envelope =
                         Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
-                    self.bcast.send_child(supervisor.id(), envelope);
+                    self.bcast.send_child_or_log(supervisor.id(), envelope);
                 }
 
                 info!("System: Launching Supervisor({}).", supervisor.id());
@@ -349,6 +357,10 @@ impl System {
                 msg: BastionMessage::RestartRequired { .. },
                 ..
             } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Panicked { .. },
+                ..
+            } => unreachable!(),
             Envelope {
                 msg: BastionMessage::RestartSubtree,
                 ..
@@ -377,6 +389,135 @@ impl System {
                 msg: BastionMessage::Faulted { id, .. },
                 ..
             } => self.restart_supervised_object(id),
+            Envelope {
+                msg: BastionMessage::Escalate { id, .. },
+                ..
+            } => {
+                warn!("System: Supervisor({}) escalated a fault.", id);
+                let msg = BastionMessage::restart_subtree();
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                self.bcast.send_child_or_log(&id, env);
+            }
+            Envelope {
+                msg: BastionMessage::SetChildWeight { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::PoisonPill { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::GracefulRestart { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Topology { ack },
+                ..
+            } => {
+                let node =
+                    TopologyNode::new(self.bcast.id(), TopologyKind::System, None, "Running");
+                node.assemble(&self.bcast, ack);
+            }
+            Envelope {
+                msg: BastionMessage::Ping,
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Pong { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendChild { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::ChildrenOlderThan { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::CancelChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::DrainMailbox { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::AwaitExit { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::WaitForChildren { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Subscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Unsubscribe { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Publish { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::RegisterName { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::SendNamed { .. },
+                ..
+            } => unreachable!(),
+            Envelope {
+                msg: BastionMessage::Broadcast { payload, ttl },
+                sign,
+                ..
+            } => {
+                if ttl == 0 {
+                    debug!(
+                        "System: Dropping an expired tree-wide broadcast: {:?}",
+                        payload
+                    );
+                } else {
+                    debug!("System: Forwarding a tree-wide broadcast: {:?}", payload);
+                    let msg = BastionMessage::Broadcast {
+                        payload,
+                        ttl: ttl - 1,
+                    };
+                    self.bcast.send_children(Envelope::new_with_sign(msg, sign));
+                }
+            }
+            env @ Envelope {
+                msg: BastionMessage::Suspend,
+                ..
+            }
+            | env @ Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("System: Forwarding {:?} down the tree.", env.msg);
+                self.bcast.send_children(env);
+            }
+            Envelope {
+                msg: BastionMessage::Reparent { .. },
+                ..
+            } => unreachable!(),
+            // Handled by the broadcast itself.
+            Envelope {
+                msg: BastionMessage::Reparented { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Ready { .. },
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Backpressure { .. },
+                ..
+            } => unreachable!(),
         }
 
         Ok(())
@@ -388,9 +529,9 @@ impl System {
             match poll!(&mut self.waiting.next()) {
                 Poll::Ready(Some(Some(supervisor))) => {
                     let id = supervisor.id();
-                    self.bcast.unregister(&id);
+                    self.bcast.unregister(id);
 
-                    if self.restart.remove(&id) {
+                    if self.restart.remove(id) {
                         self.recover(supervisor).await;
                     } else {
                         supervisor.callbacks().after_stop();
@@ -403,7 +544,7 @@ impl System {
                 Poll::Ready(None) | Poll::Pending => (),
             }
 
-            match poll!(&mut self.bcast.next()) {
+            match poll!(self.bcast.recv()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
                     msg: BastionMessage::Start,
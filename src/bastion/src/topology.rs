@@ -0,0 +1,128 @@
+//!
+//! Snapshots of the supervision tree, as returned by
+//! [`Bastion::dump_topology`].
+//!
+//! Each element only knows its direct children, so the tree is
+//! assembled by asking the elements for their topology from the
+//! system down to the children groups' elements, each element
+//! answering once all its children did.
+//!
+//! [`Bastion::dump_topology`]: ../struct.Bastion.html#method.dump_topology
+use crate::broadcast::Broadcast;
+use crate::context::BastionId;
+use crate::executor::spawn;
+use futures::channel::oneshot;
+use futures::future;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// The kind of element a [`TopologyNode`] describes.
+///
+/// [`TopologyNode`]: struct.TopologyNode.html
+pub enum TopologyKind {
+    /// The system, at the root of the tree.
+    System,
+    /// A supervisor.
+    Supervisor,
+    /// A children group.
+    Children,
+    /// An element of a children group.
+    Child,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An element of the supervision tree along with its own
+/// children, as returned by [`Bastion::dump_topology`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # Bastion::init();
+/// #
+/// Bastion::children(|children| children.with_name("workers").with_redundancy(2))
+///     .expect("Couldn't create the children group.");
+/// # Bastion::start();
+///
+/// # run!(async {
+/// let root = Bastion::dump_topology().await.expect("Couldn't dump the topology.");
+/// println!("{}", serde_json::to_string_pretty(&root).unwrap());
+/// # });
+/// #
+/// # Bastion::stop();
+/// # Bastion::block_until_stopped();
+/// ```
+///
+/// [`Bastion::dump_topology`]: ../struct.Bastion.html#method.dump_topology
+pub struct TopologyNode {
+    /// The identifier of the element.
+    pub id: String,
+    /// The kind of the element.
+    pub kind: TopologyKind,
+    /// The name of the element, for children groups, their
+    /// elements and the named supervisors.
+    pub name: Option<String>,
+    /// The path made of the names of the element and of its
+    /// parents (e.g. `/root/supervisorA/workers`), for
+    /// supervisors and children groups.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// A human-readable description of the element's state (e.g.
+    /// `Running`).
+    pub state: String,
+    /// The children of the element which answered, sorted by
+    /// identifier.
+    pub children: Vec<TopologyNode>,
+}
+
+impl TopologyNode {
+    pub(crate) fn new(
+        id: &BastionId,
+        kind: TopologyKind,
+        name: Option<String>,
+        state: impl Into<String>,
+    ) -> Self {
+        TopologyNode {
+            id: id.to_string(),
+            kind,
+            name,
+            path: None,
+            state: state.into(),
+            children: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Returns the number of direct children of the element.
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Asks the children of `bcast` for their topology and
+    /// answers `ack` with this node once they all did.
+    pub(crate) fn assemble(mut self, bcast: &Broadcast, ack: oneshot::Sender<TopologyNode>) {
+        let children = bcast.request_topologies();
+        if children.is_empty() {
+            ack.send(self).ok();
+            return;
+        }
+
+        spawn(async move {
+            // The elements which stopped in the meantime won't
+            // answer, and are left out.
+            self.children = future::join_all(children)
+                .await
+                .into_iter()
+                .filter_map(Result::ok)
+                .collect();
+            self.children.sort_by(|a, b| a.id.cmp(&b.id));
+
+            ack.send(self).ok();
+        });
+    }
+}
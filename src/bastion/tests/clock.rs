@@ -0,0 +1,96 @@
+#![cfg(feature = "testing")]
+mod common;
+
+use bastion::clock;
+use bastion::prelude::*;
+use common::wait_until;
+use futures::executor::block_on;
+use futures::FutureExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+static PROBES: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn drives_the_time_manually() {
+    let manual = ManualClock::new();
+    clock::set_clock(manual.clone());
+
+    // Sleeping only resolves once the clock was advanced enough.
+    let mut sleep = manual.sleep(Duration::from_secs(10));
+    assert!((&mut sleep).now_or_never().is_none());
+    manual.advance(Duration::from_secs(5));
+    assert!((&mut sleep).now_or_never().is_none());
+    manual.advance(Duration::from_secs(5));
+    block_on(sleep);
+
+    Bastion::init();
+    Bastion::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_restart_history(RestartHistory::new(4).with_window(Duration::from_secs(60)))
+            .with_exec(|ctx: BastionContext| async move {
+                // The first two starts fault, the third one keeps running.
+                if STARTS.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return Err(());
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| children_ref.restart_count() == 2));
+    let last = children_ref.last_restart().unwrap();
+    assert_eq!(last, manual.now());
+
+    // The window only elapses on the clock, however long it really takes.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(children_ref.restart_count(), 2);
+
+    manual.advance(Duration::from_secs(60));
+    assert_eq!(children_ref.restart_count(), 0);
+    assert_eq!(children_ref.restart_history(), vec![last, last]);
+
+    // So does the cooldown of a circuit breaker.
+    let breaker_ref = Bastion::children(|children| {
+        children
+            .with_circuit_breaker(CircuitBreaker::new(1, Duration::from_secs(60)))
+            .with_exec(|ctx: BastionContext| async move {
+                if PROBES.fetch_add(1, Ordering::SeqCst) < 1 {
+                    return Err(());
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(
+        || breaker_ref.breaker_state() == BreakerState::Open
+    ));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(breaker_ref.breaker_state(), BreakerState::Open);
+
+    manual.advance(Duration::from_secs(60));
+    assert!(wait_until(
+        || breaker_ref.breaker_state() == BreakerState::HalfOpen
+    ));
+    assert!(wait_until(|| PROBES.load(Ordering::SeqCst) == 2));
+
+    manual.advance(Duration::from_secs(60));
+    assert!(wait_until(
+        || breaker_ref.breaker_state() == BreakerState::Closed
+    ));
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+    clock::set_clock(SystemClock);
+}
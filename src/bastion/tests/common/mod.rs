@@ -0,0 +1,39 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+use bastion::prelude::*;
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Starts the system shared by the tests of a test binary if it
+/// isn't running yet, and returns a guard making the other tests of
+/// the binary wait until it is dropped.
+///
+/// The system is never stopped, and the groups created by the tests
+/// keep running until the binary exits.
+pub fn start() -> MutexGuard<'static, ()> {
+    static START: Once = Once::new();
+    static RUNNING: Mutex<()> = Mutex::new(());
+
+    START.call_once(|| {
+        Bastion::init();
+        Bastion::start();
+    });
+
+    // A failing test shouldn't fail the ones running after it.
+    RUNNING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Polls `cond` until it holds or five seconds elapsed, and returns
+/// whether it held.
+pub fn wait_until(mut cond: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if cond() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    false
+}
@@ -0,0 +1,63 @@
+use bastion::executor::CurrentThread;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn runs_the_system_on_the_driving_thread() {
+    let executor = CurrentThread::new();
+    let config = Config::new()
+        .hide_backtraces()
+        .with_executor(executor.clone());
+    Bastion::init_with(config);
+    Bastion::start();
+
+    let driver = thread::current().id();
+    Bastion::children(move |children| {
+        children.with_exec(move |_ctx: BastionContext| async move {
+            assert_eq!(thread::current().id(), driver);
+            // The first run panics and gets restarted by the supervisor.
+            if STARTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("first run");
+            }
+
+            Ok(())
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let echo = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str =!> {
+                        answer!(ctx, msg).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Nothing ran before the executor was driven.
+    assert_eq!(STARTS.load(Ordering::SeqCst), 0);
+    assert!(!executor.is_empty());
+
+    executor.run_until_stalled();
+    assert_eq!(STARTS.load(Ordering::SeqCst), 2);
+    assert!(executor.is_empty());
+
+    let reply = executor.block_on(async {
+        let answer = echo.elems()[0]
+            .ask_anonymously("ping")
+            .expect("Couldn't send the message.");
+        msg! { answer.await.expect("Couldn't receive the answer."),
+            reply: &'static str => reply;
+            _: _ => unreachable!();
+        }
+    });
+    assert_eq!(reply, "ping");
+}
@@ -0,0 +1,72 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static TARGET: AtomicUsize = AtomicUsize::new(1);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+struct Fixed;
+
+impl AutoscalePolicy for Fixed {
+    fn target(&mut self, _sample: &LoadSample) -> usize {
+        TARGET.load(Ordering::SeqCst)
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+}
+
+#[derive(Debug)]
+struct Job;
+
+fn scaled_elements() -> Option<usize> {
+    let root = run!(Bastion::dump_topology()).expect("Couldn't dump the topology.");
+    root.children.iter().find_map(|sp| {
+        sp.children
+            .iter()
+            .find(|group| group.name.as_deref() == Some("scaled"))
+            .map(TopologyNode::child_count)
+    })
+}
+
+#[test]
+fn scales_between_the_bounds() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_name("scaled")
+            .with_autoscale(1, 3, Fixed)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _job: Job => {
+                            HANDLED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The target is capped by the upper bound.
+    TARGET.store(10, Ordering::SeqCst);
+    assert!(wait_until(|| scaled_elements() == Some(3)));
+
+    children.broadcast(Job).expect("Couldn't send the message.");
+    assert!(wait_until(|| HANDLED.load(Ordering::SeqCst) == 3));
+
+    // The target is raised to the lower bound.
+    TARGET.store(0, Ordering::SeqCst);
+    assert!(wait_until(|| scaled_elements() == Some(1)));
+
+    // Only the remaining element receives the messages.
+    children.broadcast(Job).expect("Couldn't send the message.");
+    wait_until(|| HANDLED.load(Ordering::SeqCst) >= 4);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 4);
+}
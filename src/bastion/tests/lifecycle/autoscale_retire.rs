@@ -0,0 +1,82 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static TARGET: AtomicUsize = AtomicUsize::new(1);
+static STARTED: AtomicBool = AtomicBool::new(false);
+static RELEASED: AtomicBool = AtomicBool::new(false);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+struct Fixed;
+
+impl AutoscalePolicy for Fixed {
+    fn target(&mut self, _sample: &LoadSample) -> usize {
+        TARGET.load(Ordering::SeqCst)
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+}
+
+#[derive(Debug)]
+struct Job;
+
+fn elements(children: &ChildrenRef) -> usize {
+    // The hung elements can't report their topology.
+    run!(children.children_older_than(Duration::from_secs(0))).len()
+}
+
+#[test]
+fn retires_the_elements_concurrently() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_autoscale(1, 3, Fixed)
+            .with_poison_pill_timeout(Duration::from_secs(1))
+            .with_init(|| async {
+                // Only the first element starts, the others never
+                // get to handle their poison pills.
+                if STARTED.swap(true, Ordering::SeqCst) {
+                    futures::future::pending::<()>().await;
+                }
+
+                Ok(())
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                // Keeps its messages pending until released, so
+                // that it is the one kept when scaling down.
+                while !RELEASED.load(Ordering::SeqCst) {
+                    Delay::new(Duration::from_millis(10)).await;
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _job: Job => {
+                            HANDLED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    TARGET.store(3, Ordering::SeqCst);
+    assert!(wait_until(|| elements(&children) == 3));
+    children.broadcast(Job).expect("Couldn't send the message.");
+    thread::sleep(Duration::from_millis(100));
+
+    // Both hung elements are cancelled after a single timeout.
+    let started = Instant::now();
+    TARGET.store(1, Ordering::SeqCst);
+    assert!(wait_until(|| elements(&children) == 1));
+    assert!(started.elapsed() < Duration::from_millis(1800));
+
+    RELEASED.store(true, Ordering::SeqCst);
+    assert!(wait_until(|| HANDLED.load(Ordering::SeqCst) == 1));
+}
@@ -0,0 +1,49 @@
+use crate::common;
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn await_exit_resolves_once_the_element_exited() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            match msg {
+                                "stop" => return Ok(()),
+                                _ => return Err(()),
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Let the elements start.
+    thread::sleep(Duration::from_millis(100));
+
+    let elems = children.elems();
+    let (stopping, faulting) = (&elems[0], &elems[1]);
+
+    let stopped = children.await_exit(stopping);
+    stopping
+        .tell_anonymously("stop")
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(stopped), StopReason::Stopped);
+
+    let faulted = children.await_exit(faulting);
+    faulting
+        .tell_anonymously("fail")
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(faulted), StopReason::Faulted);
+
+    // The elements which already exited resolve right away.
+    assert_eq!(run!(children.await_exit(stopping)), StopReason::Stopped);
+}
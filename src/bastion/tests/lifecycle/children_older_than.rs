@@ -0,0 +1,44 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn lists_and_cancels_old_children() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+
+    let old = run!(children.children_older_than(Duration::from_millis(50)));
+    assert_eq!(old.len(), 2);
+    for elem in children.elems() {
+        assert!(old.contains(elem.id()));
+    }
+
+    let old = run!(children.children_older_than(Duration::from_secs(3600)));
+    assert!(old.is_empty());
+
+    let cancelled = children.elems()[0].id().clone();
+    children
+        .cancel_children(std::slice::from_ref(&cancelled))
+        .expect("Couldn't send the message.");
+
+    // The cancelled element is removed from the group.
+    let mut left = Vec::new();
+    assert!(wait_until(|| {
+        left = run!(children.children_older_than(Duration::from_secs(0)));
+        left.len() == 1
+    }));
+    assert!(!left.contains(&cancelled));
+}
@@ -0,0 +1,60 @@
+use crate::common;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn drains_the_mailbox_in_order() {
+    let _system = common::start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    let workers = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let counter = counter.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _n: u32 => {
+                            counter.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+    let worker = workers.elems()[0].clone();
+
+    // Draining the empty mailbox suspends the element.
+    let drained = run!(workers.drain_mailbox(worker.id()));
+    assert!(drained.is_empty());
+
+    for n in 0..3u32 {
+        worker.tell_anonymously(n).unwrap();
+    }
+    thread::sleep(Duration::from_millis(200));
+
+    let drained = run!(workers.drain_mailbox(worker.id()));
+    let mut values = Vec::new();
+    for msg in drained {
+        msg! { msg,
+            n: u32 => values.push(n);
+            _: _ => panic!("Unexpected message.");
+        }
+    }
+    assert_eq!(values, vec![0, 1, 2]);
+
+    // The drained messages aren't received once resumed.
+    workers.resume_subtree().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    // Unknown elements have nothing to drain.
+    assert!(run!(workers.drain_mailbox(workers.id())).is_empty());
+}
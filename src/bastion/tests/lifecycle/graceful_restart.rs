@@ -0,0 +1,56 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct Job;
+
+#[test]
+fn hands_over_pending_messages() {
+    let _system = common::start();
+
+    // The old element never gets to handle its messages.
+    let children = Bastion::children(|children| {
+        children.with_exec(|_ctx: BastionContext| async move {
+            std::future::pending::<()>().await;
+            Ok(())
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    for _ in 0..3 {
+        children.broadcast(Job).expect("Couldn't send the message.");
+    }
+    thread::sleep(Duration::from_millis(100));
+
+    let old = &children.elems()[0];
+    let notice = children
+        .graceful_restart_child(old, |ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    ref _job: Job => {
+                        HANDLED.fetch_add(1, Ordering::SeqCst);
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+        .expect("Couldn't send the message.");
+
+    let dead = run!(notice).expect("Couldn't receive the notice.");
+    assert_eq!(&dead.id, old.id());
+    assert_eq!(dead.reason, DeathReason::PoisonPilled);
+
+    for _ in 0..2 {
+        children.broadcast(Job).expect("Couldn't send the message.");
+    }
+
+    wait_until(|| HANDLED.load(Ordering::SeqCst) >= 5);
+    // Leave some time for the messages to be wrongly handled twice.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 5);
+}
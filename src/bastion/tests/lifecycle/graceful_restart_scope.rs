@@ -0,0 +1,85 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static INITS: AtomicUsize = AtomicUsize::new(0);
+static RELEASED: AtomicBool = AtomicBool::new(false);
+static V1_STARTS: AtomicUsize = AtomicUsize::new(0);
+static V2_STARTS: AtomicUsize = AtomicUsize::new(0);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn replaces_a_single_element_once_its_replacement_started() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_init(|| async {
+                // The elements launched after the first ones don't
+                // start until released.
+                if INITS.fetch_add(1, Ordering::SeqCst) >= 2 {
+                    while !RELEASED.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+                }
+
+                Ok(())
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                V1_STARTS.fetch_add(1, Ordering::SeqCst);
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: &'static str => return Err(());
+                        _n: u32 => {
+                            HANDLED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    assert!(wait_until(|| V1_STARTS.load(Ordering::SeqCst) == 2));
+
+    let old = &children.elems()[0];
+    let notice = children
+        .graceful_restart_child(old, |ctx: BastionContext| async move {
+            V2_STARTS.fetch_add(1, Ordering::SeqCst);
+            loop {
+                msg! { ctx.recv().await?,
+                    ref _msg: &'static str => return Err(());
+                    _: _ => ();
+                }
+            }
+        })
+        .expect("Couldn't send the message.");
+
+    // The old element keeps handling its messages until the new
+    // one started...
+    thread::sleep(Duration::from_millis(100));
+    old.tell_anonymously(1u32)
+        .expect("Couldn't send the message.");
+    assert!(wait_until(|| HANDLED.load(Ordering::SeqCst) == 1));
+    assert_eq!(V2_STARTS.load(Ordering::SeqCst), 0);
+
+    RELEASED.store(true, Ordering::SeqCst);
+    let dead = run!(notice).expect("Couldn't receive the notice.");
+    assert_eq!(&dead.id, old.id());
+    assert_eq!(dead.reason, DeathReason::PoisonPilled);
+    assert!(wait_until(|| V2_STARTS.load(Ordering::SeqCst) == 1));
+
+    // ...and only the new element runs the new closure once
+    // restarted.
+    children
+        .broadcast("crash")
+        .expect("Couldn't send the message.");
+    assert!(wait_until(|| V1_STARTS.load(Ordering::SeqCst) == 3));
+    assert!(wait_until(|| V2_STARTS.load(Ordering::SeqCst) == 2));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(V1_STARTS.load(Ordering::SeqCst), 3);
+    assert_eq!(V2_STARTS.load(Ordering::SeqCst), 2);
+}
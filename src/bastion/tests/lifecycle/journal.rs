@@ -0,0 +1,52 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone)]
+struct Increment;
+
+#[test]
+fn replays_journal_on_restart() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_journal(InMemoryJournal::<Increment>::new())
+            .with_exec(|ctx: BastionContext| async move {
+                STARTS.fetch_add(1, Ordering::SeqCst);
+                let mut counter = 0;
+                loop {
+                    msg! { ctx.recv().await?,
+                        _inc: Increment => counter += 1;
+                        _msg: &'static str => return Err(());
+                        // The element referenced by `elems` doesn't exist
+                        // anymore once restarted...
+                        ref _msg: &'static str => REPORTED.store(counter, Ordering::SeqCst);
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for _ in 0..3 {
+        child
+            .tell_anonymously(Increment)
+            .expect("Couldn't send the message.");
+    }
+    child
+        .tell_anonymously("crash")
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| STARTS.load(Ordering::SeqCst) == 2));
+
+    children
+        .broadcast("report")
+        .expect("Couldn't send the message.");
+
+    assert!(wait_until(|| REPORTED.load(Ordering::SeqCst) == 3));
+}
@@ -0,0 +1,90 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+static RELEASED: AtomicBool = AtomicBool::new(false);
+static COUNTED: AtomicUsize = AtomicUsize::new(0);
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone)]
+struct Increment;
+
+#[derive(Debug)]
+struct Counted;
+
+#[test]
+fn replays_journal_from_senders_and_keeps_pending_messages() {
+    let _system = common::start();
+
+    let journaled = Bastion::children(|children| {
+        children
+            .with_journal(InMemoryJournal::<Increment>::new())
+            .with_exec(|ctx: BastionContext| async move {
+                STARTS.fetch_add(1, Ordering::SeqCst);
+                loop {
+                    msg! { ctx.recv().await?,
+                        _inc: Increment => {
+                            ctx.tell(&signature!(), Counted)
+                                .expect("Couldn't send the message.");
+                        };
+                        _msg: &'static str => {
+                            // Lets the next message wait in the
+                            // mailbox before faulting.
+                            while !RELEASED.load(Ordering::SeqCst) {
+                                Delay::new(Duration::from_millis(10)).await;
+                            }
+                            return Err(());
+                        };
+                        _msg: u32 => PENDING.store(true, Ordering::SeqCst);
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = journaled.elems()[0].clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let child = child.clone();
+            async move {
+                for _ in 0..2 {
+                    ctx.tell(&child.addr(), Increment)
+                        .expect("Couldn't send the message.");
+                }
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        _counted: Counted => {
+                            COUNTED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| COUNTED.load(Ordering::SeqCst) == 2));
+
+    let child = &journaled.elems()[0];
+    child
+        .tell_anonymously("crash")
+        .expect("Couldn't send the message.");
+    child
+        .tell_anonymously(1u32)
+        .expect("Couldn't send the message.");
+    thread::sleep(Duration::from_millis(100));
+    RELEASED.store(true, Ordering::SeqCst);
+
+    assert!(wait_until(|| STARTS.load(Ordering::SeqCst) == 2));
+    // The replayed messages are answered to their sender...
+    assert!(wait_until(|| COUNTED.load(Ordering::SeqCst) == 4));
+    // ...and the messages that weren't journaled are still received.
+    assert!(wait_until(|| PENDING.load(Ordering::SeqCst)));
+}
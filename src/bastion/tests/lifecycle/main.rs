@@ -0,0 +1,18 @@
+//! The lifecycle of the children groups and of their elements.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod autoscale;
+mod autoscale_retire;
+mod await_exit;
+mod children_older_than;
+mod drain_mailbox;
+mod graceful_restart;
+mod graceful_restart_scope;
+mod journal;
+mod journal_senders;
+mod poison_pill;
+mod poison_pill_timeout;
+mod scoped;
+mod wait_for_children;
+mod with_init;
@@ -0,0 +1,35 @@
+use crate::common;
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn acknowledged_poison_pill() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Let the element start.
+    thread::sleep(Duration::from_millis(100));
+
+    let child = &children.elems()[0];
+    let notice = children
+        .poison_pill_child(child)
+        .expect("Couldn't send the message.");
+    let dead = run!(notice).expect("Couldn't receive the notice.");
+    assert_eq!(&dead.id, child.id());
+    assert_eq!(dead.reason, DeathReason::PoisonPilled);
+
+    // The element isn't part of the group anymore.
+    let notice = children
+        .poison_pill_child(child)
+        .expect("Couldn't send the message.");
+    assert!(run!(notice).is_err());
+}
@@ -0,0 +1,72 @@
+use crate::common;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static HUNG: AtomicBool = AtomicBool::new(false);
+static RUNNING: Mutex<Vec<BastionId>> = Mutex::new(Vec::new());
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn group_keeps_running_while_a_poison_pill_is_pending() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_poison_pill_timeout(Duration::from_secs(1))
+            .with_init(|| async {
+                // The first element never gets to handle its
+                // poison pill.
+                if !HUNG.swap(true, Ordering::SeqCst) {
+                    futures::future::pending::<()>().await;
+                }
+
+                Ok(())
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                RUNNING.lock().unwrap().push(ctx.current().id().clone());
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: u32 => {
+                            RECEIVED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Let the elements start.
+    thread::sleep(Duration::from_millis(100));
+
+    let elems = children.elems();
+    let running = RUNNING.lock().unwrap().clone();
+    let hung = elems
+        .iter()
+        .find(|elem| !running.contains(elem.id()))
+        .expect("Couldn't find the hung element.");
+
+    let started = Instant::now();
+    let notice = children
+        .poison_pill_child(hung)
+        .expect("Couldn't send the message.");
+
+    // The group forwards its messages while waiting for the
+    // element to die.
+    thread::sleep(Duration::from_millis(100));
+    children
+        .broadcast(1u32)
+        .expect("Couldn't send the message.");
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 1);
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    let dead = run!(notice).expect("Couldn't receive the notice.");
+    assert_eq!(&dead.id, hung.id());
+    assert_eq!(dead.reason, DeathReason::Cancelled);
+}
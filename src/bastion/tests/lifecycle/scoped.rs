@@ -0,0 +1,66 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static DONE: AtomicUsize = AtomicUsize::new(0);
+static FAULTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn waits_for_the_scoped_children() {
+    let _system = common::start();
+
+    let groups = Bastion::scoped(|scope| {
+        let slow = scope
+            .children(|children| {
+                children
+                    .with_redundancy(2)
+                    .with_exec(|_ctx: BastionContext| async move {
+                        thread::sleep(Duration::from_millis(100));
+                        DONE.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    })
+            })
+            .unwrap();
+        // The faulting elements are restarted and waited for.
+        let faulting = scope
+            .spawn(|_ctx: BastionContext| async move {
+                if FAULTS.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return Err(());
+                }
+
+                DONE.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .unwrap();
+
+        vec![slow, faulting]
+    });
+    assert_eq!(DONE.load(Ordering::SeqCst), 3);
+    assert_eq!(FAULTS.load(Ordering::SeqCst), 3);
+    for group in &groups {
+        assert!(wait_until(|| group.state() == ChildrenState::Stopped));
+    }
+
+    // Panicking within the scope kills the groups it created.
+    let mut running = None;
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+        Bastion::scoped(|scope| {
+            running = Some(
+                scope
+                    .spawn(|ctx: BastionContext| async move {
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    })
+                    .unwrap(),
+            );
+            panic!("test");
+        })
+    }));
+    assert!(panicked.is_err());
+    let running = running.unwrap();
+    assert!(wait_until(|| running.state() == ChildrenState::Stopped));
+}
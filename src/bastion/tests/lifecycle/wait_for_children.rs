@@ -0,0 +1,55 @@
+use crate::common;
+use bastion::prelude::*;
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+static RELEASED: AtomicBool = AtomicBool::new(false);
+static INITIALIZED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn wait_for_children_resolves_once_the_elements_are_initialized() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(3)
+            .with_init(|| async {
+                while !RELEASED.load(Ordering::SeqCst) {
+                    Delay::new(Duration::from_millis(10)).await;
+                }
+
+                INITIALIZED.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The elements can't be ready while their initializer runs...
+    let ready = run!(async {
+        let wait = children.wait_for_children(3);
+        let timeout = Delay::new(Duration::from_millis(200));
+        match future::select(Box::pin(wait), timeout).await {
+            Either::Left(_) => true,
+            Either::Right(_) => false,
+        }
+    });
+    assert!(!ready);
+
+    // ...and are once it completed.
+    RELEASED.store(true, Ordering::SeqCst);
+    run!(children.wait_for_children(3)).expect("The children group stopped.");
+    assert_eq!(INITIALIZED.load(Ordering::SeqCst), 3);
+
+    // The elements which are already ready resolve right away.
+    run!(children.wait_for_children(1)).expect("The children group stopped.");
+
+    children.stop().expect("Couldn't stop the children group.");
+    assert!(run!(children.wait_for_children(4)).is_err());
+}
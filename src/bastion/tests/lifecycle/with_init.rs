@@ -0,0 +1,72 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static::lazy_static! {
+    static ref RECEIVED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+}
+
+#[test]
+fn initializes_the_elements_before_they_run() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_init(|| async {
+                Delay::new(Duration::from_millis(300)).await;
+                Ok(())
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        n: usize => RECEIVED.lock().unwrap().push(n);
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The messages are queued while the element initializes.
+    thread::sleep(Duration::from_millis(100));
+    for n in 0..3usize {
+        children_ref.elems()[0].tell_anonymously(n).unwrap();
+    }
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(children_ref.state(), ChildrenState::Init);
+    assert!(RECEIVED.lock().unwrap().is_empty());
+
+    thread::sleep(Duration::from_millis(400));
+    assert_eq!(children_ref.state(), ChildrenState::Running);
+    assert_eq!(*RECEIVED.lock().unwrap(), vec![0, 1, 2]);
+
+    // The elements failing to initialize are restarted.
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_init(|| async {
+                // Only the third attempt succeeds.
+                if ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 3);
+    assert_eq!(children_ref.restart_count(), 2);
+    assert_eq!(children_ref.state(), ChildrenState::Running);
+}
@@ -0,0 +1,69 @@
+use crate::common;
+use bastion::prelude::*;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static ROLES: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn gathers_the_replies_of_all_elements() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(3)
+            .with_exec(|ctx: BastionContext| async move {
+                match ROLES.fetch_add(1, Ordering::SeqCst) {
+                    // Answers...
+                    0 => loop {
+                        msg! { ctx.recv().await?,
+                            question: &'static str =!> {
+                                assert_eq!(question, "ping");
+                                answer!(ctx, "pong").unwrap();
+                            };
+                            _: _ => ();
+                        }
+                    },
+                    // ...dies right away...
+                    1 => Ok(()),
+                    // ...or never answers (dropping the questions
+                    // would tell their senders that it won't).
+                    _ => {
+                        let mut questions = Vec::new();
+                        loop {
+                            questions.push(ctx.recv().await?);
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(200));
+
+    let replies: Vec<_> = run!(children_ref
+        .ask_all("ping", Duration::from_millis(300))
+        .collect());
+    assert_eq!(replies.len(), 3);
+
+    let mut answered = 0;
+    let mut unreachable = 0;
+    let mut timed_out = 0;
+    for (id, reply) in replies {
+        assert!(children_ref.elems().iter().any(|elem| elem.id() == &id));
+        match reply {
+            AskReply::Answered(answer) => {
+                answered += 1;
+                msg! { answer,
+                    msg: &'static str => assert_eq!(msg, "pong");
+                    _: _ => panic!("Unexpected answer.");
+                }
+            }
+            AskReply::Unreachable => unreachable += 1,
+            AskReply::TimedOut => timed_out += 1,
+        }
+    }
+    assert_eq!((answered, unreachable, timed_out), (1, 1, 1));
+}
@@ -0,0 +1,53 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STALLED: AtomicBool = AtomicBool::new(true);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+fn tell(children_ref: &ChildrenRef, count: usize) {
+    for n in 0..count {
+        children_ref.broadcast(n).unwrap();
+        // Lets the elements signal their group.
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn sheds_the_load_of_the_elements_at_capacity() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+            .with_backpressure(Backpressure::new(2, 0))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    while STALLED.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    ctx.recv().await?;
+                    HANDLED.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    // Both elements are at capacity after two messages each, so
+    // the other messages are routed to the dead letters...
+    tell(&children_ref, 10);
+    STALLED.store(false, Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 4);
+
+    // ...until they caught up.
+    tell(&children_ref, 2);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 6);
+}
@@ -0,0 +1,50 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+struct Payload(Vec<u8>);
+
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn shares_one_allocation_between_elements() {
+    let _system = common::start();
+
+    let seen: Arc<Mutex<Vec<usize>>> = Arc::new(Mutex::new(Vec::new()));
+    let seen_by_elems = seen.clone();
+    let children = Bastion::children(move |children| {
+        let seen = seen_by_elems.clone();
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let seen = seen.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref payload: Payload => {
+                                assert_eq!(payload.0.len(), 1024 * 1024);
+                                seen.lock().unwrap().push(payload as *const Payload as usize);
+                                RECEIVED.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let payload = Arc::new(Payload(vec![0; 1024 * 1024]));
+    let addr = Arc::as_ptr(&payload) as usize;
+    children
+        .broadcast_shared(payload.clone())
+        .expect("Couldn't send the message.");
+
+    wait_until(|| RECEIVED.load(Ordering::SeqCst) >= 3);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 3);
+    assert!(seen.iter().all(|seen| *seen == addr));
+}
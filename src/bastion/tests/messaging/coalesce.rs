@@ -0,0 +1,51 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug)]
+struct Position(usize);
+
+#[derive(Debug)]
+struct Other;
+
+lazy_static::lazy_static! {
+    static ref RECEIVED: Mutex<Vec<Option<usize>>> = Mutex::new(Vec::new());
+}
+
+#[test]
+fn only_keeps_the_latest_pending_message() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .coalesce::<Position>()
+            .with_exec(|ctx: BastionContext| async move {
+                // The element is busy while the messages are sent.
+                Delay::new(Duration::from_millis(300)).await;
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        pos: Position => RECEIVED.lock().unwrap().push(Some(pos.0));
+                        _other: Other => RECEIVED.lock().unwrap().push(None);
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+    let elem = &children_ref.elems()[0];
+    for i in 0..10 {
+        elem.tell_anonymously(Position(i)).unwrap();
+        if i == 4 {
+            elem.tell_anonymously(Other).unwrap();
+        }
+    }
+
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(*RECEIVED.lock().unwrap(), vec![None, Some(9)]);
+}
@@ -0,0 +1,64 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DEADLOCKED: AtomicUsize = AtomicUsize::new(0);
+static ANSWERED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn resolves_asks_waiting_for_each_other() {
+    let _system = common::start();
+
+    let pong = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str =!> {
+                        if msg == "ping" {
+                            // The asker is waiting for this answer...
+                            let back = ctx
+                                .ask(&signature!(), "back")
+                                .expect("Couldn't send the message.");
+                            if back.is_deadlocked() && back.await.is_err() {
+                                DEADLOCKED.fetch_add(1, Ordering::SeqCst);
+                            }
+
+                            let _ = answer!(ctx, "pong");
+                        }
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let pong = pong.elems()[0].addr();
+    let ping = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let pong = pong.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _msg: &'static str => {
+                            let answer = ctx.ask(&pong, "ping").expect("Couldn't send the message.");
+                            if answer.await.is_ok() {
+                                ANSWERED.fetch_add(1, Ordering::SeqCst);
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    ping.elems()[0]
+        .tell_anonymously("start")
+        .expect("Couldn't send the message.");
+
+    wait_until(|| ANSWERED.load(Ordering::SeqCst) >= 1);
+    assert_eq!(DEADLOCKED.load(Ordering::SeqCst), 1);
+    assert_eq!(ANSWERED.load(Ordering::SeqCst), 1);
+}
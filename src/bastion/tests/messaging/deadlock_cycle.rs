@@ -0,0 +1,69 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+static ADDRS: [OnceLock<RefAddr>; 3] = [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+static ASKED: [AtomicBool; 3] = [
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+    AtomicBool::new(false),
+];
+static DEADLOCKED: AtomicUsize = AtomicUsize::new(0);
+static RESOLVED: AtomicUsize = AtomicUsize::new(0);
+
+async fn ask_next(ctx: &BastionContext, n: usize) {
+    // Each element only asks the next one once.
+    if ASKED[n].swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let next = ADDRS[(n + 1) % 3].get().unwrap();
+    let answer = ctx.ask(next, "next").expect("Couldn't send the message.");
+    if answer.await.is_err() {
+        DEADLOCKED.fetch_add(1, Ordering::SeqCst);
+    }
+    RESOLVED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn resolves_cycles_of_asks() {
+    let _system = common::start();
+
+    for (n, addr) in ADDRS.iter().enumerate() {
+        let children = Bastion::children(move |children| {
+            children.with_exec(move |ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _msg: &'static str =!> {
+                            ask_next(&ctx, n).await;
+                            let _ = answer!(ctx, "done");
+                        };
+                        _msg: &'static str => ask_next(&ctx, n).await;
+                        _: _ => ();
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+        addr.set(children.elems()[0].addr()).unwrap();
+    }
+
+    // The three elements end up waiting for each other.
+    let first = ADDRS[0].get().unwrap();
+    Bastion::children(move |children| {
+        let first = first.clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let first = first.clone();
+            async move {
+                ctx.tell(&first, "start")
+                    .expect("Couldn't send the message.");
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| RESOLVED.load(Ordering::SeqCst) == 3));
+    assert_eq!(DEADLOCKED.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,45 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct Command {
+    id: u64,
+}
+
+#[test]
+fn drops_duplicate_commands() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .dedup_by(|cmd: &Command| cmd.id, Duration::from_secs(10))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        _cmd: Command => {
+                            RECEIVED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for id in &[1, 1, 2, 1, 3] {
+        child
+            .tell_anonymously(Command { id: *id })
+            .expect("Couldn't send the message.");
+    }
+
+    wait_until(|| RECEIVED.load(Ordering::SeqCst) >= 3);
+    // Leave some time for the duplicates to be wrongly received.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,72 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STALLED: AtomicBool = AtomicBool::new(true);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn applies_the_delivery_mode_while_the_mailbox_is_full() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_backpressure(Backpressure::new(2, 0))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    while STALLED.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    ctx.recv().await?;
+                    HANDLED.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    let child_ref = &children_ref.elems()[0];
+    for n in 0..2 {
+        child_ref.tell_anonymously(n).unwrap();
+    }
+    thread::sleep(Duration::from_millis(100));
+    assert!(child_ref.is_at_capacity());
+
+    // While the mailbox is full, the messages are only enqueued
+    // when sent at most once...
+    assert_eq!(
+        run!(child_ref.tell_anonymously_with(2, DeliveryMode::BestEffort)),
+        Err(2)
+    );
+    assert_eq!(
+        run!(child_ref.tell_anonymously_with(3, DeliveryMode::AtLeastOnce { max_retries: 10 })),
+        Err(3)
+    );
+    run!(child_ref.tell_anonymously_with(4, DeliveryMode::AtMostOnce)).unwrap();
+
+    // ...and the messages sent at least once wait for the child to
+    // catch up without blocking the executor, which it runs on.
+    let sending = child_ref.clone();
+    let sent = spawn!(async move {
+        sending
+            .tell_anonymously_with(
+                5,
+                DeliveryMode::AtLeastOnce {
+                    max_retries: usize::MAX,
+                },
+            )
+            .await
+    });
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 0);
+
+    STALLED.store(false, Ordering::SeqCst);
+    assert_eq!(run!(sent), Some(Ok(())));
+    thread::sleep(Duration::from_millis(100));
+    assert!(!child_ref.is_at_capacity());
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,55 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone)]
+struct Deposit(usize);
+
+static DEPOSITED: AtomicUsize = AtomicUsize::new(0);
+static GREETINGS: AtomicUsize = AtomicUsize::new(0);
+static UNMATCHED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn routes_messages_to_the_handler_of_their_type() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .on(|deposit: Deposit, _ctx| async move {
+                DEPOSITED.fetch_add(deposit.0, Ordering::SeqCst);
+                Ok(())
+            })
+            .on(|_msg: &'static str, _ctx| async move {
+                GREETINGS.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .on_unmatched(|msg: SignedMessage, _ctx| async move {
+                assert_eq!(msg.type_tag(), "u64");
+                UNMATCHED.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let elem = &children.elems()[0];
+    elem.tell_anonymously(Deposit(10))
+        .expect("Couldn't send the message.");
+    elem.tell_anonymously(Deposit(5))
+        .expect("Couldn't send the message.");
+    elem.tell_anonymously(42u64)
+        .expect("Couldn't send the message.");
+    assert!(wait_until(|| DEPOSITED.load(Ordering::SeqCst) == 15));
+    assert!(wait_until(|| UNMATCHED.load(Ordering::SeqCst) == 1));
+
+    // Broadcasted messages are handled by every element.
+    children
+        .broadcast("hello")
+        .expect("Couldn't send the message.");
+    children
+        .broadcast(Deposit(1))
+        .expect("Couldn't send the message.");
+    assert!(wait_until(|| GREETINGS.load(Ordering::SeqCst) == 2));
+    assert!(wait_until(|| DEPOSITED.load(Ordering::SeqCst) == 17));
+    assert_eq!(UNMATCHED.load(Ordering::SeqCst), 1);
+}
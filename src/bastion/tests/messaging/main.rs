@@ -0,0 +1,22 @@
+//! The delivery of the messages to the elements.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod ask_all;
+mod backpressure;
+mod broadcast_shared;
+mod coalesce;
+mod deadlock;
+mod deadlock_cycle;
+mod dedup;
+mod delivery;
+mod handlers;
+mod named_routing;
+mod peek;
+mod relay;
+mod resizer;
+mod send_child_confirmed;
+#[cfg(feature = "distributed")]
+mod serialization;
+mod sink;
+mod weights;
@@ -0,0 +1,51 @@
+use crate::common;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn routes_the_messages_by_name_across_restarts() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            if msg == "crash" {
+                                panic!("The element crashed.");
+                            }
+                            RECEIVED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let logger = children_ref.elems()[0].id().clone();
+    children_ref.register_name("logger", &logger).unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    children_ref.send_named("logger", "log").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 1);
+
+    // The restarted element is still registered under the name...
+    children_ref.send_named("logger", "crash").unwrap();
+    thread::sleep(Duration::from_millis(300));
+    children_ref.send_named("logger", "log").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 2);
+
+    // ...while the messages sent to unknown names aren't received.
+    children_ref.send_named("unknown", "log").unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 2);
+}
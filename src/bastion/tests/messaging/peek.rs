@@ -0,0 +1,45 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[test]
+fn peeks_without_retrieving() {
+    let _system = common::start();
+
+    let (sender, recver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let children = Bastion::children(move |children| {
+        let sender = sender.lock().unwrap().clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let sender = sender.clone();
+            async move {
+                loop {
+                    // Waits for a message without retrieving it.
+                    let tag = loop {
+                        match ctx.peek(|msg| msg.type_tag()).await {
+                            Some(tag) => break tag,
+                            None => Delay::new(Duration::from_millis(1)).await,
+                        }
+                    };
+                    let msg = ctx.try_recv().await.unwrap();
+                    sender.send((tag, msg.type_tag())).ok();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    child.tell_anonymously(1u8).unwrap();
+    child
+        .tell_anonymously("A message containing data.")
+        .unwrap();
+
+    for _ in 0..2 {
+        let (peeked, received) = recver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(peeked, received);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::common;
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn relays_the_messages_with_their_sender() {
+    let _system = common::start();
+
+    let workers = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    n: u32 =!> {
+                        answer!(ctx, n * 2).unwrap();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let worker = workers.elems()[0].clone();
+    let router = Bastion::children(|children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let worker = worker.clone();
+            async move {
+                loop {
+                    let msg: SignedMessage = ctx.recv().await?;
+                    ctx.relay(&worker.addr(), msg).unwrap();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+
+    // The worker answers the question it was relayed directly.
+    let answer = router.elems()[0].ask_anonymously(21u32).unwrap();
+    let reply = run!(answer).unwrap();
+    let sender = reply.signature().path().clone();
+    msg! { reply,
+        n: u32 => assert_eq!(n, 42);
+        _: _ => panic!("Unexpected answer.");
+    }
+    assert_eq!(sender.id(), workers.elems()[0].id());
+}
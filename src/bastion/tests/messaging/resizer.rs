@@ -0,0 +1,50 @@
+use crate::common;
+use bastion::prelude::*;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STALLED: AtomicBool = AtomicBool::new(true);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn grows_the_mailboxes_which_stay_full() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_backpressure(Backpressure::new(2, 0))
+            .with_resizer(Resizer::new(2, 8).with_interval(Duration::from_millis(20)))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    while STALLED.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    ctx.recv().await?;
+                    HANDLED.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    // The mailbox is full after two messages...
+    for n in 0..2 {
+        children_ref.broadcast(n).unwrap();
+    }
+    thread::sleep(Duration::from_millis(20));
+    let child_ref = &children_ref.elems()[0];
+    assert!(child_ref.is_at_capacity());
+
+    // ...until it stayed full long enough to grow, without
+    // dropping the messages waiting in it.
+    thread::sleep(Duration::from_millis(200));
+    assert!(!child_ref.is_at_capacity());
+    children_ref.broadcast(2).unwrap();
+
+    STALLED.store(false, Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 3);
+}
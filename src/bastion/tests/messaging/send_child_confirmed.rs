@@ -0,0 +1,48 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn confirms_the_enqueued_messages() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_middleware(|msg: &mut Msg| {
+                if msg.is::<u8>() {
+                    MiddlewareAction::Drop
+                } else {
+                    MiddlewareAction::Forward
+                }
+            })
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                    RECEIVED.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let id = children.elems()[0].id().clone();
+
+    let res = run!(children.send_child_confirmed(&id, "A message containing data."));
+    assert_eq!(res, Ok(()));
+    assert!(wait_until(|| RECEIVED.load(Ordering::SeqCst) == 1));
+
+    let res = run!(children.send_child_confirmed(&id, 0u8));
+    assert_eq!(res, Err(SendError::Dropped));
+
+    let res = run!(children.send_child_confirmed(children.id(), "Lost"));
+    assert_eq!(res, Err(SendError::UnknownChild));
+
+    // Once the group stopped, nothing can be enqueued anymore.
+    children.kill().expect("Couldn't kill the group.");
+    let mut res = Ok(());
+    assert!(wait_until(|| {
+        res = run!(children.send_child_confirmed(&id, "Lost"));
+        res.is_err()
+    }));
+    assert_eq!(res, Err(SendError::Closed));
+}
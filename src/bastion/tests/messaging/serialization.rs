@@ -0,0 +1,94 @@
+use crate::common;
+use bastion::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Order {
+    id: u64,
+    items: Vec<String>,
+}
+
+impl SerializableMessage for Order {
+    const TAG: &'static str = "tests::Order";
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Unregistered;
+
+impl SerializableMessage for Unregistered {
+    const TAG: &'static str = "tests::Unregistered";
+}
+
+fn order() -> Order {
+    Order {
+        id: 42,
+        items: vec!["apple".to_string(), "pear".to_string()],
+    }
+}
+
+#[test]
+fn round_trips_registered_messages() {
+    MessageRegistry::register::<Order>();
+    assert!(MessageRegistry::is_registered("tests::Order"));
+
+    let bytes = order().to_bytes().unwrap();
+    let msg = Msg::from_bytes(&bytes).unwrap();
+    assert_eq!(msg.downcast::<Order>().unwrap(), order());
+}
+
+#[test]
+fn rejects_unregistered_tags() {
+    let bytes = Unregistered.to_bytes().unwrap();
+    match Msg::from_bytes(&bytes) {
+        Err(SerializationError::UnknownTag(tag)) => assert_eq!(tag, "tests::Unregistered"),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    match Msg::from_bytes(b"not a message") {
+        Err(SerializationError::Codec(_)) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn encodes_the_messages_received_by_children() {
+    use std::sync::mpsc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    MessageRegistry::register::<Order>();
+
+    let _system = common::start();
+
+    let (sender, recver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let children = Bastion::children(move |children| {
+        let sender = sender.lock().unwrap().clone();
+        children.with_exec(move |ctx: BastionContext| {
+            let sender = sender.clone();
+            async move {
+                loop {
+                    let (msg, _) = ctx.recv().await?.extract();
+                    sender
+                        .send(msg.to_bytes().map_err(|err| err.to_string()))
+                        .ok();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    child.tell_anonymously(order()).unwrap();
+    let bytes = recver
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+        .unwrap();
+    let msg = Msg::from_bytes(&bytes).unwrap();
+    assert_eq!(msg.downcast::<Order>().unwrap(), order());
+
+    // The answer to a question can't be sent from another process.
+    let _answer = child.ask_anonymously(order()).unwrap();
+    let res = recver.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(res.is_err());
+}
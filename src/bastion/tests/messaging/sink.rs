@@ -0,0 +1,59 @@
+use crate::common;
+use bastion::prelude::*;
+use futures::stream::{self, StreamExt};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static STALLED: AtomicBool = AtomicBool::new(true);
+static SENT: AtomicUsize = AtomicUsize::new(0);
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn forwards_a_stream_waiting_for_capacity() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_backpressure(Backpressure::new(2, 0))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    while STALLED.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+
+                    msg! { ctx.recv().await?,
+                        _n: u32 => {
+                            HANDLED.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    let sink = children_ref.elems()[0].sink();
+    let forwarder = thread::spawn(move || {
+        run!(stream::iter(0..10u32)
+            .then(|n| async move {
+                // Lets the element signal that it is at capacity.
+                Delay::new(Duration::from_millis(20)).await;
+                SENT.fetch_add(1, Ordering::SeqCst);
+                Ok(n)
+            })
+            .forward(sink))
+    });
+
+    // The sink waits while the element is at capacity...
+    thread::sleep(Duration::from_millis(400));
+    assert!(SENT.load(Ordering::SeqCst) < 5);
+
+    // ...and no message is lost once it caught up.
+    STALLED.store(false, Ordering::SeqCst);
+    forwarder.join().unwrap().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(HANDLED.load(Ordering::SeqCst), 10);
+}
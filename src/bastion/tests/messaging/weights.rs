@@ -0,0 +1,49 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::Mutex;
+
+static RECEIVED: Mutex<Vec<BastionId>> = Mutex::new(Vec::new());
+
+async fn record(ctx: BastionContext) -> Result<(), ()> {
+    loop {
+        msg! { ctx.recv().await?,
+            ref _n: u32 => {
+                RECEIVED.lock().unwrap().push(ctx.current().id().clone());
+            };
+            _: _ => ();
+        }
+    }
+}
+
+#[test]
+fn keeps_the_weights_of_restarted_elements() {
+    let _system = common::start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+            .with_exec(record)
+    })
+    .expect("Couldn't create the children group.");
+
+    let elems = children.elems();
+    let (light, heavy) = (elems[0].clone(), elems[1].clone());
+    children.set_child_weight(heavy.id(), 3).unwrap();
+
+    // The element replacing the heavy one takes its weight.
+    let notice = children
+        .graceful_restart_child(&heavy, record)
+        .expect("Couldn't send the message.");
+    let dead = run!(notice).expect("Couldn't receive the notice.");
+    assert_eq!(&dead.id, heavy.id());
+
+    for n in 0..8u32 {
+        children.broadcast(n).expect("Couldn't send the message.");
+    }
+    assert!(wait_until(|| RECEIVED.lock().unwrap().len() == 8));
+
+    let received = RECEIVED.lock().unwrap();
+    let light_received = received.iter().filter(|id| *id == light.id()).count();
+    assert_eq!(light_received, 2);
+}
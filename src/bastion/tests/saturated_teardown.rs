@@ -0,0 +1,57 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn tears_down_saturated_groups_faulting_together() {
+    Bastion::init();
+    Bastion::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(4)
+            .with_backpressure(Backpressure::new(2, 0))
+            .with_exec(|_ctx: BastionContext| async move {
+                STARTED.fetch_add(1, Ordering::SeqCst);
+                let generation = GENERATION.load(Ordering::SeqCst);
+                // The elements never receive their messages, and
+                // all panic at once.
+                while GENERATION.load(Ordering::SeqCst) == generation {
+                    Delay::new(Duration::from_millis(10)).await;
+                }
+                panic!("The element crashed.");
+            })
+    })
+    .expect("Couldn't create the children group.");
+    thread::sleep(Duration::from_millis(100));
+
+    for n in 0..1_000u32 {
+        children_ref.broadcast(n).ok();
+    }
+    thread::sleep(Duration::from_millis(100));
+
+    // The faults are still received by the supervisor, which
+    // restarts all the elements...
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    assert!(wait_until(|| STARTED.load(Ordering::SeqCst) == 8));
+
+    // ...and the system still stops.
+    let (stopped, recver) = mpsc::channel();
+    thread::spawn(move || {
+        Bastion::stop();
+        Bastion::block_until_stopped();
+        stopped.send(()).unwrap();
+    });
+    recver
+        .recv_timeout(Duration::from_secs(10))
+        .expect("The system didn't stop.");
+}
@@ -0,0 +1,55 @@
+use bastion::prelude::*;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+lazy_static::lazy_static! {
+    static ref STOPPED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+}
+
+fn recording(name: &'static str) -> Callbacks {
+    Callbacks::new().with_after_stop(move || STOPPED.lock().unwrap().push(name))
+}
+
+fn group(children: Children, name: &'static str) -> Children {
+    children
+        .with_callbacks(recording(name))
+        .with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+}
+
+#[test]
+fn stops_the_deepest_elements_first() {
+    Bastion::init();
+    Bastion::start();
+
+    // root -> [shallow, middle -> [inner, leaf -> [deep]]]
+    Bastion::supervisor(|root| {
+        root.with_callbacks(recording("root"))
+            .children(|children| group(children, "shallow"))
+            .supervisor(|middle| {
+                middle
+                    .with_callbacks(recording("middle"))
+                    .children(|children| group(children, "inner"))
+                    .supervisor(|leaf| {
+                        leaf.with_callbacks(recording("leaf"))
+                            .children(|children| group(children, "deep"))
+                    })
+            })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    thread::sleep(Duration::from_millis(100));
+    Bastion::stop();
+    Bastion::block_until_stopped();
+
+    // Every element stopped after its descendants, and the
+    // deeper subtrees before their shallower siblings.
+    assert_eq!(
+        *STOPPED.lock().unwrap(),
+        vec!["deep", "leaf", "inner", "middle", "shallow", "root"]
+    );
+}
@@ -0,0 +1,49 @@
+use crate::common;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static SHALLOW: AtomicUsize = AtomicUsize::new(0);
+static DEEP: AtomicUsize = AtomicUsize::new(0);
+
+fn group(children: Children, received: &'static AtomicUsize) -> Children {
+    children
+        .with_redundancy(3)
+        .with_dispatch_mode(DispatchMode::WeightedRoundRobin)
+        .with_exec(move |ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    ref _msg: &'static str => {
+                        received.fetch_add(1, Ordering::SeqCst);
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+}
+
+#[test]
+fn reaches_every_element_within_the_ttl() {
+    let _system = common::start();
+
+    // sp -> [shallow, inner -> [deep]]
+    let sp_ref = Bastion::supervisor(|sp| {
+        sp.children(|children| group(children, &SHALLOW))
+            .supervisor(|inner| inner.children(|children| group(children, &DEEP)))
+    })
+    .expect("Couldn't create the supervisor.");
+    thread::sleep(Duration::from_millis(100));
+
+    // The elements of the shallow group are two levels down...
+    sp_ref.announce("Shallow.", 2).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(SHALLOW.load(Ordering::SeqCst), 3);
+    assert_eq!(DEEP.load(Ordering::SeqCst), 0);
+
+    // ...and the ones of the deep group three levels down.
+    sp_ref.announce("Deep.", 3).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(SHALLOW.load(Ordering::SeqCst), 6);
+    assert_eq!(DEEP.load(Ordering::SeqCst), 3);
+}
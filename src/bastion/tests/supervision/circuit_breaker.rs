@@ -0,0 +1,41 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn opens_then_closes_after_a_successful_probe() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_circuit_breaker(CircuitBreaker::new(2, Duration::from_millis(300)))
+            .with_exec(|ctx: BastionContext| async move {
+                // The first two starts fault, which opens the breaker,
+                // while the probe started once half-open keeps running.
+                if STARTS.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return Err(());
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(
+        || children_ref.breaker_state() == BreakerState::Open
+    ));
+    assert_eq!(STARTS.load(Ordering::SeqCst), 2);
+
+    assert!(wait_until(
+        || children_ref.breaker_state() == BreakerState::HalfOpen
+    ));
+    assert!(wait_until(
+        || children_ref.breaker_state() == BreakerState::Closed
+    ));
+    assert_eq!(STARTS.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,46 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FAULTY_STARTS: AtomicUsize = AtomicUsize::new(0);
+static SIBLING_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn escalates_to_the_grandparent() {
+    let _system = common::start();
+
+    // root (one-for-one) -> middle (escalate) -> [faulty, sibling]
+    Bastion::supervisor(|root| {
+        root.supervisor(|middle| {
+            middle
+                .with_strategy(SupervisionStrategy::Escalate)
+                .children(|children| {
+                    children.with_exec(|ctx: BastionContext| async move {
+                        if FAULTY_STARTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                            return Err(());
+                        }
+
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    })
+                })
+                .children(|children| {
+                    children.with_exec(|ctx: BastionContext| async move {
+                        SIBLING_STARTS.fetch_add(1, Ordering::SeqCst);
+
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    })
+                })
+        })
+    })
+    .expect("Couldn't create the supervisors.");
+
+    // The middle supervisor doesn't restart the faulty group
+    // itself: the root restarts its whole subtree instead, which
+    // includes the sibling group that never faulted.
+    assert!(wait_until(|| FAULTY_STARTS.load(Ordering::SeqCst) >= 2));
+    assert!(wait_until(|| SIBLING_STARTS.load(Ordering::SeqCst) >= 2));
+}
@@ -0,0 +1,55 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static FIRST_STARTS: AtomicUsize = AtomicUsize::new(0);
+static SECOND_STARTS: AtomicUsize = AtomicUsize::new(0);
+static HEALTHY_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+fn faulting_once(
+    starts: &'static AtomicUsize,
+) -> impl Fn(Children) -> Children + Send + Sync + 'static {
+    move |children| {
+        children.with_exec(move |ctx: BastionContext| async move {
+            if starts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(());
+            }
+
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    }
+}
+
+#[test]
+fn batches_simultaneous_faults() {
+    let _system = common::start();
+
+    Bastion::supervisor(|sp| {
+        sp.with_strategy(SupervisionStrategy::OneForAll)
+            .with_fault_debounce(Duration::from_millis(200))
+            .children(faulting_once(&FIRST_STARTS))
+            .children(faulting_once(&SECOND_STARTS))
+            .children(|children| {
+                children.with_exec(|ctx: BastionContext| async move {
+                    HEALTHY_STARTS.fetch_add(1, Ordering::SeqCst);
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                })
+            })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    assert!(wait_until(|| HEALTHY_STARTS.load(Ordering::SeqCst) >= 2));
+    // Leave enough time for a second restart to happen if the
+    // faults weren't batched together.
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(HEALTHY_STARTS.load(Ordering::SeqCst), 2);
+    assert_eq!(FIRST_STARTS.load(Ordering::SeqCst), 2);
+    assert_eq!(SECOND_STARTS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,42 @@
+use crate::common;
+use bastion::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn reports_responsive_and_unresponsive_elements() {
+    let _system = common::start();
+
+    let alive = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The elements stop right away, and won't answer.
+    let stopped = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|_ctx: BastionContext| async move { Ok(()) })
+    })
+    .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+
+    let report = run!(alive.health_check(Duration::from_secs(5)));
+    assert!(report.is_healthy());
+    assert_eq!(report.responded.len(), 2);
+    for elem in alive.elems() {
+        assert!(report.responded.contains(elem.id()));
+    }
+
+    let report = run!(stopped.health_check(Duration::from_millis(100)));
+    assert!(!report.is_healthy());
+    assert!(report.responded.is_empty());
+    assert_eq!(report.timed_out.len(), 2);
+}
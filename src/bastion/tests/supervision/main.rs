@@ -0,0 +1,16 @@
+//! The supervision strategies and policies, and the supervision tree.
+#[path = "../common/mod.rs"]
+mod common;
+
+mod announce;
+mod circuit_breaker;
+mod escalation;
+mod fault_debounce;
+mod health_check;
+mod named_path;
+mod panic_escalate;
+mod panic_isolation;
+mod panic_policy;
+mod restart_history;
+mod suspend;
+mod topology;
@@ -0,0 +1,47 @@
+use crate::common;
+use bastion::prelude::*;
+
+#[test]
+fn names_the_paths_after_the_parents() {
+    let _system = common::start();
+
+    let workers = Bastion::children(|children| children.with_name("workers"))
+        .expect("Couldn't create the children group.");
+    assert_eq!(workers.named_path(), "/root/workers");
+
+    let sp = Bastion::supervisor(|sp| sp.with_name("supervisorA"))
+        .expect("Couldn't create the supervisor.");
+    assert_eq!(sp.named_path(), "/root/supervisorA");
+
+    let nested = sp
+        .supervisor(|sp| sp.with_name("nested"))
+        .expect("Couldn't create the supervisor.");
+    assert_eq!(nested.named_path(), "/root/supervisorA/nested");
+
+    let group = nested
+        .children(|children| children.with_name("workers"))
+        .expect("Couldn't create the children group.");
+    assert_eq!(group.named_path(), "/root/supervisorA/nested/workers");
+
+    // The anonymous elements appear with their identifier.
+    let anonymous = sp
+        .children(|children| children)
+        .expect("Couldn't create the children group.");
+    assert_eq!(
+        anonymous.named_path(),
+        format!("/root/supervisorA/{}", anonymous.id())
+    );
+
+    // The topology dump holds the paths too.
+    let root = run!(Bastion::dump_topology()).expect("Couldn't dump the topology.");
+    let supervisor = root
+        .children
+        .iter()
+        .find(|node| node.name.as_deref() == Some("supervisorA"))
+        .expect("The supervisor wasn't dumped.");
+    assert_eq!(supervisor.path.as_deref(), Some("/root/supervisorA"));
+    assert!(supervisor
+        .children
+        .iter()
+        .any(|node| node.path.as_deref() == Some("/root/supervisorA/nested")));
+}
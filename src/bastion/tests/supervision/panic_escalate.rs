@@ -0,0 +1,63 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+static STARTED: Mutex<Vec<BastionId>> = Mutex::new(Vec::new());
+
+fn starts(id: &BastionId) -> usize {
+    STARTED
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|started| *started == id)
+        .count()
+}
+
+#[test]
+fn escalating_removes_the_panicked_element_from_its_group() {
+    let _system = common::start();
+
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(|children| {
+            children
+                .with_redundancy(2)
+                .on_panic(PanicPolicy::Escalate)
+                .with_exec(|ctx: BastionContext| async move {
+                    STARTED.lock().unwrap().push(ctx.current().id().clone());
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: u8 => panic!("told to panic");
+                            _: _ => ();
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't create the children group.");
+
+    let elems = children.elems();
+    let (panicking, sibling) = (&elems[0], &elems[1]);
+    let listed =
+        |id: &BastionId| run!(children.children_older_than(Duration::from_secs(0))).contains(id);
+    assert!(wait_until(|| starts(panicking.id()) == 1));
+
+    // The system restarts the supervisor's subtree, which
+    // relaunches the panicked element, a limited amount of
+    // times...
+    for restarts in 1..=3 {
+        run!(children.send_child_confirmed(panicking.id(), 0u8))
+            .expect("Couldn't send the message.");
+        assert!(wait_until(|| starts(panicking.id()) == restarts + 1));
+        assert!(wait_until(|| listed(panicking.id())));
+    }
+
+    // ...after which the element isn't left behind in its group.
+    run!(children.send_child_confirmed(panicking.id(), 0u8)).expect("Couldn't send the message.");
+    assert!(wait_until(|| !listed(panicking.id())));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(starts(panicking.id()), 4);
+    assert!(listed(sibling.id()));
+}
@@ -0,0 +1,60 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+static SIBLING_HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+fn handle(msg: &str, handled: &AtomicUsize) {
+    if msg == "panic" {
+        panic!("Panicking mid-handler.");
+    }
+
+    handled.fetch_add(1, Ordering::SeqCst);
+}
+
+async fn handling(ctx: BastionContext, handled: &'static AtomicUsize) -> Result<(), ()> {
+    loop {
+        msg! { ctx.recv().await?,
+            msg: &'static str => handle(msg, handled);
+            // The messages broadcasted to the group.
+            ref msg: &'static str => handle(msg, handled);
+            _: _ => ();
+        }
+    }
+}
+
+#[test]
+fn a_panicking_child_only_unwinds_itself() {
+    let _system = common::start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx| handling(ctx, &HANDLED))
+    })
+    .expect("Couldn't create the children group.");
+    let sibling_ref =
+        Bastion::children(|children| children.with_exec(|ctx| handling(ctx, &SIBLING_HANDLED)))
+            .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+    let elems = children_ref.elems();
+    elems[0].tell_anonymously("panic").unwrap();
+    thread::sleep(Duration::from_millis(200));
+
+    // The other element and the sibling group are unaffected...
+    elems[1].tell_anonymously("ping").unwrap();
+    assert!(wait_until(|| HANDLED.load(Ordering::SeqCst) >= 1));
+    sibling_ref.elems()[0].tell_anonymously("ping").unwrap();
+    assert!(wait_until(|| SIBLING_HANDLED.load(Ordering::SeqCst) >= 1));
+
+    // ...while the group restarted the panicking element and
+    // still dispatches messages to both its elements.
+    assert_eq!(children_ref.restart_count(), 1);
+    assert_eq!(children_ref.state(), ChildrenState::Running);
+    children_ref.broadcast("ping").unwrap();
+    assert!(wait_until(|| HANDLED.load(Ordering::SeqCst) >= 3));
+}
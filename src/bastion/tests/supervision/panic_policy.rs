@@ -0,0 +1,88 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static RESTARTED_STARTS: AtomicUsize = AtomicUsize::new(0);
+static STOPPED_STARTS: AtomicUsize = AtomicUsize::new(0);
+static ESCALATED_STARTS: AtomicUsize = AtomicUsize::new(0);
+static SIBLING_STARTS: AtomicUsize = AtomicUsize::new(0);
+
+fn find<'a>(node: &'a TopologyNode, name: &str) -> Option<&'a TopologyNode> {
+    if node.kind == TopologyKind::Children && node.name.as_deref() == Some(name) {
+        return Some(node);
+    }
+
+    node.children.iter().find_map(|child| find(child, name))
+}
+
+async fn panicking(ctx: BastionContext, starts: &'static AtomicUsize) -> Result<(), ()> {
+    if starts.fetch_add(1, Ordering::SeqCst) == 0 {
+        panic!("first start");
+    }
+
+    loop {
+        ctx.recv().await?;
+    }
+}
+
+#[test]
+fn applies_the_panic_policy_of_each_group() {
+    let _system = common::start();
+
+    Bastion::supervisor(|sp| {
+        sp.children(|children| {
+            children
+                .with_name("restarted")
+                .with_exec(|ctx| panicking(ctx, &RESTARTED_STARTS))
+        })
+        .children(|children| {
+            children
+                .with_name("stopped")
+                .on_panic(PanicPolicy::Stop)
+                .with_exec(|ctx| panicking(ctx, &STOPPED_STARTS))
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    // root (one-for-one) -> middle (one-for-one) -> [escalated, sibling]
+    Bastion::supervisor(|root| {
+        root.supervisor(|middle| {
+            middle
+                .children(|children| {
+                    children
+                        .on_panic(PanicPolicy::Escalate)
+                        .with_exec(|ctx| panicking(ctx, &ESCALATED_STARTS))
+                })
+                .children(|children| {
+                    children.with_exec(|ctx: BastionContext| async move {
+                        SIBLING_STARTS.fetch_add(1, Ordering::SeqCst);
+
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    })
+                })
+        })
+    })
+    .expect("Couldn't create the supervisors.");
+
+    // The default policy restarts the element.
+    assert!(wait_until(|| RESTARTED_STARTS.load(Ordering::SeqCst) >= 2));
+
+    // The middle supervisor doesn't restart the group itself: the
+    // root restarts its whole subtree instead.
+    assert!(wait_until(|| ESCALATED_STARTS.load(Ordering::SeqCst) >= 2));
+    assert!(wait_until(|| SIBLING_STARTS.load(Ordering::SeqCst) >= 2));
+
+    // Leave some time for the stopped element to be wrongly restarted.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(STOPPED_STARTS.load(Ordering::SeqCst), 1);
+
+    let root = run!(Bastion::dump_topology()).expect("Couldn't dump the topology.");
+    let stopped = find(&root, "stopped").expect("Couldn't find the children group.");
+    assert_eq!(stopped.child_count(), 0);
+    let restarted = find(&root, "restarted").expect("Couldn't find the children group.");
+    assert_eq!(restarted.child_count(), 1);
+}
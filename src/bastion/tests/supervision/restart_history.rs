@@ -0,0 +1,43 @@
+use crate::common::{self, wait_until};
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static STARTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn counts_the_restarts_over_a_window() {
+    let _system = common::start();
+
+    let before = Instant::now();
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_restart_history(RestartHistory::new(2).with_window(Duration::from_millis(300)))
+            .with_exec(|ctx: BastionContext| async move {
+                // The first three starts fault, the fourth one keeps running.
+                if STARTS.fetch_add(1, Ordering::SeqCst) < 3 {
+                    return Err(());
+                }
+
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(wait_until(|| children_ref.restart_count() == 3));
+    let last = children_ref.last_restart().unwrap();
+    assert!(last >= before);
+
+    // Only the last two restarts are remembered...
+    let history = children_ref.restart_history();
+    assert_eq!(history.len(), 2);
+    assert!(history[0] <= history[1]);
+    assert_eq!(history[1], last);
+
+    // ...and the count is reset once the window elapsed.
+    assert!(wait_until(|| children_ref.restart_count() == 0));
+    assert_eq!(children_ref.last_restart(), Some(last));
+    assert_eq!(children_ref.restart_history().len(), 2);
+}
@@ -0,0 +1,49 @@
+use crate::common;
+use bastion::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn suspends_and_resumes_the_subtree() {
+    let _system = common::start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    let sp = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let workers = sp
+        .children(|children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let counter = counter.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _n: u32 => {
+                                counter.fetch_add(1, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+        })
+        .expect("Couldn't create the children group.");
+
+    thread::sleep(Duration::from_millis(100));
+    sp.suspend_subtree().unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    let worker = workers.elems()[0].clone();
+    for n in 0..3u32 {
+        worker.tell_anonymously(n).unwrap();
+    }
+
+    // The messages are kept in the mailbox while suspended.
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    sp.resume_subtree().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(received.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,45 @@
+use crate::common;
+use bastion::prelude::*;
+
+fn find<'a>(node: &'a TopologyNode, name: &str) -> Option<&'a TopologyNode> {
+    if node.kind == TopologyKind::Children && node.name.as_deref() == Some(name) {
+        return Some(node);
+    }
+
+    node.children.iter().find_map(|child| find(child, name))
+}
+
+#[test]
+fn dumps_the_supervision_tree() {
+    let _system = common::start();
+
+    let workers = Bastion::supervisor(|sp| {
+        sp.children(|children| children.with_name("workers").with_redundancy(3))
+    })
+    .expect("Couldn't create the supervisor.");
+
+    let root = run!(Bastion::dump_topology()).expect("Couldn't dump the topology.");
+    assert_eq!(root.kind, TopologyKind::System);
+    assert_eq!(root.state, "Running");
+
+    let supervisor = root
+        .children
+        .iter()
+        .find(|sp| sp.id == workers.id().to_string())
+        .expect("Couldn't find the supervisor.");
+    assert_eq!(supervisor.kind, TopologyKind::Supervisor);
+    assert_eq!(supervisor.child_count(), 1);
+
+    let group = find(supervisor, "workers").expect("Couldn't find the children group.");
+    assert_eq!(group.state, "Running");
+    assert_eq!(group.child_count(), 3);
+    for elem in &group.children {
+        assert_eq!(elem.kind, TopologyKind::Child);
+        assert_eq!(elem.name.as_deref(), Some("workers"));
+        assert!(elem.children.is_empty());
+    }
+
+    let json = serde_json::to_string(&root).expect("Couldn't serialize the topology.");
+    let parsed: TopologyNode = serde_json::from_str(&json).expect("Couldn't parse the topology.");
+    assert_eq!(parsed, root);
+}
@@ -0,0 +1,97 @@
+//!
+//! Handle which can cancel a proc without awaiting it.
+use crate::proc_data::ProcData;
+use crate::proc_handle::{acquire_proc, cancel_proc, is_proc_done};
+use std::fmt::{self, Debug, Formatter};
+use std::ptr::NonNull;
+
+/// A handle that can cancel its proc, but not await its result.
+///
+/// Cancelling a proc through a [`ProcHandle`] requires owning or borrowing it.
+/// An `AbortHandle` decouples who can cancel the proc from who awaits its result:
+/// it can be cloned and sent to other tasks, which can then call [`abort`].
+///
+/// The handle holds its own reference to the proc, which is released when it is
+/// dropped. Dropping it doesn't cancel the proc.
+///
+/// # Example
+/// ```rust
+/// # use lightproc::prelude::*;
+/// #
+/// # fn schedule_function(proc: LightProc) {;}
+/// #
+/// let (proc, handle) = LightProc::build(
+///     async { 1 + 2 },
+///     schedule_function,
+///     ProcStack::default(),
+/// );
+///
+/// let abort_handle = handle.abort_handle();
+/// std::thread::spawn(move || abort_handle.abort())
+///     .join()
+///     .unwrap();
+///
+/// // The future is dropped instead of being polled.
+/// proc.run();
+/// ```
+///
+/// [`ProcHandle`]: ../proc_handle/struct.ProcHandle.html
+/// [`abort`]: #method.abort
+pub struct AbortHandle {
+    /// A raw proc pointer.
+    raw_proc: NonNull<()>,
+}
+
+unsafe impl Send for AbortHandle {}
+unsafe impl Sync for AbortHandle {}
+
+impl AbortHandle {
+    /// Creates a handle for the given proc, taking a new reference to it.
+    pub(crate) unsafe fn new(raw_proc: NonNull<()>) -> Self {
+        acquire_proc(raw_proc.as_ptr());
+
+        AbortHandle { raw_proc }
+    }
+
+    /// Cancels the proc.
+    ///
+    /// This has the same effect as calling [`ProcHandle::cancel`]: if the proc
+    /// has already completed, calling this method will have no effect.
+    ///
+    /// [`ProcHandle::cancel`]: ../proc_handle/struct.ProcHandle.html#method.cancel
+    pub fn abort(&self) {
+        unsafe { cancel_proc(self.raw_proc.as_ptr()) }
+    }
+
+    /// Returns `true` if the proc completed or was cancelled, in which case aborting
+    /// it has no effect.
+    pub fn is_finished(&self) -> bool {
+        unsafe { is_proc_done(self.raw_proc.as_ptr()) }
+    }
+}
+
+impl Clone for AbortHandle {
+    fn clone(&self) -> Self {
+        unsafe { AbortHandle::new(self.raw_proc) }
+    }
+}
+
+impl Debug for AbortHandle {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let pdata = self.raw_proc.as_ptr() as *const ProcData;
+
+        fmt.debug_struct("AbortHandle")
+            .field("pdata", unsafe { &(*pdata) })
+            .finish()
+    }
+}
+
+impl Drop for AbortHandle {
+    fn drop(&mut self) {
+        let ptr = self.raw_proc.as_ptr();
+        let pdata = ptr as *const ProcData;
+
+        // Release the reference held by the handle.
+        unsafe { ((*pdata).vtable.decrement)(ptr) }
+    }
+}
@@ -0,0 +1,77 @@
+//!
+//! Guard which cancels the proc it was created for when dropped.
+use crate::proc_data::ProcData;
+use crate::proc_handle::{acquire_proc, cancel_proc};
+use std::fmt::{self, Debug, Formatter};
+use std::ptr::NonNull;
+
+/// A guard that cancels its proc when it goes out of scope.
+///
+/// Dropping a [`ProcHandle`] detaches the proc: it keeps running in the background
+/// and its output is dropped once it completes. Dropping a `CancelGuard` instead
+/// cancels the proc, which gives RAII-style lifetimes to procs.
+///
+/// The guard holds its own reference to the proc, so it can outlive the handle.
+///
+/// # Example
+/// ```rust
+/// # use lightproc::prelude::*;
+/// #
+/// # fn schedule_function(proc: LightProc) {;}
+/// #
+/// let (proc, handle) = LightProc::build(
+///     async { 1 + 2 },
+///     schedule_function,
+///     ProcStack::default(),
+/// );
+///
+/// {
+///     let _guard = handle.cancel_guard();
+///     // ... the proc is cancelled at the end of this scope.
+/// }
+///
+/// // The future is dropped instead of being polled.
+/// proc.run();
+/// ```
+///
+/// [`ProcHandle`]: ../proc_handle/struct.ProcHandle.html
+pub struct CancelGuard {
+    /// A raw proc pointer.
+    raw_proc: NonNull<()>,
+}
+
+unsafe impl Send for CancelGuard {}
+unsafe impl Sync for CancelGuard {}
+
+impl CancelGuard {
+    /// Creates a guard for the given proc, taking a new reference to it.
+    pub(crate) unsafe fn new(raw_proc: NonNull<()>) -> Self {
+        acquire_proc(raw_proc.as_ptr());
+
+        CancelGuard { raw_proc }
+    }
+}
+
+impl Debug for CancelGuard {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let pdata = self.raw_proc.as_ptr() as *const ProcData;
+
+        fmt.debug_struct("CancelGuard")
+            .field("pdata", unsafe { &(*pdata) })
+            .finish()
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        let ptr = self.raw_proc.as_ptr();
+        let pdata = ptr as *const ProcData;
+
+        unsafe {
+            cancel_proc(ptr);
+
+            // Release the reference held by the guard.
+            ((*pdata).vtable.decrement)(ptr);
+        }
+    }
+}
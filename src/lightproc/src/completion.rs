@@ -0,0 +1,91 @@
+use crate::proc_handle::ProcHandle;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// The callback invoked with the output of a proc.
+type Callback<R> = Box<dyn FnOnce(Option<R>) + Send>;
+
+/// A proc's handle along with the callback to invoke once the proc completes
+/// or is closed.
+///
+/// The completion registers itself as the awaiter of the proc, so that it gets
+/// woken (and polls the handle again) by the same paths that would wake a proc
+/// awaiting the handle.
+pub(crate) struct Completion<R> {
+    pending: Mutex<Option<(ProcHandle<R>, Callback<R>)>>,
+}
+
+impl<R> Completion<R>
+where
+    R: Send + 'static,
+{
+    pub(crate) fn register<F>(handle: ProcHandle<R>, callback: F)
+    where
+        F: FnOnce(Option<R>) + Send + 'static,
+    {
+        let completion = Arc::new(Completion {
+            pending: Mutex::new(Some((handle, Box::new(callback)))),
+        });
+
+        completion.poll();
+    }
+
+    fn poll(self: &Arc<Self>) {
+        let mut pending = self.pending.lock().unwrap();
+
+        let output = match pending.as_mut() {
+            // The callback was already invoked.
+            None => return,
+            Some((handle, _)) => {
+                let waker = Waker::from(self.clone());
+                let cx = &mut Context::from_waker(&waker);
+                match Pin::new(handle).poll(cx) {
+                    Poll::Ready(output) => output,
+                    Poll::Pending => return,
+                }
+            }
+        };
+
+        // Taking the callback out guarantees that it is only invoked once.
+        let (handle, callback) = pending.take().unwrap();
+        drop(pending);
+        drop(handle);
+
+        abort_on_panic(|| callback(output));
+    }
+}
+
+impl<R> Wake for Completion<R>
+where
+    R: Send + 'static,
+{
+    fn wake(self: Arc<Self>) {
+        self.poll();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.poll();
+    }
+}
+
+/// Runs the given closure, aborting the process if it panics.
+///
+/// Callbacks are invoked from the thread completing or closing the proc, which
+/// isn't prepared to unwind.
+pub(crate) fn abort_on_panic<T>(f: impl FnOnce() -> T) -> T {
+    struct Bomb;
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            std::process::abort();
+        }
+    }
+
+    let bomb = Bomb;
+    let t = f();
+    mem::forget(bomb);
+    t
+}
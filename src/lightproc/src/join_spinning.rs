@@ -0,0 +1,82 @@
+//!
+//! Future awaiting the result of a proc which spins briefly before going to sleep.
+use crate::proc_handle::is_proc_done;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::hint;
+use std::mem;
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::task::{Context, Poll};
+
+/// A future awaiting the result of a proc like its handle does, which spins
+/// while the proc isn't done yet before registering its waker.
+///
+/// Awaiting a short proc which is about to complete this way avoids going to
+/// sleep only to be woken up right away. Only the first poll spins, the later
+/// ones happening once the proc is done.
+///
+/// This future is created by [`ProcHandle::join_spinning`] or
+/// [`RecoverableHandle::join_spinning`].
+///
+/// # Example
+/// ```rust
+/// use lightproc::prelude::*;
+/// use std::thread;
+///
+/// let (proc, handle) = LightProc::build(async { 1 + 2 }, |_| {}, ProcStack::default());
+/// thread::spawn(move || proc.run());
+///
+/// let output = futures_executor::block_on(handle.join_spinning(100));
+/// assert_eq!(output, Some(3));
+/// ```
+///
+/// [`ProcHandle::join_spinning`]: ../proc_handle/struct.ProcHandle.html#method.join_spinning
+/// [`RecoverableHandle::join_spinning`]: ../recoverable_handle/struct.RecoverableHandle.html#method.join_spinning
+pub struct JoinSpinning<H> {
+    handle: H,
+    /// The proc of the handle, which keeps it alive.
+    raw_proc: NonNull<()>,
+    /// How many times the state of the proc is checked before polling the handle.
+    spins: usize,
+}
+
+unsafe impl<H: Send> Send for JoinSpinning<H> {}
+unsafe impl<H: Sync> Sync for JoinSpinning<H> {}
+
+impl<H> JoinSpinning<H> {
+    pub(crate) fn new(handle: H, raw_proc: NonNull<()>, spins: usize) -> Self {
+        JoinSpinning {
+            handle,
+            raw_proc,
+            spins,
+        }
+    }
+}
+
+impl<H: Future + Unpin> Future for JoinSpinning<H> {
+    type Output = H::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // The handle registers the waker if the proc still isn't done afterwards.
+        let spins = mem::take(&mut self.spins);
+        for _ in 0..spins {
+            if unsafe { is_proc_done(self.raw_proc.as_ptr()) } {
+                break;
+            }
+
+            hint::spin_loop();
+        }
+
+        Pin::new(&mut self.handle).poll(cx)
+    }
+}
+
+impl<H: Debug> Debug for JoinSpinning<H> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinSpinning")
+            .field("handle", &self.handle)
+            .field("spins", &self.spins)
+            .finish()
+    }
+}
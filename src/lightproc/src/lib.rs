@@ -6,6 +6,8 @@
 //! * It uses futures with lifecycle callbacks to implement Erlang like processes.
 //! * Contains basic pid(process id) to identify processes.
 //! * All panics inside futures are propagated to upper layers.
+//! * With the `slab` feature, the memory of the processes is recycled across spawns
+//!   (see the `slab` module).
 //!
 //! The naming convention of this crate comes from [Erlang's Lightweight Processes].
 //!
@@ -19,6 +21,7 @@
 #![allow(clippy::cast_ptr_alignment)]
 
 mod catch_unwind;
+mod completion;
 mod layout_helpers;
 mod proc_data;
 mod proc_ext;
@@ -27,19 +30,29 @@ mod proc_vtable;
 mod raw_proc;
 mod state;
 
+pub mod abort_handle;
+pub mod cancel_guard;
+pub mod join_spinning;
 pub mod lightproc;
 pub mod proc_handle;
 pub mod proc_stack;
 pub mod proc_state;
 pub mod recoverable_handle;
+pub mod shared_handle;
+#[cfg(feature = "slab")]
+pub mod slab;
 
 /// The lightproc prelude.
 ///
 /// The prelude re-exports lightproc structs and handles from this crate.
 pub mod prelude {
+    pub use crate::abort_handle::*;
+    pub use crate::cancel_guard::*;
+    pub use crate::join_spinning::*;
     pub use crate::lightproc::*;
     pub use crate::proc_handle::*;
     pub use crate::proc_stack::*;
     pub use crate::proc_state::*;
     pub use crate::recoverable_handle::*;
+    pub use crate::shared_handle::*;
 }
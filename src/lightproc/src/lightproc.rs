@@ -40,6 +40,7 @@ use crate::proc_handle::ProcHandle;
 use crate::proc_stack::*;
 use crate::raw_proc::RawProc;
 use crate::recoverable_handle::RecoverableHandle;
+use std::any::Any;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
 use std::marker::PhantomData;
@@ -102,6 +103,63 @@ impl LightProc {
         (proc, RecoverableHandle(handle))
     }
 
+    ///
+    /// Creates a recoverable process which awaits the given handle and feeds the
+    /// output of its process to `f`, resolving to the output of the returned future.
+    ///
+    /// If the awaited process panicked or got cancelled, `f` isn't called and the
+    /// continuation resolves as if it panicked. Cancelling the continuation only
+    /// detaches the awaited process.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use lightproc::prelude::*;
+    /// #
+    /// # // ... basic schedule function with no waker logic
+    /// # fn schedule_function(proc: LightProc) {;}
+    /// #
+    /// let (first, handle) = LightProc::recoverable(
+    ///     async { 21 },
+    ///     schedule_function,
+    ///     ProcStack::default(),
+    /// );
+    ///
+    /// // ... creating a process doubling the output of the first one
+    /// let (continuation, handle) = LightProc::and_then(
+    ///     handle,
+    ///     |res| async move { res * 2 },
+    ///     schedule_function,
+    ///     ProcStack::default(),
+    /// );
+    /// ```
+    pub fn and_then<R, F, Fut, T, S>(
+        handle: RecoverableHandle<R>,
+        f: F,
+        schedule: S,
+        stack: ProcStack,
+    ) -> (LightProc, RecoverableHandle<T>)
+    where
+        R: Send + 'static,
+        F: FnOnce(R) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+        S: Fn(LightProc) + Send + Sync + 'static,
+    {
+        let future = async move {
+            match handle.await {
+                Some(res) => AssertUnwindSafe(f(res)).catch_unwind().await,
+                None => {
+                    let aborted: Box<dyn Any + Send> =
+                        Box::new("the awaited process didn't complete");
+                    Err(aborted)
+                }
+            }
+        };
+
+        let (proc, handle) = Self::build(future, schedule, stack);
+        (proc, RecoverableHandle(handle))
+    }
+
     ///
     /// Creates a standard process which will stop it's execution on occurrence of panic.
     ///
@@ -1,16 +1,22 @@
 //!
 //! Handle for tasks which don't need to unwind panics inside
 //! the given futures.
+use crate::abort_handle::AbortHandle;
+use crate::cancel_guard::CancelGuard;
+use crate::completion::{abort_on_panic, Completion};
+use crate::join_spinning::JoinSpinning;
 use crate::proc_data::ProcData;
-use crate::proc_stack::ProcStack;
+use crate::proc_stack::{DropPolicy, ProcStack};
+use crate::shared_handle::SharedProcHandle;
 use crate::state::*;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
 use std::marker::{PhantomData, Unpin};
+use std::mem;
 use std::pin::Pin;
 use std::ptr::NonNull;
 use std::sync::atomic::Ordering;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 
 /// A handle that awaits the result of a proc.
 ///
@@ -38,52 +44,211 @@ impl<R> ProcHandle<R> {
     ///
     /// When a proc is cancelled, its future cannot be polled again and will be dropped instead.
     pub fn cancel(&self) {
-        let ptr = self.raw_proc.as_ptr();
-        let pdata = ptr as *const ProcData;
+        unsafe { cancel_proc(self.raw_proc.as_ptr()) }
+    }
 
-        unsafe {
-            let mut state = (*pdata).state.load(Ordering::Acquire);
+    /// Returns a [`CancelGuard`] which cancels the proc when dropped.
+    ///
+    /// Dropping the handle itself only detaches the proc, which keeps running
+    /// in the background, unless its stack has a [`DropPolicy::Cancel`] drop
+    /// policy. The guard can be used to tie the lifetime of the proc
+    /// to a scope instead.
+    ///
+    /// [`CancelGuard`]: ../cancel_guard/struct.CancelGuard.html
+    /// [`DropPolicy::Cancel`]: ../proc_stack/enum.DropPolicy.html#variant.Cancel
+    pub fn cancel_guard(&self) -> CancelGuard {
+        unsafe { CancelGuard::new(self.raw_proc) }
+    }
 
-            loop {
-                // If the proc has been completed or closed, it can't be cancelled.
-                if state & (COMPLETED | CLOSED) != 0 {
-                    break;
-                }
+    /// Returns an [`AbortHandle`] which can cancel the proc without owning
+    /// or borrowing this handle.
+    ///
+    /// This allows handing the ability to cancel the proc to another task
+    /// while keeping the handle around to await its result.
+    ///
+    /// [`AbortHandle`]: ../abort_handle/struct.AbortHandle.html
+    pub fn abort_handle(&self) -> AbortHandle {
+        unsafe { AbortHandle::new(self.raw_proc) }
+    }
 
-                // If the proc is not scheduled nor running, we'll need to schedule it.
-                let new = if state & (SCHEDULED | RUNNING) == 0 {
-                    (state | SCHEDULED | CLOSED) + REFERENCE
-                } else {
-                    state | CLOSED
-                };
+    /// Consumes the handle, invoking `callback` with the output of the proc once
+    /// it completes, instead of awaiting it.
+    ///
+    /// The callback is invoked exactly once, with `None` if the proc panicked or
+    /// was cancelled. It runs on the thread completing or cancelling the proc (or
+    /// on the current thread if the proc is already done), and the process is
+    /// aborted if it panics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::prelude::*;
+    /// use std::sync::mpsc;
+    ///
+    /// let (proc, handle) = LightProc::build(async { 1 + 1 }, |_| {}, ProcStack::default());
+    /// let (sender, recver) = mpsc::channel();
+    /// handle.on_complete(move |output| sender.send(output).unwrap());
+    ///
+    /// proc.run();
+    /// assert_eq!(recver.recv(), Ok(Some(2)));
+    /// ```
+    pub fn on_complete<F>(self, callback: F)
+    where
+        R: Send + 'static,
+        F: FnOnce(Option<R>) + Send + 'static,
+    {
+        Completion::register(self, callback)
+    }
 
-                // Mark the proc as closed.
-                match (*pdata).state.compare_exchange_weak(
-                    state,
-                    new,
-                    Ordering::AcqRel,
-                    Ordering::Acquire,
-                ) {
-                    Ok(_) => {
-                        // If the proc is not scheduled nor running, schedule it so that its future
-                        // gets dropped by the executor.
-                        if state & (SCHEDULED | RUNNING) == 0 {
-                            ((*pdata).vtable.schedule)(ptr);
-                        }
+    /// Consumes the handle, returning a [`SharedProcHandle`] which can be cloned
+    /// to let several tasks await the output of the proc, each of them getting a
+    /// clone of it.
+    ///
+    /// [`SharedProcHandle`]: ../shared_handle/struct.SharedProcHandle.html
+    pub fn shared(self) -> SharedProcHandle<R>
+    where
+        R: Clone,
+    {
+        SharedProcHandle::new(self)
+    }
 
-                        // Notify the awaiter that the proc has been closed.
-                        if state & AWAITER != 0 {
-                            (*pdata).notify();
-                        }
+    /// Consumes the handle, returning a raw pointer to the proc.
+    ///
+    /// The reference held by the handle is transferred to the pointer, which keeps
+    /// the proc alive until a handle is reconstructed from it using [`from_raw`] and
+    /// dropped. This allows passing the handle across an FFI boundary as an opaque
+    /// pointer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::prelude::*;
+    ///
+    /// let (proc, handle) = LightProc::build(async { 1 + 1 }, |_| {}, ProcStack::default());
+    /// let ptr = handle.into_raw();
+    ///
+    /// proc.run();
+    ///
+    /// let handle = unsafe { ProcHandle::<i32>::from_raw(ptr) };
+    /// assert_eq!(futures_executor::block_on(handle), Some(2));
+    /// ```
+    ///
+    /// [`from_raw`]: #method.from_raw
+    pub fn into_raw(self) -> *mut () {
+        let ptr = self.raw_proc.as_ptr();
+        // The reference now belongs to the pointer.
+        mem::forget(self);
+        ptr
+    }
 
-                        break;
-                    }
-                    Err(s) => state = s,
-                }
+    /// Reconstructs a handle from a pointer returned by [`into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// * `ptr` must have been returned by [`into_raw`], and a handle must be
+    ///   reconstructed from it exactly once: reconstructing it several times would
+    ///   release the same reference several times, while never reconstructing it
+    ///   leaks the proc.
+    /// * `R` must be the output type of the handle `ptr` was returned by, since
+    ///   the output of the proc is read as an `R`.
+    ///
+    /// [`into_raw`]: #method.into_raw
+    pub unsafe fn from_raw(ptr: *mut ()) -> ProcHandle<R> {
+        ProcHandle {
+            raw_proc: NonNull::new_unchecked(ptr),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Polls the handle for the output of the proc, like awaiting it.
+    ///
+    /// Since the handle is `Unpin`, this spares pinning it when driving it from a hand-written
+    /// future or a custom executor. The waker of `cx` is woken up once the proc completes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::prelude::*;
+    /// use std::future;
+    ///
+    /// let (proc, mut handle) = LightProc::build(async { 1 + 1 }, |_| {}, ProcStack::default());
+    /// proc.run();
+    ///
+    /// let output = futures_executor::block_on(future::poll_fn(|cx| handle.poll_result(cx)));
+    /// assert_eq!(output, Some(2));
+    /// ```
+    pub fn poll_result(&mut self, cx: &mut Context<'_>) -> Poll<Option<R>> {
+        Pin::new(self).poll(cx)
+    }
+
+    /// Registers `waker` to be woken up once the proc completes or is closed, without
+    /// polling the handle, and returns whether the proc already is.
+    ///
+    /// This lets a custom reactor be notified of the completion of the proc, before
+    /// polling the handle to take its output. A single waker is registered at a time:
+    /// this replaces the waker registered by the last call or poll, which is dropped
+    /// (the process is aborted if dropping it panics).
+    ///
+    /// The ordering guarantees are the following:
+    ///
+    /// * If `false` is returned, `waker` was registered before the proc completed or
+    ///   was closed, and is woken up once it is (unless it was replaced meanwhile).
+    /// * If `true` is returned, the proc completed or was closed, either before or
+    ///   right after `waker` was registered, in which case `waker` may or may not be
+    ///   woken up. Polling the handle then returns right away.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::prelude::*;
+    /// use std::sync::Arc;
+    /// use std::task::{Wake, Waker};
+    ///
+    /// struct Notified;
+    ///
+    /// impl Wake for Notified {
+    ///     fn wake(self: Arc<Self>) {
+    ///         // Tells the reactor that the proc is done...
+    ///     }
+    /// }
+    ///
+    /// let (proc, handle) = LightProc::build(async { 1 + 1 }, |_| {}, ProcStack::default());
+    /// assert!(!handle.register_waker(Waker::from(Arc::new(Notified))));
+    ///
+    /// proc.run();
+    /// assert!(handle.register_waker(Waker::from(Arc::new(Notified))));
+    /// ```
+    pub fn register_waker(&self, waker: Waker) -> bool {
+        let ptr = self.raw_proc.as_ptr();
+        let pdata = ptr as *const ProcData;
+
+        unsafe {
+            if is_proc_done(ptr) {
+                return true;
             }
+
+            let previous = (*pdata).swap_awaiter(Some(waker));
+            abort_on_panic(|| drop(previous));
+
+            // The proc could have completed or been closed just before the
+            // waker was registered, in which case it isn't woken up.
+            is_proc_done(ptr)
         }
     }
 
+    /// Consumes the handle, returning a future awaiting the output of the proc which
+    /// checks up to `spins` times whether the proc is done before going to sleep.
+    ///
+    /// This spares a context switch when awaiting a short proc which is expected to
+    /// complete quickly. With zero spins, the future behaves like the handle (see
+    /// [`JoinSpinning`]).
+    ///
+    /// [`JoinSpinning`]: ../join_spinning/struct.JoinSpinning.html
+    pub fn join_spinning(self, spins: usize) -> JoinSpinning<Self> {
+        let raw_proc = self.raw_proc;
+        JoinSpinning::new(self, raw_proc, spins)
+    }
+
     /// Returns a reference to the stack stored inside the proc.
     pub fn stack(&self) -> &ProcStack {
         let offset = ProcData::offset_stack();
@@ -185,6 +350,11 @@ impl<R> Drop for ProcHandle<R> {
         let mut output = None;
 
         unsafe {
+            // The stack is only dropped along with the last reference to the proc.
+            if self.stack().drop_policy() == DropPolicy::Cancel {
+                cancel_proc(ptr);
+            }
+
             // Optimistically assume the `ProcHandle` is being dropped just after creating the
             // proc. This is a common case so if the handle is not used, the overhead of it is only
             // one compare-exchange operation.
@@ -255,3 +425,70 @@ impl<R> Drop for ProcHandle<R> {
         drop(output);
     }
 }
+
+/// Takes a new reference to the proc behind the given pointer, the same way
+/// cloning a waker does.
+pub(crate) unsafe fn acquire_proc(ptr: *const ()) {
+    let pdata = ptr as *const ProcData;
+
+    let state = (*pdata).state.fetch_add(REFERENCE, Ordering::Relaxed);
+
+    // If the reference count overflowed, abort.
+    if state > isize::MAX as usize {
+        std::process::abort();
+    }
+}
+
+/// Returns whether the proc behind the given pointer completed or was closed, in
+/// which case polling its handle doesn't register the waker.
+pub(crate) unsafe fn is_proc_done(ptr: *const ()) -> bool {
+    let pdata = ptr as *const ProcData;
+
+    (*pdata).state.load(Ordering::Acquire) & (COMPLETED | CLOSED) != 0
+}
+
+/// Cancels the proc behind the given pointer.
+///
+/// If the proc is neither scheduled nor running, it is scheduled one more time
+/// so that its future gets dropped by the executor.
+pub(crate) unsafe fn cancel_proc(ptr: *const ()) {
+    let pdata = ptr as *const ProcData;
+
+    let mut state = (*pdata).state.load(Ordering::Acquire);
+
+    loop {
+        // If the proc has been completed or closed, it can't be cancelled.
+        if state & (COMPLETED | CLOSED) != 0 {
+            break;
+        }
+
+        // If the proc is not scheduled nor running, we'll need to schedule it.
+        let new = if state & (SCHEDULED | RUNNING) == 0 {
+            (state | SCHEDULED | CLOSED) + REFERENCE
+        } else {
+            state | CLOSED
+        };
+
+        // Mark the proc as closed.
+        match (*pdata)
+            .state
+            .compare_exchange_weak(state, new, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => {
+                // If the proc is not scheduled nor running, schedule it so that its future
+                // gets dropped by the executor.
+                if state & (SCHEDULED | RUNNING) == 0 {
+                    ((*pdata).vtable.schedule)(ptr);
+                }
+
+                // Notify the awaiter that the proc has been closed.
+                if state & AWAITER != 0 {
+                    (*pdata).notify();
+                }
+
+                break;
+            }
+            Err(s) => state = s,
+        }
+    }
+}
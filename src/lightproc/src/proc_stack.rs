@@ -11,6 +11,8 @@ use std::fmt::{self, Debug, Formatter};
 
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::Span;
 
 /// Stack abstraction for lightweight processes
 ///
@@ -51,6 +53,85 @@ pub struct ProcStack {
     /// This callback is only called when a panic has been occurred.
     /// Mind that [ProcHandle](proc_handle/struct.ProcHandle.html) is not using this
     pub(crate) after_panic: Option<Arc<dyn Fn(ProcState) + Send + Sync>>,
+
+    /// Scheduling priority of the process
+    pub(crate) priority: Priority,
+
+    /// Size of the OS thread stack requested by the process
+    ///
+    /// Only blocking executors, which can run the process on a dedicated
+    /// thread, are expected to honor it.
+    pub(crate) stack_size: Option<usize>,
+
+    /// How long the process may run before it is cancelled
+    ///
+    /// Only executors watching the deadlines are expected to honor it.
+    pub(crate) deadline: Option<Duration>,
+
+    /// Span entered while the process is polled
+    ///
+    /// Nothing is entered when there isn't one.
+    pub(crate) span: Option<Span>,
+
+    /// Key-value tags of the process
+    ///
+    /// Nothing is allocated for the processes without tags, while the clones of
+    /// a stack share its tags.
+    pub(crate) metadata: Option<Arc<Vec<(String, String)>>>,
+
+    /// What dropping the handle of the process does
+    pub(crate) drop_policy: DropPolicy,
+}
+
+/// Scheduling priority of a lightweight process
+///
+/// Executors can use it to decide which processes should run first.
+/// Processes have a [Priority::Normal] priority by default.
+///
+/// # Example
+///
+/// ```rust
+/// use lightproc::proc_stack::{Priority, ProcStack};
+///
+/// let stack = ProcStack::default().with_priority(Priority::High);
+///
+/// assert_eq!(stack.priority(), Priority::High);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Priority {
+    /// Processes that should run before any other process.
+    High,
+    /// The default priority of processes.
+    #[default]
+    Normal,
+    /// Processes that can wait for the others to run.
+    Low,
+}
+
+/// What dropping the handle of a lightweight process does
+///
+/// Processes are [DropPolicy::Detach]ed by default.
+///
+/// # Example
+///
+/// ```rust
+/// use lightproc::prelude::*;
+///
+/// let stack = ProcStack::default().with_drop_policy(DropPolicy::Cancel);
+/// let (proc, handle) = LightProc::build(async { 1 + 1 }, |_| {}, stack);
+///
+/// // The process is cancelled along with its handle.
+/// drop(handle);
+/// proc.run();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DropPolicy {
+    /// The process keeps running in the background, and its future is only dropped
+    /// without being polled again if nothing else references the process anymore.
+    #[default]
+    Detach,
+    /// The process is cancelled, unless it already completed.
+    Cancel,
 }
 
 impl ProcStack {
@@ -69,6 +150,129 @@ impl ProcStack {
         self
     }
 
+    /// Adds a scheduling priority for the process which is going to take this stack
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::{Priority, ProcStack};
+    ///
+    /// ProcStack::default()
+    ///     .with_priority(Priority::Low);
+    /// ```
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets what dropping the handle of the process which is going to take this
+    /// stack does
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::{DropPolicy, ProcStack};
+    ///
+    /// ProcStack::default()
+    ///     .with_drop_policy(DropPolicy::Cancel);
+    /// ```
+    pub fn with_drop_policy(mut self, drop_policy: DropPolicy) -> Self {
+        self.drop_policy = drop_policy;
+        self
+    }
+
+    /// Requests an OS thread stack of the given size (in bytes) for the process
+    /// which is going to take this stack
+    ///
+    /// Asynchronous processes ignore it, while blocking ones are run on a thread
+    /// with this stack size, which prevents recursive workloads from overflowing
+    /// the default stack.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// ProcStack::default()
+    ///     .with_stack_size(16 * 1024 * 1024);
+    /// ```
+    pub fn with_stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    /// Sets how long the process which is going to take this stack may run, counting
+    /// from its spawn, before it is cancelled.
+    ///
+    /// Executors watching the deadlines cancel the process once it is exceeded, after
+    /// which awaiting it yields `None` like awaiting a cancelled process does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    /// use std::time::Duration;
+    ///
+    /// ProcStack::default()
+    ///     .with_deadline(Duration::from_secs(5));
+    /// ```
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Adds a `tracing` span which is entered each time the process which is going to
+    /// take this stack is polled, and exited once the poll returns
+    ///
+    /// The events recorded by the process are then attributed to this span, even if
+    /// the process is resumed on another thread after yielding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    /// use tracing::info_span;
+    ///
+    /// ProcStack::default()
+    ///     .with_tracing_span(info_span!("worker", id = 1));
+    /// ```
+    pub fn with_tracing_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Adds a key-value tag to the process which is going to take this stack,
+    /// replacing the value of the tag with the same key if there is one
+    ///
+    /// The tags are included in the `Debug` output of the stack and of the handles
+    /// of the process, and can be retrieved with [ProcStack::get_metadata], e.g. by
+    /// the `tracing` layers recording the events of the process.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// ProcStack::default()
+    ///     .with_metadata("request_id", "42")
+    ///     .with_metadata("tenant", "acme");
+    /// ```
+    pub fn with_metadata<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let key = key.into();
+        let value = value.into();
+
+        let metadata = Arc::make_mut(self.metadata.get_or_insert_with(Default::default));
+        match metadata.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => metadata.push((key, value)),
+        }
+        self
+    }
+
     /// Adds state for the process which is going to be embedded into this stack.
     ///
     /// # Example
@@ -159,6 +363,108 @@ impl ProcStack {
         self.pid.load(Ordering::Acquire)
     }
 
+    /// Get the scheduling priority of the process which takes this stack.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::{Priority, ProcStack};
+    ///
+    /// let proc = ProcStack::default();
+    ///
+    /// assert_eq!(proc.priority(), Priority::Normal);
+    /// ```
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Get what dropping the handle of the process which takes this stack does.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::{DropPolicy, ProcStack};
+    ///
+    /// let proc = ProcStack::default();
+    ///
+    /// assert_eq!(proc.drop_policy(), DropPolicy::Detach);
+    /// ```
+    pub fn drop_policy(&self) -> DropPolicy {
+        self.drop_policy
+    }
+
+    /// Get the OS thread stack size requested by the process which takes this stack.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// let proc = ProcStack::default().with_stack_size(1 << 20);
+    ///
+    /// assert_eq!(proc.stack_size(), Some(1 << 20));
+    /// ```
+    pub fn stack_size(&self) -> Option<usize> {
+        self.stack_size
+    }
+
+    /// Get how long the process which takes this stack may run before it is cancelled.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    /// use std::time::Duration;
+    ///
+    /// let proc = ProcStack::default().with_deadline(Duration::from_secs(5));
+    ///
+    /// assert_eq!(proc.deadline(), Some(Duration::from_secs(5)));
+    /// ```
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Get the `tracing` span entered while the process which takes this stack is polled.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// let proc = ProcStack::default();
+    ///
+    /// assert!(proc.tracing_span().is_none());
+    /// ```
+    pub fn tracing_span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Get the value of the tag with the given key of the process which takes this stack.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// let proc = ProcStack::default().with_metadata("tenant", "acme");
+    ///
+    /// assert_eq!(proc.get_metadata("tenant"), Some("acme"));
+    /// assert_eq!(proc.get_metadata("request_id"), None);
+    /// ```
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Get all the key-value tags of the process which takes this stack, in the order
+    /// they were added.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// let proc = ProcStack::default()
+    ///     .with_metadata("request_id", "42")
+    ///     .with_metadata("tenant", "acme");
+    ///
+    /// let tags: Vec<_> = proc.metadata().collect();
+    /// assert_eq!(tags, vec![("request_id", "42"), ("tenant", "acme")]);
+    /// ```
+    pub fn metadata(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.metadata
+            .iter()
+            .flat_map(|metadata| metadata.iter())
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
     /// Get the state which is embedded into this [ProcStack].
     ///
     /// ```rust
@@ -214,6 +520,12 @@ impl Default for ProcStack {
             before_start: None,
             after_complete: None,
             after_panic: None,
+            priority: Priority::default(),
+            stack_size: None,
+            deadline: None,
+            span: None,
+            metadata: None,
+            drop_policy: DropPolicy::default(),
         }
     }
 }
@@ -226,10 +538,25 @@ impl Debug for ProcStack {
             .field("before_start", &self.before_start.is_some())
             .field("after_complete", &self.after_complete.is_some())
             .field("after_panic", &self.after_panic.is_some())
+            .field("priority", &self.priority)
+            .field("stack_size", &self.stack_size)
+            .field("deadline", &self.deadline)
+            .field("span", &self.span)
+            .field("metadata", &MetadataDebug(self))
+            .field("drop_policy", &self.drop_policy)
             .finish()
     }
 }
 
+/// Formats the tags of a stack as a map.
+struct MetadataDebug<'a>(&'a ProcStack);
+
+impl Debug for MetadataDebug<'_> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_map().entries(self.0.metadata()).finish()
+    }
+}
+
 impl Clone for ProcStack {
     fn clone(&self) -> Self {
         ProcStack {
@@ -238,6 +565,12 @@ impl Clone for ProcStack {
             before_start: self.before_start.clone(),
             after_complete: self.after_complete.clone(),
             after_panic: self.after_panic.clone(),
+            priority: self.priority,
+            stack_size: self.stack_size,
+            deadline: self.deadline,
+            span: self.span.clone(),
+            metadata: self.metadata.clone(),
+            drop_policy: self.drop_policy,
         }
     }
 }
@@ -5,8 +5,12 @@ use crate::proc_data::ProcData;
 use crate::proc_layout::ProcLayout;
 use crate::proc_stack::ProcStack;
 use crate::proc_vtable::ProcVTable;
+#[cfg(feature = "slab")]
+use crate::slab::{alloc, dealloc};
 use crate::state::*;
-use std::alloc::{self, Layout};
+use std::alloc::Layout;
+#[cfg(not(feature = "slab"))]
+use std::alloc::{alloc, dealloc};
 use std::cell::Cell;
 use std::future::Future;
 use std::mem::{self, ManuallyDrop};
@@ -48,7 +52,7 @@ where
 
         unsafe {
             // Allocate enough space for the entire proc.
-            let raw_proc = match NonNull::new(alloc::alloc(proc_layout.layout) as *mut ()) {
+            let raw_proc = match NonNull::new(alloc(proc_layout.layout) as *mut ()) {
                 None => std::process::abort(),
                 Some(p) => p,
             };
@@ -340,7 +344,7 @@ where
         (raw.stack as *mut ProcStack).drop_in_place();
 
         // Finally, deallocate the memory reserved by the proc.
-        alloc::dealloc(ptr as *mut u8, proc_layout.layout);
+        dealloc(ptr as *mut u8, proc_layout.layout);
     }
 
     /// Runs a proc.
@@ -400,7 +404,11 @@ where
             (*before_start_cb.clone())((*raw.stack).state.clone());
         }
 
-        let poll = <F as Future>::poll(Pin::new_unchecked(&mut *raw.future), cx);
+        let poll = {
+            // The span is only entered for the duration of the poll.
+            let _entered = (*raw.stack).span.as_ref().map(|span| span.enter());
+            <F as Future>::poll(Pin::new_unchecked(&mut *raw.future), cx)
+        };
         mem::forget(guard);
 
         match poll {
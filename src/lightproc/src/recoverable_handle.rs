@@ -1,12 +1,15 @@
 //!
 //! Handle for recoverable process
+use crate::abort_handle::AbortHandle;
+use crate::cancel_guard::CancelGuard;
+use crate::join_spinning::JoinSpinning;
 use crate::proc_data::ProcData;
 use crate::proc_handle::ProcHandle;
 use crate::proc_stack::ProcStack;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 use std::thread;
 
 /// Recoverable handle which encapsulates a standard Proc Handle and contain all panics inside.
@@ -24,6 +27,56 @@ impl<R> RecoverableHandle<R> {
         self.0.cancel()
     }
 
+    /// Returns a [`CancelGuard`] which cancels the proc when dropped.
+    ///
+    /// Dropping the handle itself only detaches the proc, which keeps running
+    /// in the background. The guard can be used to tie the lifetime of the proc
+    /// to a scope instead.
+    ///
+    /// [`CancelGuard`]: ../cancel_guard/struct.CancelGuard.html
+    pub fn cancel_guard(&self) -> CancelGuard {
+        self.0.cancel_guard()
+    }
+
+    /// Returns an [`AbortHandle`] which can cancel the proc without owning
+    /// or borrowing this handle.
+    ///
+    /// [`AbortHandle`]: ../abort_handle/struct.AbortHandle.html
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.0.abort_handle()
+    }
+
+    /// Polls the handle for the output of the proc, like awaiting it, without pinning it
+    /// first.
+    ///
+    /// See [`ProcHandle::poll_result`].
+    ///
+    /// [`ProcHandle::poll_result`]: ../proc_handle/struct.ProcHandle.html#method.poll_result
+    pub fn poll_result(&mut self, cx: &mut Context<'_>) -> Poll<Option<R>> {
+        Pin::new(self).poll(cx)
+    }
+
+    /// Registers `waker` to be woken up once the proc completes or is closed, without
+    /// polling the handle, and returns whether the proc already is.
+    ///
+    /// See [`ProcHandle::register_waker`].
+    ///
+    /// [`ProcHandle::register_waker`]: ../proc_handle/struct.ProcHandle.html#method.register_waker
+    pub fn register_waker(&self, waker: Waker) -> bool {
+        self.0.register_waker(waker)
+    }
+
+    /// Consumes the handle, returning a future awaiting the output of the proc which
+    /// checks up to `spins` times whether the proc is done before going to sleep.
+    ///
+    /// See [`ProcHandle::join_spinning`].
+    ///
+    /// [`ProcHandle::join_spinning`]: ../proc_handle/struct.ProcHandle.html#method.join_spinning
+    pub fn join_spinning(self, spins: usize) -> JoinSpinning<Self> {
+        let raw_proc = self.0.raw_proc;
+        JoinSpinning::new(self, raw_proc, spins)
+    }
+
     /// Returns a reference to the stack stored inside the proc.
     pub fn stack(&self) -> &ProcStack {
         self.0.stack()
@@ -0,0 +1,144 @@
+//!
+//! Handle which lets several awaiters get the result of a proc.
+use crate::proc_handle::ProcHandle;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// A handle that awaits the result of a proc and can be cloned, each clone
+/// resolving to a clone of the result.
+///
+/// A [`ProcHandle`] can only be awaited once, as its result is moved out of the
+/// proc. A `SharedProcHandle` instead keeps the result once the proc is done,
+/// which allows broadcasting a computed value to several tasks.
+///
+/// Like a `ProcHandle`, it resolves to `None` if the proc panicked or was
+/// cancelled. The proc is detached once all the clones are dropped.
+///
+/// This handle is created by [`ProcHandle::shared`].
+///
+/// # Example
+/// ```rust
+/// use lightproc::prelude::*;
+/// use std::thread;
+///
+/// let (proc, handle) = LightProc::build(async { 1 + 2 }, |_| {}, ProcStack::default());
+/// let handle = handle.shared();
+///
+/// let awaiters = (0..2)
+///     .map(|_| {
+///         let handle = handle.clone();
+///         thread::spawn(move || futures_executor::block_on(handle))
+///     })
+///     .collect::<Vec<_>>();
+///
+/// proc.run();
+/// for awaiter in awaiters {
+///     assert_eq!(awaiter.join().unwrap(), Some(3));
+/// }
+/// assert_eq!(futures_executor::block_on(handle), Some(3));
+/// ```
+///
+/// [`ProcHandle`]: ../proc_handle/struct.ProcHandle.html
+/// [`ProcHandle::shared`]: ../proc_handle/struct.ProcHandle.html#method.shared
+pub struct SharedProcHandle<R> {
+    shared: Arc<Shared<R>>,
+}
+
+struct Shared<R> {
+    state: Mutex<SharedState<R>>,
+    /// Wakes up all the awaiters once the proc is done.
+    awaiters: Arc<Awaiters>,
+}
+
+enum SharedState<R> {
+    Pending(ProcHandle<R>),
+    Done(Option<R>),
+}
+
+#[derive(Default)]
+struct Awaiters(Mutex<Vec<Waker>>);
+
+impl<R> SharedProcHandle<R> {
+    pub(crate) fn new(handle: ProcHandle<R>) -> Self {
+        SharedProcHandle {
+            shared: Arc::new(Shared {
+                state: Mutex::new(SharedState::Pending(handle)),
+                awaiters: Arc::new(Awaiters::default()),
+            }),
+        }
+    }
+}
+
+impl Awaiters {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.0.lock().unwrap();
+        if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+impl Wake for Awaiters {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let wakers = mem::take(&mut *self.0.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl<R: Clone> Future for SharedProcHandle<R> {
+    type Output = Option<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let shared = &self.shared;
+        let mut state = shared.state.lock().unwrap();
+
+        if let SharedState::Pending(handle) = &mut *state {
+            // The proc wakes up all the awaiters rather than the last one polling it,
+            // which might be dropped before the proc is done.
+            shared.awaiters.register(cx.waker());
+            let waker = Waker::from(shared.awaiters.clone());
+
+            match Pin::new(handle).poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(output) => {
+                    *state = SharedState::Done(output);
+                    shared.awaiters.wake_by_ref();
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match &*state {
+            SharedState::Done(output) => Poll::Ready(output.clone()),
+            SharedState::Pending(_) => unreachable!(),
+        }
+    }
+}
+
+impl<R> Clone for SharedProcHandle<R> {
+    fn clone(&self) -> Self {
+        SharedProcHandle {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<R> Debug for SharedProcHandle<R> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let mut debug = fmt.debug_struct("SharedProcHandle");
+        match &*self.shared.state.lock().unwrap() {
+            SharedState::Pending(handle) => debug.field("handle", handle),
+            SharedState::Done(output) => debug.field("done", &output.is_some()),
+        };
+        debug.finish()
+    }
+}
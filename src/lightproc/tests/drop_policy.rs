@@ -0,0 +1,42 @@
+use lightproc::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn spawn(policy: DropPolicy, polled: Arc<AtomicBool>) -> (LightProc, ProcHandle<()>) {
+    LightProc::build(
+        async move { polled.store(true, Ordering::SeqCst) },
+        |_| {},
+        ProcStack::default().with_drop_policy(policy),
+    )
+}
+
+#[test]
+fn detached_procs_keep_running() {
+    let polled = Arc::new(AtomicBool::new(false));
+    let (proc, handle) = spawn(DropPolicy::default(), polled.clone());
+
+    drop(handle);
+    proc.run();
+    assert!(polled.load(Ordering::SeqCst));
+}
+
+#[test]
+fn cancelled_procs_stop_with_their_handle() {
+    let polled = Arc::new(AtomicBool::new(false));
+    let (proc, handle) = spawn(DropPolicy::Cancel, polled.clone());
+
+    drop(handle);
+    proc.run();
+    assert!(!polled.load(Ordering::SeqCst));
+    assert_eq!(Arc::strong_count(&polled), 1);
+}
+
+#[test]
+fn completed_procs_are_unaffected() {
+    let polled = Arc::new(AtomicBool::new(false));
+    let (proc, handle) = spawn(DropPolicy::Cancel, polled.clone());
+
+    proc.run();
+    drop(handle);
+    assert!(polled.load(Ordering::SeqCst));
+}
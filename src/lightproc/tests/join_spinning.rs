@@ -0,0 +1,34 @@
+use futures_executor::block_on;
+use lightproc::prelude::*;
+use std::thread;
+
+#[test]
+fn resolves_like_the_handle() {
+    for spins in &[0, 1_000_000] {
+        let (proc, handle) = LightProc::build(async { 42 }, |_| {}, ProcStack::default());
+        let runner = thread::spawn(move || proc.run());
+
+        assert_eq!(block_on(handle.join_spinning(*spins)), Some(42));
+        runner.join().unwrap();
+    }
+}
+
+#[test]
+fn resolves_once_the_proc_is_closed() {
+    let (proc, handle) = LightProc::recoverable(
+        async {
+            panic!("test");
+        },
+        |_| {},
+        ProcStack::default(),
+    );
+    let runner = thread::spawn(move || proc.run());
+
+    assert_eq!(block_on(handle.join_spinning(1_000)), None::<()>);
+    runner.join().unwrap();
+
+    let (proc, handle) = LightProc::build(async { 42 }, |_| {}, ProcStack::default());
+    handle.cancel();
+    proc.run();
+    assert_eq!(block_on(handle.join_spinning(1_000)), None);
+}
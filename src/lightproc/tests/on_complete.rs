@@ -0,0 +1,62 @@
+use lightproc::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+#[test]
+fn fires_once_the_proc_completes() {
+    let (proc, handle) = LightProc::build(async { 42usize }, |_| {}, ProcStack::default());
+    let (sender, recver) = mpsc::channel();
+    handle.on_complete(move |output| sender.send(output).unwrap());
+
+    assert!(recver.try_recv().is_err());
+    thread::spawn(move || proc.run()).join().unwrap();
+    assert_eq!(recver.recv(), Ok(Some(42)));
+    assert!(recver.recv().is_err());
+}
+
+#[test]
+fn fires_right_away_if_the_proc_already_completed() {
+    let (proc, handle) = LightProc::build(async { 42usize }, |_| {}, ProcStack::default());
+    proc.run();
+
+    let (sender, recver) = mpsc::channel();
+    handle.on_complete(move |output| sender.send(output).unwrap());
+    assert_eq!(recver.try_recv(), Ok(Some(42)));
+}
+
+#[test]
+fn fires_once_on_cancellation() {
+    let fired = Arc::new(AtomicUsize::new(0));
+
+    let (proc, handle) = LightProc::build(
+        std::future::pending::<usize>(),
+        |_| {},
+        ProcStack::default(),
+    );
+    let abort = handle.abort_handle();
+    let (sender, recver) = mpsc::channel();
+    let counter = fired.clone();
+    handle.on_complete(move |output| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        sender.send(output).unwrap();
+    });
+
+    proc.run();
+    abort.abort();
+    assert_eq!(recver.recv(), Ok(None));
+
+    // Cancelling again doesn't fire the callback another time.
+    abort.abort();
+    assert_eq!(fired.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn fires_when_the_proc_is_dropped() {
+    let (proc, handle) = LightProc::build(async { 42usize }, |_| {}, ProcStack::default());
+    let (sender, recver) = mpsc::channel();
+    handle.on_complete(move |output| sender.send(output).unwrap());
+
+    drop(proc);
+    assert_eq!(recver.recv(), Ok(None));
+}
@@ -0,0 +1,75 @@
+use lightproc::prelude::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+// Counts the wake ups.
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// A hand-written state machine waiting for two procs in turn.
+struct Both {
+    first: Option<ProcHandle<usize>>,
+    second: RecoverableHandle<usize>,
+    sum: usize,
+}
+
+impl Future for Both {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<usize> {
+        if let Some(first) = &mut self.first {
+            match first.poll_result(cx) {
+                Poll::Ready(output) => {
+                    self.sum += output.unwrap();
+                    self.first = None;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        self.second
+            .poll_result(cx)
+            .map(|output| self.sum + output.unwrap())
+    }
+}
+
+#[test]
+fn polls_the_handles_without_pinning() {
+    let scheduled = Arc::new(Mutex::new(Vec::new()));
+    let schedule = {
+        let scheduled = scheduled.clone();
+        move |proc| scheduled.lock().unwrap().push(proc)
+    };
+
+    let (first, first_handle) =
+        LightProc::build(async { 1 }, schedule.clone(), ProcStack::default());
+    let (second, second_handle) =
+        LightProc::recoverable(async { 2 }, schedule, ProcStack::default());
+    let mut both = Both {
+        first: Some(first_handle),
+        second: second_handle,
+        sum: 0,
+    };
+
+    let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(Pin::new(&mut both).poll(&mut cx), Poll::Pending);
+    first.run();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    assert_eq!(Pin::new(&mut both).poll(&mut cx), Poll::Pending);
+    second.run();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+
+    assert_eq!(Pin::new(&mut both).poll(&mut cx), Poll::Ready(3));
+}
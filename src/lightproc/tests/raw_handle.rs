@@ -0,0 +1,48 @@
+use futures_executor::block_on;
+use lightproc::prelude::*;
+use std::sync::Arc;
+
+#[test]
+fn round_trips_through_a_raw_pointer() {
+    let shared = Arc::new(());
+    let captured = shared.clone();
+
+    let (proc, handle) = LightProc::build(
+        async move {
+            drop(captured);
+            42usize
+        },
+        |_| {},
+        ProcStack::default(),
+    );
+    let ptr = handle.into_raw();
+
+    proc.run();
+    assert_eq!(Arc::strong_count(&shared), 1);
+
+    let handle = unsafe { ProcHandle::<usize>::from_raw(ptr) };
+    assert_eq!(block_on(handle), Some(42));
+}
+
+#[test]
+fn dropping_the_reconstructed_handle_releases_the_proc() {
+    let shared = Arc::new(());
+    let captured = shared.clone();
+
+    let (proc, handle) = LightProc::build(
+        async move {
+            let _captured = captured;
+            std::future::pending::<()>().await
+        },
+        |_| {},
+        ProcStack::default(),
+    );
+    let ptr = handle.into_raw();
+
+    // The pointer keeps the proc alive while its future is dropped.
+    drop(proc);
+    assert_eq!(Arc::strong_count(&shared), 1);
+
+    let handle = unsafe { ProcHandle::<()>::from_raw(ptr) };
+    assert_eq!(block_on(handle), None);
+}
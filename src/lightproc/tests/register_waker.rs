@@ -0,0 +1,47 @@
+use futures_executor::block_on;
+use lightproc::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::thread;
+
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+impl Wake for Counter {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn wakes_the_registered_waker_on_completion() {
+    let (proc, handle) = LightProc::build(async { 42 }, |_| {}, ProcStack::default());
+    let first = Arc::new(Counter::default());
+    let second = Arc::new(Counter::default());
+
+    assert!(!handle.register_waker(Waker::from(first.clone())));
+    // The second waker replaces the first one.
+    assert!(!handle.register_waker(Waker::from(second.clone())));
+
+    thread::spawn(move || proc.run()).join().unwrap();
+    assert_eq!(first.0.load(Ordering::SeqCst), 0);
+    assert_eq!(second.0.load(Ordering::SeqCst), 1);
+
+    assert!(handle.register_waker(Waker::from(first.clone())));
+    assert_eq!(block_on(handle), Some(42));
+}
+
+#[test]
+fn wakes_the_registered_waker_on_cancellation() {
+    let (proc, handle) = LightProc::recoverable(async { 42 }, |_| {}, ProcStack::default());
+    let counter = Arc::new(Counter::default());
+
+    assert!(!handle.register_waker(Waker::from(counter.clone())));
+    handle.cancel();
+    proc.run();
+
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert!(handle.register_waker(Waker::from(counter.clone())));
+    assert_eq!(block_on(handle), None);
+}
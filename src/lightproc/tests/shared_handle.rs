@@ -0,0 +1,51 @@
+use futures_executor::block_on;
+use lightproc::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn every_awaiter_gets_the_output() {
+    let (proc, handle) = LightProc::build(
+        async { String::from("output") },
+        |_| {},
+        ProcStack::default(),
+    );
+    let handle = handle.shared();
+
+    let awaiters = (0..4)
+        .map(|_| {
+            let handle = handle.clone();
+            thread::spawn(move || block_on(handle))
+        })
+        .collect::<Vec<_>>();
+
+    // Some awaiters wait for the proc, which is run once they are all spawned.
+    thread::sleep(Duration::from_millis(50));
+    proc.run();
+
+    for awaiter in awaiters {
+        assert_eq!(awaiter.join().unwrap(), Some(String::from("output")));
+    }
+
+    // The output is kept for the awaiters coming late.
+    assert_eq!(block_on(handle.clone()), Some(String::from("output")));
+    assert_eq!(block_on(handle), Some(String::from("output")));
+}
+
+#[test]
+fn every_awaiter_sees_the_cancellation() {
+    let (proc, handle) = LightProc::build(async { 42 }, |_| {}, ProcStack::default());
+    let abort_handle = handle.abort_handle();
+    let handle = handle.shared();
+
+    let awaiter = {
+        let handle = handle.clone();
+        thread::spawn(move || block_on(handle))
+    };
+
+    abort_handle.abort();
+    proc.run();
+
+    assert_eq!(awaiter.join().unwrap(), None);
+    assert_eq!(block_on(handle), None);
+}
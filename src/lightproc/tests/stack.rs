@@ -13,3 +13,50 @@ fn stack_copy() {
 
     assert_eq!(stack2.get_pid(), 12);
 }
+
+#[test]
+fn stack_tracing_span() {
+    use lightproc::prelude::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::{info_span, Span};
+
+    let subscriber = tracing_subscriber::registry();
+    tracing::subscriber::with_default(subscriber, || {
+        let span = info_span!("proc");
+        let current = Arc::new(Mutex::new(None));
+
+        let proc_current = current.clone();
+        let (proc, _handle) = LightProc::build(
+            async move {
+                *proc_current.lock().unwrap() = Span::current().id();
+            },
+            |_| {},
+            ProcStack::default().with_tracing_span(span.clone()),
+        );
+        proc.run();
+
+        // The span was entered while polling, and exited afterwards.
+        assert_eq!(*current.lock().unwrap(), span.id());
+        assert!(Span::current().id().is_none());
+    });
+}
+
+#[test]
+fn stack_metadata() {
+    use lightproc::prelude::*;
+
+    let stack = ProcStack::default()
+        .with_metadata("request_id", "41")
+        .with_metadata("tenant", "acme")
+        .with_metadata("request_id", "42");
+    let (proc, handle) = LightProc::build(async {}, |_| {}, stack);
+
+    // The tags are surfaced by the handles of the process.
+    let output = format!("{:?}", handle);
+    assert!(output.contains(r#"metadata: {"request_id": "42", "tenant": "acme"}"#));
+    assert_eq!(proc.stack().get_metadata("tenant"), Some("acme"));
+
+    let stack = ProcStack::default();
+    assert!(stack.metadata().next().is_none());
+    assert!(format!("{:?}", stack).contains("metadata: {}"));
+}